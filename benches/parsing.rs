@@ -0,0 +1,50 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+use people::bench::synthetic_log;
+use people::config::Config;
+use people::log::{self, parse_log_file_content};
+use people::use_cases;
+
+/// About 27 years of daily entries
+const DAYS: usize = 10_000;
+const PEOPLE: usize = 1_000;
+
+fn tokenize(c: &mut Criterion) {
+    let content = synthetic_log(PEOPLE, DAYS);
+    c.bench_function("tokenize (10k days)", |b| {
+        b.iter(|| log::tokenize(&content))
+    });
+}
+
+fn parse(c: &mut Criterion) {
+    let content = synthetic_log(PEOPLE, DAYS);
+    c.bench_function("parse_log_file_content (10k days)", |b| {
+        b.iter(|| parse_log_file_content(&content))
+    });
+}
+
+fn split(c: &mut Criterion) {
+    let config = Config::builder().people_dir("/tmp/people").build();
+    let log = parse_log_file_content(&synthetic_log(PEOPLE, DAYS));
+    c.bench_function("split_log_per_person (1k people, 10k days)", |b| {
+        b.iter_batched(
+            || log.clone(),
+            |log| use_cases::split_log_per_person(log, &config),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn summary(c: &mut Criterion) {
+    let log = parse_log_file_content(&synthetic_log(PEOPLE, DAYS));
+    c.bench_function("get_last_interactions (1k people, 10k days)", |b| {
+        b.iter(|| use_cases::get_last_interactions(&log))
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = tokenize, parse, split, summary
+}
+criterion_main!(benches);
@@ -0,0 +1,63 @@
+use std::fs;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+use people::bench;
+use people::config::Config;
+use people::log::{parse_log_file_content, Log};
+use people::operations::Transaction;
+use people::use_cases;
+
+fn synthetic_log(people: usize, days: usize) -> Log {
+    parse_log_file_content(&bench::synthetic_log(people, days))
+}
+
+fn split(c: &mut Criterion) {
+    let config = Config::builder().people_dir("/tmp/people").build();
+
+    c.bench_function("split_log_per_person (500 people, 365 days)", |b| {
+        b.iter_batched(
+            || synthetic_log(500, 365),
+            |log| use_cases::split_log_per_person(log, &config),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn write(c: &mut Criterion) {
+    let dir = std::env::temp_dir().join("people-bench-per-person");
+    let config = Config::builder()
+        .people_dir(&dir)
+        .front_matter(vec![])
+        .build();
+
+    let mut group = c.benchmark_group("write_person_logs (500 people)");
+    for jobs in [1, 8] {
+        group.bench_function(format!("{jobs} jobs"), |b| {
+            b.iter_batched(
+                || {
+                    // start from scratch, so that every file is written
+                    let _ = fs::remove_dir_all(&dir);
+                    let person_log = config.get_person_log_path("JohnDoe");
+                    fs::create_dir_all(person_log.parent().unwrap()).unwrap();
+                    use_cases::split_log_per_person(synthetic_log(500, 365), &config)
+                },
+                |per_person| {
+                    let transaction = Transaction::begin(&config, "bench");
+                    use_cases::write_person_logs(per_person, &config, jobs, &transaction)
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = split, write
+}
+criterion_main!(benches);
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use people::formatter::format_content;
+
+fuzz_target!(|content: &str| {
+    // formatting twice changing the content is reported as an error
+    if let Err(reason) = format_content(content) {
+        panic!("{reason}");
+    }
+});
@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use people::config::{LogMode, LogSyntax};
+use people::log::{parse_log_file_content_with_options, ParseOptions};
+use people::log_format;
+
+fuzz_target!(|content: &str| {
+    for mode in [LogMode::People, LogMode::Journal] {
+        let options = ParseOptions {
+            mode,
+            ..Default::default()
+        };
+        parse_log_file_content_with_options(content, &options);
+    }
+    log_format::for_syntax(LogSyntax::Org).parse(content, &ParseOptions::default());
+});
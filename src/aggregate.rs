@@ -0,0 +1,289 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::log::Log;
+use crate::model::PersonName;
+
+/// The dates from `start` to `end`, both included
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Window {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Window {
+    /// The `length` that ends on `end`, e.g. the last 90 days, starting at the
+    /// earliest date if it is longer than that
+    pub fn ending(end: NaiveDate, length: Duration) -> Window {
+        let start = end
+            .checked_sub_signed(length)
+            .and_then(|start| start.checked_add_signed(Duration::days(1)));
+        Window {
+            start: start.unwrap_or(NaiveDate::MIN),
+            end,
+        }
+    }
+
+    /// The window of the same length right before this one
+    pub fn previous(&self) -> Window {
+        let length = self.end - self.start + Duration::days(1);
+        Window::ending(self.start.pred_opt().unwrap_or(NaiveDate::MIN), length)
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+/// Entries per day within the window, with the person or, without one, with
+/// anyone not ignored
+pub fn count_per_day(
+    log: &Log,
+    config: &Config,
+    person: Option<&PersonName>,
+    window: Window,
+) -> BTreeMap<NaiveDate, usize> {
+    let mut counts: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for day in log.days.iter().filter(|day| window.contains(day.date)) {
+        let entries = day
+            .entries
+            .iter()
+            .filter(|entry| match person {
+                Some(person) => entry.interacted().any(|name| name == person),
+                None => entry.interacted().any(|name| !config.is_ignored(name)),
+            })
+            .count();
+
+        if entries > 0 {
+            *counts.entry(day.date).or_default() += entries;
+        }
+    }
+
+    counts
+}
+
+/// Entries per person within the window, ignored people left out
+pub fn count_per_person(log: &Log, config: &Config, window: Window) -> BTreeMap<PersonName, usize> {
+    let mut counts: BTreeMap<PersonName, usize> = BTreeMap::new();
+    for day in log.days.iter().filter(|day| window.contains(day.date)) {
+        for entry in day.entries.iter() {
+            for person in entry.interacted().filter(|name| !config.is_ignored(name)) {
+                *counts.entry(person.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    counts
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Table,
+    Json,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(ReportFormat::Table),
+            "json" => Ok(ReportFormat::Json),
+            other => Err(format!(
+                "unsupported format found: {other:?}, expected 'table' or 'json'"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct TopPerson {
+    pub person: PersonName,
+    pub interactions: usize,
+}
+
+/// The `limit` people with the most entries in the window, most first
+pub fn top_people(log: &Log, config: &Config, window: Window, limit: usize) -> Vec<TopPerson> {
+    let mut top: Vec<TopPerson> = count_per_person(log, config, window)
+        .into_iter()
+        .map(|(person, interactions)| TopPerson {
+            person,
+            interactions,
+        })
+        .collect();
+
+    // counts come sorted by name, so ties stay in alphabetical order
+    top.sort_by_key(|person| Reverse(person.interactions));
+    top.truncate(limit);
+    top
+}
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendDirection {
+    Rising,
+    Steady,
+    Falling,
+}
+
+impl fmt::Display for TrendDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let direction = match self {
+            TrendDirection::Rising => "rising",
+            TrendDirection::Steady => "steady",
+            TrendDirection::Falling => "falling",
+        };
+        write!(f, "{direction}")
+    }
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct Trend {
+    pub person: PersonName,
+    /// Entries in the previous window
+    pub before: usize,
+    /// Entries in the current window
+    pub now: usize,
+    pub direction: TrendDirection,
+}
+
+impl Trend {
+    pub fn change(&self) -> i64 {
+        self.now as i64 - self.before as i64
+    }
+}
+
+/// Compare each person's entries in the window with the window before it.
+/// Biggest changes first, rising before falling.
+pub fn trends(log: &Log, config: &Config, window: Window) -> Vec<Trend> {
+    let now = count_per_person(log, config, window);
+    let before = count_per_person(log, config, window.previous());
+
+    let mut people: Vec<&PersonName> = now.keys().chain(before.keys()).collect();
+    people.sort();
+    people.dedup();
+
+    let mut trends: Vec<Trend> = people
+        .into_iter()
+        .map(|person| {
+            let now = now.get(person).copied().unwrap_or(0);
+            let before = before.get(person).copied().unwrap_or(0);
+            Trend {
+                person: person.clone(),
+                before,
+                now,
+                direction: match now.cmp(&before) {
+                    std::cmp::Ordering::Greater => TrendDirection::Rising,
+                    std::cmp::Ordering::Equal => TrendDirection::Steady,
+                    std::cmp::Ordering::Less => TrendDirection::Falling,
+                },
+            }
+        })
+        .collect();
+
+    trends.sort_by_key(|trend| (Reverse(trend.change().abs()), Reverse(trend.change())));
+    trends
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    fn log() -> Log {
+        parse_log_file_content(indoc!(
+            "
+            # 2000-01-02
+
+            - #JohnDoe :
+            - #JohnDoe, #JaneDoe :
+
+            # 2000-01-05
+
+            - #JaneDoe :
+            - #Ignored :
+
+            # 2000-01-08
+
+            - #JaneDoe :
+            - #Abu :
+            "
+        ))
+    }
+
+    fn config() -> Config {
+        Config::builder()
+            .people_dir("/tmp/people")
+            .ignore(vec!["Ignored".to_string()])
+            .build()
+    }
+
+    #[test]
+    fn test_windows() {
+        let window = Window::ending(d("2000-01-10"), Duration::days(5));
+
+        assert_eq!(window.start, d("2000-01-06"));
+        assert_eq!(
+            window.previous(),
+            Window {
+                start: d("2000-01-01"),
+                end: d("2000-01-05"),
+            }
+        );
+
+        let window = Window::ending(d("2000-01-10"), Duration::days(100_000_000));
+        assert_eq!(window.start, NaiveDate::MIN);
+        assert_eq!(window.previous().end, NaiveDate::MIN);
+    }
+
+    #[test]
+    fn test_top_people() {
+        let window = Window::ending(d("2000-01-08"), Duration::days(7));
+
+        let top = top_people(&log(), &config(), window, 2);
+
+        assert_eq!(
+            top,
+            vec![
+                TopPerson {
+                    person: "JaneDoe".to_string(),
+                    interactions: 3,
+                },
+                TopPerson {
+                    person: "JohnDoe".to_string(),
+                    interactions: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trends() {
+        let window = Window::ending(d("2000-01-10"), Duration::days(5));
+
+        let trends: Vec<(String, usize, usize, TrendDirection)> = trends(&log(), &config(), window)
+            .into_iter()
+            .map(|trend| (trend.person, trend.before, trend.now, trend.direction))
+            .collect();
+
+        assert_eq!(
+            trends,
+            vec![
+                ("JohnDoe".to_string(), 2, 0, TrendDirection::Falling),
+                ("Abu".to_string(), 0, 1, TrendDirection::Rising),
+                ("JaneDoe".to_string(), 2, 1, TrendDirection::Falling),
+            ]
+        );
+    }
+}
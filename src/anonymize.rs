@@ -0,0 +1,376 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_yaml::{Mapping, Value};
+
+use crate::config::{Config, Person};
+use crate::log::{find_log_files, Log, ParseOptions};
+use crate::model::PersonName;
+use crate::rename::replace_references;
+
+/// Config sections that point to other people directories or hold credentials
+static DROPPED_SECTIONS: [&str; 4] = ["profiles", "carddav", "caldav", "smtp"];
+
+/// 64-bit FNV-1a, stable across platforms and Rust versions unlike `Hash`
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// `A`, `B`, ..., `Z`, `AA`, `AB`... hashtags don't take digits
+fn letters(index: usize) -> String {
+    let mut letters = vec![];
+    let mut rest = index + 1;
+    while rest > 0 {
+        rest -= 1;
+        letters.push((b'A' + (rest % 26) as u8) as char);
+        rest /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Every name in the logs and in the config, aliases included
+pub fn collect_names(log: &Log, config: &Config) -> BTreeSet<PersonName> {
+    let mut names: BTreeSet<PersonName> = BTreeSet::new();
+    for person in config.people.iter() {
+        names.insert(person.name.clone());
+        names.extend(person.aliases.iter().cloned());
+    }
+
+    for day in log.days.iter() {
+        for entry in day.entries.iter() {
+            names.extend(entry.related.iter().cloned());
+        }
+    }
+
+    names
+}
+
+/// `PersonA`, `PersonB`... in an order that only depends on the names and the
+/// salt, so the same input always gets the same pseudonyms
+pub fn pseudonyms(names: &BTreeSet<PersonName>, salt: &str) -> BTreeMap<PersonName, PersonName> {
+    let mut shuffled: Vec<&PersonName> = names.iter().collect();
+    shuffled.sort_by_key(|name| (fnv1a(&format!("{salt}{name}")), name.to_string()));
+
+    shuffled
+        .into_iter()
+        .enumerate()
+        .map(|(index, name)| (name.clone(), format!("Person{}", letters(index))))
+        .collect()
+}
+
+pub fn anonymize_content(
+    content: &str,
+    pseudonyms: &BTreeMap<PersonName, PersonName>,
+    options: &ParseOptions,
+) -> String {
+    let (anonymized, _) =
+        replace_references(content, options, |name| pseudonyms.get(name).cloned());
+    anonymized
+}
+
+/// Names and aliases replaced, contact details dropped
+pub fn anonymize_people(
+    people: &[Person],
+    pseudonyms: &BTreeMap<PersonName, PersonName>,
+) -> Vec<Person> {
+    let pseudonym = |name: &PersonName| pseudonyms.get(name).cloned().unwrap_or(name.clone());
+
+    people
+        .iter()
+        .map(|person| Person {
+            name: pseudonym(&person.name),
+            aliases: person.aliases.iter().map(pseudonym).collect(),
+            email: None,
+            phone: None,
+            telegram: None,
+            ..person.clone()
+        })
+        .collect()
+}
+
+/// The config file pointing to `people_dir`, with the anonymized people and
+/// without profiles nor servers. `ignore` and `not_people` names are replaced
+/// too, patterns are kept as they are.
+pub fn anonymize_config(
+    content: &str,
+    people: &[Person],
+    pseudonyms: &BTreeMap<PersonName, PersonName>,
+    people_dir: &Path,
+) -> Result<String, String> {
+    let mut document: Value = match serde_yaml::from_str(content) {
+        Ok(document) => document,
+        Err(error) => return Err(format!("failed to parse because {error}")),
+    };
+
+    let mapping: &mut Mapping = match document.as_mapping_mut() {
+        Some(mapping) => mapping,
+        None => return Err("expected config file to be a YAML mapping".to_string()),
+    };
+
+    for section in DROPPED_SECTIONS {
+        mapping.remove(section);
+    }
+
+    mapping.insert(
+        Value::from("people_dir"),
+        Value::from(people_dir.display().to_string()),
+    );
+
+    let people_value = match serde_yaml::to_value(people) {
+        Ok(value) => value,
+        Err(error) => return Err(format!("failed to serialize people because {error}")),
+    };
+    mapping.insert(Value::from("people"), people_value);
+
+    for section in ["ignore", "not_people"] {
+        if let Some(Value::Sequence(names)) = mapping.get_mut(section) {
+            for name in names.iter_mut() {
+                let pseudonym = name.as_str().and_then(|name| pseudonyms.get(name));
+                if let Some(pseudonym) = pseudonym {
+                    *name = Value::from(pseudonym.as_str());
+                }
+            }
+        }
+    }
+
+    match serde_yaml::to_string(&document) {
+        Ok(anonymized) => Ok(anonymized),
+        Err(error) => Err(format!("failed to serialize config because {error}")),
+    }
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        if let Err(reason) = fs::create_dir_all(dir) {
+            return Err(format!("failed to create {dir:?}, reason: {reason}"));
+        }
+    }
+
+    match fs::write(path, content) {
+        Ok(()) => Ok(()),
+        Err(reason) => Err(format!("failed to write {path:?}, reason: {reason}")),
+    }
+}
+
+/// Where each log file is exported to: the same place under `output` as under
+/// `people_dir`, so that the `log_globs` of the exported config still find
+/// them. Logs outside of `people_dir` go to `<output>/log`. Fails if two logs
+/// would be written to the same path.
+fn exported_log_paths(
+    paths: Vec<PathBuf>,
+    people_dir: &Path,
+    output: &Path,
+) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let mut exported: BTreeMap<PathBuf, PathBuf> = BTreeMap::new();
+    let mut pairs: Vec<(PathBuf, PathBuf)> = vec![];
+    for path in paths {
+        let exported_path = match path.strip_prefix(people_dir) {
+            Ok(relative) => output.join(relative),
+            Err(_) => output
+                .join("log")
+                .join(path.file_name().expect("log files have a name")),
+        };
+        if let Some(other) = exported.insert(exported_path.clone(), path.clone()) {
+            return Err(format!(
+                "failed to export {other:?} and {path:?}, both would be written to {exported_path:?}"
+            ));
+        }
+        pairs.push((path, exported_path));
+    }
+
+    Ok(pairs)
+}
+
+/// Write the anonymized log files under `output`, where they are under
+/// `people_dir`, and the config to `<output>/config.yaml`. Returns the written
+/// paths.
+pub fn export(
+    log: &Log,
+    config: &Config,
+    config_content: &str,
+    output: &Path,
+    salt: &str,
+) -> Result<Vec<PathBuf>, String> {
+    let options = ParseOptions::from_config(config);
+    let pseudonyms = pseudonyms(&collect_names(log, config), salt);
+
+    let mut written: Vec<PathBuf> = vec![];
+    for (path, anonymized_path) in
+        exported_log_paths(find_log_files(config), &config.people_dir, output)?
+    {
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+        };
+        write(
+            &anonymized_path,
+            &anonymize_content(&content, &pseudonyms, &options),
+        )?;
+        written.push(anonymized_path);
+    }
+
+    let people = anonymize_people(&config.people, &pseudonyms);
+    let config_path = output.join("config.yaml");
+    write(
+        &config_path,
+        &anonymize_config(config_content, &people, &pseudonyms, output)?,
+    )?;
+    written.push(config_path);
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+
+    use super::*;
+
+    #[test]
+    fn test_letters() {
+        let all: Vec<String> = [0, 1, 25, 26, 27, 701, 702].map(letters).to_vec();
+        assert_eq!(all, vec!["A", "B", "Z", "AA", "AB", "ZZ", "AAA"]);
+    }
+
+    #[test]
+    fn test_pseudonyms_are_deterministic_per_salt() {
+        let names: BTreeSet<PersonName> = ["JaneDoe", "JohnDoe", "Abu"].map(String::from).into();
+
+        let first = pseudonyms(&names, "salt");
+
+        assert_eq!(pseudonyms(&names, "salt"), first);
+        assert_ne!(pseudonyms(&names, "pepper"), first);
+        let mut values: Vec<&PersonName> = first.values().collect();
+        values.sort();
+        assert_eq!(values, vec!["PersonA", "PersonB", "PersonC"]);
+    }
+
+    #[test]
+    fn test_exported_log_paths() {
+        let people_dir = Path::new("/tmp/people");
+        let output = Path::new("/tmp/out");
+        let paths = |paths: &[&str]| -> Vec<PathBuf> { paths.iter().map(PathBuf::from).collect() };
+
+        assert_eq!(
+            exported_log_paths(
+                paths(&[
+                    "/tmp/people/2023/log.md",
+                    "/tmp/people/2024/log.md",
+                    "/tmp/elsewhere/other.md",
+                ]),
+                people_dir,
+                output
+            ),
+            Ok(vec![
+                (
+                    "/tmp/people/2023/log.md".into(),
+                    "/tmp/out/2023/log.md".into()
+                ),
+                (
+                    "/tmp/people/2024/log.md".into(),
+                    "/tmp/out/2024/log.md".into()
+                ),
+                (
+                    "/tmp/elsewhere/other.md".into(),
+                    "/tmp/out/log/other.md".into()
+                ),
+            ])
+        );
+        assert_eq!(
+            exported_log_paths(
+                paths(&["/tmp/people/log/log.md", "/tmp/elsewhere/log.md"]),
+                people_dir,
+                output
+            ),
+            Err("failed to export \"/tmp/people/log/log.md\" and \"/tmp/elsewhere/log.md\", both would be written to \"/tmp/out/log/log.md\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_anonymize() {
+        let content = indoc!(
+            "
+            # 2000-01-01
+
+            - #JaneDoe, #Jo :
+              - with #JohnDoe, see https://example.com/#JaneDoe
+            "
+        );
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                aliases: vec!["Jo".to_string()],
+                email: Some("jane@example.com".to_string()),
+                ..Default::default()
+            })
+            .build();
+        let options = ParseOptions::from_config(&config);
+        let log = parse_log_file_content(content);
+        let pseudonyms: BTreeMap<PersonName, PersonName> = [
+            ("JaneDoe", "PersonA"),
+            ("Jo", "PersonB"),
+            ("JohnDoe", "PersonC"),
+        ]
+        .map(|(name, pseudonym)| (name.to_string(), pseudonym.to_string()))
+        .into();
+
+        assert_eq!(
+            collect_names(&log, &config),
+            pseudonyms.keys().cloned().collect()
+        );
+        assert_eq!(
+            anonymize_content(content, &pseudonyms, &options),
+            indoc!(
+                "
+                # 2000-01-01
+
+                - #PersonA, #PersonB :
+                  - with #PersonC, see https://example.com/#JaneDoe
+                "
+            )
+        );
+        assert_eq!(
+            anonymize_people(&config.people, &pseudonyms),
+            vec![Person {
+                name: "PersonA".to_string(),
+                aliases: vec!["PersonB".to_string()],
+                ..Default::default()
+            }]
+        );
+
+        let config_content = indoc!(
+            "
+            people_dir: ~/people
+            ignore:
+              - JohnDoe
+              - Test*
+            smtp:
+              host: smtp.example.com
+            "
+        );
+        let people = anonymize_people(&config.people, &pseudonyms);
+        assert_eq!(
+            anonymize_config(config_content, &people, &pseudonyms, Path::new("/tmp/out")),
+            Ok(indoc!(
+                "
+                people_dir: /tmp/out
+                ignore:
+                - PersonC
+                - Test*
+                people:
+                - name: PersonA
+                  aliases:
+                  - PersonB
+                "
+            )
+            .to_string())
+        );
+    }
+}
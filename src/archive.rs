@@ -0,0 +1,152 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::config::{Config, Person};
+use crate::model::PersonName;
+use crate::use_cases::LastInteraction;
+
+/// People not seen for longer than `over` who are neither ignored nor already
+/// archived, least recently seen first
+pub fn inactive(
+    interactions: Vec<LastInteraction>,
+    config: &Config,
+    over: Duration,
+    today: NaiveDate,
+) -> Vec<LastInteraction> {
+    let mut inactive: Vec<LastInteraction> = interactions
+        .into_iter()
+        .filter(|interaction| interaction.last < today - over)
+        .filter(|interaction| !config.is_ignored(&interaction.person))
+        .filter(|interaction| !config.is_archived(&interaction.person))
+        .collect();
+    inactive.sort_by(|a, b| a.last.cmp(&b.last).then_with(|| a.person.cmp(&b.person)));
+    inactive
+}
+
+/// The `people` and `archived` sections once `names` are moved to `archived`.
+/// People only known from the logs are archived with just their name.
+pub fn archive(config: &Config, names: &[PersonName]) -> (Vec<Person>, Vec<Person>) {
+    let (moved, people): (Vec<Person>, Vec<Person>) = config
+        .people
+        .iter()
+        .cloned()
+        .partition(|person| names.contains(&person.name));
+
+    let mut archived = config.archived.clone();
+    for name in names {
+        if config.is_archived(name) {
+            continue;
+        }
+        match moved.iter().find(|person| &person.name == name) {
+            Some(person) => archived.push(person.clone()),
+            None => archived.push(Person {
+                name: name.clone(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    (people, archived)
+}
+
+/// The `people` and `archived` sections once `name` is moved back to `people`
+pub fn unarchive(config: &Config, name: &PersonName) -> Result<(Vec<Person>, Vec<Person>), String> {
+    let (moved, archived): (Vec<Person>, Vec<Person>) = config
+        .archived
+        .iter()
+        .cloned()
+        .partition(|person| &person.name == name);
+
+    if moved.is_empty() {
+        return Err(format!("{name} is not archived"));
+    }
+
+    let mut people = config.people.clone();
+    people.extend(moved);
+    Ok((people, archived))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+    use crate::use_cases;
+
+    use super::*;
+
+    fn person(name: &str) -> Person {
+        Person {
+            name: name.to_string(),
+            location: "Berlin".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_inactive() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2020-01-01
+
+            - #JaneDoe :
+            - #JohnDoe :
+            - #Abu :
+
+            # 2019-01-01
+
+            - #Zoe :
+
+            # 2024-01-01
+
+            - #JohnDoe :
+            "
+        ));
+        let config = Config::builder()
+            .ignore(vec!["Abu".to_string()])
+            .archived(person("Zoe"))
+            .build();
+
+        let inactive = inactive(
+            use_cases::get_last_interactions(&log),
+            &config,
+            Duration::days(730),
+            d("2024-06-01"),
+        );
+
+        let names: Vec<&str> = inactive.iter().map(|i| i.person.as_str()).collect();
+        assert_eq!(names, vec!["JaneDoe"]);
+    }
+
+    #[test]
+    fn test_archive_and_unarchive() {
+        let config = Config::builder()
+            .person(person("JaneDoe"))
+            .person(person("JohnDoe"))
+            .build();
+
+        let (people, archived) = archive(&config, &["JaneDoe".to_string(), "Abu".to_string()]);
+        assert_eq!(people, vec![person("JohnDoe")]);
+        assert_eq!(
+            archived,
+            vec![
+                person("JaneDoe"),
+                Person {
+                    name: "Abu".to_string(),
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let config = Config::builder()
+            .people(people)
+            .archived(person("JaneDoe"))
+            .build();
+        assert_eq!(
+            unarchive(&config, &"JaneDoe".to_string()),
+            Ok((vec![person("JohnDoe"), person("JaneDoe")], vec![]))
+        );
+        assert!(unarchive(&config, &"JohnDoe".to_string()).is_err());
+    }
+}
@@ -0,0 +1,367 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use chrono::NaiveDateTime;
+
+use crate::config::{self, Config};
+use crate::files::temporary_path;
+
+const ARCHIVE_PREFIX: &str = "people-";
+const ARCHIVE_EXTENSION: &str = ".tar.zst";
+
+/// First entry of every archive, so that restoring knows where `people_dir` goes
+const CONFIG_ENTRY: &str = "config.yaml";
+/// Everything in `people_dir` is archived under this directory
+const PEOPLE_ENTRY: &str = "people";
+/// The files in `include` are archived under this directory, named after their
+/// position in `include`
+const INCLUDE_ENTRY: &str = "include";
+
+/// `people-20000101-093000.tar.zst`, which sorts oldest first
+pub fn archive_name(now: NaiveDateTime) -> String {
+    format!(
+        "{ARCHIVE_PREFIX}{}{ARCHIVE_EXTENSION}",
+        now.format("%Y%m%d-%H%M%S")
+    )
+}
+
+fn is_archive_name(name: &str) -> bool {
+    name.starts_with(ARCHIVE_PREFIX) && name.ends_with(ARCHIVE_EXTENSION)
+}
+
+/// Files under `dir`, recursively and sorted, leaving the `skip` directories out
+fn find_files(dir: &Path, skip: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(reason) => return Err(format!("failed to read {dir:?}, reason: {reason}")),
+    };
+
+    let mut files: Vec<PathBuf> = vec![];
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if skip.contains(&path) {
+            continue;
+        }
+        if path.is_dir() {
+            files.extend(find_files(&path, skip)?);
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Archive the config file, the files it includes and `people_dir` into `dir`,
+/// leaving previous backups out. Returns the path of the new archive.
+pub fn create_backup(
+    config: &Config,
+    config_path: &Path,
+    dir: &Path,
+    now: NaiveDateTime,
+) -> Result<PathBuf, String> {
+    if let Err(reason) = fs::create_dir_all(dir) {
+        return Err(format!("failed to create {dir:?}, reason: {reason}"));
+    }
+
+    let path = dir.join(archive_name(now));
+    if path.exists() {
+        return Err(format!("backup {path:?} already exists"));
+    }
+
+    let temporary = temporary_path(&path);
+    if let Err(reason) = write_archive(config, config_path, dir, &temporary) {
+        let _ = fs::remove_file(&temporary);
+        return Err(reason);
+    }
+
+    match fs::rename(&temporary, &path) {
+        Ok(()) => Ok(path),
+        Err(reason) => {
+            let _ = fs::remove_file(&temporary);
+            Err(format!("failed to write {path:?}, reason: {reason}"))
+        }
+    }
+}
+
+fn write_archive(
+    config: &Config,
+    config_path: &Path,
+    backup_dir: &Path,
+    path: &Path,
+) -> Result<(), String> {
+    let file = match File::create(path) {
+        Ok(file) => file,
+        Err(reason) => return Err(format!("failed to create {path:?}, reason: {reason}")),
+    };
+    let encoder = match zstd::Encoder::new(file, 0) {
+        Ok(encoder) => encoder.auto_finish(),
+        Err(reason) => return Err(format!("failed to compress {path:?}, reason: {reason}")),
+    };
+    let mut archive = tar::Builder::new(encoder);
+
+    let append = |archive: &mut tar::Builder<_>, source: &Path, name: PathBuf| match archive
+        .append_path_with_name(source, &name)
+    {
+        Ok(()) => Ok(()),
+        Err(reason) => Err(format!("failed to archive {source:?}, reason: {reason}")),
+    };
+
+    append(&mut archive, config_path, PathBuf::from(CONFIG_ENTRY))?;
+
+    let config_content = match fs::read_to_string(config_path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {config_path:?}, reason: {reason}")),
+    };
+    let includes = config::include_paths(&config_content, config_path)?;
+    for (index, include) in includes.iter().enumerate() {
+        // archived with the rest of `people_dir`
+        if include.starts_with(&config.people_dir) {
+            continue;
+        }
+        let name = Path::new(INCLUDE_ENTRY).join(index.to_string());
+        append(&mut archive, include, name)?;
+    }
+
+    // neither the archives in the config nor the ones next to this one
+    let skip = [config.get_backup_dir(), backup_dir.to_path_buf()];
+    for file in find_files(&config.people_dir, &skip)? {
+        let relative = file
+            .strip_prefix(&config.people_dir)
+            .expect("files are found under people_dir");
+        append(&mut archive, &file, Path::new(PEOPLE_ENTRY).join(relative))?;
+    }
+
+    match archive.into_inner() {
+        Ok(_) => Ok(()),
+        Err(reason) => Err(format!("failed to write {path:?}, reason: {reason}")),
+    }
+}
+
+/// The oldest archives beyond the `keep` most recent ones
+pub fn backups_to_prune(names: &[String], keep: usize) -> Vec<String> {
+    let mut archives: Vec<&String> = names.iter().filter(|name| is_archive_name(name)).collect();
+    archives.sort();
+
+    let excess = archives.len().saturating_sub(keep);
+    archives.into_iter().take(excess).cloned().collect()
+}
+
+/// Delete the oldest archives in `dir` beyond the `keep` most recent ones.
+/// Returns the deleted paths.
+pub fn prune_backups(dir: &Path, keep: usize) -> Result<Vec<PathBuf>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(reason) => return Err(format!("failed to read {dir:?}, reason: {reason}")),
+    };
+    let names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+
+    let mut deleted: Vec<PathBuf> = vec![];
+    for name in backups_to_prune(&names, keep) {
+        let path = dir.join(name);
+        if let Err(reason) = fs::remove_file(&path) {
+            return Err(format!("failed to delete {path:?}, reason: {reason}"));
+        }
+        deleted.push(path);
+    }
+
+    Ok(deleted)
+}
+
+/// Where an archived file goes once restored: under `people_dir`, or where the
+/// config includes it from. Refuses paths that would escape `people_dir`.
+fn restored_path(
+    entry: &Path,
+    people_dir: &Path,
+    includes: &[PathBuf],
+) -> Result<Option<PathBuf>, String> {
+    if let Ok(index) = entry.strip_prefix(INCLUDE_ENTRY) {
+        let include = index
+            .to_str()
+            .and_then(|index| index.parse::<usize>().ok())
+            .and_then(|index| includes.get(index));
+        return match include {
+            Some(path) => Ok(Some(path.clone())),
+            None => Err(format!("unexpected path in backup: {entry:?}")),
+        };
+    }
+
+    let relative = match entry.strip_prefix(PEOPLE_ENTRY) {
+        Ok(relative) => relative,
+        Err(_) => return Ok(None),
+    };
+
+    let is_safe = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    match is_safe {
+        true => Ok(Some(people_dir.join(relative))),
+        false => Err(format!("unexpected path in backup: {entry:?}")),
+    }
+}
+
+fn is_empty_dir(dir: &Path) -> bool {
+    match fs::read_dir(dir) {
+        Ok(mut entries) => entries.next().is_none(),
+        Err(_) => true,
+    }
+}
+
+/// Restore `people_dir`, the config file and the files it includes from an
+/// archive made by `create_backup`. Without `force`, refuses to write into a
+/// non-empty `people_dir` or over an existing config or included file. Returns
+/// `people_dir`.
+pub fn restore(archive_path: &Path, force: bool) -> Result<PathBuf, String> {
+    let read_error =
+        |reason: std::io::Error| format!("failed to read {archive_path:?}, reason: {reason}");
+
+    let file = match File::open(archive_path) {
+        Ok(file) => file,
+        Err(reason) => return Err(format!("failed to open {archive_path:?}, reason: {reason}")),
+    };
+    let decoder = match zstd::Decoder::new(file) {
+        Ok(decoder) => decoder,
+        Err(reason) => return Err(read_error(reason)),
+    };
+    let mut archive = tar::Archive::new(decoder);
+    let mut entries = archive.entries().map_err(read_error)?;
+
+    // the config comes first, so everything is checked before writing anything
+    let not_a_backup = format!("{archive_path:?} is not a people backup");
+    let mut config_entry = match entries.next() {
+        Some(entry) => entry.map_err(read_error)?,
+        None => return Err(not_a_backup),
+    };
+    if config_entry.path().map_err(read_error)?.as_os_str() != CONFIG_ENTRY {
+        return Err(not_a_backup);
+    }
+    let mut config_content = String::new();
+    config_entry
+        .read_to_string(&mut config_content)
+        .map_err(read_error)?;
+
+    let config = match Config::from_yaml_str(&config_content) {
+        Ok(config) => config,
+        Err(reason) => return Err(format!("failed to read config in backup, {reason}")),
+    };
+
+    let config_path = match config::get_config_path() {
+        Ok(path) => path,
+        Err(_) => return Err("home directory not found".to_string()),
+    };
+    let includes = match config::include_paths(&config_content, &config_path) {
+        Ok(includes) => includes,
+        Err(reason) => return Err(format!("failed to read config in backup, {reason}")),
+    };
+
+    if !force {
+        if !is_empty_dir(&config.people_dir) {
+            return Err(format!(
+                "{:?} is not empty, use --force to restore over it",
+                config.people_dir
+            ));
+        }
+        if config_path.exists() {
+            return Err(format!(
+                "{config_path:?} already exists, use --force to restore over it"
+            ));
+        }
+        if let Some(path) = includes
+            .iter()
+            .find(|path| !path.starts_with(&config.people_dir) && path.exists())
+        {
+            return Err(format!(
+                "{path:?} already exists, use --force to restore over it"
+            ));
+        }
+    }
+
+    for entry in entries {
+        let mut entry = entry.map_err(read_error)?;
+        let entry_path = entry.path().map_err(read_error)?.to_path_buf();
+        let Some(path) = restored_path(&entry_path, &config.people_dir, &includes)? else {
+            continue;
+        };
+
+        if let Some(dir) = path.parent() {
+            if let Err(reason) = fs::create_dir_all(dir) {
+                return Err(format!("failed to create {dir:?}, reason: {reason}"));
+            }
+        }
+        if let Err(reason) = entry.unpack(&path) {
+            return Err(format!("failed to restore {path:?}, reason: {reason}"));
+        }
+    }
+
+    if let Some(dir) = config_path.parent() {
+        if let Err(reason) = fs::create_dir_all(dir) {
+            return Err(format!("failed to create {dir:?}, reason: {reason}"));
+        }
+    }
+    if let Err(reason) = fs::write(&config_path, config_content) {
+        return Err(format!("failed to write {config_path:?}, reason: {reason}"));
+    }
+
+    Ok(config.people_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_archive_name() {
+        let now = d("2000-01-02").and_hms_opt(9, 30, 5).unwrap();
+
+        assert_eq!(archive_name(now), "people-20000102-093005.tar.zst");
+    }
+
+    #[test]
+    fn test_backups_to_prune() {
+        let names: Vec<String> = [
+            "people-20000103-000000.tar.zst",
+            "notes.txt",
+            "people-20000101-000000.tar.zst",
+            "people-20000102-000000.tar.zst",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        assert_eq!(
+            backups_to_prune(&names, 1),
+            vec![
+                "people-20000101-000000.tar.zst".to_string(),
+                "people-20000102-000000.tar.zst".to_string(),
+            ]
+        );
+        assert_eq!(backups_to_prune(&names, 10), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_restored_path() {
+        let people_dir = Path::new("/tmp/people");
+        let includes = vec![PathBuf::from("/home/me/.config/people/family.yaml")];
+        let restored = |entry: &str| restored_path(Path::new(entry), people_dir, &includes);
+
+        assert_eq!(
+            restored("people/log/2000.md"),
+            Ok(Some(PathBuf::from("/tmp/people/log/2000.md")))
+        );
+        assert_eq!(
+            restored("include/0"),
+            Ok(Some(PathBuf::from("/home/me/.config/people/family.yaml")))
+        );
+        assert_eq!(restored("other.txt"), Ok(None));
+        assert!(restored("people/../../etc/passwd").is_err());
+        assert!(restored("include/1").is_err());
+    }
+}
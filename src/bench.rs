@@ -0,0 +1,127 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use chrono::NaiveDate;
+
+use crate::config::{Config, LogSyntax};
+use crate::log::{self, Day, Log, ParseOptions};
+use crate::log_format;
+use crate::use_cases;
+
+/// Log content with three entries per day, each mentioning two of `people`
+pub fn synthetic_log(people: usize, days: usize) -> String {
+    let start = NaiveDate::from_ymd_opt(2000, 1, 1).expect("2000-01-01 is a valid date");
+    let mut content = String::new();
+    for day in 0..days {
+        let date = start + chrono::Duration::days(day as i64);
+        content.push_str(&format!("# {date}\n\n"));
+        for entry in 0..3 {
+            let main = (day * 3 + entry) % people;
+            let other = (main + 7) % people;
+            content.push_str(&format!(
+                "- #Person{} :\n  - coffee with #Person{}\n    - talked about stuff\n",
+                synthetic_name(main),
+                synthetic_name(other)
+            ));
+        }
+        content.push('\n');
+    }
+    content
+}
+
+/// Letters only, as hashtags only take letters
+fn synthetic_name(index: usize) -> String {
+    let mut name = String::new();
+    let mut rest = index;
+    loop {
+        name.push((b'a' + (rest % 26) as u8) as char);
+        rest /= 26;
+        if rest == 0 {
+            return name;
+        }
+    }
+}
+
+/// Fastest time of each step of reading the logs, out of a few runs
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timings {
+    pub tokenize: Duration,
+    pub parse: Duration,
+    pub split: Duration,
+    pub summary: Duration,
+    pub days: usize,
+    pub entries: usize,
+}
+
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} days, {} entries", self.days, self.entries)?;
+        for (step, elapsed) in [
+            ("tokenize", self.tokenize),
+            ("parse", self.parse),
+            ("split per person", self.split),
+            ("summary", self.summary),
+        ] {
+            writeln!(f, "{step:<18}{:>10.2} ms", elapsed.as_secs_f64() * 1000.0)?;
+        }
+        Ok(())
+    }
+}
+
+fn fastest<T>(runs: usize, mut step: impl FnMut() -> T) -> Duration {
+    (0..runs.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            let _ = step();
+            start.elapsed()
+        })
+        .min()
+        .expect("at least one run")
+}
+
+fn parse(files: &[(String, LogSyntax)], options: &ParseOptions) -> Log {
+    let days: Vec<Day> = files
+        .iter()
+        .flat_map(|(content, syntax)| log_format::for_syntax(*syntax).parse(content, options))
+        .collect();
+    Log { days }
+}
+
+/// Time each step from the content of the log files to the summary
+pub fn measure(files: &[(String, LogSyntax)], config: &Config, runs: usize) -> Timings {
+    let options = ParseOptions::from_config(config);
+    let log = parse(files, &options);
+
+    Timings {
+        tokenize: fastest(runs, || {
+            files
+                .iter()
+                .map(|(content, _)| log::tokenize(content))
+                .sum::<usize>()
+        }),
+        parse: fastest(runs, || parse(files, &options)),
+        split: fastest(runs, || {
+            use_cases::split_log_per_person(log.clone(), config)
+        }),
+        summary: fastest(runs, || use_cases::get_last_interactions(&log)),
+        days: log.days.len(),
+        entries: log.days.iter().map(|day| day.entries.len()).sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_measure_synthetic_log() {
+        let content = synthetic_log(30, 10);
+        assert_eq!(log::tokenize(&content), 100);
+
+        let files = vec![(content, LogSyntax::Markdown)];
+        let timings = measure(&files, &Config::builder().build(), 1);
+        assert_eq!((timings.days, timings.entries), (10, 30));
+    }
+}
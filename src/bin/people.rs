@@ -0,0 +1,3268 @@
+use std::collections::BTreeSet;
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+use std::{env, fs, process, thread};
+
+use chrono::{Duration, NaiveDate};
+use clap::{CommandFactory, Parser, Subcommand};
+use dialoguer::{FuzzySelect, Select};
+use serde::Serialize;
+use tabular::{Row, Table};
+use tracing::info;
+
+use people::aggregate::{self, ReportFormat, Window};
+use people::anonymize;
+use people::archive;
+use people::backup;
+use people::bench;
+use people::caldav;
+use people::chat_import::{self, ChatFormat};
+use people::co_mentions::{self, CoMentions};
+use people::completions::{self, CompletionShell};
+use people::config::{self, Config, LogSyntax, ReminderInterval};
+use people::contact;
+use people::contacts::{self, CardDavProvider, ContactsProvider};
+use people::csv_import::{self, ColumnMap};
+use people::dates::{DateDisplay, DateStyle};
+use people::dav;
+use people::dedupe;
+use people::digest::{self, DigestFormat, Period};
+use people::duplicates;
+use people::edit::{self, EditTarget};
+use people::editor;
+use people::ext;
+use people::facts::{self, FieldNote};
+use people::files;
+use people::followups;
+use people::formatter;
+use people::git;
+use people::heatmap::{self, HeatmapFormat};
+use people::hooks;
+use people::ics;
+use people::links;
+use people::listing::{Listing, ListingArgs};
+use people::locations::{self, CurrentLocation};
+use people::log;
+use people::log::{Entry, Log, LogInput, ParseOptions};
+use people::logging::{self, LoggingArgs};
+use people::mail;
+use people::metrics;
+use people::migrations;
+use people::model::{InteractionKind, Location, PersonName, Theme};
+use people::ndjson;
+use people::notifications;
+use people::notifiers::{self, Channel, QuietHours};
+use people::operations::{self, Transaction};
+use people::people_index;
+use people::pick::{self, PickAction};
+use people::plan::{self, PlanFormat};
+use people::profile::PersonProfile;
+use people::prompt;
+use people::rename;
+use people::render::{self, ReminderContext};
+use people::resolve;
+use people::review;
+use people::rollup;
+use people::roster::{self, PersonChanges};
+use people::server;
+use people::show::{self, ShowFormat};
+use people::site::{self, SiteTemplates};
+use people::state;
+use people::suggest_tags::{self, Spellings};
+use people::tiers::{self, TierChange};
+use people::trip;
+use people::use_cases::{self, AdherenceReport, LogWritten};
+use people::vcard;
+use people::verify_names;
+
+#[derive(Parser)]
+#[command(name = "people", about = "Keep track of your interactions with people")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Use the people directory and people of this profile in the config
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+    /// Run the command once per profile, with a section per profile
+    #[arg(long, global = true, conflicts_with = "profile")]
+    all_profiles: bool,
+    /// Pretend today is this date, e.g. 2024-05-01
+    #[arg(long, global = true, value_name = "DATE")]
+    today: Option<NaiveDate>,
+    /// Read this log file, or stdin if `-`, instead of the logs in the people directory
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        conflicts_with = "all_profiles"
+    )]
+    input: Option<PathBuf>,
+    /// Fail instead of changing any file in the people directory or the config
+    #[arg(long, global = true)]
+    read_only: bool,
+    #[command(flatten)]
+    logging: LoggingArgs,
+}
+
+#[derive(Clone, Subcommand)]
+enum Command {
+    /// Show how late you reach out to people once their reminder is due
+    Adherence,
+    /// Show how often you met each `frequency` goal over the last year
+    Goals,
+    /// List who is overdue a reach out, optionally as desktop notifications
+    Remind {
+        /// Send a desktop notification per overdue person
+        #[arg(long)]
+        notify: bool,
+        /// Keep running and notify once per day (implies --notify)
+        #[arg(long)]
+        daemon: bool,
+        /// Do not notify again about a person for this many days
+        #[arg(long, default_value_t = 7)]
+        snooze_days: i64,
+        /// Stop notifying about a person for --snooze-days
+        #[arg(long, value_name = "PERSON")]
+        snooze: Option<PersonName>,
+        #[command(subcommand)]
+        action: Option<RemindAction>,
+    },
+    /// Print everything logged with a person, like their per-person log
+    Show {
+        person: PersonName,
+        /// Only the last N entries
+        #[arg(long, value_name = "N")]
+        last: Option<usize>,
+        /// Only the entries on or after this date, e.g. `2024-01-31`
+        #[arg(long)]
+        since: Option<NaiveDate>,
+        /// Only the entries of this kind, e.g. `call`
+        #[arg(long)]
+        kind: Option<InteractionKind>,
+        /// `md` or `json`
+        #[arg(long, default_value = "md")]
+        format: ShowFormat,
+        /// Open the most recent of the entries in $EDITOR instead of printing them
+        #[arg(long)]
+        edit: bool,
+        /// How to show the dates of the days in markdown: `absolute`, as
+        /// `date_format` in the config, or `relative`, e.g. `3 days ago`
+        #[arg(long, value_name = "STYLE", default_value = "absolute")]
+        dates: DateStyle,
+    },
+    /// Everything known about a person: config, stats, who they are seen with and recent entries
+    Profile {
+        person: PersonName,
+        /// `md` or `json`
+        #[arg(long, default_value = "md")]
+        format: ShowFormat,
+    },
+    /// Every entry that mentions both people
+    Mutual { a: PersonName, b: PersonName },
+    /// Pairs of people with themes or a location in common who are never mentioned together
+    Introductions {
+        /// `table` or `json`
+        #[arg(long, default_value = "table")]
+        format: ReportFormat,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Open the log in $EDITOR at a day (today by default) or at the latest entry with a person
+    Edit {
+        /// A date like `2024-05-01`, or a person
+        target: Option<String>,
+    },
+    /// Choose a person with a fuzzy finder, then what to do with them
+    Pick {
+        /// `show`, `edit` or `contacted`, instead of asking
+        #[arg(long)]
+        action: Option<PickAction>,
+        /// Print the people to choose from, one per line, e.g. for `fzf`
+        #[arg(long)]
+        print: bool,
+    },
+    /// Suggest whom to write about next, e.g. people with sparse notes
+    Prompt {
+        /// How many people to suggest
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// Make the suggestions repeatable
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// When each entry with a person was committed to git, against its stated date
+    History {
+        person: PersonName,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Every gift idea noted in entries as `gift:`, `gift-idea:` or `gift-ideas:`, per person
+    GiftIdeas {
+        /// Only the gift ideas for this person
+        person: Option<PersonName>,
+        /// Only people whose birthday is within this long, soonest first, e.g. `30d`
+        #[arg(long, value_parser = use_cases::parse_duration, conflicts_with = "person")]
+        upcoming: Option<Duration>,
+        /// `table` or `json`
+        #[arg(long, default_value = "table")]
+        format: ReportFormat,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Upcoming birthdays and anniversaries of the day you first met someone
+    Anniversaries {
+        /// Only the anniversaries of the day you first met
+        #[arg(long)]
+        met: bool,
+        /// How far ahead to look, e.g. `30d` or `3 months`
+        #[arg(long, default_value = "30d", value_parser = use_cases::parse_duration)]
+        upcoming: Duration,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Every value noted for a key in entries, e.g. `people field book`
+    Field {
+        key: String,
+        /// Only the values noted in entries with this person
+        person: Option<PersonName>,
+        /// `table` or `json`
+        #[arg(long, default_value = "table")]
+        format: ReportFormat,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Questions noted as `- ? ...` in entries and not answered with `- ✓ ...` or `- done: ...` since
+    Followups {
+        /// Only the follow-ups with this person
+        person: Option<PersonName>,
+        /// Include the answered ones too
+        #[arg(long)]
+        all: bool,
+        /// `table` or `json`
+        #[arg(long, default_value = "table")]
+        format: ReportFormat,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// The latest value of each `key: value` line in the entries with a person
+    Facts {
+        person: PersonName,
+        /// `table` or `json`
+        #[arg(long, default_value = "table")]
+        format: ReportFormat,
+    },
+    /// List who to reach out to, e.g. as a checklist for a weekly note
+    Plan {
+        /// `table` or `markdown`
+        #[arg(long, default_value = "table")]
+        format: PlanFormat,
+        #[command(subcommand)]
+        action: Option<PlanAction>,
+    },
+    /// Group people into fresh/warm/cooling/cold and compare with a month ago
+    Tiers,
+    /// Compose an email with who is overdue, upcoming birthdays and recent interactions
+    Digest {
+        /// `week` or `month`
+        #[arg(long, default_value = "week")]
+        period: Period,
+        /// `markdown` or `html`
+        #[arg(long, default_value = "markdown")]
+        format: DigestFormat,
+        /// Send it through the SMTP server in the config instead of printing it
+        #[arg(long)]
+        send: bool,
+    },
+    /// Calendar of interactions over the last year, with everyone or one person
+    Heatmap {
+        person: Option<PersonName>,
+        /// `text` or `svg`
+        #[arg(long, default_value = "text")]
+        format: HeatmapFormat,
+    },
+    /// People not seen for a long time
+    Inactive {
+        /// How long without seeing them, e.g. `2y` or `18 months`
+        #[arg(long, default_value = "2y", value_parser = use_cases::parse_duration)]
+        over: Duration,
+        /// Move them to `archived` in the config, so they are no longer reminded of
+        #[arg(long)]
+        archive: bool,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Move an archived person back to `people` in the config
+    Unarchive { person: PersonName },
+    /// Add, change or remove people in the config, keeping its comments
+    Person {
+        #[command(subcommand)]
+        action: PersonAction,
+    },
+    /// Who you interacted with most lately
+    Top {
+        /// How far back to look, e.g. `90d`, `2w` or `6 months`
+        #[arg(long, default_value = "90d", value_parser = use_cases::parse_duration)]
+        last: Duration,
+        /// `table` or `json`
+        #[arg(long, default_value = "table")]
+        format: ReportFormat,
+        // 10 people unless `--limit` is given
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Who you see more or less than before: the last 90 days against the 90 before
+    Trends {
+        /// Length of the periods compared, e.g. `3m` or `30d`
+        #[arg(long, default_value = "90d", value_parser = use_cases::parse_duration)]
+        period: Duration,
+        /// `table` or `json`
+        #[arg(long, default_value = "table")]
+        format: ReportFormat,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Who you saw this week or month, and who you usually see but didn't
+    Review {
+        /// Review the last 7 days (default)
+        #[arg(long, conflicts_with = "month")]
+        week: bool,
+        /// Review the last 30 days
+        #[arg(long)]
+        month: bool,
+    },
+    /// Find tags that likely refer to the same person and suggest aliases
+    Dedupe {
+        /// Add the suggested aliases to the config
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Find entries with the same date and content in the logs, e.g. copied between devices
+    DedupeEntries {
+        /// Remove all copies but the first, formatting the files they were in
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Rewrite `#OldTag` as `#NewTag` across the logs and regenerate the per-person logs
+    Rename {
+        old: PersonName,
+        new: PersonName,
+        /// Show the changes without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Normalize the log files: indentation, bullets, blank lines and the order of days
+    Fmt {
+        /// List the files that are not formatted and exit with an error, without writing them
+        #[arg(long)]
+        check: bool,
+    },
+    /// Merge two versions of a log file, keeping the days and entries of both, e.g. as a git merge driver
+    MergeFiles {
+        ours: PathBuf,
+        theirs: PathBuf,
+        /// Version both started from, so that entries removed in ours are not added back
+        #[arg(long)]
+        base: Option<PathBuf>,
+        /// File to write the merged log to, can be one of the merged files
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Find person references that only differ by Unicode normalization or invisible characters
+    VerifyNames {
+        /// Normalize the references in place
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Find known people mentioned by name but not tagged, e.g. `John Doe` for `#JohnDoe`
+    SuggestTags {
+        /// Replace the mentions with the tags in place
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Revert the files changed by the last rename, fmt, dedupe-entries, fix or per-person run
+    Undo {
+        /// Show what the last operation changed without reverting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// How to reach someone: email, phone, Telegram
+    Contact {
+        person: PersonName,
+        /// Open the preferred contact method, e.g. the mail client for an email
+        #[arg(long)]
+        open: bool,
+    },
+    /// Where someone lives, and where they lived before
+    Where { person: PersonName },
+    /// Who lives in a place, e.g. `people near berlin`
+    Near { location: String },
+    /// Who to ping when visiting a place, most overdue a reach out first
+    Trip {
+        location: String,
+        /// First day of the trip (default: today)
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// Last day of the trip (default: the first one)
+        #[arg(long)]
+        to: Option<NaiveDate>,
+    },
+    /// List the URLs and files mentioned in entries, per person and most recent first
+    Links {
+        /// Only show the links shared with this person
+        person: Option<PersonName>,
+        /// Same as the positional person, kept for compatibility
+        #[arg(
+            long = "person",
+            value_name = "PERSON",
+            conflicts_with = "person",
+            hide = true
+        )]
+        person_flag: Option<PersonName>,
+        /// Only check that the attached files exist under the people directory
+        #[arg(long)]
+        check: bool,
+    },
+    /// Write a week's entries, grouped by person, to `<people_dir>/rollups/<week>.md`
+    Rollup {
+        /// e.g. `2024-W20`, last week by default
+        #[arg(long, value_parser = rollup::parse_week, value_name = "WEEK")]
+        week: Option<NaiveDate>,
+    },
+    /// Print Prometheus metrics, e.g. days since the last contact with each person
+    Metrics {
+        /// Write them to this file instead, e.g. for node_exporter's textfile collector
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Serve a read-only JSON API over the logs
+    Serve {
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Address to listen on, use 0.0.0.0 to reach it from other devices
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// Reload the logs when they change
+        #[arg(long)]
+        watch: bool,
+    },
+    /// Generate a static HTML site with an index and a page per person
+    Publish {
+        /// Directory to write the pages to
+        #[arg(long, default_value = "site")]
+        output: PathBuf,
+    },
+    /// Print a shell completion script, or data for shell completions and pickers
+    Completions {
+        /// `bash`, `zsh` or `fish`
+        shell: Option<CompletionShell>,
+        /// List known people, refreshing the cached list if the config or logs changed
+        #[arg(long, conflicts_with = "shell")]
+        people: bool,
+    },
+    /// Post who is overdue a reach out to a chat, e.g. a Telegram bot
+    Notify {
+        /// `telegram` or `slack`
+        #[arg(long)]
+        channel: Channel,
+        /// Don't post between these times, e.g. `22:00-08:00`
+        #[arg(long)]
+        quiet_hours: Option<QuietHours>,
+    },
+    /// Find who you emailed with in an mbox file or a Maildir, kept apart from the logs
+    ScanMail {
+        path: PathBuf,
+        /// Only scan emails this recent, e.g. `3m`
+        #[arg(long, value_parser = use_cases::parse_duration)]
+        since: Option<Duration>,
+    },
+    /// Run the commands in the `hooks` config on changes since the last run
+    Hooks {
+        #[command(subcommand)]
+        action: HooksAction,
+    },
+    /// Sync people metadata with other tools
+    Sync {
+        #[command(subcommand)]
+        target: SyncTarget,
+    },
+    /// Export data to other tools
+    #[command(args_conflicts_with_subcommands = true)]
+    Export {
+        #[command(subcommand)]
+        target: Option<ExportTarget>,
+        /// Copy the logs and the config to --output with every name replaced by
+        /// a pseudonym, e.g. to share a bug reproduction
+        #[arg(long, requires = "output")]
+        anonymize: bool,
+        /// Directory to write the anonymized copy to
+        #[arg(long, requires = "anonymize")]
+        output: Option<PathBuf>,
+        /// Same salt, same pseudonyms
+        #[arg(long, default_value = "people")]
+        salt: String,
+    },
+    /// Import data from other tools
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+    /// Archive the people directory, the config and its includes, deleting the oldest archives beyond `backup.keep`
+    Backup {
+        /// Directory to write the archive to (default: `backup.dir` in the config)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+    /// Restore the people directory, the config and its includes from an archive made by `people backup`
+    Restore {
+        archive: PathBuf,
+        /// Restore over a non-empty people directory and an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Time each step of reading the logs, to spot slow parsing on your machine
+    Bench {
+        /// Generate logs instead of reading yours
+        #[arg(long)]
+        synthetic: bool,
+        /// Days of the generated logs
+        #[arg(long, default_value_t = 10_000, requires = "synthetic")]
+        days: usize,
+        /// People in the generated logs
+        #[arg(long, default_value_t = 1_000, requires = "synthetic")]
+        people: usize,
+        /// Times to run each step, the fastest is shown
+        #[arg(long, default_value_t = 5)]
+        runs: usize,
+    },
+    /// Check or upgrade the config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Run the `people-<NAME>` executable on PATH, passing it the parsed log as JSON on stdin
+    #[command(subcommand_value_name = "NAME")]
+    Ext {
+        #[command(subcommand)]
+        extension: Extension,
+    },
+}
+
+/// The name of the extension and its arguments, which are passed as they are,
+/// even the ones `people` has flags for like `-v`
+#[derive(Clone, Subcommand)]
+enum Extension {
+    #[command(external_subcommand)]
+    Run(Vec<String>),
+}
+
+#[derive(Clone, Subcommand)]
+enum PlanAction {
+    /// Log a reach out to everyone checked off in a plan exported as markdown
+    Import {
+        path: PathBuf,
+        /// Day of the reach outs, e.g. `2024-01-31` (default: today)
+        #[arg(long)]
+        date: Option<NaiveDate>,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum RemindAction {
+    /// Count a person as reached out to today, until it is logged
+    Done { person: PersonName },
+    /// Stop reminding about a person for a while, e.g. `2w` or `10 days`
+    Snooze {
+        person: PersonName,
+        #[arg(default_value = "1 week")]
+        duration: ReminderInterval,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum PersonAction {
+    /// Add a person to `people`
+    Add {
+        name: PersonName,
+        #[arg(long)]
+        location: Option<Location>,
+        /// e.g. `3 weeks`
+        #[arg(long)]
+        remind_after: Option<ReminderInterval>,
+        /// Theme to add the person to, e.g. `family`, can be repeated
+        #[arg(long)]
+        group: Vec<Theme>,
+    },
+    /// Change a person in `people` or `archived`
+    Edit {
+        name: PersonName,
+        #[arg(long)]
+        location: Option<Location>,
+        /// e.g. `3 weeks`
+        #[arg(long)]
+        remind_after: Option<ReminderInterval>,
+        /// Theme to add the person to, e.g. `family`, can be repeated
+        #[arg(long)]
+        group: Vec<Theme>,
+        /// Theme to take the person out of, can be repeated
+        #[arg(long)]
+        remove_group: Vec<Theme>,
+    },
+    /// Remove a person from the config, keeping their logs
+    Remove { name: PersonName },
+}
+
+#[derive(Clone, Subcommand)]
+enum ConfigAction {
+    /// List every problem in the config file, e.g. unknown keys or invalid durations
+    Validate {
+        /// Config file to check instead of the one in $HOME
+        path: Option<PathBuf>,
+    },
+    /// Upgrade the config file to the latest format, keeping a copy of the original
+    Migrate {
+        /// Config file to upgrade instead of the one in $HOME
+        path: Option<PathBuf>,
+        /// Print the changes without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum HooksAction {
+    /// Run `on_overdue` and `on_contact_logged` for each change since the last run
+    Run {
+        /// List the hooks that would run, without running them or saving the state
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum SyncTarget {
+    /// Pull names and birthdays from the CardDAV address book in the config
+    Contacts {
+        /// Write additions and updates to the config
+        #[arg(long)]
+        apply: bool,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum ExportTarget {
+    /// Push each entry as a journal to the CalDAV calendar in the config
+    Caldav {
+        /// Print the journals instead of uploading them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print every day of the logs, with its entries, as a line of JSON
+    Json {
+        /// Write to this file instead
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum ImportSource {
+    /// Add or update people in the config from a vCard file
+    Vcard {
+        path: PathBuf,
+        /// Show the changes without writing the config
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Add interactions exported from other tools to the logs
+    Csv {
+        path: PathBuf,
+        /// Columns (starting at 1) of each field, e.g. `date=1,person=2,note=3`
+        #[arg(long)]
+        map: ColumnMap,
+    },
+    /// Add an entry per day of a chat exported from WhatsApp or Signal to the logs
+    Chat {
+        path: PathBuf,
+        /// Who the chat is with
+        #[arg(long)]
+        person: PersonName,
+        /// `whatsapp`, or `signal` as exported by signal-export
+        #[arg(long, default_value = "whatsapp")]
+        format: ChatFormat,
+        /// Add every message instead of how many each sender wrote
+        #[arg(long)]
+        full: bool,
+    },
+    /// Report calendar events with known people that have no entry on their date
+    Ics {
+        path: PathBuf,
+        /// Skip events before this date
+        #[arg(long)]
+        since: Option<NaiveDate>,
+        /// Add an entry for each of them to the logs, to fill in later
+        #[arg(long)]
+        stubs: bool,
+    },
+    /// Write the days exported by `people export json` as markdown log files
+    Json {
+        path: PathBuf,
+        /// Directory to write the log files to
+        #[arg(long)]
+        output: PathBuf,
+    },
+}
+
+fn format_lateness(lateness: Option<f64>) -> String {
+    match lateness {
+        Some(days) => format!("{days:.1} days"),
+        None => "".to_string(),
+    }
+}
+
+fn format_adherence(report: AdherenceReport) -> String {
+    if report.people.is_empty() {
+        return "No reminder history yet.".to_string();
+    }
+
+    let mut table = Table::new("{:<}  {:>}  {:>}  {:>}");
+    table.add_row(
+        Row::new()
+            .with_cell("PERSON")
+            .with_cell("TIMES OVERDUE")
+            .with_cell("AVG LATENESS")
+            .with_cell("OVERDUE NOW"),
+    );
+
+    for adherence in report.people {
+        let overdue_now = match adherence.overdue_now {
+            Some(days) => format!("{days} days"),
+            None => "".to_string(),
+        };
+
+        table.add_row(
+            Row::new()
+                .with_cell(adherence.person)
+                .with_cell(adherence.times_overdue)
+                .with_cell(format_lateness(adherence.average_lateness))
+                .with_cell(overdue_now),
+        );
+    }
+
+    let overall = match report.average_lateness {
+        Some(_) => format_lateness(report.average_lateness),
+        None => "n/a".to_string(),
+    };
+
+    format!("{table}\nAverage lateness overall: {overall}")
+}
+
+fn adherence(config: &Config, today: NaiveDate) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+
+    let path = config.get_state_path();
+    let mut state = state::load_state(&path)?;
+    use_cases::update_reminder_history(&mut state, &log, config, today);
+    state::save_state(config, &state)?;
+
+    let report = use_cases::compute_adherence(&state, today);
+    println!("{}", format_adherence(report));
+
+    Ok(())
+}
+
+fn goals(config: &Config, today: NaiveDate) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let goals = use_cases::compute_goals(&log, config, today);
+    if goals.is_empty() {
+        println!("No goals set, add a frequency to people or themes in the config.");
+        return Ok(());
+    }
+
+    let mut table = Table::new("{:<}  {:<}  {:>}  {:>}  {:>}");
+    table.add_row(
+        Row::new()
+            .with_cell("PERSON")
+            .with_cell("GOAL")
+            .with_cell("MET")
+            .with_cell("%")
+            .with_cell("STREAK"),
+    );
+
+    for goal in goals {
+        table.add_row(
+            Row::new()
+                .with_cell(&goal.person)
+                .with_cell(&goal.frequency)
+                .with_cell(format!("{}/{}", goal.met, goal.intervals))
+                .with_cell(format!("{}%", goal.percentage()))
+                .with_cell(goal.streak),
+        );
+    }
+    print!("{table}");
+
+    Ok(())
+}
+
+fn remind_once(
+    config: &Config,
+    notify: bool,
+    snooze_days: i64,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let path = config.get_state_path();
+    let mut state = state::load_state(&path)?;
+    state.clear_expired_snoozes(today);
+
+    let interactions = use_cases::get_last_interactions(&log);
+    let interactions = use_cases::apply_acknowledgments(interactions, &mut state);
+    let interactions = use_cases::identify_reachouts(interactions, config, today);
+
+    let due = use_cases::due_reminders(interactions, config, &state, today);
+    match &config.templates.reminder {
+        Some(template) => {
+            let context = ReminderContext::new(&due, config, today);
+            print!("{}", render::render_file(template, &context)?);
+        }
+        None if due.is_empty() => println!("Nobody is overdue a reach out."),
+        None => {
+            for interaction in due.iter() {
+                let (title, body) = notifications::reminder_message(interaction);
+                match config
+                    .get_person(&interaction.person)
+                    .and_then(contact::preferred_contact)
+                {
+                    Some(contact) => println!("{title}: {body} ({contact})"),
+                    None => println!("{title}: {body}"),
+                }
+            }
+        }
+    }
+
+    if notify {
+        let until = snooze_until(today, snooze_days)?;
+        for interaction in due.iter() {
+            notifications::send_reminder(interaction)?;
+            state.snooze(interaction.person.clone(), until);
+        }
+    }
+
+    state::save_state(config, &state)
+}
+
+fn acknowledge(config: &Config, person: PersonName, today: NaiveDate) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let person = resolve_person(&person, &log, config)?;
+
+    let path = config.get_state_path();
+    let mut state = state::load_state(&path)?;
+    state.acknowledge(person.clone(), today);
+    state::save_state(config, &state)?;
+
+    println!("{person} counted as reached out on {today}, until you log it");
+
+    Ok(())
+}
+
+fn snooze_until(today: NaiveDate, snooze_days: i64) -> Result<NaiveDate, String> {
+    match Duration::try_days(snooze_days).and_then(|days| today.checked_add_signed(days)) {
+        Some(until) => Ok(until),
+        None => Err("--snooze-days is too large".to_string()),
+    }
+}
+
+fn snooze(
+    config: &Config,
+    person: PersonName,
+    snooze_days: i64,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let person = resolve_person(&person, &log, config)?;
+    let until = snooze_until(today, snooze_days)?;
+
+    let path = config.get_state_path();
+    let mut state = state::load_state(&path)?;
+    state.snooze(person.clone(), until);
+    state::save_state(config, &state)?;
+
+    println!("{person} snoozed until {until}");
+
+    Ok(())
+}
+
+fn remind(
+    config: &Config,
+    notify: bool,
+    daemon: bool,
+    snooze_days: i64,
+    snooze_person: Option<PersonName>,
+    today: Option<NaiveDate>,
+) -> Result<(), String> {
+    if snooze_days < 0 {
+        return Err("--snooze-days cannot be negative".to_string());
+    }
+
+    if let Some(person) = snooze_person {
+        return snooze(config, person, snooze_days, current_date(config, today));
+    }
+
+    if !daemon {
+        return remind_once(config, notify, snooze_days, current_date(config, today));
+    }
+
+    let mut last_check: Option<NaiveDate> = None;
+    loop {
+        let today = current_date(config, today);
+        if last_check != Some(today) {
+            // a failed check should not bring the daemon down, try again tomorrow
+            if let Err(reason) = remind_once(config, true, snooze_days, today) {
+                eprintln!("ERROR: {reason}");
+            }
+            last_check = Some(today);
+        }
+
+        thread::sleep(std::time::Duration::from_secs(60 * 60));
+    }
+}
+
+fn format_tiers(changes: &[TierChange]) -> String {
+    if changes.is_empty() {
+        return "No interactions logged yet.".to_string();
+    }
+
+    let mut table = Table::new("{:<}  {:<}  {:<}  {:<}  {:<}");
+    table.add_row(
+        Row::new()
+            .with_cell("")
+            .with_cell("PERSON")
+            .with_cell("LAST")
+            .with_cell("NOW")
+            .with_cell("A MONTH AGO"),
+    );
+
+    for change in changes {
+        let before = match change.before {
+            Some(tier) => tier.to_string(),
+            None => "".to_string(),
+        };
+
+        table.add_row(
+            Row::new()
+                .with_cell(if change.just_started_cooling() {
+                    "!"
+                } else {
+                    ""
+                })
+                .with_cell(&change.person)
+                .with_cell(change.last)
+                .with_cell(change.now)
+                .with_cell(before),
+        );
+    }
+
+    let cooling: Vec<&str> = changes
+        .iter()
+        .filter(|change| change.just_started_cooling())
+        .map(|change| change.person.as_str())
+        .collect();
+
+    if cooling.is_empty() {
+        return format!("{table}");
+    }
+
+    format!(
+        "{table}\nJust slipped from warm to cooling: {}",
+        cooling.join(", ")
+    )
+}
+
+fn facts(config: &Config, person: PersonName, format: ReportFormat) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let person = resolve_person(&person, &log, config)?;
+    let facts = facts::facts(&log, &person);
+
+    if format == ReportFormat::Json {
+        println!("{}", to_json(&facts)?);
+        return Ok(());
+    }
+
+    if facts.is_empty() {
+        println!("No facts about {person} yet, add `key: value` lines to their entries.");
+        return Ok(());
+    }
+
+    let mut table = Table::new("{:<}  {:<}  {:<}");
+    table.add_row(
+        Row::new()
+            .with_cell("KEY")
+            .with_cell("VALUE")
+            .with_cell("SINCE"),
+    );
+    for fact in facts {
+        table.add_row(
+            Row::new()
+                .with_cell(fact.key)
+                .with_cell(fact.value)
+                .with_cell(fact.date),
+        );
+    }
+    print!("{table}");
+
+    Ok(())
+}
+
+fn print_field_notes(
+    notes: Vec<FieldNote>,
+    format: ReportFormat,
+    listing: &ListingArgs,
+) -> Result<(), String> {
+    let notes = listing.page(notes);
+    if format == ReportFormat::Json {
+        println!("{}", to_json(&notes)?);
+        return Ok(());
+    }
+
+    let mut table = Listing::new("{:<}  {:<}  {:<}", ["PERSON", "DATE", "NOTE"]);
+    for note in notes {
+        table.add_row(vec![note.person, note.date.to_string(), note.value]);
+    }
+    print!("{}", table.render(listing.plain));
+
+    Ok(())
+}
+
+fn gift_ideas(
+    config: &Config,
+    person: Option<PersonName>,
+    upcoming: Option<Duration>,
+    format: ReportFormat,
+    listing: &ListingArgs,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let person = match person {
+        Some(person) => Some(resolve_person(&person, &log, config)?),
+        None => None,
+    };
+    let mut notes = facts::field_notes(&log, &facts::GIFT_KEYS, person.as_ref());
+
+    if let Some(upcoming) = upcoming {
+        let birthdays = digest::upcoming_birthdays(config, today, today + upcoming);
+        let position = |person: &PersonName| birthdays.iter().position(|b| &b.person == person);
+        notes.retain(|note| position(&note.person).is_some());
+        notes.sort_by_key(|note| position(&note.person));
+    }
+
+    if notes.is_empty() && format == ReportFormat::Table {
+        println!("No gift ideas found.");
+        return Ok(());
+    }
+
+    print_field_notes(notes, format, listing)
+}
+
+fn field(
+    config: &Config,
+    key: String,
+    person: Option<PersonName>,
+    format: ReportFormat,
+    listing: &ListingArgs,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let person = match person {
+        Some(person) => Some(resolve_person(&person, &log, config)?),
+        None => None,
+    };
+    let notes = facts::field_notes(&log, &[key.as_str()], person.as_ref());
+
+    if notes.is_empty() && format == ReportFormat::Table {
+        println!("No `{key}:` lines found in entries.");
+        return Ok(());
+    }
+
+    print_field_notes(notes, format, listing)
+}
+
+fn followups(
+    config: &Config,
+    person: Option<PersonName>,
+    all: bool,
+    format: ReportFormat,
+    listing: &ListingArgs,
+) -> Result<(), String> {
+    let options = ParseOptions::from_config(config);
+    let log = log::read_config_logs(config)?;
+    let person = match person {
+        Some(person) => Some(resolve_person(&person, &log, config)?),
+        None => None,
+    };
+
+    let mut followups = followups::followups(&log, &options);
+    followups.retain(|followup| all || followup.answered.is_none());
+    if let Some(person) = person {
+        followups.retain(|followup| followup.people.contains(&person));
+    }
+    let followups = listing.page(followups);
+
+    if format == ReportFormat::Json {
+        println!("{}", to_json(&followups)?);
+        return Ok(());
+    }
+    if followups.is_empty() {
+        println!("No open follow-ups.");
+        return Ok(());
+    }
+
+    let mut table = Listing::new(
+        "{:<}  {:<}  {:<}  {:<}",
+        ["DATE", "PEOPLE", "QUESTION", "ANSWERED"],
+    );
+    for followup in followups {
+        let people: Vec<&str> = followup.people.iter().map(String::as_str).collect();
+        table.add_row(vec![
+            followup.date.to_string(),
+            people.join(", "),
+            followup.question,
+            match followup.answered {
+                Some(date) => date.to_string(),
+                None => "-".to_string(),
+            },
+        ]);
+    }
+    print!("{}", table.render(listing.plain));
+
+    Ok(())
+}
+
+fn history(config: &Config, person: PersonName, listing: &ListingArgs) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let person = resolve_person(&person, &log, config)?;
+
+    let entries = git::history(config, &person)?;
+    if entries.is_empty() {
+        return Err(format!("nothing logged with {person}"));
+    }
+
+    let mut table = Listing::new(
+        "{:<}  {:<}  {:>}  {:<}  {:<}",
+        ["DATE", "RECORDED", "LAG", "COMMIT", "ENTRY"],
+    );
+
+    for entry in listing.page(entries) {
+        let (recorded, lag, commit) = match (entry.recorded, entry.lag(), &entry.commit) {
+            (Some(recorded), Some(lag), Some(commit)) => (
+                recorded.to_string(),
+                format!("{lag} days"),
+                commit[..8].to_string(),
+            ),
+            _ => ("not committed".to_string(), "".to_string(), "".to_string()),
+        };
+        table.add_row(vec![
+            entry.date.to_string(),
+            recorded,
+            lag,
+            commit,
+            entry.line,
+        ]);
+    }
+
+    print!("{}", table.render(listing.plain));
+    Ok(())
+}
+
+/// The entries with the person, resolved from what was typed, that `show` prints
+fn shown_history(
+    config: &Config,
+    person: PersonName,
+    last: Option<usize>,
+    since: Option<NaiveDate>,
+    kind: Option<InteractionKind>,
+) -> Result<(PersonName, Log), String> {
+    let log = log::read_config_logs(config)?;
+    let person = resolve_person(&person, &log, config)?;
+    let log = match kind {
+        Some(kind) => log.with_kind(&kind),
+        None => log,
+    };
+
+    match show::person_history(log, &person, config, since, last) {
+        Some(history) => Ok((person, history)),
+        None => Err(format!("nothing logged with {person}")),
+    }
+}
+
+/// Open the most recent of the entries in $EDITOR
+fn edit_latest(person: &PersonName, history: &Log) -> Result<(), String> {
+    let latest = history
+        .days
+        .last()
+        .and_then(|day| day.entries.last())
+        .map(|entry| entry.as_ref());
+    match latest {
+        Some(Entry {
+            source_file: Some(path),
+            line_range,
+            ..
+        }) => editor::open(path, line_range.start),
+        _ => Err(format!("the entries with {person} are not in a file")),
+    }
+}
+
+fn show(
+    config: &Config,
+    person: &PersonName,
+    history: &Log,
+    format: ShowFormat,
+    display: DateDisplay,
+    today: NaiveDate,
+) -> Result<(), String> {
+    match format {
+        ShowFormat::Markdown if !display.is_iso() => {
+            print!("{}", display.render_log(history, today))
+        }
+        ShowFormat::Markdown => {
+            print!("{}", use_cases::render_person_log(person, history, config)?)
+        }
+        ShowFormat::Json => println!("{}", show::to_json(person, history)),
+    }
+
+    Ok(())
+}
+
+fn edit_logs(config: &Config, target: Option<String>, today: NaiveDate) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let target = match target {
+        Some(target) => EditTarget::parse(&target),
+        None => EditTarget::Date(today),
+    };
+
+    let (path, line) = match target {
+        EditTarget::Date(date) => {
+            let transaction = Transaction::begin(config, "edit");
+            let result = edit::day_location(config, &log, date, &transaction);
+            transaction.finish(result)?
+        }
+        EditTarget::Person(person) => {
+            let person = resolve_person(&person, &log, config)?;
+            match edit::latest_entry_location(log, &person, config) {
+                Some(location) => location,
+                None => return Err(format!("nothing logged with {person} in a file")),
+            }
+        }
+    };
+
+    editor::open(&path, line)
+}
+
+fn prompt(config: &Config, count: usize, seed: Option<u64>) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let candidates = prompt::candidates(&log, config);
+    if candidates.is_empty() {
+        println!("Nobody to suggest, check the `prompt` weights in the config.");
+        return Ok(());
+    }
+
+    let mut rng = match seed {
+        Some(seed) => fastrand::Rng::with_seed(seed),
+        None => fastrand::Rng::new(),
+    };
+    for candidate in prompt::pick(candidates, count, &mut rng) {
+        let reasons: Vec<String> = candidate
+            .reasons
+            .iter()
+            .map(|reason| reason.to_string())
+            .collect();
+        println!("Write about {}: {}", candidate.person, reasons.join(", "));
+    }
+
+    Ok(())
+}
+
+fn profile(
+    config: &Config,
+    person: PersonName,
+    format: ShowFormat,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let person = resolve_person(&person, &log, config)?;
+    let history = match show::person_history(log, &person, config, None, None) {
+        Some(history) => history,
+        None if config.get_person(&person).is_some() => log::Log { days: vec![] },
+        None => return Err(format!("nothing logged with {person}")),
+    };
+
+    let profile = PersonProfile::new(&person, &history, config, today);
+    match format {
+        ShowFormat::Markdown => print!("{}", profile.to_markdown()),
+        ShowFormat::Json => match serde_json::to_string_pretty(&profile) {
+            Ok(json) => println!("{json}"),
+            Err(reason) => return Err(format!("failed to serialize profile, reason: {reason}")),
+        },
+    }
+
+    Ok(())
+}
+
+fn mutual(config: &Config, a: PersonName, b: PersonName) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let a = resolve_person(&a, &log, config)?;
+    let b = resolve_person(&b, &log, config)?;
+
+    let mutual = co_mentions::mutual_entries(log, &a, &b);
+    if mutual.days.is_empty() {
+        println!("No entries with both {a} and {b}.");
+        return Ok(());
+    }
+    print!("{mutual}");
+
+    Ok(())
+}
+
+fn introductions(
+    config: &Config,
+    format: ReportFormat,
+    listing: &ListingArgs,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let co_mentions = CoMentions::new(&log, config);
+    let introductions = listing.page(co_mentions::introductions(&log, &co_mentions, config));
+
+    if format == ReportFormat::Json {
+        println!("{}", to_json(&introductions)?);
+        return Ok(());
+    }
+
+    if introductions.is_empty() {
+        println!("No one to introduce.");
+        return Ok(());
+    }
+
+    let mut table = Listing::new("{:<}  {:<}  {:<}", ["PERSON", "PERSON", "IN COMMON"]);
+    for introduction in introductions {
+        let mut common = introduction.themes;
+        if let Some(location) = introduction.location {
+            common.push(location);
+        }
+        table.add_row(vec![introduction.a, introduction.b, common.join(", ")]);
+    }
+    print!("{}", table.render(listing.plain));
+
+    Ok(())
+}
+
+fn pick(
+    config: &Config,
+    action: Option<PickAction>,
+    print: bool,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let mut state = state::load_state(&config.get_state_path())?;
+    let interactions = use_cases::get_last_interactions(&log);
+    let interactions = use_cases::apply_acknowledgments(interactions, &mut state);
+    let mut interactions = use_cases::identify_reachouts(interactions, config, today);
+    interactions.retain(|interaction| !config.is_ignored(&interaction.person));
+    let choices = pick::choices(interactions, today);
+
+    // piped, e.g. into `fzf`
+    if print || !io::stdout().is_terminal() {
+        for choice in choices {
+            println!("{}", choice.line);
+        }
+        return Ok(());
+    }
+
+    if choices.is_empty() {
+        println!("No interactions logged yet.");
+        return Ok(());
+    }
+
+    let lines: Vec<&str> = choices.iter().map(|choice| choice.line.as_str()).collect();
+    let picked = FuzzySelect::new()
+        .with_prompt("Person")
+        .items(&lines)
+        .default(0)
+        .interact_opt();
+    let person = match picked {
+        Ok(Some(index)) => choices[index].person.clone(),
+        Ok(None) => return Ok(()),
+        Err(reason) => return Err(format!("failed to pick a person, reason: {reason}")),
+    };
+
+    let action = match action {
+        Some(action) => action,
+        None => {
+            let actions = PickAction::all();
+            let picked = Select::new()
+                .with_prompt(&person)
+                .items(&actions)
+                .default(0)
+                .interact_opt();
+            match picked {
+                Ok(Some(index)) => actions[index],
+                Ok(None) => return Ok(()),
+                Err(reason) => return Err(format!("failed to pick an action, reason: {reason}")),
+            }
+        }
+    };
+
+    match action {
+        PickAction::Show => {
+            let (person, history) = shown_history(config, person, None, None, None)?;
+            show(
+                config,
+                &person,
+                &history,
+                ShowFormat::Markdown,
+                DateDisplay::iso(),
+                today,
+            )
+        }
+        PickAction::Edit => edit_logs(config, Some(person), today),
+        PickAction::Contacted => acknowledge(config, person, today),
+    }
+}
+
+fn plan(config: &Config, format: PlanFormat, today: NaiveDate) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let mut state = state::load_state(&config.get_state_path())?;
+    state.clear_expired_snoozes(today);
+
+    let interactions = use_cases::get_last_interactions(&log);
+    let interactions = use_cases::apply_acknowledgments(interactions, &mut state);
+    let interactions = use_cases::identify_reachouts(interactions, config, today);
+
+    let due = use_cases::due_reminders(interactions, config, &state, today);
+    if due.is_empty() {
+        println!("Nobody is overdue a reach out.");
+        return Ok(());
+    }
+
+    match format {
+        PlanFormat::Markdown => print!("{}", plan::to_markdown(&due)),
+        PlanFormat::Table => {
+            let mut table = Table::new("{:<}  {:>}  {:>}");
+            table.add_row(
+                Row::new()
+                    .with_cell("Person")
+                    .with_cell("Last")
+                    .with_cell("Overdue"),
+            );
+            for interaction in due {
+                let overdue = interaction.days_beyond_reachout_threshold.unwrap_or(0);
+                table.add_row(
+                    Row::new()
+                        .with_cell(interaction.person)
+                        .with_cell(interaction.last)
+                        .with_cell(format!("{overdue}d")),
+                );
+            }
+            print!("{table}");
+        }
+    }
+
+    Ok(())
+}
+
+fn import_plan(
+    config: &Config,
+    path: PathBuf,
+    date: Option<NaiveDate>,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+
+    let checked = plan::parse_checked(&content);
+    if checked.is_empty() {
+        println!("Nothing is checked off in {path:?}.");
+        return Ok(());
+    }
+
+    let date = date.unwrap_or(today);
+    let reachouts = plan::reachouts_log(checked, date);
+    let transaction = Transaction::begin(config, "plan import");
+    let result = csv_import::merge_into_log_files(reachouts, config, &transaction);
+    let written = transaction.finish(result)?;
+    if written.is_empty() {
+        println!("Nothing to log, all reach outs are already in the logs.");
+    }
+
+    for (path, added) in written {
+        println!("{added} reach outs logged in {path:?}");
+    }
+
+    Ok(())
+}
+
+fn show_tiers(config: &Config, today: NaiveDate) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+
+    let changes = tiers::compute_tiers(&log, config, today)?;
+    println!("{}", format_tiers(&changes));
+
+    Ok(())
+}
+
+fn digest(
+    config: &Config,
+    period: Period,
+    format: DigestFormat,
+    send: bool,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let state = state::load_state(&config.get_state_path())?;
+
+    let digest = digest::build_digest(log, config, state, today, period)?;
+    let body = digest::render(&digest, format);
+
+    if !send {
+        print!("{body}");
+        return Ok(());
+    }
+
+    let smtp = match &config.smtp {
+        Some(smtp) => smtp,
+        None => return Err("no mail server configured, add `smtp` to the config".to_string()),
+    };
+
+    digest::send(smtp, &digest::subject(&digest), body, format)?;
+    println!("Digest sent to {}", smtp.to);
+
+    Ok(())
+}
+
+fn review(config: &Config, month: bool, today: NaiveDate) -> Result<(), String> {
+    let period = match month {
+        true => Period::Month,
+        false => Period::Week,
+    };
+    let log = log::read_config_logs(config)?;
+
+    let review = review::build_review(&log, config, today, period);
+    print!("{}", review::to_markdown(&review));
+
+    Ok(())
+}
+
+fn heatmap(
+    config: &Config,
+    person: Option<PersonName>,
+    format: HeatmapFormat,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let person = match person {
+        Some(person) => Some(resolve_person(&person, &log, config)?),
+        None => None,
+    };
+
+    let heatmap = heatmap::build_heatmap(&log, config, person.as_ref(), today);
+    match format {
+        HeatmapFormat::Text => print!("{}", heatmap::to_text(&heatmap)),
+        HeatmapFormat::Svg => print!("{}", heatmap::to_svg(&heatmap)),
+    }
+
+    Ok(())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => Ok(json),
+        Err(reason) => Err(format!("failed to serialize to JSON, reason: {reason}")),
+    }
+}
+
+/// People listed by `people top` unless `--limit` is given
+const TOP_PEOPLE: usize = 10;
+
+fn anniversaries(
+    config: &Config,
+    met: bool,
+    upcoming: Duration,
+    listing: &ListingArgs,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let end = today + upcoming;
+
+    let mut first_met = use_cases::get_first_interactions(&log);
+    first_met.retain(|person, _| !config.is_ignored(person));
+    let mut occasions: Vec<(digest::UpcomingBirthday, &str)> =
+        digest::upcoming_metiversaries(&first_met, today, end)
+            .into_iter()
+            .map(|upcoming| (upcoming, "met"))
+            .collect();
+    if !met {
+        occasions.extend(
+            digest::upcoming_birthdays(config, today, end)
+                .into_iter()
+                .filter(|upcoming| !config.is_ignored(&upcoming.person))
+                .map(|upcoming| (upcoming, "birthday")),
+        );
+    }
+    occasions.sort_by(|(a, _), (b, _)| a.date.cmp(&b.date).then_with(|| a.person.cmp(&b.person)));
+    let occasions = listing.page(occasions);
+
+    if occasions.is_empty() {
+        println!("No anniversaries until {end}.");
+        return Ok(());
+    }
+
+    let mut table = Listing::new(
+        "{:<}  {:<}  {:<}  {:>}",
+        ["DATE", "PERSON", "OCCASION", "YEARS"],
+    );
+    for (upcoming, occasion) in occasions {
+        table.add_row(vec![
+            upcoming.date.to_string(),
+            upcoming.person,
+            occasion.to_string(),
+            upcoming.age.to_string(),
+        ]);
+    }
+    print!("{}", table.render(listing.plain));
+
+    Ok(())
+}
+
+fn inactive(
+    config: &Config,
+    over: Duration,
+    archive: bool,
+    listing: &ListingArgs,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let interactions = use_cases::get_last_interactions(&log);
+    let inactive = archive::inactive(interactions, config, over, today);
+
+    if inactive.is_empty() {
+        println!("No one unseen since {}.", today - over);
+        return Ok(());
+    }
+
+    // the whole list is archived, whatever page of it is shown
+    let mut table = Listing::new("{:<}  {:<}  {:>}", ["PERSON", "LAST SEEN", "DAYS AGO"]);
+    for interaction in listing.page(inactive.clone()) {
+        table.add_row(vec![
+            interaction.person.clone(),
+            interaction.last.to_string(),
+            interaction.ago(today).to_string(),
+        ]);
+    }
+    print!("{}", table.render(listing.plain));
+
+    if !archive {
+        return Ok(());
+    }
+
+    let names: Vec<PersonName> = inactive.into_iter().map(|i| i.person).collect();
+    let (people, archived) = archive::archive(config, &names);
+    let transaction = Transaction::begin(config, "inactive --archive");
+    let result = config::save_archive(config, &people, &archived, &transaction);
+    let config_path = transaction.finish(result)?;
+    println!("Archived {} people in {config_path:?}", names.len());
+
+    Ok(())
+}
+
+fn unarchive(config: &Config, person: PersonName) -> Result<(), String> {
+    let (people, archived) = archive::unarchive(config, &person)?;
+    let transaction = Transaction::begin(config, "unarchive");
+    let result = config::save_archive(config, &people, &archived, &transaction);
+    let config_path = transaction.finish(result)?;
+    println!("Unarchived {person} in {config_path:?}");
+
+    Ok(())
+}
+
+fn person_action(config: &Config, action: PersonAction) -> Result<(), String> {
+    match action {
+        PersonAction::Add {
+            name,
+            location,
+            remind_after,
+            group,
+        } => {
+            let changes = PersonChanges {
+                location,
+                remind_after,
+                add_groups: group,
+                ..Default::default()
+            };
+            let people = roster::add(config, &name, &changes)?;
+            let transaction = Transaction::begin(config, "person add");
+            let result = config::save_people(config, &people, &transaction);
+            let config_path = transaction.finish(result)?;
+            println!("Added {name} to {config_path:?}");
+        }
+        PersonAction::Edit {
+            name,
+            location,
+            remind_after,
+            group,
+            remove_group,
+        } => {
+            let changes = PersonChanges {
+                location,
+                remind_after,
+                add_groups: group,
+                remove_groups: remove_group,
+            };
+            if changes.is_empty() {
+                return Err("nothing to change, see `people person edit --help`".to_string());
+            }
+            let (people, archived) = roster::edit(config, &name, &changes)?;
+            let transaction = Transaction::begin(config, "person edit");
+            let result = config::save_archive(config, &people, &archived, &transaction);
+            let config_path = transaction.finish(result)?;
+            println!("Updated {name} in {config_path:?}");
+        }
+        PersonAction::Remove { name } => {
+            let (people, archived) = roster::remove(config, &name)?;
+            let transaction = Transaction::begin(config, "person remove");
+            let result = config::save_archive(config, &people, &archived, &transaction);
+            let config_path = transaction.finish(result)?;
+            println!("Removed {name} from {config_path:?}");
+        }
+    }
+
+    Ok(())
+}
+
+fn top(
+    config: &Config,
+    last: Duration,
+    format: ReportFormat,
+    listing: &ListingArgs,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let window = Window::ending(today, last);
+    let listing = ListingArgs {
+        limit: Some(listing.limit.unwrap_or(TOP_PEOPLE)),
+        ..listing.clone()
+    };
+    let top = aggregate::top_people(&log, config, window, usize::MAX);
+    let top = listing.page(top);
+
+    if format == ReportFormat::Json {
+        println!("{}", to_json(&top)?);
+        return Ok(());
+    }
+
+    if top.is_empty() {
+        println!("No interactions since {}.", window.start);
+        return Ok(());
+    }
+
+    let mut table = Listing::new("{:<}  {:>}", ["PERSON", "INTERACTIONS"]);
+    for person in top {
+        table.add_row(vec![person.person, person.interactions.to_string()]);
+    }
+    print!("{}", table.render(listing.plain));
+
+    Ok(())
+}
+
+fn trends(
+    config: &Config,
+    period: Duration,
+    format: ReportFormat,
+    listing: &ListingArgs,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let window = Window::ending(today, period);
+    let trends = listing.page(aggregate::trends(&log, config, window));
+
+    if format == ReportFormat::Json {
+        println!("{}", to_json(&trends)?);
+        return Ok(());
+    }
+
+    if trends.is_empty() {
+        println!("No interactions since {}.", window.previous().start);
+        return Ok(());
+    }
+
+    let before = window.previous();
+    let mut table = Listing::new(
+        "{:<}  {:>}  {:>}  {:>}  {:<}",
+        vec![
+            "PERSON".to_string(),
+            format!("{} - {}", before.start, before.end),
+            format!("{} - {}", window.start, window.end),
+            "CHANGE".to_string(),
+            "TREND".to_string(),
+        ],
+    );
+    for trend in trends {
+        table.add_row(vec![
+            trend.person.clone(),
+            trend.before.to_string(),
+            trend.now.to_string(),
+            format!("{:+}", trend.change()),
+            trend.direction.to_string(),
+        ]);
+    }
+    print!("{}", table.render(listing.plain));
+
+    Ok(())
+}
+
+fn export_anonymized(config: &Config, output: PathBuf, salt: String) -> Result<(), String> {
+    if output.exists() {
+        return Err(format!("{output:?} already exists, choose a new directory"));
+    }
+
+    let config_path = match config::get_config_path() {
+        Ok(path) => path,
+        Err(_) => return Err("home directory not found".to_string()),
+    };
+    let config_content = match fs::read_to_string(&config_path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {config_path:?}, reason: {reason}")),
+    };
+
+    let log = log::read_config_logs(config)?;
+    for path in anonymize::export(&log, config, &config_content, &output, &salt)? {
+        println!("Written {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn export_json(config: &Config, output: Option<PathBuf>) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let content = ndjson::to_ndjson(&log)?;
+    match output {
+        Some(output) => files::write_atomically(&output, &content),
+        None => {
+            print!("{content}");
+            Ok(())
+        }
+    }
+}
+
+fn run_bench(
+    config: &Config,
+    synthetic: bool,
+    days: usize,
+    people: usize,
+    runs: usize,
+) -> Result<(), String> {
+    let files: Vec<(String, LogSyntax)> = match synthetic {
+        true => vec![(bench::synthetic_log(people, days), LogSyntax::Markdown)],
+        false => {
+            let mut files = vec![];
+            for (path, syntax) in log::find_log_files_with_syntax(config) {
+                match fs::read_to_string(&path) {
+                    Ok(content) => files.push((content, syntax)),
+                    Err(reason) => {
+                        return Err(format!("failed to read {path:?}, reason: {reason}"))
+                    }
+                }
+            }
+            files
+        }
+    };
+
+    print!("{}", bench::measure(&files, config, runs));
+    Ok(())
+}
+
+fn backup(config: &Config, dir: Option<PathBuf>) -> Result<(), String> {
+    let config_path = match config::get_config_path() {
+        Ok(path) => path,
+        Err(_) => return Err("home directory not found".to_string()),
+    };
+
+    let dir = dir.unwrap_or_else(|| config.get_backup_dir());
+    if dir.starts_with(&config.people_dir) {
+        config.check_writable(&dir)?;
+    }
+    let now = chrono::Local::now().naive_local();
+    let archive = backup::create_backup(config, &config_path, &dir, now)?;
+    println!("Written {}", archive.display());
+
+    if let Some(keep) = config.backup.keep {
+        for path in backup::prune_backups(&dir, keep)? {
+            println!("Deleted {}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+fn restore(archive: PathBuf, force: bool) -> Result<(), String> {
+    let people_dir = backup::restore(&archive, force)?;
+    println!("Restored {}", people_dir.display());
+    Ok(())
+}
+
+fn config_action(action: ConfigAction) -> Result<(), String> {
+    match action {
+        ConfigAction::Validate { path } => validate_config(path),
+        ConfigAction::Migrate { path, dry_run } => migrate_config(path, dry_run),
+    }
+}
+
+/// `path` if given, otherwise the config file in $HOME, with its content
+fn read_config_file(path: Option<PathBuf>) -> Result<(PathBuf, String), String> {
+    let path = match path {
+        Some(path) => path,
+        None => match config::get_config_path() {
+            Ok(path) => path,
+            Err(_) => return Err("home directory not found".to_string()),
+        },
+    };
+    match fs::read_to_string(&path) {
+        Ok(content) => Ok((path, content)),
+        Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+    }
+}
+
+fn validate_config(path: Option<PathBuf>) -> Result<(), String> {
+    let (path, content) = read_config_file(path)?;
+
+    let problems = config::validate_config(&content);
+    if problems.is_empty() {
+        println!("No problems found in {}", path.display());
+        return Ok(());
+    }
+
+    for problem in problems.iter() {
+        match problem.line {
+            Some(line) => println!("{}:{line}: {}", path.display(), problem.message),
+            None => println!("{}: {}", path.display(), problem.message),
+        }
+    }
+    Err(format!("{} problems found in {path:?}", problems.len()))
+}
+
+fn migrate_config(path: Option<PathBuf>, dry_run: bool) -> Result<(), String> {
+    let (path, content) = read_config_file(path)?;
+    let migrated = match migrations::migrate_config(&content)? {
+        Some(migrated) => migrated,
+        None => {
+            println!(
+                "{} is already at version {}",
+                path.display(),
+                migrations::CONFIG_VERSION
+            );
+            return Ok(());
+        }
+    };
+
+    println!(
+        "{}: version {} to {}",
+        path.display(),
+        migrated.from,
+        migrations::CONFIG_VERSION
+    );
+    for change in migrated.changes.iter() {
+        println!("- {change}");
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    // the people directory, where the change is recorded, comes from the config
+    let config = Config::from_yaml_str(&migrated.content)?;
+    let transaction = Transaction::begin(&config, "config migrate");
+    let backup = PathBuf::from(format!("{}.bak", path.display()));
+    let result = transaction
+        .write(&backup, &content)
+        .and_then(|()| transaction.write(&path, &migrated.content));
+    transaction.finish(result)?;
+    println!(
+        "Written {} (original kept in {})",
+        path.display(),
+        backup.display()
+    );
+    Ok(())
+}
+
+fn dedupe_entries(config: &Config, fix: bool) -> Result<(), String> {
+    let options = ParseOptions::from_config(config);
+    let duplicates = duplicates::find_duplicates_in_logs(config, &options)?;
+    if duplicates.is_empty() {
+        println!("No duplicate entries found.");
+        return Ok(());
+    }
+
+    for duplicate in duplicates.iter() {
+        let also_in = match duplicate.path == duplicate.original {
+            true => "twice in the same file".to_string(),
+            false => format!("also in {}", duplicate.original.display()),
+        };
+        println!(
+            "{} {}: {} ({also_in})",
+            duplicate.date,
+            duplicate.path.display(),
+            duplicate.first_line()
+        );
+    }
+
+    if !fix {
+        println!("\nUse --fix to remove them.");
+        return Ok(());
+    }
+
+    let transaction = Transaction::begin(config, "dedupe-entries --fix");
+    let result = duplicates::fix_duplicates(&duplicates, &transaction);
+    transaction.finish(result)?;
+    println!("\nRemoved {} duplicate entries.", duplicates.len());
+    Ok(())
+}
+
+fn dedupe(config: &Config, fix: bool) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let merges = dedupe::find_merges(&log, config);
+    if merges.is_empty() {
+        println!("No look-alike tags found.");
+        return Ok(());
+    }
+
+    for merge in merges.iter() {
+        println!("{merge}");
+    }
+
+    if !fix {
+        println!("\nUse --fix to add them as aliases to the config.");
+        return Ok(());
+    }
+
+    let people = dedupe::apply_merges(&config.people, &merges);
+    let transaction = Transaction::begin(config, "dedupe --fix");
+    let result = config::save_people(config, &people, &transaction);
+    let config_path = transaction.finish(result)?;
+    println!("Aliases added to {config_path:?}");
+
+    Ok(())
+}
+
+fn rename(config: &Config, old: PersonName, new: PersonName, dry_run: bool) -> Result<(), String> {
+    if !rename::is_valid_tag(&new) {
+        return Err(format!("{new:?} cannot be written as a hashtag"));
+    }
+
+    let options = ParseOptions::from_config(config);
+    let renamed = rename::plan_rename(config, &old, &new, &options)?;
+    if renamed.is_empty() {
+        return Err(format!("no references to {old:?} found in the logs"));
+    }
+
+    for file in renamed.iter() {
+        println!("{}: {} references", file.path.display(), file.references);
+        if dry_run {
+            print!("{}", file.diff());
+        }
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    let transaction = Transaction::begin(config, &format!("rename {old} {new}"));
+    let result = rename::apply_rename(&renamed, &transaction);
+    let result = result.and_then(|()| log::read_config_logs(config));
+    let log = match result {
+        Ok(log) => log,
+        Err(reason) => return transaction.finish(Err(reason)),
+    };
+    let mut per_person_logs = use_cases::split_log_per_person(log, config);
+    // the old person is gone from the logs, drop their file too
+    per_person_logs.entry(old.clone()).or_insert(None);
+
+    let index = match config.per_person.index {
+        true => Some(people_index::index_rows(&per_person_logs, config)),
+        false => None,
+    };
+    let mut results = use_cases::write_person_logs(
+        per_person_logs,
+        config,
+        use_cases::default_jobs(),
+        &transaction,
+    );
+    if let Some(rows) = index {
+        results.push(people_index::write_people_index(
+            &rows,
+            config,
+            &transaction,
+        ));
+    }
+    transaction.commit()?;
+    for result in results.iter() {
+        match result {
+            LogWritten::FailedToWrite(path, reason) | LogWritten::FailedToDelete(path, reason) => {
+                eprintln!("ERROR: failed to update {path:?}, reason: {reason}")
+            }
+            _ => {}
+        }
+    }
+    git::commit_per_person_logs(config, &results)?;
+
+    println!("{old} renamed to {new}");
+    if config.get_person(&old).is_some() {
+        println!("{old} is still in the config, rename it there too");
+    }
+
+    Ok(())
+}
+
+/// Formatting stdin prints the formatted log instead of writing it back
+fn fmt_stdin(content: &str, check: bool) -> Result<(), String> {
+    let formatted = match formatter::format_content(content) {
+        Ok(formatted) => formatted,
+        Err(reason) => return Err(format!("failed to format stdin, reason: {reason}")),
+    };
+
+    match check {
+        true if formatted != content => Err("stdin is not formatted".to_string()),
+        true => Ok(()),
+        false => {
+            print!("{formatted}");
+            Ok(())
+        }
+    }
+}
+
+fn fmt(config: &Config, check: bool) -> Result<(), String> {
+    let paths = match &config.input {
+        Some(LogInput {
+            path: Some(path), ..
+        }) => vec![path.clone()],
+        Some(LogInput {
+            path: None,
+            content,
+        }) => return fmt_stdin(content, check),
+        // only markdown logs are formatted
+        None => log::find_log_files_with_syntax(config)
+            .into_iter()
+            .filter(|(_, syntax)| *syntax == LogSyntax::Markdown)
+            .map(|(path, _)| path)
+            .collect(),
+    };
+    let unformatted = formatter::check_files(&paths)?;
+    if unformatted.is_empty() {
+        println!("All {} log files are formatted.", paths.len());
+        return Ok(());
+    }
+
+    if check {
+        for file in unformatted.iter() {
+            println!("{}", file.path.display());
+        }
+        return Err(format!(
+            "{} log files are not formatted, run `people fmt` to format them",
+            unformatted.len()
+        ));
+    }
+
+    let transaction = Transaction::begin(config, "fmt");
+    let result = formatter::write_formatted(&unformatted, &transaction);
+    transaction.finish(result)?;
+    for file in unformatted.iter() {
+        println!("Formatted {}", file.path.display());
+    }
+
+    Ok(())
+}
+
+fn verify_names(config: &Config, fix: bool) -> Result<(), String> {
+    let issues = verify_names::audit_logs(config)?;
+    if issues.is_empty() {
+        println!("All names are normalized.");
+        return Ok(());
+    }
+
+    for issue in issues.iter() {
+        println!("{issue}");
+    }
+
+    if !fix {
+        return Err(format!(
+            "{} names are not normalized, use --fix to normalize them",
+            issues.len()
+        ));
+    }
+
+    let transaction = Transaction::begin(config, "verify-names --fix");
+    let result = verify_names::fix_logs(config, &transaction);
+    for path in transaction.finish(result)? {
+        println!("Fixed {path:?}");
+    }
+
+    Ok(())
+}
+
+fn undo(config: &Config, dry_run: bool) -> Result<(), String> {
+    if dry_run {
+        let Some((_, operation)) = operations::last_operation(config)? else {
+            println!("Nothing to undo.");
+            return Ok(());
+        };
+        println!(
+            "`{}` on {}",
+            operation.command,
+            operation.time.format("%Y-%m-%d %H:%M")
+        );
+        for change in operation.files.iter() {
+            let action = match (&change.before, &change.after) {
+                (None, _) => "delete",
+                (Some(_), None) => "restore",
+                (Some(_), Some(_)) => "revert",
+            };
+            println!("  {action} {}", change.path.display());
+        }
+        return Ok(());
+    }
+
+    let operation = operations::undo(config)?;
+    println!(
+        "Undid `{}`, {} files reverted",
+        operation.command,
+        operation.files.len()
+    );
+
+    Ok(())
+}
+
+fn suggest_tags(config: &Config, fix: bool) -> Result<(), String> {
+    let options = ParseOptions::from_config(config);
+    let log = log::read_logs(config, &options)?;
+    let spellings = Spellings::new(&log, config);
+
+    let suggestions = suggest_tags::audit_logs(config, &spellings, &options)?;
+    if suggestions.is_empty() {
+        println!("No untagged mentions found.");
+        return Ok(());
+    }
+
+    for suggestion in suggestions.iter() {
+        println!("{suggestion}");
+    }
+
+    if !fix {
+        return Err(format!(
+            "{} mentions are not tagged, use --fix to tag them",
+            suggestions.len()
+        ));
+    }
+
+    let transaction = Transaction::begin(config, "suggest-tags --fix");
+    let result = suggest_tags::fix_logs(config, &spellings, &options, &transaction);
+    for path in transaction.finish(result)? {
+        println!("Fixed {path:?}");
+    }
+
+    Ok(())
+}
+
+fn show_contact(config: &Config, person: PersonName, open: bool) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let person = resolve_person(&person, &log, config)?;
+    let methods = match config.get_person(&person) {
+        Some(details) => contact::contact_methods(details),
+        None => vec![],
+    };
+
+    if methods.is_empty() {
+        return Err(format!(
+            "no contact details for {person}, add an email, phone or telegram to them in the config"
+        ));
+    }
+
+    if open {
+        return contact::open_url(&methods[0].url);
+    }
+
+    let mut table = Table::new("{:<}  {:<}");
+    for method in methods {
+        table.add_row(Row::new().with_cell(method.label).with_cell(method.value));
+    }
+    print!("{table}");
+
+    Ok(())
+}
+
+fn show_where(config: &Config, person: PersonName) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let person = resolve_person(&person, &log, config)?;
+    let timelines = locations::location_timelines(&log, config);
+
+    match locations::current_location(&person, config, &timelines) {
+        Some(CurrentLocation::Logged(change)) => {
+            println!("{person} is in {} since {}", change.location, change.date)
+        }
+        Some(CurrentLocation::Configured(location)) => {
+            println!("{person} is in {location} (from the config)")
+        }
+        None => return Err(format!("no location known for {person}")),
+    }
+
+    if let Some(timeline) = timelines.get(&person) {
+        for change in timeline {
+            println!("  {}  {}", change.date, change.location);
+        }
+    }
+
+    Ok(())
+}
+
+fn show_near(config: &Config, location: String) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let timelines = locations::location_timelines(&log, config);
+
+    let near = locations::people_near(&location, config, &timelines);
+    if near.is_empty() {
+        println!("Nobody is in {location:?}.");
+        return Ok(());
+    }
+
+    let mut table = Table::new("{:<}  {:<}  {:<}");
+    for (person, current) in near {
+        let since = match &current {
+            CurrentLocation::Logged(change) => format!("since {}", change.date),
+            CurrentLocation::Configured(_) => "from the config".to_string(),
+        };
+        table.add_row(
+            Row::new()
+                .with_cell(person)
+                .with_cell(current.location())
+                .with_cell(since),
+        );
+    }
+    print!("{table}");
+
+    Ok(())
+}
+
+fn trip(config: &Config, location: String, from: NaiveDate, to: NaiveDate) -> Result<(), String> {
+    if to < from {
+        return Err(format!("--to {to} is before --from {from}"));
+    }
+
+    let log = log::read_config_logs(config)?;
+    let contacts = trip::plan_trip(&log, config, &location, from, to);
+    if contacts.is_empty() {
+        println!("Nobody is in {location:?}.");
+        return Ok(());
+    }
+
+    let mut table = Table::new("{:<}  {:<}  {:<}  {:>}  {:<}");
+    table.add_row(
+        Row::new()
+            .with_cell("PERSON")
+            .with_cell("LOCATION")
+            .with_cell("LAST")
+            .with_cell("OVERDUE")
+            .with_cell(""),
+    );
+    for contact in contacts {
+        let last = match contact.last {
+            Some(last) => last.to_string(),
+            None => "never".to_string(),
+        };
+        let overdue = match contact.days_overdue {
+            Some(days) => format!("{days}d"),
+            None => "".to_string(),
+        };
+        let birthday = match contact.birthday {
+            Some(date) => format!("birthday on {date}"),
+            None => "".to_string(),
+        };
+        table.add_row(
+            Row::new()
+                .with_cell(contact.person)
+                .with_cell(contact.location)
+                .with_cell(last)
+                .with_cell(overdue)
+                .with_cell(birthday),
+        );
+    }
+    print!("{table}");
+
+    Ok(())
+}
+
+fn show_links(config: &Config, person: Option<PersonName>) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let person = match person {
+        Some(person) => Some(resolve_person(&person, &log, config)?),
+        None => None,
+    };
+    let links = links::collect_links(&log, config, person.as_ref());
+
+    if links.is_empty() {
+        println!("No links found.");
+        return Ok(());
+    }
+
+    let missing: Vec<PathBuf> = links::missing_attachments(&log, &config.people_dir)
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect();
+
+    let mut sections: Vec<String> = vec![];
+    for (person, person_links) in links {
+        let mut table = Table::new("  {:<}  {:<}{:<}");
+        for link in person_links {
+            let is_missing = links::is_attachment(&link.url)
+                && missing.contains(&config.people_dir.join(&link.url));
+            table.add_row(
+                Row::new()
+                    .with_cell(link.date)
+                    .with_cell(link.url)
+                    .with_cell(if is_missing { "  (missing)" } else { "" }),
+            );
+        }
+        sections.push(format!("{person}\n{table}"));
+    }
+    print!("{}", sections.join("\n"));
+
+    Ok(())
+}
+
+fn check_attachments(config: &Config) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let missing = links::missing_attachments(&log, &config.people_dir);
+    if missing.is_empty() {
+        println!("All attached files exist.");
+        return Ok(());
+    }
+
+    for (date, path) in missing.iter() {
+        println!("{date}  {}", path.display());
+    }
+
+    Err(format!("{} attached files not found", missing.len()))
+}
+
+fn notify(
+    config: &Config,
+    channel: Channel,
+    quiet_hours: Option<QuietHours>,
+    today: NaiveDate,
+) -> Result<(), String> {
+    if let Some(quiet_hours) = quiet_hours {
+        if quiet_hours.contains(config.now().time()) {
+            info!("within quiet hours, not posting");
+            return Ok(());
+        }
+    }
+
+    let log = log::read_config_logs(config)?;
+    let path = config.get_state_path();
+    let mut state = state::load_state(&path)?;
+
+    let interactions = use_cases::get_last_interactions(&log);
+    let interactions = use_cases::apply_acknowledgments(interactions, &mut state);
+    let interactions = use_cases::identify_reachouts(interactions, config, today);
+    let due = use_cases::due_reminders(interactions, config, &state, today);
+    if due.is_empty() {
+        println!("Nobody is overdue a reach out, not posting.");
+        return Ok(());
+    }
+
+    let message = match &config.templates.notify {
+        Some(template) => {
+            let context = ReminderContext::new(&due, config, today);
+            render::render_file(template, &context)?
+        }
+        None => notifiers::reminders_message(&due, config),
+    };
+
+    notifiers::notifier(channel, config)?.send(&message)?;
+    println!("Posted {} people overdue a reach out.", due.len());
+    Ok(())
+}
+
+fn scan_mail(
+    config: &Config,
+    path: PathBuf,
+    since: Option<Duration>,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let messages = mail::read_mailbox(&path)?;
+    let since = match since {
+        Some(since) => match today.checked_sub_signed(since) {
+            Some(since) => Some(since),
+            None => return Err(format!("--since goes back before {}", NaiveDate::MIN)),
+        },
+        None => None,
+    };
+    let found = mail::passive_interactions(&messages, config, since);
+
+    let state_path = config.get_state_path();
+    let mut state = state::load_state(&state_path)?;
+    let mut added = 0;
+    for (person, dates) in found.iter() {
+        let known = state.passive.entry(person.clone()).or_default();
+        for date in dates {
+            if known.insert(*date) {
+                added += 1;
+            }
+        }
+    }
+    state::save_state(config, &state)?;
+
+    println!(
+        "Scanned {} emails: {added} new days with {} people.",
+        messages.len(),
+        found.len()
+    );
+    Ok(())
+}
+
+fn run_hooks(config: &Config, dry_run: bool, today: NaiveDate) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let path = config.get_state_path();
+    let mut state = state::load_state(&path)?;
+    let interactions = use_cases::get_last_interactions(&log);
+    let interactions = use_cases::apply_acknowledgments(interactions, &mut state);
+    let mut interactions = use_cases::identify_reachouts(interactions, config, today);
+    interactions.retain(|interaction| !config.is_ignored(&interaction.person));
+
+    if state.hooks.is_none() {
+        println!("First run, hooks will run for changes from now on");
+    }
+
+    let mut failed: Vec<String> = vec![];
+    for event in hooks::events(state.hooks.as_ref(), &interactions) {
+        let command = match event.command(&config.hooks) {
+            Some(command) => command,
+            None => continue,
+        };
+
+        println!("{} {}: {command}", event.name(), event.person());
+        if dry_run {
+            continue;
+        }
+        if let Err(reason) = hooks::run_hook(command, &event) {
+            failed.push(reason);
+        }
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    state.hooks = Some(hooks::snapshot(&interactions, today));
+    state::save_state(config, &state)?;
+
+    match failed.is_empty() {
+        true => Ok(()),
+        false => Err(format!("some hooks failed:\n{}", failed.join("\n"))),
+    }
+}
+
+fn write_rollup(config: &Config, week: Option<NaiveDate>, today: NaiveDate) -> Result<(), String> {
+    let monday = week.unwrap_or_else(|| rollup::last_week(today));
+    let log = log::read_config_logs(config)?;
+    let content = rollup::render_rollup(&log, config, monday);
+
+    let path = config.get_rollup_path(monday);
+    if fs::read_to_string(&path).is_ok_and(|existing| existing == content) {
+        println!("Rollup already up to date: {path:?}");
+        return Ok(());
+    }
+    let transaction = Transaction::begin(config, "rollup");
+    let result = transaction.write(&path, &content);
+    transaction.finish(result)?;
+    println!("Rollup written: {path:?}");
+    Ok(())
+}
+
+fn print_metrics(config: &Config, output: Option<PathBuf>, today: NaiveDate) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let mut state = state::load_state(&config.get_state_path())?;
+    let interactions = use_cases::get_last_interactions(&log);
+    let interactions = use_cases::apply_acknowledgments(interactions, &mut state);
+    let mut interactions = use_cases::identify_reachouts(interactions, config, today);
+    interactions.retain(|interaction| !config.is_ignored(&interaction.person));
+
+    let metrics = metrics::render_metrics(&log, &interactions, today);
+    match output {
+        Some(path) => files::write_atomically(&path, &metrics),
+        None => {
+            print!("{metrics}");
+            Ok(())
+        }
+    }
+}
+
+fn serve(
+    config: &Config,
+    host: String,
+    port: u16,
+    watch: bool,
+    today: Option<NaiveDate>,
+) -> Result<(), String> {
+    let address = format!("{host}:{port}");
+    println!("Listening on http://{address}");
+    server::serve(config, &address, watch, today)
+}
+
+fn publish(config: &Config, output: PathBuf, today: NaiveDate) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+
+    let templates = SiteTemplates::from_config(config)?;
+    let pages = site::build_site(log, config, &templates, today)?;
+    site::write_site(&pages, &output)?;
+    println!("{} pages written to {output:?}", pages.len());
+
+    Ok(())
+}
+
+fn completions(config: &Config, people: bool) -> Result<(), String> {
+    if !people {
+        return Err("nothing to complete, use --people or pass a shell".to_string());
+    }
+
+    for person in completions::refresh_people_cache(config)? {
+        println!("{person}");
+    }
+
+    Ok(())
+}
+
+fn confirm(question: &str) -> bool {
+    print!("{question} [y/N] ");
+    if io::stdout().flush().is_err() {
+        return false;
+    }
+
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+
+    matches!(answer.trim(), "y" | "Y" | "yes")
+}
+
+/// Ask which of the candidates was meant, unless nobody can answer
+fn choose_person(candidates: &[PersonName]) -> Option<PersonName> {
+    if !io::stdin().is_terminal() {
+        return None;
+    }
+
+    for (index, candidate) in candidates.iter().enumerate() {
+        println!("{}) {candidate}", index + 1);
+    }
+    print!("Which one? ");
+    if io::stdout().flush().is_err() {
+        return None;
+    }
+
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return None;
+    }
+
+    match answer.trim().parse::<usize>() {
+        Ok(choice) if choice >= 1 => candidates.get(choice - 1).cloned(),
+        _ => None,
+    }
+}
+
+fn resolve_person(person: &str, log: &log::Log, config: &Config) -> Result<PersonName, String> {
+    resolve::resolve_person(person, log, config, choose_person)
+}
+
+fn import_vcard(config: &Config, path: PathBuf, dry_run: bool) -> Result<(), String> {
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+
+    let contacts = vcard::parse_vcards(&content);
+    let changes = vcard::plan_import(&config.people, &contacts);
+    if changes.is_empty() {
+        println!(
+            "Config is already up to date with {} contacts.",
+            contacts.len()
+        );
+        return Ok(());
+    }
+
+    for change in changes.iter() {
+        print!("{change}");
+    }
+
+    if dry_run || !confirm("Apply these changes to the config?") {
+        return Ok(());
+    }
+
+    let people = vcard::apply_changes(&config.people, &changes);
+    let transaction = Transaction::begin(config, "import vcard");
+    let result = config::save_people(config, &people, &transaction);
+    let config_path = transaction.finish(result)?;
+    println!("Config updated: {config_path:?}");
+
+    Ok(())
+}
+
+fn sync_contacts(config: &Config, apply: bool) -> Result<(), String> {
+    let provider: Box<dyn ContactsProvider> = match &config.carddav {
+        Some(carddav) => Box::new(CardDavProvider::new(carddav.clone())),
+        None => {
+            return Err("no contacts provider configured, add `carddav` to the config".to_string())
+        }
+    };
+
+    let contacts = provider.fetch_contacts()?;
+    let report = contacts::plan_sync(&config.people, &contacts);
+
+    if report.changes.is_empty() && report.conflicts.is_empty() {
+        println!(
+            "Config is already in sync with {} contacts.",
+            contacts.len()
+        );
+        return Ok(());
+    }
+
+    for change in report.changes.iter() {
+        print!("{change}");
+    }
+
+    for conflict in report.conflicts.iter() {
+        println!(
+            "! {}: birthday is {} in the config but {} in the contacts",
+            conflict.person, conflict.config, conflict.provider
+        );
+    }
+
+    if !apply || report.changes.is_empty() {
+        return Ok(());
+    }
+
+    let people = vcard::apply_changes(&config.people, &report.changes);
+    let transaction = Transaction::begin(config, "contacts sync");
+    let result = config::save_people(config, &people, &transaction);
+    let config_path = transaction.finish(result)?;
+    println!("Config updated: {config_path:?}");
+
+    Ok(())
+}
+
+fn export_caldav(config: &Config, dry_run: bool) -> Result<(), String> {
+    let log = log::read_config_logs(config)?;
+    let journals = caldav::log_to_journals(&log, config);
+
+    if dry_run {
+        for journal in journals.iter() {
+            print!("{}", journal.ics);
+        }
+        return Ok(());
+    }
+
+    let server = match &config.caldav {
+        Some(server) => server,
+        None => return Err("no calendar configured, add `caldav` to the config".to_string()),
+    };
+
+    let path = config.get_state_path();
+    let mut state = state::load_state(&path)?;
+    let exported: BTreeSet<String> = journals.iter().map(|journal| journal.uid.clone()).collect();
+    let gone: Vec<&String> = state.exported_journals.difference(&exported).collect();
+    if journals.is_empty() && gone.is_empty() {
+        println!("No entries to export.");
+        return Ok(());
+    }
+
+    // what was exported is remembered, which read-only mode would refuse
+    config.check_writable(&path)?;
+    let authorization = dav::authorization(server)?;
+    for journal in journals.iter() {
+        caldav::upload_journal(server, &authorization, journal)?;
+    }
+    for uid in gone.iter() {
+        caldav::delete_journal(server, &authorization, uid)?;
+    }
+    println!(
+        "{} journals exported to {}, {} deleted",
+        journals.len(),
+        server.url,
+        gone.len()
+    );
+
+    state.exported_journals = exported;
+    state::save_state(config, &state)
+}
+
+fn import_csv(config: &Config, path: PathBuf, map: ColumnMap) -> Result<(), String> {
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+
+    let imported = csv_import::parse_csv(&content, &map)?;
+    let transaction = Transaction::begin(config, "import csv");
+    let result = csv_import::merge_into_log_files(imported, config, &transaction);
+    let written = transaction.finish(result)?;
+    if written.is_empty() {
+        println!("Nothing to import, all entries are already in the logs.");
+    }
+
+    for (path, added) in written {
+        println!("{added} entries added to {path:?}");
+    }
+
+    Ok(())
+}
+
+fn import_chat(
+    config: &Config,
+    path: PathBuf,
+    person: PersonName,
+    format: ChatFormat,
+    full: bool,
+) -> Result<(), String> {
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+
+    let messages = chat_import::parse_chat(&content, format)?;
+    let kind = config.kinds.iter().find(|kind| kind.as_str() == "message");
+    let imported = chat_import::chat_log(&messages, &person, full, kind);
+    let transaction = Transaction::begin(config, "import chat");
+    let result = csv_import::merge_into_log_files(imported, config, &transaction);
+    let written = transaction.finish(result)?;
+    if written.is_empty() {
+        println!("Nothing to import, all days are already in the logs.");
+    }
+
+    for (path, added) in written {
+        println!("{added} entries added to {path:?}");
+    }
+
+    Ok(())
+}
+
+fn import_ics(
+    config: &Config,
+    path: PathBuf,
+    since: Option<NaiveDate>,
+    stubs: bool,
+) -> Result<(), String> {
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+
+    let log = log::read_config_logs(config)?;
+    let events = ics::parse_events(&content, config.timezone);
+    let spellings = Spellings::new(&log, config);
+    let meetings = ics::match_events(&events, &spellings, config, since);
+    let unlogged = ics::unlogged(meetings, &log);
+    if unlogged.is_empty() {
+        println!("Every meeting with known people is logged.");
+        return Ok(());
+    }
+
+    for meeting in unlogged.iter() {
+        println!("{meeting}");
+    }
+
+    if !stubs {
+        return Ok(());
+    }
+
+    let transaction = Transaction::begin(config, "import ics");
+    let result = csv_import::merge_into_log_files(ics::stubs(&unlogged), config, &transaction);
+    for (path, added) in transaction.finish(result)? {
+        println!("{added} entries added to {path:?}");
+    }
+
+    Ok(())
+}
+
+fn import_json(path: PathBuf, output: PathBuf) -> Result<(), String> {
+    if output.exists() {
+        return Err(format!("{output:?} already exists, choose a new directory"));
+    }
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+
+    let log = ndjson::from_ndjson(&content)?;
+    if let Err(reason) = fs::create_dir_all(&output) {
+        return Err(format!("failed to create {output:?}, reason: {reason}"));
+    }
+    for (name, content) in ndjson::markdown_files(log) {
+        let path = output.join(name);
+        files::write_atomically(&path, &content)?;
+        println!("Written {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn run_extension(config: &Config, name: String, args: Vec<String>) -> Result<(), String> {
+    let path_var = env::var_os("PATH").unwrap_or_default();
+    let executable = match ext::find_extension(&name, &path_var) {
+        Some(executable) => executable,
+        None => {
+            return Err(format!(
+                "no {} executable found on PATH",
+                ext::executable_name(&name)
+            ))
+        }
+    };
+
+    let config_path = match config::get_config_path() {
+        Ok(path) => path,
+        Err(_) => return Err("home directory not found".to_string()),
+    };
+
+    let log = log::read_config_logs(config)?;
+    let status = ext::run(&executable, &args, &config_path, &log)?;
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
+
+    Ok(())
+}
+
+/// `--today` if given, otherwise today in the configured timezone
+fn current_date(config: &Config, today: Option<NaiveDate>) -> NaiveDate {
+    today.unwrap_or_else(|| config.today())
+}
+
+fn run(command: Command, config: &Config, today: Option<NaiveDate>) -> Result<(), String> {
+    let date = current_date(config, today);
+    match command {
+        Command::Adherence => adherence(config, date),
+        Command::Goals => goals(config, date),
+        Command::Remind {
+            notify,
+            daemon,
+            snooze_days,
+            snooze: snooze_person,
+            action,
+        } => match action {
+            Some(RemindAction::Done { person }) => acknowledge(config, person, date),
+            Some(RemindAction::Snooze { person, duration }) => {
+                snooze(config, person, duration.duration().num_days(), date)
+            }
+            None => remind(config, notify, daemon, snooze_days, snooze_person, today),
+        },
+        Command::Show {
+            person,
+            last,
+            since,
+            kind,
+            format,
+            edit,
+            dates,
+        } => {
+            let (person, history) = shown_history(config, person, last, since, kind)?;
+            match edit {
+                true => edit_latest(&person, &history),
+                false => show(
+                    config,
+                    &person,
+                    &history,
+                    format,
+                    config.date_display(dates),
+                    date,
+                ),
+            }
+        }
+        Command::Edit { target } => edit_logs(config, target, date),
+        Command::Profile { person, format } => profile(config, person, format, date),
+        Command::Mutual { a, b } => mutual(config, a, b),
+        Command::Introductions { format, listing } => introductions(config, format, &listing),
+        Command::Pick { action, print } => pick(config, action, print, date),
+        Command::Prompt { count, seed } => prompt(config, count, seed),
+        Command::History { person, listing } => history(config, person, &listing),
+        Command::Facts { person, format } => facts(config, person, format),
+        Command::Followups {
+            person,
+            all,
+            format,
+            listing,
+        } => followups(config, person, all, format, &listing),
+        Command::GiftIdeas {
+            person,
+            upcoming,
+            format,
+            listing,
+        } => gift_ideas(config, person, upcoming, format, &listing, date),
+        Command::Anniversaries {
+            met,
+            upcoming,
+            listing,
+        } => anniversaries(config, met, upcoming, &listing, date),
+        Command::Field {
+            key,
+            person,
+            format,
+            listing,
+        } => field(config, key, person, format, &listing),
+        Command::Plan { format, action } => match action {
+            None => plan(config, format, date),
+            Some(PlanAction::Import {
+                path,
+                date: logged_on,
+            }) => import_plan(config, path, logged_on, date),
+        },
+        Command::Tiers => show_tiers(config, date),
+        Command::Digest {
+            period,
+            format,
+            send,
+        } => digest(config, period, format, send, date),
+        Command::Heatmap { person, format } => heatmap(config, person, format, date),
+        Command::Inactive {
+            over,
+            archive,
+            listing,
+        } => inactive(config, over, archive, &listing, date),
+        Command::Unarchive { person } => unarchive(config, person),
+        Command::Person { action } => person_action(config, action),
+        Command::Top {
+            last,
+            format,
+            listing,
+        } => top(config, last, format, &listing, date),
+        Command::Trends {
+            period,
+            format,
+            listing,
+        } => trends(config, period, format, &listing, date),
+        Command::Review { month, .. } => review(config, month, date),
+        Command::Dedupe { fix } => dedupe(config, fix),
+        Command::DedupeEntries { fix } => dedupe_entries(config, fix),
+        Command::Rename { old, new, dry_run } => rename(config, old, new, dry_run),
+        Command::Fmt { check } => fmt(config, check),
+        Command::MergeFiles {
+            ours,
+            theirs,
+            base,
+            output,
+        } => formatter::merge_files(base.as_deref(), &ours, &theirs, &output),
+        Command::VerifyNames { fix } => verify_names(config, fix),
+        Command::SuggestTags { fix } => suggest_tags(config, fix),
+        Command::Undo { dry_run } => undo(config, dry_run),
+        Command::Contact { person, open } => show_contact(config, person, open),
+        Command::Where { person } => show_where(config, person),
+        Command::Near { location } => show_near(config, location),
+        Command::Trip { location, from, to } => {
+            let from = from.unwrap_or(date);
+            trip(config, location, from, to.unwrap_or(from))
+        }
+        Command::Links {
+            person,
+            person_flag,
+            check,
+        } => match check {
+            true => check_attachments(config),
+            false => show_links(config, person.or(person_flag)),
+        },
+        Command::Rollup { week } => write_rollup(config, week, date),
+        Command::Metrics { output } => print_metrics(config, output, date),
+        Command::Notify {
+            channel,
+            quiet_hours,
+        } => notify(config, channel, quiet_hours, date),
+        Command::ScanMail { path, since } => scan_mail(config, path, since, date),
+        Command::Hooks { action } => match action {
+            HooksAction::Run { dry_run } => run_hooks(config, dry_run, date),
+        },
+        Command::Serve { port, host, watch } => serve(config, host, port, watch, today),
+        Command::Publish { output } => publish(config, output, date),
+        Command::Completions { people, .. } => completions(config, people),
+        Command::Sync { target } => match target {
+            SyncTarget::Contacts { apply } => sync_contacts(config, apply),
+        },
+        Command::Export {
+            target,
+            anonymize,
+            output,
+            salt,
+        } => match (target, output) {
+            (Some(ExportTarget::Caldav { dry_run }), _) => export_caldav(config, dry_run),
+            (Some(ExportTarget::Json { output }), _) => export_json(config, output),
+            (None, Some(output)) if anonymize => export_anonymized(config, output, salt),
+            (None, _) => Err("expected an export target or --anonymize".to_string()),
+        },
+        Command::Import { source } => match source {
+            ImportSource::Vcard { path, dry_run } => import_vcard(config, path, dry_run),
+            ImportSource::Csv { path, map } => import_csv(config, path, map),
+            ImportSource::Chat {
+                path,
+                person,
+                format,
+                full,
+            } => import_chat(config, path, person, format, full),
+            ImportSource::Ics { path, since, stubs } => import_ics(config, path, since, stubs),
+            ImportSource::Json { path, output } => import_json(path, output),
+        },
+        Command::Backup { dir } => backup(config, dir),
+        Command::Restore { archive, force } => restore(archive, force),
+        Command::Bench {
+            synthetic,
+            days,
+            people,
+            runs,
+        } => run_bench(config, synthetic, days, people, runs),
+        Command::Config { action } => config_action(action),
+        Command::Ext {
+            extension: Extension::Run(mut args),
+        } => {
+            let name = args.remove(0);
+            run_extension(config, name, args)
+        }
+    }
+}
+
+/// Commands that keep running or write to a single place can't be repeated per profile
+fn supports_all_profiles(command: &Command) -> bool {
+    !matches!(
+        command,
+        Command::Serve { .. }
+            | Command::Metrics { .. }
+            | Command::Publish { .. }
+            | Command::Completions { .. }
+            | Command::Restore { .. }
+            | Command::Config { .. }
+            | Command::Person { .. }
+            | Command::MergeFiles { .. }
+            | Command::Remind { daemon: true, .. }
+            | Command::Remind {
+                action: Some(_),
+                ..
+            }
+    )
+}
+
+fn run_all_profiles(
+    command: Command,
+    config: &Config,
+    today: Option<NaiveDate>,
+) -> Result<(), String> {
+    if !supports_all_profiles(&command) {
+        return Err("this command does not support --all-profiles".to_string());
+    }
+
+    let mut failed = vec![];
+    for (index, (name, profile_config)) in config.all_profiles().into_iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        println!("==> {name} <==");
+        // stdout and stderr interleave in the terminal, keep the header first
+        let _ = io::stdout().flush();
+
+        if let Err(reason) = run(command.clone(), &profile_config, today) {
+            eprintln!("ERROR: {reason}");
+            failed.push(name);
+        }
+    }
+
+    match failed.is_empty() {
+        true => Ok(()),
+        false => Err(format!("failed for profiles: {}", failed.join(", "))),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    logging::init(&cli.logging);
+
+    // completion scripts are set up before there is any config
+    if let Command::Completions {
+        shell: Some(shell), ..
+    } = cli.command
+    {
+        print!(
+            "{}",
+            completions::completion_script(shell, &mut Cli::command())
+        );
+        return;
+    }
+
+    // restoring is how a config gets back in place, validating is how it gets
+    // fixed, and git runs merge drivers wherever, so none can need one
+    let writes_without_config = matches!(
+        &cli.command,
+        Command::Restore { .. }
+            | Command::MergeFiles { .. }
+            | Command::Config {
+                action: ConfigAction::Migrate { dry_run: false, .. }
+            }
+    );
+    if cli.read_only && writes_without_config {
+        eprintln!("ERROR: this command writes files, which --read-only does not allow");
+        process::exit(2);
+    }
+    let result = match &cli.command {
+        Command::Restore { archive, force } => Some(restore(archive.clone(), *force)),
+        Command::Config { action } => Some(config_action(action.clone())),
+        Command::MergeFiles {
+            ours,
+            theirs,
+            base,
+            output,
+        } => Some(formatter::merge_files(
+            base.as_deref(),
+            ours,
+            theirs,
+            output,
+        )),
+        _ => None,
+    };
+    if let Some(result) = result {
+        if let Err(reason) = result {
+            eprintln!("ERROR: {reason}");
+            process::exit(2);
+        }
+        return;
+    }
+
+    info!("Loading config...");
+    let mut config = match config::get_profile_config(cli.profile.as_deref()) {
+        Ok(config) => config,
+        Err(reason) => {
+            eprintln!("ERROR: {}", reason);
+            process::exit(1);
+        }
+    };
+
+    if cli.read_only {
+        config.read_only = true;
+    }
+
+    if let Some(path) = &cli.input {
+        match LogInput::read(path) {
+            Ok(input) => config.input = Some(input),
+            Err(reason) => {
+                eprintln!("ERROR: {reason}");
+                process::exit(1);
+            }
+        }
+    }
+
+    let result = match cli.all_profiles {
+        true => run_all_profiles(cli.command, &config, cli.today),
+        false => run(cli.command, &config, cli.today),
+    };
+
+    if let Err(reason) = result {
+        eprintln!("ERROR: {reason}");
+        process::exit(2);
+    }
+}
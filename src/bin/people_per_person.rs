@@ -1,15 +1,83 @@
 use std::process;
 
+use clap::Parser;
 use people::use_cases;
-use people::use_cases::LogWritten;
+use people::use_cases::{LogWritten, PlannedWrite};
 use tracing::info;
 
-use people::config;
+use people::config::{self, Config, IndexSort, LogOrder};
+use people::git;
 use people::log;
+use people::log::ParseOptions;
+use people::logging::{self, LoggingArgs};
+use people::model::PersonName;
+use people::operations::Transaction;
+use people::people_index;
+
+/// Write a log per person, with every entry they are mentioned in
+#[derive(Parser)]
+struct Cli {
+    /// Show which files would be written, changed or deleted without touching them
+    #[arg(long)]
+    dry_run: bool,
+    /// Like --dry-run, but also print a unified diff per changed file
+    #[arg(long)]
+    diff: bool,
+    /// Files written at the same time (default: one per CPU, up to 8)
+    #[arg(long, value_name = "N")]
+    jobs: Option<usize>,
+    /// Use the people directory and people of this profile in the config
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+    /// `oldest-first` or `newest-first` (default: `per_person.order` in the config)
+    #[arg(long, value_name = "ORDER")]
+    order: Option<LogOrder>,
+    /// Only the most recent N entries of each person
+    #[arg(long, value_name = "N")]
+    last_entries: Option<usize>,
+    /// Only the entries of the last N years
+    #[arg(long, value_name = "N")]
+    last_years: Option<u32>,
+    /// Start each log with when the person was last seen and how often
+    #[arg(long)]
+    summary_header: bool,
+    /// Also write an index.md that links to everyone's log
+    #[arg(long)]
+    index: bool,
+    /// Order of the index: `name`, `last` or `entries`
+    #[arg(long, value_name = "KEY")]
+    index_sort: Option<IndexSort>,
+    #[command(flatten)]
+    logging: LoggingArgs,
+}
+
+fn preview(per_person_logs: Vec<(PersonName, Option<log::Log>)>, config: &Config, diff: bool) {
+    for (person, person_log) in per_person_logs {
+        let planned = match use_cases::plan_person_log(person, person_log, config) {
+            Ok(planned) => planned,
+            Err(reason) => {
+                eprintln!("ERROR: {reason}");
+                continue;
+            }
+        };
+
+        if matches!(planned, PlannedWrite::Unchanged(_)) && !diff {
+            continue;
+        }
+
+        eprintln!("{planned}");
+        if diff {
+            print!("{}", planned.diff());
+        }
+    }
+}
 
 fn main() {
+    let cli = Cli::parse();
+    logging::init(&cli.logging);
+
     info!("Loading config...");
-    let config = match config::get_config() {
+    let mut config = match config::get_profile_config(cli.profile.as_deref()) {
         Ok(config) => config,
         Err(reason) => {
             eprintln!("ERROR: {}", reason);
@@ -17,19 +85,95 @@ fn main() {
         }
     };
 
-    let log = log::read_logs(&config.people_dir);
+    if let Some(order) = cli.order {
+        config.per_person.order = order;
+    }
+    if cli.last_entries.is_some() {
+        config.per_person.last_entries = cli.last_entries;
+    }
+    if cli.last_years.is_some() {
+        config.per_person.last_years = cli.last_years;
+    }
+    if cli.summary_header {
+        config.per_person.summary_header = true;
+    }
+    if cli.index {
+        config.per_person.index = true;
+    }
+    if let Some(index_sort) = cli.index_sort {
+        config.per_person.index_sort = index_sort;
+    }
+
+    let log = match log::read_logs(&config, &ParseOptions::from_config(&config)) {
+        Ok(log) => log,
+        Err(reason) => {
+            eprintln!("ERROR: {reason}");
+            process::exit(1);
+        }
+    };
     let per_person_logs = use_cases::split_log_per_person(log, &config);
-    for (person, person_log) in per_person_logs {
-        match use_cases::write_person_log(person, person_log, config.get_per_person_dir()) {
-            LogWritten::Written(path) => eprintln!("Report written to {path:#?}"),
+    if per_person_logs.is_empty() {
+        eprintln!("No people found in logs, nothing to write");
+        return;
+    }
+
+    if cli.dry_run || cli.diff {
+        let mut per_person_logs: Vec<(PersonName, Option<log::Log>)> =
+            per_person_logs.into_iter().collect();
+        per_person_logs.sort_by(|a, b| a.0.cmp(&b.0));
+        preview(per_person_logs, &config, cli.diff);
+        return;
+    }
+
+    if let Err(reason) = config.check_writable(&config.people_dir) {
+        eprintln!("ERROR: {reason}");
+        process::exit(1);
+    }
+
+    let jobs = cli.jobs.unwrap_or_else(use_cases::default_jobs);
+    let (mut written, mut unchanged, mut deleted) = (0, 0, 0);
+    let transaction = Transaction::begin(&config, "people_per_person");
+    let index = match config.per_person.index {
+        true => Some(people_index::index_rows(&per_person_logs, &config)),
+        false => None,
+    };
+    let mut results = use_cases::write_person_logs(per_person_logs, &config, jobs, &transaction);
+    if let Some(rows) = index {
+        results.push(people_index::write_people_index(
+            &rows,
+            &config,
+            &transaction,
+        ));
+    }
+    for result in results.iter() {
+        match result {
+            LogWritten::Written(path) => {
+                written += 1;
+                eprintln!("Report written to {path:#?}")
+            }
+            LogWritten::Unchanged(_) => unchanged += 1,
             LogWritten::FailedToWrite(path, reason) => {
                 eprintln!("ERROR: failed to write {path:#?}  --  reason: {reason}")
             }
             LogWritten::NothingToDelete(path) => eprintln!("Nothing to delete: {path:#?}"),
-            LogWritten::Deleted(path) => eprintln!("Report deleted: {path:#?}"),
+            LogWritten::Deleted(path) => {
+                deleted += 1;
+                eprintln!("Report deleted: {path:#?}")
+            }
             LogWritten::FailedToDelete(path, reason) => {
                 eprintln!("{path:#?}  --  reason: {reason}")
             }
         }
     }
+
+    eprintln!("{written} written, {unchanged} unchanged, {deleted} deleted");
+    if let Err(reason) = transaction.commit() {
+        eprintln!("ERROR: failed to record the changes for `people undo`, reason: {reason}");
+    }
+
+    match git::commit_per_person_logs(&config, &results) {
+        Ok(true) => eprintln!("Per-person logs committed"),
+        Ok(false) => {}
+        Err(reason) => eprintln!("ERROR: failed to commit the per-person logs, reason: {reason}"),
+    }
 }
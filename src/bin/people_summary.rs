@@ -1,165 +1,188 @@
-use std::collections::HashSet;
+use std::path::PathBuf;
 use std::process;
 
-use people::config;
+use people::config::{self, MentionLevel, SummaryGrouping};
+use people::dates::DateStyle;
+use people::listing::ListingArgs;
 use people::log;
-use people::model::DaysAgo;
-use people::model::PersonName;
+use people::logging::{self, LoggingArgs};
+use people::model::InteractionKind;
+use people::render::{self, SummaryContext};
+use people::state;
+use people::summary::{self, Column, InteractionStats, SummarySort, TableOptions};
 use people::use_cases;
 use people::use_cases::LastInteraction;
 use tracing::info;
 
-use chrono::Local;
-use tabular::{Row, Table};
+use chrono::NaiveDate;
+use clap::Parser;
 
 fn discard_ignored(
     interactions: Vec<LastInteraction>,
     config: &config::Config,
 ) -> Vec<LastInteraction> {
-    let mut ignored: HashSet<PersonName> = HashSet::new();
-    for person in &config.ignore {
-        ignored.insert(person.clone());
-    }
-
     interactions
         .into_iter()
-        .filter(|interaction| ignored.contains(&interaction.person) == false)
+        .filter(|interaction| !config.is_ignored(&interaction.person))
         .collect()
 }
 
-type BoundaryOffset = usize;
-type Boundary = i64;
-
-struct Spacer {
-    boundaries: Vec<Boundary>,
-    next_boundary_offset: Option<BoundaryOffset>,
+/// Show when you last interacted with each person
+#[derive(Parser)]
+struct Cli {
+    /// Use the people directory and people of this profile in the config
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+    /// Pretend today is this date, e.g. 2024-05-01
+    #[arg(long, value_name = "DATE")]
+    today: Option<NaiveDate>,
+    /// Only count interactions of this kind, e.g. `met`
+    #[arg(long)]
+    kind: Option<InteractionKind>,
+    /// Group people by `days-ago` or by `month` of their last interaction,
+    /// instead of the `summary.group_by` in the config
+    #[arg(long, value_name = "GROUPING")]
+    group_by: Option<SummaryGrouping>,
+    /// Which people of an entry it counts as an interaction with: `main`
+    /// (first line), `related` (other lines) or `both`, instead of the
+    /// `summary.count` in the config
+    #[arg(long, value_name = "LEVEL")]
+    count: Option<MentionLevel>,
+    /// Sort people by `name`, `last` interaction or how `overdue` they are
+    #[arg(long, default_value = "last")]
+    sort: SummarySort,
+    /// Reverse the order of the people
+    #[arg(long)]
+    reverse: bool,
+    /// Comma-separated columns to show: days-ago, person, last, overdue,
+    /// location, themes, count, activity and mail
+    #[arg(long, value_delimiter = ',', value_name = "COLUMNS")]
+    columns: Vec<Column>,
+    /// Show people overdue a reach out, per their `remind_after`, right after
+    /// the pinned ones
+    #[arg(long)]
+    overdue_first: bool,
+    /// How to show dates: `absolute`, as `date_format` in the config, or
+    /// `relative`, e.g. `3 days ago`. `--plain` always shows ISO dates.
+    #[arg(long, value_name = "STYLE", default_value = "absolute")]
+    dates: DateStyle,
+    /// Add the amount of interactions with each person and a sparkline of
+    /// the last 12 weeks
+    #[arg(long)]
+    stats: bool,
+    #[command(flatten)]
+    listing: ListingArgs,
+    /// Read this log file, or stdin if `-`, instead of the logs in the people directory
+    #[arg(long, value_name = "PATH")]
+    input: Option<PathBuf>,
+    #[command(flatten)]
+    logging: LoggingArgs,
 }
 
-impl Spacer {
-    fn new(boundaries: Vec<Boundary>) -> Spacer {
-        let no_boundaries = *(&boundaries.is_empty());
-
-        Spacer {
-            boundaries,
-            next_boundary_offset: if no_boundaries { None } else { Some(0) },
-        }
-    }
+fn main() {
+    let cli = Cli::parse();
+    logging::init(&cli.logging);
 
-    fn next_boundary(&self) -> Option<Boundary> {
-        match self.next_boundary_offset {
-            Some(offset) => {
-                if let Some(boundary_ref) = self.boundaries.get(offset) {
-                    return Some(boundary_ref.clone());
-                } else {
-                    return None;
-                }
-            }
-            None => return None,
+    info!("Loading config...");
+    let mut config = match config::get_profile_config(cli.profile.as_deref()) {
+        Ok(config) => config,
+        Err(reason) => {
+            eprintln!("ERROR: {}", reason);
+            process::exit(1);
         }
-    }
+    };
 
-    fn jump_to_next_boundary(&mut self) -> () {
-        let last_offset = match self.next_boundary_offset {
-            Some(offset) => offset,
-            None => {
-                self.next_boundary_offset = None;
-                return;
+    if let Some(path) = &cli.input {
+        match log::LogInput::read(path) {
+            Ok(input) => config.input = Some(input),
+            Err(reason) => {
+                eprintln!("ERROR: {reason}");
+                process::exit(1);
             }
-        };
-
-        let new_offset = last_offset + 1;
-
-        if new_offset < self.boundaries.len() {
-            self.next_boundary_offset = Some(new_offset);
-        } else {
-            self.next_boundary_offset = None;
         }
     }
 
-    fn should_show_space(&mut self, current: DaysAgo) -> bool {
-        let next_boundary = match self.next_boundary() {
-            None => return false,
-            Some(boundary) => boundary,
-        };
-
-        let show_space = current >= next_boundary;
-
-        if show_space {
-            self.jump_to_next_boundary();
-        }
-
-        show_space
+    if let Some(group_by) = cli.group_by {
+        config.summary.group_by = group_by;
     }
-}
-
-fn format_last_interactions(interactions: Vec<LastInteraction>) -> String {
-    let today = Local::now().naive_local().date();
-
-    let mut sorted_interactions = interactions.clone();
-    sorted_interactions.sort_by_key(|interaction| interaction.last);
-    sorted_interactions.reverse();
-
-    let mut table = Table::new("{:>}  {:<}  {:<}    {:<}");
-    table.add_row(
-        Row::new()
-            .with_cell("Days ago")
-            .with_cell("PERSON")
-            .with_cell("LAST")
-            .with_cell("reach out"),
-    );
-
-    let empty_row = Row::new()
-        .with_cell("")
-        .with_cell("")
-        .with_cell("")
-        .with_cell("");
-
-    let mut spacer = Spacer::new(vec![7, 14, 28]);
-
-    for interaction in sorted_interactions {
-        let ago = interaction.ago(today);
-        if spacer.should_show_space(ago) {
-            table.add_row(empty_row.clone());
-        }
-
-        let mut reach_out: String = "".to_string();
-        if let Some(days_to_reminder) = interaction.days_beyond_reachout_threshold {
-            reach_out = format!("{days_to_reminder} days ago");
-        }
-
-        table.add_row(
-            Row::new()
-                .with_cell(ago)
-                .with_cell(interaction.person)
-                .with_cell(interaction.last)
-                .with_cell(reach_out),
-        );
+    if let Some(count) = cli.count {
+        config.summary.count = count;
     }
 
-    format!("{table}")
-}
-
-fn main() {
-    info!("Loading config...");
-    let config = match config::get_config() {
-        Ok(config) => config,
+    let today = cli.today.unwrap_or_else(|| config.today());
+    let log = match log::read_config_logs(&config) {
+        Ok(log) => log,
         Err(reason) => {
-            eprintln!("ERROR: {}", reason);
+            eprintln!("ERROR: {reason}");
             process::exit(1);
         }
     };
+    let log = match &cli.kind {
+        Some(kind) => log.with_kind(kind),
+        None => log,
+    };
 
-    let log = log::read_logs(&config.people_dir);
-
-    let all_without_reminders = use_cases::get_last_interactions(&log);
-    let all_with_reminders = match use_cases::identify_reachouts(all_without_reminders, &config) {
-        Ok(r) => r,
+    let mut state = match state::load_state(&config.get_state_path()) {
+        Ok(state) => state,
         Err(reason) => {
-            eprintln!("{reason}");
-            process::exit(2);
+            eprintln!("ERROR: {reason}");
+            process::exit(1);
         }
     };
+    let all_without_reminders = use_cases::get_last_interactions_at(&log, config.summary.count);
+    let all_without_reminders = use_cases::apply_acknowledgments(all_without_reminders, &mut state);
+    let all_with_reminders = use_cases::identify_reachouts(all_without_reminders, &config, today);
     let desired = discard_ignored(all_with_reminders, &config);
-    let summary = format_last_interactions(desired);
-    println!("{summary}");
+    let (pinned, rest) = use_cases::split_pinned(desired, &config);
+    let never_seen = use_cases::pinned_never_seen(&pinned, &config);
+
+    let summary = match &config.templates.summary {
+        Some(template) => {
+            let context = SummaryContext::new(
+                &summary::sort_interactions(pinned, cli.sort, cli.reverse),
+                &never_seen,
+                &summary::sort_interactions(rest, cli.sort, cli.reverse),
+                today,
+            );
+            match render::render_file(template, &context) {
+                Ok(rendered) => rendered,
+                Err(reason) => {
+                    eprintln!("ERROR: {reason}");
+                    process::exit(2);
+                }
+            }
+        }
+        None => {
+            let mut columns = match cli.columns.is_empty() {
+                true => Column::defaults(),
+                false => cli.columns.clone(),
+            };
+            // emails found by `people scan-mail`, next to the logged interactions
+            if cli.columns.is_empty() && !state.passive.is_empty() {
+                columns.push(Column::Mail);
+            }
+            if cli.stats {
+                for column in [Column::Count, Column::Activity] {
+                    if !columns.contains(&column) {
+                        columns.push(column);
+                    }
+                }
+            }
+
+            let options = TableOptions {
+                columns,
+                sort: cli.sort,
+                reverse: cli.reverse,
+                overdue_first: cli.overdue_first,
+                listing: cli.listing.clone(),
+                dates: config.date_display(cli.dates),
+            };
+            let stats = InteractionStats::new(&log, today).with_passive(&state.passive);
+            summary::format_table(pinned, never_seen, rest, &options, &config, &stats, today)
+        }
+    };
+    match cli.listing.plain {
+        true => print!("{summary}"),
+        false => println!("{summary}"),
+    }
 }
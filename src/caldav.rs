@@ -0,0 +1,244 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use crate::config::{Config, DavConfig};
+use crate::dav;
+use crate::log::{Entry, Log};
+use crate::model::PersonName;
+
+static MAX_LINE_LENGTH: usize = 75;
+
+/// A log entry rendered as an iCalendar VJOURNAL
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Journal {
+    pub uid: String,
+    pub ics: String,
+}
+
+/// FNV-1a, which unlike `DefaultHasher` is stable across Rust releases
+fn stable_hash(text: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// The UID comes from where the entry is rather than from what it says, so
+/// exporting twice updates the existing journals, even of edited entries,
+/// instead of duplicating them. `position` counts the entries of the date in
+/// the file.
+fn entry_uid(date: NaiveDate, source_file: &str, position: usize) -> String {
+    let hash = stable_hash(&format!("{source_file}\n{position}"));
+    format!("people-{date}-{hash:016x}")
+}
+
+/// The log file relative to the people directory, with `/` separators, so
+/// that it is the same on every machine
+fn source_key(path: Option<&Path>, people_dir: &Path) -> String {
+    let Some(path) = path else {
+        return "".to_string();
+    };
+    let relative = path.strip_prefix(people_dir).unwrap_or(path);
+    let parts: Vec<String> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect();
+    parts.join("/")
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Split lines longer than 75 bytes, continuation lines start with a space
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+
+    for ch in line.chars() {
+        if length + ch.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(ch);
+        length += ch.len_utf8();
+    }
+
+    folded
+}
+
+fn sorted(people: &HashSet<PersonName>) -> Vec<PersonName> {
+    let mut people: Vec<PersonName> = people.iter().cloned().collect();
+    people.sort();
+    people
+}
+
+pub fn entry_to_journal(uid: String, date: NaiveDate, entry: &Entry) -> Journal {
+    let day = date.format("%Y%m%d");
+    let categories: Vec<String> = sorted(&entry.related)
+        .iter()
+        .map(|person| escape_text(person))
+        .collect();
+
+    let lines: Vec<String> = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//people//people//EN".to_string(),
+        "BEGIN:VJOURNAL".to_string(),
+        format!("UID:{uid}"),
+        format!("DTSTAMP:{day}T000000Z"),
+        format!("DTSTART;VALUE=DATE:{day}"),
+        format!("SUMMARY:{}", escape_text(&sorted(&entry.main).join(", "))),
+        format!("DESCRIPTION:{}", escape_text(&entry.content)),
+        format!("CATEGORIES:{}", categories.join(",")),
+        "END:VJOURNAL".to_string(),
+        "END:VCALENDAR".to_string(),
+    ];
+
+    let ics: String = lines
+        .iter()
+        .map(|line| format!("{}\r\n", fold_line(line)))
+        .collect();
+
+    Journal { uid, ics }
+}
+
+/// Render every entry that is not only about ignored people
+pub fn log_to_journals(log: &Log, config: &Config) -> Vec<Journal> {
+    let mut positions: HashMap<(NaiveDate, String), usize> = HashMap::new();
+    let mut journals: Vec<Journal> = vec![];
+    for day in log.days.iter() {
+        for entry in day.entries.iter() {
+            // ignoring someone does not move the entries after theirs
+            let source_file = source_key(entry.source_file.as_deref(), &config.people_dir);
+            let position = positions
+                .entry((day.date, source_file.clone()))
+                .or_default();
+            let uid = entry_uid(day.date, &source_file, *position);
+            *position += 1;
+
+            if entry.main.iter().all(|person| config.is_ignored(person)) {
+                continue;
+            }
+            journals.push(entry_to_journal(uid, day.date, entry));
+        }
+    }
+
+    journals
+}
+
+/// Create or update the journal in the CalDAV calendar
+pub fn upload_journal(
+    server: &DavConfig,
+    authorization: &str,
+    journal: &Journal,
+) -> Result<(), String> {
+    let url = dav::resource_url(&server.url, &format!("{}.ics", journal.uid));
+
+    match ureq::put(&url)
+        .set("Authorization", authorization)
+        .set("Content-Type", "text/calendar; charset=utf-8")
+        .send_string(&journal.ics)
+    {
+        Ok(_) => Ok(()),
+        Err(reason) => Err(format!("failed to upload {url}, reason: {reason}")),
+    }
+}
+
+/// Delete a journal exported before, if it is still there
+pub fn delete_journal(server: &DavConfig, authorization: &str, uid: &str) -> Result<(), String> {
+    let url = dav::resource_url(&server.url, &format!("{uid}.ics"));
+
+    match ureq::delete(&url)
+        .set("Authorization", authorization)
+        .call()
+    {
+        Ok(_) | Err(ureq::Error::Status(404, _)) => Ok(()),
+        Err(reason) => Err(format!("failed to delete {url}, reason: {reason}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_entry_to_journal() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-02
+
+            - #JohnDoe :
+              - stuff: blah, bleh #Bleh
+            "
+        ));
+        let entry = &log.days[0].entries[0];
+
+        let journal = entry_to_journal("people-2000-01-02-1".to_string(), d("2000-01-02"), entry);
+
+        let expected = [
+            "BEGIN:VCALENDAR",
+            "VERSION:2.0",
+            "PRODID:-//people//people//EN",
+            "BEGIN:VJOURNAL",
+            "UID:people-2000-01-02-1",
+            "DTSTAMP:20000102T000000Z",
+            "DTSTART;VALUE=DATE:20000102",
+            "SUMMARY:JohnDoe",
+            "DESCRIPTION:- #JohnDoe :\\n  - stuff: blah\\, bleh #Bleh",
+            "CATEGORIES:Bleh,JohnDoe",
+            "END:VJOURNAL",
+            "END:VCALENDAR",
+            "",
+        ]
+        .join("\r\n");
+
+        assert_eq!(journal.ics, expected);
+    }
+
+    #[test]
+    fn test_uid_is_stable() {
+        let config = Config::builder().people_dir("/people").build();
+        let uids = |content: &str| -> Vec<String> {
+            let mut log = parse_log_file_content(content);
+            for entry in log.days.iter_mut().flat_map(|day| day.entries.iter_mut()) {
+                Arc::make_mut(entry).source_file = Some("/people/2000-people.md".into());
+            }
+            log_to_journals(&log, &config)
+                .into_iter()
+                .map(|journal| journal.uid)
+                .collect()
+        };
+
+        let before = uids("# 2000-01-02\n\n- #JohnDoe :\n\n- #JaneDoe :\n");
+        let after = uids("# 2000-01-02\n\n- #JohnDoe : lunch\n\n- #JaneDoe :\n");
+
+        assert_eq!(before[0], "people-2000-01-02-910164101ce3c772");
+        assert_ne!(before[0], before[1]);
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_fold_long_lines() {
+        let line = "x".repeat(80);
+
+        let expected = format!("{}\r\n {}", "x".repeat(75), "x".repeat(5));
+
+        assert_eq!(fold_line(&line), expected);
+    }
+}
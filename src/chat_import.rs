@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use regex::Regex;
+
+use crate::links;
+use crate::log::{DatePrecision, Day, Entry, Log};
+use crate::model::{InteractionKind, PersonName};
+
+/// `31/12/2023, 21:15 - Jane Doe: hi` or `[31/12/2023, 21:15:03] Jane Doe: hi`
+static WHATSAPP_PATTERN: &str = r"^\[?(\d{1,2})[/.](\d{1,2})[/.](\d{2,4}),? (\d{1,2}:\d{2})(?::\d{2})?(?:\s?[APap]\.?[Mm]\.?)?\]?(?: -)? (.*)$";
+/// `[2023-12-31 21:15] Jane Doe: hi`, as written by signal-export
+static SIGNAL_PATTERN: &str = r"^\[(\d{4}-\d{2}-\d{2}) (\d{1,2}:\d{2})(?::\d{2})?\] (.*)$";
+
+/// App the chat was exported from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChatFormat {
+    WhatsApp,
+    Signal,
+}
+
+impl FromStr for ChatFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "whatsapp" => Ok(ChatFormat::WhatsApp),
+            "signal" => Ok(ChatFormat::Signal),
+            other => Err(format!(
+                "expected 'whatsapp' or 'signal', but found {other:?}"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChatMessage {
+    pub date: NaiveDate,
+    pub time: String,
+    pub sender: String,
+    pub text: String,
+}
+
+/// A message line before its date is read, as the day and month order of
+/// WhatsApp exports depends on the phone's locale
+struct RawMessage {
+    date: (u32, u32, i32),
+    time: String,
+    sender: String,
+    text: String,
+}
+
+fn parse_year(year: &str) -> i32 {
+    let year: i32 = year.parse().unwrap_or(0);
+    match year < 100 {
+        true => 2000 + year,
+        false => year,
+    }
+}
+
+/// Split `Jane Doe: hi`, or nothing for notices like `Messages are encrypted`
+fn split_sender(rest: &str) -> Option<(String, String)> {
+    let (sender, text) = rest.split_once(": ")?;
+    Some((sender.trim().to_string(), text.trim().to_string()))
+}
+
+fn parse_raw_messages(content: &str, format: ChatFormat) -> Vec<RawMessage> {
+    let pattern = match format {
+        ChatFormat::WhatsApp => WHATSAPP_PATTERN,
+        ChatFormat::Signal => SIGNAL_PATTERN,
+    };
+    let message_re = Regex::new(pattern).unwrap();
+
+    let mut messages: Vec<RawMessage> = vec![];
+    // notices are skipped, and so are their continuation lines
+    let mut in_message = false;
+    for line in content.lines() {
+        let line = line.trim_start_matches(['\u{feff}', '\u{200e}']);
+        let cap = match message_re.captures(line) {
+            Some(cap) => cap,
+            None => {
+                if let (true, Some(last)) = (in_message, messages.last_mut()) {
+                    if !line.trim().is_empty() {
+                        last.text.push(' ');
+                        last.text.push_str(line.trim());
+                    }
+                }
+                continue;
+            }
+        };
+
+        let (date, time, rest) = match format {
+            ChatFormat::WhatsApp => (
+                (
+                    cap[1].parse().unwrap_or(0),
+                    cap[2].parse().unwrap_or(0),
+                    parse_year(&cap[3]),
+                ),
+                cap[4].to_string(),
+                cap[5].to_string(),
+            ),
+            ChatFormat::Signal => {
+                let date = &cap[1];
+                (
+                    (
+                        date[8..10].parse().unwrap_or(0),
+                        date[5..7].parse().unwrap_or(0),
+                        date[..4].parse().unwrap_or(0),
+                    ),
+                    cap[2].to_string(),
+                    cap[3].to_string(),
+                )
+            }
+        };
+
+        in_message = match split_sender(&rest) {
+            Some((sender, text)) => {
+                messages.push(RawMessage {
+                    date,
+                    time,
+                    sender,
+                    text,
+                });
+                true
+            }
+            None => false,
+        };
+    }
+    messages
+}
+
+/// The messages of an exported chat. WhatsApp dates are read day first, unless
+/// that would put a month beyond 12, e.g. `12/31/23`.
+pub fn parse_chat(content: &str, format: ChatFormat) -> Result<Vec<ChatMessage>, String> {
+    let raw = parse_raw_messages(content, format);
+    let month_first = format == ChatFormat::WhatsApp
+        && raw.iter().any(|message| message.date.1 > 12)
+        && raw.iter().all(|message| message.date.0 <= 12);
+
+    let mut messages: Vec<ChatMessage> = vec![];
+    for message in raw {
+        let (first, second, year) = message.date;
+        let (day, month) = match month_first {
+            true => (second, first),
+            false => (first, second),
+        };
+        let date = match NaiveDate::from_ymd_opt(year, month, day) {
+            Some(date) => date,
+            None => {
+                return Err(format!(
+                    "unsupported date found: day {day}, month {month}, year {year}"
+                ))
+            }
+        };
+        messages.push(ChatMessage {
+            date,
+            time: message.time,
+            sender: message.sender,
+            text: message.text,
+        });
+    }
+
+    match messages.is_empty() {
+        true => Err("no messages found, is it the right --format?".to_string()),
+        false => Ok(messages),
+    }
+}
+
+fn day_entry(
+    person: &PersonName,
+    messages: &[&ChatMessage],
+    full: bool,
+    kind: Option<&InteractionKind>,
+) -> Entry {
+    let mut lines: Vec<String> = vec![match kind {
+        Some(kind) => format!("- #{person} ({kind}):"),
+        None => format!("- #{person} :"),
+    }];
+
+    match full {
+        true => {
+            for message in messages {
+                lines.push(format!(
+                    "  - {} {}: {}",
+                    message.time, message.sender, message.text
+                ));
+            }
+        }
+        false => {
+            let mut senders: BTreeMap<&str, usize> = BTreeMap::new();
+            for message in messages {
+                *senders.entry(message.sender.as_str()).or_default() += 1;
+            }
+            let counts: Vec<String> = senders
+                .iter()
+                .map(|(sender, count)| format!("{count} from {sender}"))
+                .collect();
+            let total = match messages.len() {
+                1 => "1 message".to_string(),
+                count => format!("{count} messages"),
+            };
+            lines.push(format!("  - {total}: {}", counts.join(", ")));
+        }
+    }
+
+    let content = lines.join("\n");
+    Entry {
+        main: [person.clone()].into(),
+        related: [person.clone()].into(),
+        links: links::find_links(&content),
+        content,
+        kind: kind.cloned(),
+        fields: BTreeMap::new(),
+        source_file: None,
+        line_range: 0..0,
+    }
+}
+
+/// An entry with the person per day of the chat, with the amount of messages
+/// of each sender or, if `full`, every message
+pub fn chat_log(
+    messages: &[ChatMessage],
+    person: &PersonName,
+    full: bool,
+    kind: Option<&InteractionKind>,
+) -> Log {
+    let mut per_day: BTreeMap<NaiveDate, Vec<&ChatMessage>> = BTreeMap::new();
+    for message in messages {
+        per_day.entry(message.date).or_default().push(message);
+    }
+
+    let days = per_day
+        .into_iter()
+        .map(|(date, messages)| Day {
+            date,
+            precision: DatePrecision::Day,
+            entries: vec![Arc::new(day_entry(person, &messages, full, kind))],
+            source_file: None,
+            line_range: 0..0,
+        })
+        .collect();
+
+    Log { days }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_whatsapp_chat() {
+        let content = indoc!(
+            "
+            12/31/23, 9:15 PM - Messages and calls are end-to-end encrypted.
+            12/31/23, 9:15 PM - Jane Doe: Happy new year!
+            See you soon
+            1/1/24, 10:02 AM - Me: You too
+            "
+        );
+
+        assert_eq!(
+            parse_chat(content, ChatFormat::WhatsApp),
+            Ok(vec![
+                ChatMessage {
+                    date: d("2023-12-31"),
+                    time: "9:15".to_string(),
+                    sender: "Jane Doe".to_string(),
+                    text: "Happy new year! See you soon".to_string(),
+                },
+                ChatMessage {
+                    date: d("2024-01-01"),
+                    time: "10:02".to_string(),
+                    sender: "Me".to_string(),
+                    text: "You too".to_string(),
+                },
+            ])
+        );
+
+        let ios = "\u{200e}[05/03/2024, 21:15:03] Jane Doe: hi\n";
+        assert_eq!(
+            parse_chat(ios, ChatFormat::WhatsApp).unwrap()[0].date,
+            d("2024-03-05")
+        );
+        assert!(parse_chat("hi", ChatFormat::WhatsApp).is_err());
+    }
+
+    #[test]
+    fn test_chat_log() {
+        let content = indoc!(
+            "
+            [2024-03-05 21:15] Jane Doe: hi
+            [2024-03-05 21:16] Me: hey
+            [2024-03-05 21:17] Jane Doe: dinner?
+            [2024-03-06 08:00] Me: sure
+            "
+        );
+        let messages = parse_chat(content, ChatFormat::Signal).unwrap();
+        let person = "JaneDoe".to_string();
+
+        let summary = chat_log(&messages, &person, false, Some(&"message".to_string()));
+        assert_eq!(
+            summary.days[0].entries[0].content,
+            "- #JaneDoe (message):\n  - 3 messages: 2 from Jane Doe, 1 from Me"
+        );
+        assert_eq!(summary.days[1].date, d("2024-03-06"));
+
+        let full = chat_log(&messages, &person, true, None);
+        assert_eq!(
+            full.days[0].entries[0].content,
+            indoc!(
+                "
+                - #JaneDoe :
+                  - 21:15 Jane Doe: hi
+                  - 21:16 Me: hey
+                  - 21:17 Jane Doe: dinner?"
+            )
+        );
+    }
+}
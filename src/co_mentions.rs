@@ -0,0 +1,253 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::locations::{self, normalize_location};
+use crate::log::{Day, Entry, Log};
+use crate::model::{Location, PersonName, Theme};
+
+/// How many entries each pair of people is mentioned in together. Ignored
+/// people are left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoMentions {
+    /// Keyed by the pair in alphabetical order
+    pairs: BTreeMap<(PersonName, PersonName), usize>,
+}
+
+fn pair_key<'a>(a: &'a PersonName, b: &'a PersonName) -> (&'a PersonName, &'a PersonName) {
+    match a <= b {
+        true => (a, b),
+        false => (b, a),
+    }
+}
+
+impl CoMentions {
+    pub fn new(log: &Log, config: &Config) -> CoMentions {
+        let mut pairs: BTreeMap<(PersonName, PersonName), usize> = BTreeMap::new();
+        for entry in log.days.iter().flat_map(|day| day.entries.iter()) {
+            let people: Vec<&PersonName> = entry
+                .related
+                .iter()
+                .filter(|person| !config.is_ignored(person))
+                .collect();
+            for (i, a) in people.iter().enumerate() {
+                for b in people[i + 1..].iter() {
+                    let (a, b) = pair_key(a, b);
+                    *pairs.entry((a.clone(), b.clone())).or_default() += 1;
+                }
+            }
+        }
+
+        CoMentions { pairs }
+    }
+
+    pub fn times(&self, a: &PersonName, b: &PersonName) -> usize {
+        let (a, b) = pair_key(a, b);
+        self.pairs
+            .get(&(a.clone(), b.clone()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The people mentioned with `person` and how many times, most first
+    pub fn with(&self, person: &PersonName) -> Vec<(PersonName, usize)> {
+        let mut others: Vec<(PersonName, usize)> = self
+            .pairs
+            .iter()
+            .filter_map(|((a, b), times)| match (a == person, b == person) {
+                (true, _) => Some((b.clone(), *times)),
+                (_, true) => Some((a.clone(), *times)),
+                _ => None,
+            })
+            .collect();
+        others.sort_by_key(|(other, times)| (Reverse(*times), other.clone()));
+        others
+    }
+
+    /// Every pair mentioned together at least once, alphabetically
+    pub fn pairs(&self) -> impl Iterator<Item = (&PersonName, &PersonName, usize)> {
+        self.pairs.iter().map(|((a, b), times)| (a, b, *times))
+    }
+}
+
+/// Only the entries that mention both `a` and `b`
+pub fn mutual_entries(log: Log, a: &PersonName, b: &PersonName) -> Log {
+    let days = log
+        .days
+        .into_iter()
+        .filter_map(|day| {
+            let entries: Vec<Arc<Entry>> = day
+                .entries
+                .into_iter()
+                .filter(|entry| entry.related.contains(a) && entry.related.contains(b))
+                .collect();
+            match entries.is_empty() {
+                true => None,
+                false => Some(Day { entries, ..day }),
+            }
+        })
+        .collect();
+
+    Log { days }
+}
+
+/// Two people with something in common who have never been mentioned together
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct Introduction {
+    pub a: PersonName,
+    pub b: PersonName,
+    pub themes: Vec<Theme>,
+    /// Where both are, if in the same place
+    pub location: Option<Location>,
+}
+
+impl Introduction {
+    /// A shared theme or place counts 1 each
+    pub fn score(&self) -> usize {
+        self.themes.len() + usize::from(self.location.is_some())
+    }
+}
+
+/// Pairs of people in the config that share themes or a location but are not
+/// in any entry together, most in common first
+pub fn introductions(log: &Log, co_mentions: &CoMentions, config: &Config) -> Vec<Introduction> {
+    let timelines = locations::location_timelines(log, config);
+    let people: Vec<(&PersonName, BTreeSet<&Theme>, Option<Location>)> = config
+        .people
+        .iter()
+        .filter(|person| !config.is_ignored(&person.name))
+        .map(|person| {
+            let location = locations::current_location(&person.name, config, &timelines)
+                .map(|location| location.location().clone());
+            (&person.name, person.themes.iter().collect(), location)
+        })
+        .collect();
+
+    let mut introductions: Vec<Introduction> = vec![];
+    for (i, (a, a_themes, a_location)) in people.iter().enumerate() {
+        for (b, b_themes, b_location) in people[i + 1..].iter() {
+            if co_mentions.times(a, b) > 0 {
+                continue;
+            }
+            let location = match (a_location, b_location) {
+                (Some(a), Some(b)) if normalize_location(a) == normalize_location(b) => {
+                    Some(a.clone())
+                }
+                _ => None,
+            };
+            let (a, b) = pair_key(a, b);
+            let introduction = Introduction {
+                a: a.clone(),
+                b: b.clone(),
+                themes: a_themes
+                    .intersection(b_themes)
+                    .map(|t| (*t).clone())
+                    .collect(),
+                location,
+            };
+            if introduction.score() > 0 {
+                introductions.push(introduction);
+            }
+        }
+    }
+
+    introductions.sort_by(|x, y| {
+        y.score()
+            .cmp(&x.score())
+            .then_with(|| (&x.a, &x.b).cmp(&(&y.a, &y.b)))
+    });
+    introductions
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Person;
+    use crate::log::parse_log_file_content;
+
+    use super::*;
+
+    fn log() -> Log {
+        parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JaneDoe, #JohnDoe : lunch
+            - #Abu, #JaneDoe : drinks with #JohnDoe
+
+            # 2000-01-02
+
+            - #JaneDoe : call
+            "
+        ))
+    }
+
+    #[test]
+    fn test_co_mentions() {
+        let log = log();
+        let co_mentions = CoMentions::new(&log, &Config::builder().build());
+
+        assert_eq!(
+            co_mentions.times(&"JohnDoe".to_string(), &"JaneDoe".to_string()),
+            2
+        );
+        assert_eq!(
+            co_mentions.with(&"JaneDoe".to_string()),
+            vec![("JohnDoe".to_string(), 2), ("Abu".to_string(), 1)]
+        );
+
+        let mutual = mutual_entries(log, &"JaneDoe".to_string(), &"Abu".to_string());
+        assert_eq!(
+            mutual.to_string(),
+            "# 2000-01-01\n\n- #Abu, #JaneDoe : drinks with #JohnDoe\n"
+        );
+    }
+
+    #[test]
+    fn test_introductions() {
+        let person = |name: &str, location: &str, themes: &[&str]| Person {
+            name: name.to_string(),
+            location: location.to_string(),
+            themes: themes.iter().map(|theme| theme.to_string()).collect(),
+            ..Default::default()
+        };
+        let config = Config::builder()
+            .person(person("JaneDoe", "Berlin", &["climbing", "work"]))
+            .person(person("JohnDoe", "Berlin", &["climbing"]))
+            .person(person("Abu", "berlin", &["climbing", "work"]))
+            .person(person("Zoe", "Bilbao", &["chess"]))
+            .build();
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JaneDoe, #JohnDoe : climbing
+            "
+        ));
+
+        let introductions = introductions(&log, &CoMentions::new(&log, &config), &config);
+
+        assert_eq!(
+            introductions,
+            vec![
+                Introduction {
+                    a: "Abu".to_string(),
+                    b: "JaneDoe".to_string(),
+                    themes: vec!["climbing".to_string(), "work".to_string()],
+                    location: Some("Berlin".to_string()),
+                },
+                Introduction {
+                    a: "Abu".to_string(),
+                    b: "JohnDoe".to_string(),
+                    themes: vec!["climbing".to_string()],
+                    location: Some("Berlin".to_string()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use clap_complete::Shell;
+use tracing::debug;
+
+use crate::config::{self, Config};
+use crate::log::{self, Log, ParseOptions};
+use crate::model::PersonName;
+
+/// Everyone mentioned in the logs or listed in the config, except ignored people
+pub fn list_people(log: &Log, config: &Config) -> Vec<PersonName> {
+    let mut people: BTreeSet<PersonName> = BTreeSet::new();
+
+    for day in log.days.iter() {
+        for entry in day.entries.iter() {
+            people.extend(entry.related.iter().cloned());
+        }
+    }
+
+    for person in config.people.iter() {
+        people.insert(person.name.clone());
+    }
+
+    people.retain(|person| !config.is_ignored(person));
+
+    people.into_iter().collect()
+}
+
+pub(crate) fn modified_at(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// The cache is stale if it does not exist or any source changed after it
+fn is_cache_stale(cache: &Path, sources: &[PathBuf]) -> bool {
+    let cached_at = match modified_at(cache) {
+        Some(time) => time,
+        None => return true,
+    };
+
+    sources.iter().any(|source| match modified_at(source) {
+        Some(time) => time > cached_at,
+        None => false,
+    })
+}
+
+/// Regenerate the people cache if the config or the logs changed since the
+/// last time, and return the people in it
+pub fn refresh_people_cache(config: &Config) -> Result<Vec<PersonName>, String> {
+    let cache = config.get_people_cache_path();
+
+    let mut sources: Vec<PathBuf> = log::find_log_files(config);
+    sources.push(config.people_dir.join("log"));
+    if let Ok(config_path) = config::get_config_path() {
+        sources.push(config_path);
+    }
+
+    if !is_cache_stale(&cache, &sources) {
+        debug!("people cache is up to date: {cache:?}");
+        if let Ok(content) = fs::read_to_string(&cache) {
+            return Ok(content.lines().map(|line| line.to_string()).collect());
+        }
+    }
+
+    let log = log::read_logs(config, &ParseOptions::from_config(config))?;
+    let people = list_people(&log, config);
+    // a stale cache is better than failing
+    if config.read_only {
+        return Ok(people);
+    }
+
+    if let Some(dir) = cache.parent() {
+        if let Err(reason) = fs::create_dir_all(dir) {
+            return Err(format!("failed to create {dir:?}, reason: {reason}"));
+        }
+    }
+
+    let content: String = people.iter().map(|person| format!("{person}\n")).collect();
+    match fs::write(&cache, content) {
+        Ok(()) => Ok(people),
+        Err(reason) => Err(format!("failed to write {cache:?}, reason: {reason}")),
+    }
+}
+
+static BASH_NAMES: &str = include_str!("templates/completions/people.bash");
+static ZSH_NAMES: &str = include_str!("templates/completions/people.zsh");
+static FISH_NAMES: &str = include_str!("templates/completions/people.fish");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for CompletionShell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(CompletionShell::Bash),
+            "zsh" => Ok(CompletionShell::Zsh),
+            "fish" => Ok(CompletionShell::Fish),
+            other => Err(format!(
+                "unsupported shell found: {other:?}, expected 'bash', 'zsh' or 'fish'"
+            )),
+        }
+    }
+}
+
+/// Completion script for the `people` CLI, whose arguments are described by
+/// `command`. Person names are completed with `people completions --people`,
+/// so they are always up to date.
+pub fn completion_script(shell: CompletionShell, command: &mut clap::Command) -> String {
+    let (generator, names) = match shell {
+        CompletionShell::Bash => (Shell::Bash, BASH_NAMES),
+        CompletionShell::Zsh => (Shell::Zsh, ZSH_NAMES),
+        CompletionShell::Fish => (Shell::Fish, FISH_NAMES),
+    };
+
+    let mut script: Vec<u8> = vec![];
+    clap_complete::generate(generator, command, "people", &mut script);
+
+    let mut script = String::from_utf8_lossy(&script).to_string();
+    script.push_str(names);
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Person;
+    use crate::log::parse_log_file_content;
+
+    use super::*;
+
+    #[test]
+    fn test_completion_script() {
+        let mut command = clap::Command::new("people")
+            .subcommand(clap::Command::new("show").arg(clap::Arg::new("person")));
+
+        let script = completion_script(CompletionShell::Bash, &mut command);
+
+        assert!(script.contains("_people()"));
+        assert!(script.ends_with(
+            "complete -F _people_with_names -o nosort -o bashdefault -o default people\n"
+        ));
+    }
+
+    #[test]
+    fn test_list_people() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - met #Bleh and #Ignored
+            "
+        ));
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .ignore(vec!["Ignored".to_string()])
+            .person(Person {
+                name: "Abu".to_string(),
+                ..Default::default()
+            })
+            .build();
+
+        let expected = vec!["Abu".to_string(), "Bleh".to_string(), "JohnDoe".to_string()];
+
+        assert_eq!(list_people(&log, &config), expected);
+    }
+
+    #[test]
+    fn test_missing_cache_is_stale() {
+        assert!(is_cache_stale(Path::new("/this/path/does/not/exist"), &[]));
+    }
+}
@@ -1,89 +1,2063 @@
-use expanduser::expanduser;
+use core::fmt;
+use glob::Pattern;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::ops::Range;
 use std::path::PathBuf;
+use std::path::{Component, Path, MAIN_SEPARATOR};
+use std::str::FromStr;
 
-use serde::Deserialize;
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use regex::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_yaml::{Mapping, Value};
 use tracing::{debug, info};
 
+use crate::dates::{self, DateDisplay, DateStyle};
+use crate::files;
+use crate::log::LogInput;
+use crate::migrations;
 use crate::model::DurationStr;
-use crate::model::{Location, PersonName, Theme};
+use crate::model::{InteractionKind, Location, PersonName, Theme};
+use crate::operations::Transaction;
+use crate::use_cases::parse_duration;
 
-const CONFIG_PATH: &str = ".config/people/config.yaml";
+const CONFIG_PATH: [&str; 3] = [".config", "people", "config.yaml"];
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub people_dir: PathBuf,
-    pub ignore: Vec<PersonName>,
+    pub ignore: Vec<IgnorePattern>,
+    /// Hashtags that are topics rather than people, e.g. `Budget`. They are
+    /// not recognised as people at all.
+    pub not_people: Vec<IgnorePattern>,
+    pub people: Vec<Person>,
+    /// People you are no longer in touch with: their logs are kept, but they
+    /// are never reminded of
+    pub archived: Vec<Person>,
+    pub front_matter: Vec<FrontMatterField>,
+    pub references: ReferenceSyntax,
+    /// Address book to sync people metadata from
+    pub carddav: Option<DavConfig>,
+    /// Calendar to export entries to as journals
+    pub caldav: Option<DavConfig>,
+    pub tiers: TierThresholds,
+    pub templates: Templates,
+    /// Mail server to send the digest through
+    pub smtp: Option<SmtpConfig>,
+    /// Bot that `people notify --channel telegram` posts through
+    pub telegram: Option<TelegramConfig>,
+    /// Incoming webhook that `people notify --channel slack` posts to
+    pub slack: Option<SlackConfig>,
+    /// Named sets of people logs, e.g. `work` and `personal`
+    pub profiles: BTreeMap<String, Profile>,
+    /// Recognised in entries like `- #JaneDoe (call):` or `- #JaneDoe :call:`
+    pub kinds: Vec<InteractionKind>,
+    /// Where "today" is decided, e.g. `Europe/Madrid`. Defaults to the system's
+    pub timezone: Option<Tz>,
+    /// How often to see everyone with a theme, unless they have their own `frequency`
+    pub theme_frequencies: BTreeMap<Theme, Frequency>,
+    pub backup: BackupConfig,
+    pub git: GitConfig,
+    /// Drop entries with the same date and content as an earlier one while
+    /// reading the logs, e.g. copied between devices
+    pub dedupe_entries: bool,
+    /// Where each person's log is written, under `people_dir`
+    pub per_person_path: PersonPathTemplate,
+    /// Where the log files are, e.g. `log/*people.md`
+    pub log_globs: Vec<LogGlob>,
+    pub log_mode: LogMode,
+    /// Where entries of a journal must mention someone to be read
+    pub journal_mentions: MentionLevel,
+    /// Refuse log files that are not valid UTF-8 instead of replacing the
+    /// invalid bytes
+    pub strict_encoding: bool,
+    /// How dates are shown, a strftime format like `%d %b %Y`. ISO if none.
+    pub date_format: Option<String>,
+    /// Refuse to change any file in `people_dir` or the config, e.g. for a
+    /// directory synced from another machine
+    pub read_only: bool,
+    pub per_person: PerPersonConfig,
+    pub summary: SummaryConfig,
+    pub hooks: HooksConfig,
+    pub prompt: PromptConfig,
+    /// File in `include` each person comes from. People in the main config
+    /// file are not in it.
+    pub included: BTreeMap<PersonName, PathBuf>,
+    /// Read instead of the log files in `people_dir`, given with `--input`
+    pub input: Option<LogInput>,
+}
+
+/// A people directory with its own people, selected with `--profile <name>`
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Profile {
+    pub people_dir: PathBuf,
+    #[serde(default)]
+    pub ignore: Vec<IgnorePattern>,
+    #[serde(default)]
+    pub not_people: Vec<IgnorePattern>,
+    #[serde(default)]
     pub people: Vec<Person>,
 }
 
 impl Config {
-    pub fn get_per_person_dir(&self) -> PathBuf {
-        self.people_dir.join("per-person-logs")
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// Build the config from the content of a config file, without looking
+    /// for the file in `$HOME`
+    pub fn from_yaml_str(content: &str) -> Result<Config, String> {
+        match parse_config(content.to_string()) {
+            Ok(config_file) => config_from_file(config_file),
+            Err(parse_failure) => Err(format!("failed to parse because {parse_failure}")),
+        }
+    }
+
+    /// How to show dates to the user in `style`, with the `date_format`
+    pub fn date_display(&self, style: DateStyle) -> DateDisplay {
+        DateDisplay {
+            style,
+            format: self.date_format.clone(),
+        }
+    }
+
+    /// Fails in read-only mode, before writing or deleting `path`
+    pub fn check_writable(&self, path: &Path) -> Result<(), String> {
+        match self.read_only {
+            true => Err(format!(
+                "not writing {path:?} in read-only mode, see `--read-only` or `read_only` in the config"
+            )),
+            false => Ok(()),
+        }
+    }
+
+    pub fn get_person_log_path(&self, person: &str) -> PathBuf {
+        self.people_dir.join(self.per_person_path.render(person))
+    }
+
+    /// `index.md` next to the per-person logs, or above them if they are in
+    /// directories of their own
+    pub fn get_people_index_path(&self) -> PathBuf {
+        self.people_dir
+            .join(self.per_person_path.base_dir())
+            .join("index.md")
+    }
+
+    /// Where new days of a year go, `<people_dir>/log/<year>-people.md`
+    pub fn get_year_log_path(&self, date: NaiveDate) -> PathBuf {
+        let year = date.year();
+        self.people_dir
+            .join("log")
+            .join(format!("{year}-people.md"))
+    }
+
+    pub fn get_state_path(&self) -> PathBuf {
+        self.people_dir.join(".people").join("state.yaml")
+    }
+
+    /// `<people_dir>/rollups/2024-W20.md` for the week starting on `monday`
+    pub fn get_rollup_path(&self, monday: NaiveDate) -> PathBuf {
+        self.people_dir
+            .join("rollups")
+            .join(format!("{}.md", monday.format("%G-W%V")))
+    }
+
+    /// Plain-text list of people, one per line, for shell prompts and launchers
+    pub fn get_people_cache_path(&self) -> PathBuf {
+        self.people_dir.join(".people").join("people.txt")
+    }
+
+    /// Operations that `people undo` can revert, `<people_dir>/.people/ops/`
+    pub fn get_operations_dir(&self) -> PathBuf {
+        self.people_dir.join(".people").join("ops")
+    }
+
+    pub fn get_backup_dir(&self) -> PathBuf {
+        match &self.backup.dir {
+            Some(dir) => dir.clone(),
+            None => self.people_dir.join(".people").join("backups"),
+        }
+    }
+
+    /// Today's date in the configured timezone
+    pub fn today(&self) -> NaiveDate {
+        match self.timezone {
+            Some(timezone) => Utc::now().with_timezone(&timezone).date_naive(),
+            None => Local::now().date_naive(),
+        }
+    }
+
+    /// The current time in the configured timezone
+    pub fn now(&self) -> NaiveDateTime {
+        match self.timezone {
+            Some(timezone) => Utc::now().with_timezone(&timezone).naive_local(),
+            None => Local::now().naive_local(),
+        }
+    }
+
+    /// Whether the person matches any of the `ignore` patterns
+    pub fn is_ignored(&self, name: &str) -> bool {
+        self.ignore.iter().any(|pattern| pattern.matches(name))
+    }
+
+    /// The person's own `frequency`, or else the most frequent of their themes'
+    pub fn get_frequency<'a>(&'a self, person: &'a Person) -> Option<&'a Frequency> {
+        if let Some(frequency) = &person.frequency {
+            return Some(frequency);
+        }
+
+        person
+            .themes
+            .iter()
+            .filter_map(|theme| self.theme_frequencies.get(theme))
+            .min_by_key(|frequency| frequency.duration())
+    }
+
+    /// The person in `people`, or else in `archived`
+    pub fn get_person(&self, name: &PersonName) -> Option<&Person> {
+        self.all_people().find(|person| &person.name == name)
+    }
+
+    /// Everyone in `people` followed by everyone in `archived`
+    pub fn all_people(&self) -> impl Iterator<Item = &Person> {
+        self.people.iter().chain(self.archived.iter())
+    }
+
+    pub fn is_archived(&self, name: &PersonName) -> bool {
+        self.archived.iter().any(|person| &person.name == name)
+    }
+
+    /// The same config, but with the people directory, ignored people and
+    /// people of the profile `name`
+    pub fn for_profile(&self, name: &str) -> Result<Config, String> {
+        let profile = match self.profiles.get(name) {
+            Some(profile) => profile,
+            None => {
+                let known: Vec<&str> = self.profiles.keys().map(|key| key.as_str()).collect();
+                return Err(format!(
+                    "profile {name:?} not found in config, expected one of: {}",
+                    known.join(", ")
+                ));
+            }
+        };
+
+        Ok(Config {
+            people_dir: profile.people_dir.clone(),
+            ignore: profile.ignore.clone(),
+            not_people: profile.not_people.clone(),
+            people: profile.people.clone(),
+            ..self.clone()
+        })
+    }
+
+    /// The top-level config followed by every profile, for commands that run
+    /// across all of them
+    pub fn all_profiles(&self) -> Vec<(String, Config)> {
+        let mut configs = vec![(DEFAULT_PROFILE.to_string(), self.clone())];
+        for name in self.profiles.keys() {
+            let config = self
+                .for_profile(name)
+                .expect("profile names come from the config itself");
+            configs.push((name.clone(), config));
+        }
+        configs
+    }
+}
+
+pub fn default_kinds() -> Vec<InteractionKind> {
+    vec!["call".to_string(), "met".to_string(), "message".to_string()]
+}
+
+/// Name of the top-level `people_dir`, `ignore` and `people` in output that
+/// covers every profile
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Build a `Config` programmatically, e.g. in tests or embedding applications
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        ConfigBuilder {
+            config: Config {
+                people_dir: PathBuf::new(),
+                ignore: vec![],
+                not_people: vec![],
+                people: vec![],
+                archived: vec![],
+                front_matter: FrontMatterField::all(),
+                references: ReferenceSyntax::default(),
+                carddav: None,
+                caldav: None,
+                tiers: TierThresholds::default(),
+                templates: Templates::default(),
+                smtp: None,
+                telegram: None,
+                slack: None,
+                profiles: BTreeMap::new(),
+                kinds: default_kinds(),
+                timezone: None,
+                theme_frequencies: BTreeMap::new(),
+                backup: BackupConfig::default(),
+                git: GitConfig::default(),
+                dedupe_entries: false,
+                per_person_path: PersonPathTemplate::default(),
+                log_globs: vec![LogGlob::default()],
+                log_mode: LogMode::default(),
+                journal_mentions: MentionLevel::default(),
+                strict_encoding: false,
+                date_format: None,
+                read_only: false,
+                per_person: PerPersonConfig::default(),
+                summary: SummaryConfig::default(),
+                hooks: HooksConfig::default(),
+                prompt: PromptConfig::default(),
+                included: BTreeMap::new(),
+                input: None,
+            },
+        }
+    }
+}
+
+impl ConfigBuilder {
+    pub fn people_dir(mut self, people_dir: impl Into<PathBuf>) -> ConfigBuilder {
+        self.config.people_dir = people_dir.into();
+        self
+    }
+
+    /// Names or patterns, see `IgnorePattern`. Panics if a pattern is invalid.
+    pub fn ignore(mut self, ignore: Vec<PersonName>) -> ConfigBuilder {
+        self.config.ignore = parse_patterns(ignore);
+        self
+    }
+
+    /// Names or patterns, see `IgnorePattern`. Panics if a pattern is invalid.
+    pub fn not_people(mut self, not_people: Vec<PersonName>) -> ConfigBuilder {
+        self.config.not_people = parse_patterns(not_people);
+        self
+    }
+
+    pub fn people(mut self, people: Vec<Person>) -> ConfigBuilder {
+        self.config.people = people;
+        self
+    }
+
+    pub fn person(mut self, person: Person) -> ConfigBuilder {
+        self.config.people.push(person);
+        self
+    }
+
+    pub fn archived(mut self, person: Person) -> ConfigBuilder {
+        self.config.archived.push(person);
+        self
+    }
+
+    pub fn front_matter(mut self, front_matter: Vec<FrontMatterField>) -> ConfigBuilder {
+        self.config.front_matter = front_matter;
+        self
+    }
+
+    pub fn references(mut self, references: ReferenceSyntax) -> ConfigBuilder {
+        self.config.references = references;
+        self
+    }
+
+    pub fn carddav(mut self, carddav: DavConfig) -> ConfigBuilder {
+        self.config.carddav = Some(carddav);
+        self
+    }
+
+    pub fn caldav(mut self, caldav: DavConfig) -> ConfigBuilder {
+        self.config.caldav = Some(caldav);
+        self
+    }
+
+    pub fn theme_frequency(
+        mut self,
+        theme: impl Into<Theme>,
+        frequency: Frequency,
+    ) -> ConfigBuilder {
+        self.config
+            .theme_frequencies
+            .insert(theme.into(), frequency);
+        self
+    }
+
+    pub fn tiers(mut self, tiers: TierThresholds) -> ConfigBuilder {
+        self.config.tiers = tiers;
+        self
+    }
+
+    pub fn backup(mut self, backup: BackupConfig) -> ConfigBuilder {
+        self.config.backup = backup;
+        self
+    }
+
+    pub fn summary(mut self, summary: SummaryConfig) -> ConfigBuilder {
+        self.config.summary = summary;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: HooksConfig) -> ConfigBuilder {
+        self.config.hooks = hooks;
+        self
+    }
+
+    pub fn prompt(mut self, prompt: PromptConfig) -> ConfigBuilder {
+        self.config.prompt = prompt;
+        self
+    }
+
+    pub fn per_person_path(mut self, per_person_path: PersonPathTemplate) -> ConfigBuilder {
+        self.config.per_person_path = per_person_path;
+        self
+    }
+
+    pub fn log_globs(mut self, log_globs: Vec<LogGlob>) -> ConfigBuilder {
+        self.config.log_globs = log_globs;
+        self
+    }
+
+    pub fn log_mode(mut self, log_mode: LogMode) -> ConfigBuilder {
+        self.config.log_mode = log_mode;
+        self
+    }
+
+    pub fn journal_mentions(mut self, journal_mentions: MentionLevel) -> ConfigBuilder {
+        self.config.journal_mentions = journal_mentions;
+        self
+    }
+
+    pub fn strict_encoding(mut self, strict_encoding: bool) -> ConfigBuilder {
+        self.config.strict_encoding = strict_encoding;
+        self
+    }
+
+    pub fn date_format(mut self, date_format: &str) -> ConfigBuilder {
+        self.config.date_format = Some(date_format.to_string());
+        self
+    }
+
+    pub fn per_person(mut self, per_person: PerPersonConfig) -> ConfigBuilder {
+        self.config.per_person = per_person;
+        self
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> ConfigBuilder {
+        self.config.read_only = read_only;
+        self
+    }
+
+    pub fn input(mut self, input: LogInput) -> ConfigBuilder {
+        self.config.input = Some(input);
+        self
+    }
+
+    pub fn dedupe_entries(mut self, dedupe_entries: bool) -> ConfigBuilder {
+        self.config.dedupe_entries = dedupe_entries;
+        self
+    }
+
+    pub fn git(mut self, git: GitConfig) -> ConfigBuilder {
+        self.config.git = git;
+        self
+    }
+
+    pub fn templates(mut self, templates: Templates) -> ConfigBuilder {
+        self.config.templates = templates;
+        self
+    }
+
+    pub fn smtp(mut self, smtp: SmtpConfig) -> ConfigBuilder {
+        self.config.smtp = Some(smtp);
+        self
+    }
+
+    pub fn telegram(mut self, telegram: TelegramConfig) -> ConfigBuilder {
+        self.config.telegram = Some(telegram);
+        self
+    }
+
+    pub fn slack(mut self, slack: SlackConfig) -> ConfigBuilder {
+        self.config.slack = Some(slack);
+        self
+    }
+
+    pub fn kinds(mut self, kinds: Vec<InteractionKind>) -> ConfigBuilder {
+        self.config.kinds = kinds;
+        self
+    }
+
+    pub fn timezone(mut self, timezone: Tz) -> ConfigBuilder {
+        self.config.timezone = Some(timezone);
+        self
+    }
+
+    pub fn profile(mut self, name: impl Into<String>, profile: Profile) -> ConfigBuilder {
+        self.config.profiles.insert(name.into(), profile);
+        self
+    }
+
+    pub fn build(self) -> Config {
+        self.config
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Person {
+    pub name: PersonName,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<PersonName>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub location: Location,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub themes: Vec<Theme>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remind_after: Option<ReminderInterval>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub birthday: Option<NaiveDate>,
+    /// Always show this person at the top of the summary
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    /// Telegram username, with or without the `@`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telegram: Option<String>,
+    /// How often you want to see them, e.g. `every 3 weeks`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency: Option<Frequency>,
+}
+
+/// A `remind_after` that is validated when the config is loaded, e.g. `3 months`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReminderInterval {
+    text: DurationStr,
+    duration: Duration,
+}
+
+impl ReminderInterval {
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl FromStr for ReminderInterval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let duration = parse_duration(s)?;
+        Ok(ReminderInterval {
+            text: s.trim().to_string(),
+            duration,
+        })
+    }
+}
+
+impl fmt::Display for ReminderInterval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Serialize for ReminderInterval {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for ReminderInterval {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
+
+/// A contact goal, e.g. `every 3 weeks`, validated when the config is loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frequency {
+    text: String,
+    duration: Duration,
+}
+
+impl Frequency {
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+        let interval = text.strip_prefix("every ").unwrap_or(text);
+        let duration = parse_duration(interval)?;
+        if duration <= Duration::zero() {
+            return Err(format!(
+                "failed to parse '{text}', reason: it must be positive"
+            ));
+        }
+
+        Ok(Frequency {
+            text: text.to_string(),
+            duration,
+        })
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Serialize for Frequency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for Frequency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Where a person's log goes under `people_dir`, e.g. `per-person-logs/{person}.md`.
+/// `{person}` is the name made safe for file names and `{first_letter}` its
+/// first letter, uppercased.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PersonPathTemplate {
+    text: String,
+}
+
+const PERSON_PLACEHOLDER: &str = "{person}";
+const FIRST_LETTER_PLACEHOLDER: &str = "{first_letter}";
+
+impl PersonPathTemplate {
+    /// The directory every per-person log is in, up to the first placeholder
+    pub fn base_dir(&self) -> PathBuf {
+        Path::new(&self.text)
+            .components()
+            .take_while(|component| !component.as_os_str().to_string_lossy().contains('{'))
+            .collect()
+    }
+
+    pub fn render(&self, person: &str) -> PathBuf {
+        let slug = files::slug(person);
+        let first_letter: String = slug.chars().take(1).flat_map(char::to_uppercase).collect();
+        PathBuf::from(
+            self.text
+                .replace(PERSON_PLACEHOLDER, &slug)
+                .replace(FIRST_LETTER_PLACEHOLDER, &first_letter),
+        )
+    }
+}
+
+impl Default for PersonPathTemplate {
+    fn default() -> Self {
+        PersonPathTemplate {
+            text: "per-person-logs/{person}.md".to_string(),
+        }
+    }
+}
+
+impl FromStr for PersonPathTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+        if !text.contains(PERSON_PLACEHOLDER) {
+            return Err(format!(
+                "failed to parse '{text}', reason: it must contain {PERSON_PLACEHOLDER}"
+            ));
+        }
+
+        let rest = text
+            .replace(PERSON_PLACEHOLDER, "")
+            .replace(FIRST_LETTER_PLACEHOLDER, "");
+        if rest.contains(['{', '}']) {
+            return Err(format!(
+                "failed to parse '{text}', reason: only {PERSON_PLACEHOLDER} and {FIRST_LETTER_PLACEHOLDER} are supported"
+            ));
+        }
+
+        let is_relative = Path::new(text)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if !is_relative {
+            return Err(format!(
+                "failed to parse '{text}', reason: it must be a path inside people_dir"
+            ));
+        }
+
+        Ok(PersonPathTemplate {
+            text: text.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for PersonPathTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for PersonPathTemplate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Syntax of log files, see `log_format`
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSyntax {
+    #[default]
+    Markdown,
+    /// Emacs org-mode
+    Org,
+}
+
+impl LogSyntax {
+    /// Org for `.org` files, markdown for anything else
+    pub fn from_path(path: &str) -> LogSyntax {
+        match path.ends_with(".org") {
+            true => LogSyntax::Org,
+            false => LogSyntax::Markdown,
+        }
+    }
+}
+
+/// Pattern of log file paths, relative to `people_dir` unless absolute, e.g.
+/// `archive/**/*.people.md`, and the syntax of the files
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogGlob {
+    text: String,
+    syntax: LogSyntax,
+}
+
+impl LogGlob {
+    pub fn syntax(&self) -> LogSyntax {
+        self.syntax
+    }
+
+    pub fn with_syntax(self, syntax: LogSyntax) -> LogGlob {
+        LogGlob { syntax, ..self }
+    }
+
+    /// The pattern with `people_dir` in front if it is relative
+    pub fn pattern(&self, people_dir: &Path) -> String {
+        if Path::new(&self.text).is_absolute() {
+            return self.text.clone();
+        }
+
+        let base = Pattern::escape(&people_dir.to_string_lossy());
+        format!("{base}{MAIN_SEPARATOR}{}", self.text)
+    }
+}
+
+impl Default for LogGlob {
+    fn default() -> Self {
+        LogGlob {
+            text: "log/*people.md".to_string(),
+            syntax: LogSyntax::Markdown,
+        }
+    }
+}
+
+impl FromStr for LogGlob {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = match files::expand_home(s.trim()) {
+            Ok(path) => path.to_string_lossy().to_string(),
+            Err(reason) => return Err(format!("failed to parse '{s}', reason: {reason}")),
+        };
+
+        match Pattern::new(&text) {
+            Ok(_) => Ok(LogGlob {
+                syntax: LogSyntax::from_path(&text),
+                text,
+            }),
+            Err(reason) => Err(format!("failed to parse '{text}', reason: {reason}")),
+        }
+    }
+}
+
+impl fmt::Display for LogGlob {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// A glob alone, or with the syntax of its files if their extension doesn't
+/// tell it, e.g. `{glob: notes/*.txt, format: org}`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LogGlobFile {
+    Glob(String),
+    WithFormat { glob: String, format: LogSyntax },
+}
+
+impl<'de> Deserialize<'de> for LogGlob {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match LogGlobFile::deserialize(deserializer)? {
+            LogGlobFile::Glob(text) => text.parse().map_err(de::Error::custom),
+            LogGlobFile::WithFormat { glob, format } => glob
+                .parse()
+                .map(|glob: LogGlob| glob.with_syntax(format))
+                .map_err(de::Error::custom),
+        }
+    }
+}
+
+/// A name in `ignore` or `not_people`: either exact (`JohnDoe`), a glob
+/// (`Test*`, `tmp_?`) or a regex between slashes (`/^tmp_/`)
+#[derive(Clone, Debug)]
+pub struct IgnorePattern {
+    text: String,
+    matcher: NameMatcher,
+}
+
+#[derive(Clone, Debug)]
+enum NameMatcher {
+    Exact,
+    Glob(glob::Pattern),
+    Regex(Regex),
+}
+
+impl IgnorePattern {
+    pub fn matches(&self, name: &str) -> bool {
+        match &self.matcher {
+            NameMatcher::Exact => self.text == name,
+            NameMatcher::Glob(pattern) => pattern.matches(name),
+            NameMatcher::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+impl PartialEq for IgnorePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl FromStr for IgnorePattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim().to_string();
+
+        let matcher = if text.len() > 1 && text.starts_with('/') && text.ends_with('/') {
+            match Regex::new(&text[1..text.len() - 1]) {
+                Ok(regex) => NameMatcher::Regex(regex),
+                Err(reason) => return Err(format!("invalid regex {text:?}: {reason}")),
+            }
+        } else if text.contains(['*', '?', '[']) {
+            match glob::Pattern::new(&text) {
+                Ok(pattern) => NameMatcher::Glob(pattern),
+                Err(reason) => return Err(format!("invalid glob {text:?}: {reason}")),
+            }
+        } else {
+            NameMatcher::Exact
+        };
+
+        Ok(IgnorePattern { text, matcher })
+    }
+}
+
+impl fmt::Display for IgnorePattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for IgnorePattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
+
+fn parse_patterns(patterns: Vec<PersonName>) -> Vec<IgnorePattern> {
+    patterns
+        .iter()
+        .map(|pattern| match pattern.parse() {
+            Ok(pattern) => pattern,
+            Err(reason) => panic!("{reason}"),
+        })
+        .collect()
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    /// Format of the config file, see `migrations`
+    pub version: Option<u64>,
+    pub people_dir: Box<PathBuf>,
+    /// More files with `people`, `archived`, `ignore` and `not_people`
+    pub include: Option<Vec<PathBuf>>,
+    pub ignore: Option<Vec<IgnorePattern>>,
+    pub not_people: Option<Vec<IgnorePattern>>,
+    pub people: Option<Vec<Person>>,
+    pub archived: Option<Vec<Person>>,
+    pub front_matter: Option<Vec<FrontMatterField>>,
+    pub references: Option<ReferenceSyntax>,
+    pub carddav: Option<DavConfig>,
+    pub caldav: Option<DavConfig>,
+    pub tiers: Option<TierThresholds>,
+    pub templates: Option<Templates>,
+    pub smtp: Option<SmtpConfig>,
+    pub telegram: Option<TelegramConfig>,
+    pub slack: Option<SlackConfig>,
+    pub profiles: Option<BTreeMap<String, Profile>>,
+    pub kinds: Option<Vec<InteractionKind>>,
+    pub timezone: Option<Tz>,
+    pub theme_frequencies: Option<BTreeMap<Theme, Frequency>>,
+    pub backup: Option<BackupConfig>,
+    pub git: Option<GitConfig>,
+    pub dedupe_entries: Option<bool>,
+    pub per_person_path: Option<PersonPathTemplate>,
+    pub log_globs: Option<Vec<LogGlob>>,
+    pub log_mode: Option<LogMode>,
+    pub journal_mentions: Option<MentionLevel>,
+    pub strict_encoding: Option<bool>,
+    pub date_format: Option<String>,
+    pub read_only: Option<bool>,
+    pub per_person: Option<PerPersonConfig>,
+    pub summary: Option<SummaryConfig>,
+    pub hooks: Option<HooksConfig>,
+    pub prompt: Option<PromptConfig>,
+}
+
+/// WebDAV server (CardDAV or CalDAV) and its credentials
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct DavConfig {
+    pub url: String,
+    pub username: String,
+    pub password: Option<String>,
+    /// Command that prints the password, e.g. `pass show carddav`
+    pub password_command: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// Defaults to 465 (SMTPS)
+    pub port: Option<u16>,
+    pub username: String,
+    pub password: Option<String>,
+    pub password_command: Option<String>,
+    pub from: String,
+    pub to: String,
+}
+
+/// Telegram bot and the chat it posts to
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct TelegramConfig {
+    pub token: Option<String>,
+    /// Command that prints the token, e.g. `pass show telegram`
+    pub token_command: Option<String>,
+    pub chat_id: String,
+}
+
+/// Slack incoming webhook, whose URL is a secret too
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SlackConfig {
+    pub webhook_url: Option<String>,
+    pub webhook_url_command: Option<String>,
+}
+
+/// Syntaxes that are recognised as references to a person in the logs
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ReferenceSyntax {
+    /// `#JaneDoe`
+    pub hashtags: bool,
+    /// `[[Jane Doe]]`
+    pub wikilinks: bool,
+    pub wikilink_names: WikilinkNames,
+}
+
+impl Default for ReferenceSyntax {
+    fn default() -> Self {
+        ReferenceSyntax {
+            hashtags: true,
+            wikilinks: false,
+            wikilink_names: WikilinkNames::Join,
+        }
+    }
+}
+
+/// How a wikilink target is turned into a person name when it does not match
+/// any configured name or alias
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WikilinkNames {
+    /// `[[Jane Doe]]` becomes `JaneDoe`
+    Join,
+    /// `[[Jane Doe]]` becomes `Jane Doe`
+    Verbatim,
+}
+
+/// How long since the last interaction until a person is considered warm,
+/// cooling or cold. More recent than `warm` is fresh.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TierThresholds {
+    pub warm: DurationStr,
+    pub cooling: DurationStr,
+    pub cold: DurationStr,
+}
+
+impl Default for TierThresholds {
+    fn default() -> Self {
+        TierThresholds {
+            warm: "2 weeks".to_string(),
+            cooling: "1 month".to_string(),
+            cold: "3 months".to_string(),
+        }
+    }
+}
+
+/// Where `people backup` writes archives and how many it keeps
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// Defaults to `<people_dir>/.people/backups`
+    pub dir: Option<PathBuf>,
+    /// Delete the oldest archives beyond this many, keep them all if not set
+    pub keep: Option<usize>,
+}
+
+/// What the per-person logs show and in which order
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PerPersonConfig {
+    pub order: LogOrder,
+    /// Only the most recent entries, e.g. `50`
+    pub last_entries: Option<usize>,
+    /// Only the entries of the last years, e.g. `2`
+    pub last_years: Option<u32>,
+    /// Start with when they were last seen and how often you usually meet
+    pub summary_header: bool,
+    /// Also write an `index.md` that links to everyone's log
+    pub index: bool,
+    pub index_sort: IndexSort,
+}
+
+/// Order of the people in the index of the per-person logs
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexSort {
+    /// Alphabetically
+    #[default]
+    Name,
+    /// Most recent interaction first
+    Last,
+    /// Most entries first
+    Entries,
+}
+
+impl FromStr for IndexSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(IndexSort::Name),
+            "last" => Ok(IndexSort::Last),
+            "entries" => Ok(IndexSort::Entries),
+            other => Err(format!(
+                "expected 'name', 'last' or 'entries', but found {other:?}"
+            )),
+        }
+    }
+}
+
+/// Which days of a per-person log come first
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogOrder {
+    #[default]
+    OldestFirst,
+    NewestFirst,
+}
+
+impl FromStr for LogOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "oldest-first" | "oldest_first" => Ok(LogOrder::OldestFirst),
+            "newest-first" | "newest_first" => Ok(LogOrder::NewestFirst),
+            other => Err(format!(
+                "expected 'oldest-first' or 'newest-first', but found {other:?}"
+            )),
+        }
+    }
+}
+
+/// Shell commands that `people hooks run` runs when someone's state changes,
+/// with the event as JSON on stdin
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// When a person goes beyond their `remind_after` threshold
+    pub on_overdue: Option<String>,
+    /// When a new interaction with a person is logged
+    pub on_contact_logged: Option<String>,
+}
+
+/// How `people prompt` weighs whom to suggest writing about next
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PromptConfig {
+    /// Theme of the people to pick at random from, e.g. `family`. Everyone if unset.
+    pub group: Option<Theme>,
+    pub weights: PromptWeights,
+}
+
+/// Relative chances of each reason to suggest someone, 0 to never use it
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PromptWeights {
+    /// People with few or short entries
+    pub sparse: u32,
+    /// Anyone in the `group`
+    pub group: u32,
+}
+
+impl Default for PromptWeights {
+    fn default() -> Self {
+        PromptWeights {
+            sparse: 3,
+            group: 1,
+        }
+    }
+}
+
+/// What to do when `people_dir` is a git repository
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct GitConfig {
+    /// Commit the per-person logs every time they are regenerated
+    pub auto_commit: bool,
+}
+
+/// How `people_summary` groups people by their last interaction
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SummaryConfig {
+    /// Days ago where each group starts, in ascending order
+    pub boundaries: Vec<i64>,
+    /// Header of each group, one more than `boundaries`. No headers if empty.
+    pub labels: Vec<String>,
+    pub group_by: SummaryGrouping,
+    /// Whose last interaction an entry updates
+    pub count: MentionLevel,
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        SummaryConfig {
+            boundaries: vec![7, 14, 28],
+            labels: vec![],
+            group_by: SummaryGrouping::DaysAgo,
+            count: MentionLevel::Main,
+        }
+    }
+}
+
+impl SummaryConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.boundaries.iter().any(|boundary| *boundary <= 0) {
+            return Err("summary boundaries must be positive".to_string());
+        }
+        if self.boundaries.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err("summary boundaries must be in ascending order".to_string());
+        }
+        if !self.labels.is_empty() && self.labels.len() != self.boundaries.len() + 1 {
+            return Err(format!(
+                "expected {} summary labels, one per group, but found {}",
+                self.boundaries.len() + 1,
+                self.labels.len()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What puts people in the same group of the summary
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryGrouping {
+    /// Days since the last interaction, split at the `boundaries`
+    #[default]
+    DaysAgo,
+    /// Month of the last interaction, each with a header like `May 2024`
+    Month,
+}
+
+impl FromStr for SummaryGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "days-ago" | "days_ago" => Ok(SummaryGrouping::DaysAgo),
+            "month" => Ok(SummaryGrouping::Month),
+            other => Err(format!(
+                "expected 'days-ago' or 'month', but found {other:?}"
+            )),
+        }
+    }
+}
+
+/// What the log files are about, which decides what in them is read
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogMode {
+    /// Files only about people, where every line under a date is an entry
+    #[default]
+    People,
+    /// General daily notes: only entries that mention someone are read, and
+    /// other headings and anything before the first date are skipped
+    Journal,
+}
+
+/// Where in an entry a person is mentioned
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MentionLevel {
+    /// In the first line, e.g. `- #JaneDoe : lunch`
+    #[default]
+    Main,
+    /// Only in the lines after the first one, e.g. `  - #JohnDoe was there too`
+    Related,
+    /// Anywhere in the entry
+    Both,
+}
+
+impl FromStr for MentionLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "main" => Ok(MentionLevel::Main),
+            "related" => Ok(MentionLevel::Related),
+            "both" => Ok(MentionLevel::Both),
+            other => Err(format!(
+                "expected 'main', 'related' or 'both', but found {other:?}"
+            )),
+        }
+    }
+}
+
+/// Tera templates that replace the built-in output formats
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Templates {
+    pub summary: Option<PathBuf>,
+    pub per_person: Option<PathBuf>,
+    pub reminder: Option<PathBuf>,
+    /// Message of `people notify`, gets the same variables as `reminder`
+    pub notify: Option<PathBuf>,
+    /// Index page of `people publish`
+    pub site_index: Option<PathBuf>,
+    /// Per-person pages of `people publish`
+    pub site_person: Option<PathBuf>,
+}
+
+fn expand_path(path: Option<PathBuf>) -> Result<Option<PathBuf>, String> {
+    match path {
+        Some(path) => Ok(Some(files::expand_home(&path.to_string_lossy())?)),
+        None => Ok(None),
+    }
+}
+
+/// Metadata fields that can be added as YAML front matter to per-person logs
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FrontMatterField {
+    Name,
+    Aliases,
+    Location,
+    Themes,
+    LastInteraction,
+    TotalEntries,
+    /// Format of the per-person log, see `migrations::LOG_VERSION`
+    Version,
+}
+
+impl FrontMatterField {
+    pub fn all() -> Vec<FrontMatterField> {
+        vec![
+            FrontMatterField::Name,
+            FrontMatterField::Aliases,
+            FrontMatterField::Location,
+            FrontMatterField::Themes,
+            FrontMatterField::LastInteraction,
+            FrontMatterField::TotalEntries,
+            FrontMatterField::Version,
+        ]
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            FrontMatterField::Name => "name",
+            FrontMatterField::Aliases => "aliases",
+            FrontMatterField::Location => "location",
+            FrontMatterField::Themes => "themes",
+            FrontMatterField::LastInteraction => "last_interaction",
+            FrontMatterField::TotalEntries => "total_entries",
+            FrontMatterField::Version => "version",
+        }
+    }
+}
+
+type ErrorReason = String;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    HomeNotFound,
+    ConfigFileNotFound(PathBuf),
+    ConfigFileHasUnsupportedFormat(ErrorReason),
+}
+
+/// Configs in an older format are read as they are, as long as every key in
+/// them is known: nothing is dropped while loading, `people config migrate`
+/// does it
+fn parse_config(content: String) -> Result<ConfigFile, String> {
+    let migrated = migrations::migrate_config(&content)?;
+    if let Some(migrated) = &migrated {
+        info!(
+            "config is at version {}, run `people config migrate` to upgrade it to {}",
+            migrated.from,
+            migrations::CONFIG_VERSION
+        );
+    }
+
+    match serde_yaml::from_str::<ConfigFile>(&content) {
+        Ok(config_file) => Ok(config_file),
+        Err(error) => {
+            debug!("failed to parse config file, reason: {error:?}");
+            match migrated {
+                Some(migrated) if !migrated.changes.is_empty() => Err(format!(
+                    "{error}, run `people config migrate` to remove the keys version {} didn't use",
+                    migrated.from
+                )),
+                _ => Err(error.to_string()),
+            }
+        }
+    }
+}
+
+/// Something wrong in a config file, at a line if it could be told
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigProblem {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Numbers of the lines, starting at 1, that match `predicate`
+fn lines_matching(content: &str, predicate: impl Fn(&str) -> bool) -> Vec<usize> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| predicate(line))
+        .map(|(index, _)| index + 1)
+        .collect()
+}
+
+/// Every problem found in the content of a config file, instead of only the
+/// first one like `Config::from_yaml_str`. Each top-level key and each person
+/// is checked on its own.
+pub fn validate_config(content: &str) -> Vec<ConfigProblem> {
+    let document: Value = match serde_yaml::from_str(content) {
+        Ok(document) => document,
+        Err(error) => {
+            return vec![ConfigProblem {
+                line: error.location().map(|location| location.line()),
+                message: error.to_string(),
+            }]
+        }
+    };
+    let mapping = match document.as_mapping() {
+        Some(mapping) => mapping,
+        None => {
+            return vec![ConfigProblem {
+                line: None,
+                message: "expected config file to be a YAML mapping".to_string(),
+            }]
+        }
+    };
+
+    let key_line = |key: &str| {
+        let prefix = format!("{key}:");
+        lines_matching(content, |line| line.starts_with(&prefix))
+            .first()
+            .copied()
+    };
+    let mut problems: Vec<ConfigProblem> = vec![];
+
+    let people_dir = Value::from("people_dir");
+    if !mapping.contains_key(&people_dir) {
+        problems.push(ConfigProblem {
+            line: None,
+            message: "missing people_dir".to_string(),
+        });
+    }
+
+    match migrations::config_version(mapping) {
+        Ok(version) if version > migrations::CONFIG_VERSION => problems.push(ConfigProblem {
+            line: key_line("version"),
+            message: format!(
+                "version {version} is newer than {}, the latest this version of people supports",
+                migrations::CONFIG_VERSION
+            ),
+        }),
+        Ok(_) => {}
+        Err(reason) => problems.push(ConfigProblem {
+            line: key_line("version"),
+            message: reason,
+        }),
+    }
+
+    let mut names: Vec<PersonName> = vec![];
+    for (key, value) in mapping.iter() {
+        let key = match key.as_str() {
+            Some(key) => key,
+            None => {
+                problems.push(ConfigProblem {
+                    line: None,
+                    message: format!("expected keys to be text, but found {key:?}"),
+                });
+                continue;
+            }
+        };
+
+        if key == "version" {
+            continue;
+        }
+
+        if key == "people" || key == "archived" {
+            let people = match value.as_sequence() {
+                Some(people) => people,
+                None => {
+                    problems.push(ConfigProblem {
+                        line: key_line(key),
+                        message: format!("{key}: expected a list of people"),
+                    });
+                    continue;
+                }
+            };
+            for (index, person) in people.iter().enumerate() {
+                let name = person.get("name").and_then(Value::as_str);
+                let line = name.and_then(|name| {
+                    let occurrence = names.iter().filter(|known| *known == name).count();
+                    lines_matching(content, |line| {
+                        let line = line.trim_start().trim_start_matches("- ");
+                        line.strip_prefix("name:")
+                            .is_some_and(|value| value.trim().trim_matches(['"', '\'']) == name)
+                    })
+                    .get(occurrence)
+                    .copied()
+                });
+                if let Err(error) = serde_yaml::from_value::<Person>(person.clone()) {
+                    problems.push(ConfigProblem {
+                        line: line.or_else(|| key_line(key)),
+                        message: format!("{key}[{index}]: {error}"),
+                    });
+                }
+                if let Some(name) = name {
+                    if names.iter().any(|known| known == name) {
+                        problems.push(ConfigProblem {
+                            line,
+                            message: format!("{name} is in the config more than once"),
+                        });
+                    }
+                    names.push(name.to_string());
+                }
+            }
+            continue;
+        }
+
+        let mut alone = Mapping::new();
+        alone.insert(people_dir.clone(), Value::from("/"));
+        alone.insert(Value::from(key), value.clone());
+        if let Err(error) = serde_yaml::from_value::<ConfigFile>(Value::Mapping(alone)) {
+            problems.push(ConfigProblem {
+                line: key_line(key),
+                message: format!("{key}: {error}"),
+            });
+        }
+    }
+
+    // what can only be checked with the whole config, e.g. profile names
+    if problems.is_empty() {
+        if let Err(reason) = Config::from_yaml_str(content) {
+            problems.push(ConfigProblem {
+                line: None,
+                message: reason,
+            });
+        }
+    }
+
+    problems
+}
+
+/// Path where the user config file is expected to be
+pub fn get_config_path() -> Result<PathBuf, ConfigError> {
+    match home::home_dir() {
+        Some(home) => Ok(config_path_in(&home)),
+        None => {
+            debug!("could not find the home directory");
+            Err(ConfigError::HomeNotFound)
+        }
+    }
+}
+
+/// `~/.config/people/config.yaml`, on every platform
+fn config_path_in(home: &Path) -> PathBuf {
+    CONFIG_PATH
+        .iter()
+        .fold(home.to_path_buf(), |path, component| path.join(component))
+}
+
+fn load_config_from_user_config_file() -> Result<ConfigFile, ConfigError> {
+    let path = get_config_path()?;
+
+    if !path.exists() {
+        return Err(ConfigError::ConfigFileNotFound(path));
+    }
+
+    let content = match files::read_text(&path, true) {
+        Ok(content) => content,
+        Err(reason) => return Err(ConfigError::ConfigFileHasUnsupportedFormat(reason)),
+    };
+
+    match parse_config(content) {
+        Ok(config_file) => Ok(config_file),
+        Err(error) => Err(ConfigError::ConfigFileHasUnsupportedFormat(error)),
+    }
+}
+
+/// The lists of people of a file in `include`
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct IncludedFile {
+    pub ignore: Option<Vec<IgnorePattern>>,
+    pub not_people: Option<Vec<IgnorePattern>>,
+    pub people: Option<Vec<Person>>,
+    pub archived: Option<Vec<Person>>,
+}
+
+fn read_included_file(path: &Path) -> Result<IncludedFile, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+    match serde_yaml::from_str::<IncludedFile>(&content) {
+        Ok(file) => Ok(file),
+        Err(reason) => Err(format!("failed to parse {path:?}, reason: {reason}")),
+    }
+}
+
+/// Where a file in `include` is, relative to the directory of the config file
+fn include_path(config_dir: &Path, path: &Path) -> Result<PathBuf, String> {
+    Ok(config_dir.join(files::expand_home(&path.to_string_lossy())?))
+}
+
+/// The files in `include` of the config file `content`, found relative to the
+/// config file at `main_path`
+pub fn include_paths(content: &str, main_path: &Path) -> Result<Vec<PathBuf>, String> {
+    let config_file = parse_config(content.to_string())?;
+    let config_dir = main_path.parent().unwrap_or(Path::new("."));
+    config_file
+        .include
+        .unwrap_or_default()
+        .iter()
+        .map(|path| include_path(config_dir, path))
+        .collect()
+}
+
+/// Add the lists of people of every file in `include` to the ones of the main
+/// config file at `main_path`, relative to which they are found, and `read`.
+/// Returns the file each included person comes from. A name or alias in more
+/// than one file is an error.
+fn merge_includes(
+    mut config_file: ConfigFile,
+    main_path: &Path,
+    read: impl Fn(&Path) -> Result<IncludedFile, String>,
+) -> Result<(ConfigFile, BTreeMap<PersonName, PathBuf>), String> {
+    let config_dir = main_path.parent().unwrap_or(Path::new("."));
+    let mut included: BTreeMap<PersonName, PathBuf> = BTreeMap::new();
+    let mut known: BTreeMap<PersonName, PathBuf> = BTreeMap::new();
+    let mut claim = |person: &Person, path: &Path| {
+        for name in std::iter::once(&person.name).chain(person.aliases.iter()) {
+            match known.get(name) {
+                Some(other) if other != path => {
+                    return Err(format!("{name} is in both {other:?} and {path:?}"))
+                }
+                _ => known.insert(name.clone(), path.to_path_buf()),
+            };
+        }
+        Ok(())
+    };
+
+    let main_people = config_file.people.iter().flatten();
+    for person in main_people.chain(config_file.archived.iter().flatten()) {
+        claim(person, main_path)?;
+    }
+
+    let mut paths: Vec<PathBuf> = vec![];
+    for path in config_file.include.clone().unwrap_or_default() {
+        let path = include_path(config_dir, &path)?;
+        if paths.contains(&path) {
+            return Err(format!("{path:?} is included more than once"));
+        }
+
+        let file = read(&path)?;
+        for person in file.people.iter().chain(file.archived.iter()).flatten() {
+            claim(person, &path)?;
+            included.insert(person.name.clone(), path.clone());
+        }
+
+        let lists = [
+            (&mut config_file.people, file.people),
+            (&mut config_file.archived, file.archived),
+        ];
+        for (list, people) in lists {
+            list.get_or_insert_with(Vec::new)
+                .extend(people.unwrap_or_default());
+        }
+        let patterns = [
+            (&mut config_file.ignore, file.ignore),
+            (&mut config_file.not_people, file.not_people),
+        ];
+        for (list, more) in patterns {
+            list.get_or_insert_with(Vec::new)
+                .extend(more.unwrap_or_default());
+        }
+        paths.push(path);
     }
+
+    Ok((config_file, included))
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq)]
-pub struct Person {
-    pub name: PersonName,
-    pub location: Location,
-    pub themes: Vec<Theme>,
-    pub remind_after: Option<DurationStr>,
+/// Lines taken by the top-level `key` of a YAML document, from its own line to
+/// the next top-level key, leaving out the comments and blank lines before it
+fn section_lines(lines: &[&str], key: &str) -> Option<Range<usize>> {
+    let is_top_level_key = |line: &str| {
+        !line.starts_with([' ', '\t', '#', '-', '\r', '\n']) && !line.starts_with("...")
+    };
+    let prefix = format!("{key}:");
+    let start = lines.iter().position(|line| line.starts_with(&prefix))?;
+
+    let mut end = lines[start + 1..]
+        .iter()
+        .position(|line| is_top_level_key(line))
+        .map(|offset| start + 1 + offset)
+        .unwrap_or(lines.len());
+    while end > start + 1 {
+        let line = lines[end - 1].trim();
+        if !(line.is_empty() || line.starts_with('#')) {
+            break;
+        }
+        end -= 1;
+    }
+
+    Some(start..end)
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq)]
-struct ConfigFile {
-    pub people_dir: Box<PathBuf>,
-    pub ignore: Option<Vec<PersonName>>,
-    pub people: Option<Vec<Person>>,
+/// Replace the text of a top-level `key` with `value`, or remove it if none.
+/// The rest of the text, comments included, is left as it is.
+fn splice_section(content: &str, key: &str, value: Option<&Value>) -> Result<String, String> {
+    let replacement = match value {
+        Some(value) => {
+            let mut section = Mapping::new();
+            section.insert(Value::from(key), value.clone());
+            match serde_yaml::to_string(&section) {
+                Ok(text) => text,
+                Err(error) => return Err(format!("failed to serialize {key} because {error}")),
+            }
+        }
+        None => "".to_string(),
+    };
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    match section_lines(&lines, key) {
+        Some(range) => Ok(format!(
+            "{}{replacement}{}",
+            lines[..range.start].concat(),
+            lines[range.end..].concat()
+        )),
+        None if content.is_empty() || content.ends_with('\n') => {
+            Ok(format!("{content}{replacement}"))
+        }
+        None => Ok(format!("{content}\n{replacement}")),
+    }
 }
 
-type ErrorReason = String;
+/// A person in a section of people, with the comments before them and the
+/// text they were read from
+struct PersonItem<'a> {
+    comments: Vec<&'a str>,
+    text: Vec<&'a str>,
+    person: Person,
+}
 
-#[derive(Debug)]
-pub enum ConfigError {
-    HomeNotFound,
-    ConfigFileNotFound(PathBuf),
-    ConfigFileHasUnsupportedFormat(ErrorReason),
+/// The block list items of a section of people, and how much they are
+/// indented. `None` if it is laid out in any other way.
+fn person_items<'a>(lines: &[&'a str]) -> Option<(usize, Vec<PersonItem<'a>>)> {
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+    let is_comment = |line: &str| line.trim().is_empty() || line.trim().starts_with('#');
+
+    let first = lines.iter().find(|line| !is_comment(line))?;
+    let indent = indent_of(first);
+
+    let mut items: Vec<(Vec<&str>, Vec<&str>)> = vec![];
+    let mut comments: Vec<&str> = vec![];
+    for line in lines {
+        if is_comment(line) {
+            comments.push(line);
+            continue;
+        }
+        if indent_of(line) == indent && line.trim_start().starts_with('-') {
+            items.push((std::mem::take(&mut comments), vec![line]));
+            continue;
+        }
+        match items.last_mut() {
+            Some((_, text)) if indent_of(line) > indent => {
+                text.append(&mut comments);
+                text.push(line);
+            }
+            _ => return None,
+        }
+    }
+    if let Some((_, text)) = items.last_mut() {
+        text.append(&mut comments);
+    }
+
+    let items = items
+        .into_iter()
+        .map(|(comments, text)| {
+            let yaml: String = text
+                .iter()
+                .map(|line| &line[indent.min(indent_of(line))..])
+                .collect();
+            match serde_yaml::from_str::<Vec<Person>>(&yaml) {
+                Ok(mut people) if people.len() == 1 => Some(PersonItem {
+                    comments,
+                    text,
+                    person: people.remove(0),
+                }),
+                _ => None,
+            }
+        })
+        .collect::<Option<Vec<PersonItem>>>()?;
+
+    Some((indent, items))
 }
 
-fn parse_config(content: String) -> Result<ConfigFile, String> {
-    match serde_yaml::from_str::<ConfigFile>(&content) {
-        Ok(config_file) => Ok(config_file),
-        Err(error) => {
-            debug!("failed to parse config file, reason: {error:?}");
-            return Err(error.to_string());
+/// Replace the people of the top-level `key` with `people`, rewriting only the
+/// ones that changed. Everyone else is left as they were, comments included.
+fn splice_people(content: &str, key: &str, people: &[Person]) -> Result<String, String> {
+    let value = match serde_yaml::to_value(people) {
+        Ok(value) => value,
+        Err(error) => return Err(format!("failed to serialize {key} because {error}")),
+    };
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let range = match section_lines(&lines, key) {
+        Some(range) => range,
+        None => return splice_section(content, key, Some(&value)),
+    };
+    let (indent, mut items) = match lines[range.start].trim_end() == format!("{key}:") {
+        true => match person_items(&lines[range.start + 1..range.end]) {
+            Some(found) => found,
+            None => return splice_section(content, key, Some(&value)),
+        },
+        false => return splice_section(content, key, Some(&value)),
+    };
+
+    let mut section: Vec<String> = vec![lines[range.start].to_string()];
+    for person in people {
+        if let Some(index) = items.iter().position(|item| &item.person == person) {
+            let item = items.remove(index);
+            section.extend(
+                item.comments
+                    .iter()
+                    .chain(item.text.iter())
+                    .map(|line| line.to_string()),
+            );
+            continue;
+        }
+        if let Some(index) = items
+            .iter()
+            .position(|item| item.person.name == person.name)
+        {
+            let item = items.remove(index);
+            section.extend(item.comments.iter().map(|line| line.to_string()));
         }
+        let text = match serde_yaml::to_string(&[person]) {
+            Ok(text) => text,
+            Err(error) => {
+                return Err(format!(
+                    "failed to serialize {} because {error}",
+                    person.name
+                ))
+            }
+        };
+        section.extend(
+            text.lines()
+                .map(|line| format!("{}{line}\n", " ".repeat(indent))),
+        );
     }
+
+    Ok(format!(
+        "{}{}{}",
+        lines[..range.start].concat(),
+        section.concat(),
+        lines[range.end..].concat()
+    ))
 }
 
-fn load_config_from_user_config_file() -> Result<ConfigFile, ConfigError> {
-    let home_str = match std::env::var("HOME") {
-        Ok(home) => home,
-        Err(error) => {
-            debug!("could not find HOME environment variable, reason: {error:?}");
-            return Err(ConfigError::HomeNotFound);
+/// Replace sections of people of the config file content, like `people`,
+/// keeping the rest. Empty sections other than `people` are removed. Comments
+/// are kept, but for the ones of removed people, unless the file is laid out
+/// in a way that only rewriting it whole can handle.
+fn replace_people_in_config(
+    content: &str,
+    sections: &[(&str, &[Person])],
+) -> Result<String, String> {
+    let mut document: Value = match serde_yaml::from_str(content) {
+        Ok(document) => document,
+        Err(error) => return Err(format!("failed to parse because {error}")),
+    };
+
+    let mapping: &mut Mapping = match document.as_mapping_mut() {
+        Some(mapping) => mapping,
+        None => return Err("expected config file to be a YAML mapping".to_string()),
+    };
+
+    let mut spliced = content.to_string();
+    for (section, people) in sections {
+        if people.is_empty() && *section != "people" {
+            mapping.remove(*section);
+            spliced = splice_section(&spliced, section, None)?;
+            continue;
         }
+        let people_value = match serde_yaml::to_value(people) {
+            Ok(value) => value,
+            Err(error) => return Err(format!("failed to serialize {section} because {error}")),
+        };
+        spliced = splice_people(&spliced, section, people)?;
+        mapping.insert(Value::from(*section), people_value);
+    }
+
+    match serde_yaml::from_str::<Value>(&spliced) {
+        Ok(result) if result == document => return Ok(spliced),
+        _ => debug!("config sections could not be replaced in place, rewriting it whole"),
+    }
+
+    match serde_yaml::to_string(&document) {
+        Ok(updated) => Ok(updated),
+        Err(error) => Err(format!("failed to serialize config because {error}")),
+    }
+}
+
+/// Overwrite the `people` section of the user config file
+pub fn save_people(
+    config: &Config,
+    people: &[Person],
+    transaction: &Transaction,
+) -> Result<PathBuf, String> {
+    save_people_sections(config, &[("people", people)], transaction)
+}
+
+/// Overwrite the `people` and `archived` sections of the user config file
+pub fn save_archive(
+    config: &Config,
+    people: &[Person],
+    archived: &[Person],
+    transaction: &Transaction,
+) -> Result<PathBuf, String> {
+    save_people_sections(
+        config,
+        &[("people", people), ("archived", archived)],
+        transaction,
+    )
+}
+
+/// People that came from an included file are written back to it, and
+/// everyone else to the main config file
+fn save_people_sections(
+    config: &Config,
+    sections: &[(&str, &[Person])],
+    transaction: &Transaction,
+) -> Result<PathBuf, String> {
+    let path = match get_config_path() {
+        Ok(path) => path,
+        Err(_) => return Err("home directory not found".to_string()),
     };
+    config.check_writable(&path)?;
+
+    let mut files: Vec<&PathBuf> = config.included.values().collect();
+    files.sort();
+    files.dedup();
+    for file in files {
+        let from_file = |person: &&Person| config.included.get(&person.name) == Some(file);
+        write_people_sections(file, sections, from_file, transaction)?;
+    }
 
-    let home = Path::new(&home_str);
-    let path = home.join(CONFIG_PATH.to_string());
+    let from_main = |person: &&Person| !config.included.contains_key(&person.name);
+    write_people_sections(&path, sections, from_main, transaction)?;
 
-    if path.exists() == false {
-        return Err(ConfigError::ConfigFileNotFound(path));
+    Ok(path)
+}
+
+fn write_people_sections(
+    path: &Path,
+    sections: &[(&str, &[Person])],
+    keep: impl Fn(&&Person) -> bool,
+    transaction: &Transaction,
+) -> Result<(), String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+
+    let kept: Vec<(&str, Vec<Person>)> = sections
+        .iter()
+        .map(|(section, people)| (*section, people.iter().filter(&keep).cloned().collect()))
+        .collect();
+    let kept: Vec<(&str, &[Person])> = kept
+        .iter()
+        .map(|(section, people)| (*section, people.as_slice()))
+        .collect();
+
+    let updated = replace_people_in_config(&content, &kept)?;
+    if updated == content {
+        return Ok(());
     }
+    transaction.write(path, &updated)
+}
 
-    let content = fs::read_to_string(&path).unwrap();
+fn config_from_file(config_file: ConfigFile) -> Result<Config, String> {
+    let ignore: Vec<IgnorePattern> = config_file.ignore.unwrap_or_default();
 
-    match parse_config(content) {
-        Ok(config_file) => Ok(config_file),
-        Err(error) => {
-            return Err(ConfigError::ConfigFileHasUnsupportedFormat(
-                error.to_string(),
+    let people_dir = files::expand_home(&config_file.people_dir.to_string_lossy())?;
+
+    if let Some(format) = &config_file.date_format {
+        dates::validate_format(format)?;
+    }
+
+    let people: Vec<Person> = config_file.people.unwrap_or_default();
+
+    let front_matter: Vec<FrontMatterField> = match config_file.front_matter {
+        Some(fields) => fields,
+        None => FrontMatterField::all(),
+    };
+
+    let references = config_file.references.unwrap_or_default();
+
+    let log_globs = match config_file.log_globs {
+        Some(globs) if globs.is_empty() => return Err("log_globs can't be empty".to_string()),
+        Some(globs) => globs,
+        None => vec![LogGlob::default()],
+    };
+
+    let summary = config_file.summary.unwrap_or_default();
+    summary.validate()?;
+
+    let backup = config_file.backup.unwrap_or_default();
+    let backup = BackupConfig {
+        dir: expand_path(backup.dir)?,
+        ..backup
+    };
+
+    let templates = config_file.templates.unwrap_or_default();
+    let templates = Templates {
+        summary: expand_path(templates.summary)?,
+        per_person: expand_path(templates.per_person)?,
+        reminder: expand_path(templates.reminder)?,
+        notify: expand_path(templates.notify)?,
+        site_index: expand_path(templates.site_index)?,
+        site_person: expand_path(templates.site_person)?,
+    };
+
+    let mut profiles = config_file.profiles.unwrap_or_default();
+    for (name, profile) in profiles.iter_mut() {
+        if name == DEFAULT_PROFILE {
+            return Err(format!(
+                "profile name {DEFAULT_PROFILE:?} is reserved for the top-level people_dir"
             ));
         }
+        profile.people_dir = files::expand_home(&profile.people_dir.to_string_lossy())?;
     }
+
+    let config = Config {
+        people_dir,
+        ignore,
+        not_people: config_file.not_people.unwrap_or_default(),
+        people,
+        archived: config_file.archived.unwrap_or_default(),
+        front_matter,
+        references,
+        carddav: config_file.carddav,
+        caldav: config_file.caldav,
+        tiers: config_file.tiers.unwrap_or_default(),
+        templates,
+        smtp: config_file.smtp,
+        telegram: config_file.telegram,
+        slack: config_file.slack,
+        profiles,
+        kinds: config_file.kinds.unwrap_or_else(default_kinds),
+        timezone: config_file.timezone,
+        theme_frequencies: config_file.theme_frequencies.unwrap_or_default(),
+        backup,
+        git: config_file.git.unwrap_or_default(),
+        dedupe_entries: config_file.dedupe_entries.unwrap_or(false),
+        per_person_path: config_file.per_person_path.unwrap_or_default(),
+        log_globs,
+        log_mode: config_file.log_mode.unwrap_or_default(),
+        journal_mentions: config_file.journal_mentions.unwrap_or_default(),
+        strict_encoding: config_file.strict_encoding.unwrap_or(false),
+        date_format: config_file.date_format,
+        read_only: config_file.read_only.unwrap_or(false),
+        per_person: config_file.per_person.unwrap_or_default(),
+        summary,
+        hooks: config_file.hooks.unwrap_or_default(),
+        prompt: config_file.prompt.unwrap_or_default(),
+        included: BTreeMap::new(),
+        input: None,
+    };
+
+    Ok(config)
 }
 
 pub fn get_config() -> Result<Config, String> {
@@ -91,7 +2065,7 @@ pub fn get_config() -> Result<Config, String> {
         Ok(config) => config,
         Err(reason) => {
             let reason = match reason {
-                ConfigError::HomeNotFound => format!("HOME not found"),
+                ConfigError::HomeNotFound => "home directory not found".to_string(),
                 ConfigError::ConfigFileNotFound(expected_path) => {
                     format!("expected file at {expected_path:?}, but it does not exist")
                 }
@@ -104,35 +2078,38 @@ pub fn get_config() -> Result<Config, String> {
         }
     };
 
-    let ignore: Vec<PersonName> = match config_file.ignore {
-        Some(people) => people,
-        None => vec![],
-    };
-
-    let people_dir = match expanduser(config_file.people_dir.display().to_string()) {
+    let path = match get_config_path() {
         Ok(path) => path,
-        Err(reason) => return Err(reason.to_string()),
+        Err(_) => return Err("home directory not found".to_string()),
     };
-
-    let people: Vec<Person> = match config_file.people {
-        Some(people) => people,
-        None => vec![],
+    let (config_file, included) = match merge_includes(config_file, &path, read_included_file) {
+        Ok(merged) => merged,
+        Err(reason) => return Err(format!("failed to include files, reason: {reason}")),
     };
 
-    let config = Config {
-        people_dir,
-        ignore,
-        people,
-    };
+    let config = config_from_file(config_file)?;
+    Ok(Config { included, ..config })
+}
 
-    Ok(config)
+/// Like `get_config`, narrowed down to `profile` if any
+pub fn get_profile_config(profile: Option<&str>) -> Result<Config, String> {
+    let config = get_config()?;
+    match profile {
+        Some(name) => config.for_profile(name),
+        None => Ok(config),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use indoc::indoc;
     use pretty_assertions::assert_eq;
 
+    fn patterns(texts: &[&str]) -> Vec<IgnorePattern> {
+        texts.iter().map(|text| text.parse().unwrap()).collect()
+    }
+
     #[test]
     fn test_parse_config_with_ignore() {
         let config_file_content = r#"
@@ -144,14 +2121,57 @@ mod tests {
         .to_string();
 
         let expected = Ok(ConfigFile {
+            version: None,
             people_dir: Box::new(Path::new("~/people").to_path_buf()),
-            ignore: Some(vec!["JohnDoe".to_string(), "JaneDoe".to_string()]),
+            include: None,
+            not_people: None,
+            ignore: Some(patterns(&["JohnDoe", "JaneDoe"])),
             people: None,
+            archived: None,
+            front_matter: None,
+            references: None,
+            carddav: None,
+            caldav: None,
+            tiers: None,
+            templates: None,
+            smtp: None,
+            telegram: None,
+            slack: None,
+            profiles: None,
+            kinds: None,
+            timezone: None,
+            theme_frequencies: None,
+            backup: None,
+            git: None,
+            dedupe_entries: None,
+            per_person_path: None,
+            log_globs: None,
+            log_mode: None,
+            journal_mentions: None,
+            strict_encoding: None,
+            date_format: None,
+            read_only: None,
+            per_person: None,
+            summary: None,
+            hooks: None,
+            prompt: None,
         });
 
         assert_eq!(parse_config(config_file_content), expected);
     }
 
+    #[test]
+    fn test_parse_config_without_version_keeps_every_key() {
+        let config_file = parse_config("people_dir: ~/people\nread_only: true\n".to_string());
+        assert_eq!(config_file.unwrap().read_only, Some(true));
+
+        let error = parse_config("people_dir: ~/people\nremind: weekly\n".to_string()).unwrap_err();
+        assert!(error.starts_with("unknown field `remind`"), "{error}");
+        assert!(
+            error.ends_with("run `people config migrate` to remove the keys version 1 didn't use")
+        );
+    }
+
     #[test]
     fn test_parse_config_without_ignore() {
         let config_file_content = r#"
@@ -160,9 +2180,40 @@ mod tests {
         .to_string();
 
         let expected = Ok(ConfigFile {
+            version: None,
             people_dir: Box::new(Path::new("~/people").to_path_buf()),
+            include: None,
             ignore: None,
+            not_people: None,
             people: None,
+            archived: None,
+            front_matter: None,
+            references: None,
+            carddav: None,
+            caldav: None,
+            tiers: None,
+            templates: None,
+            smtp: None,
+            telegram: None,
+            slack: None,
+            profiles: None,
+            kinds: None,
+            timezone: None,
+            theme_frequencies: None,
+            backup: None,
+            git: None,
+            dedupe_entries: None,
+            per_person_path: None,
+            log_globs: None,
+            log_mode: None,
+            journal_mentions: None,
+            strict_encoding: None,
+            date_format: None,
+            read_only: None,
+            per_person: None,
+            summary: None,
+            hooks: None,
+            prompt: None,
         });
 
         assert_eq!(parse_config(config_file_content), expected);
@@ -178,9 +2229,40 @@ mod tests {
         .to_string();
 
         let expected = Ok(ConfigFile {
+            version: None,
             people_dir: Box::new(Path::new("~/people").to_path_buf()),
-            ignore: Some(vec!["Lucía".to_string()]),
+            include: None,
+            not_people: None,
+            ignore: Some(patterns(&["Lucía"])),
             people: None,
+            archived: None,
+            front_matter: None,
+            references: None,
+            carddav: None,
+            caldav: None,
+            tiers: None,
+            templates: None,
+            smtp: None,
+            telegram: None,
+            slack: None,
+            profiles: None,
+            kinds: None,
+            timezone: None,
+            theme_frequencies: None,
+            backup: None,
+            git: None,
+            dedupe_entries: None,
+            per_person_path: None,
+            log_globs: None,
+            log_mode: None,
+            journal_mentions: None,
+            strict_encoding: None,
+            date_format: None,
+            read_only: None,
+            per_person: None,
+            summary: None,
+            hooks: None,
+            prompt: None,
         });
 
         assert_eq!(parse_config(config_file_content), expected);
@@ -199,20 +2281,778 @@ mod tests {
               - painting
               - uni
             remind_after: 3 months
+            pinned: true
+            email: foo@bar.com
+            telegram: "@foobar"
         "#
         .to_string();
 
         let expected = Ok(ConfigFile {
+            version: None,
             people_dir: Box::new(Path::new("~/people").to_path_buf()),
-            ignore: Some(vec!["Lucía".to_string()]),
+            include: None,
+            not_people: None,
+            ignore: Some(patterns(&["Lucía"])),
             people: Some(vec![Person {
                 name: "FooBar".to_string(),
+                aliases: vec![],
                 location: "Here".to_string(),
                 themes: vec!["painting".to_string(), "uni".to_string()],
-                remind_after: Some("3 months".to_string()),
+                remind_after: Some("3 months".parse().unwrap()),
+                birthday: None,
+                pinned: true,
+                email: Some("foo@bar.com".to_string()),
+                phone: None,
+                telegram: Some("@foobar".to_string()),
+                frequency: None,
             }]),
+            archived: None,
+            front_matter: None,
+            references: None,
+            carddav: None,
+            caldav: None,
+            tiers: None,
+            templates: None,
+            smtp: None,
+            telegram: None,
+            slack: None,
+            profiles: None,
+            kinds: None,
+            timezone: None,
+            theme_frequencies: None,
+            backup: None,
+            git: None,
+            dedupe_entries: None,
+            per_person_path: None,
+            log_globs: None,
+            log_mode: None,
+            journal_mentions: None,
+            strict_encoding: None,
+            date_format: None,
+            read_only: None,
+            per_person: None,
+            summary: None,
+            hooks: None,
+            prompt: None,
+        });
+
+        assert_eq!(parse_config(config_file_content), expected);
+    }
+
+    #[test]
+    fn test_parse_config_with_front_matter_fields() {
+        let config_file_content = r#"
+        people_dir: ~/people
+        front_matter:
+          - name
+          - last_interaction
+        "#
+        .to_string();
+
+        let expected = Ok(ConfigFile {
+            version: None,
+            people_dir: Box::new(Path::new("~/people").to_path_buf()),
+            include: None,
+            ignore: None,
+            not_people: None,
+            people: None,
+            archived: None,
+            front_matter: Some(vec![
+                FrontMatterField::Name,
+                FrontMatterField::LastInteraction,
+            ]),
+            references: None,
+            carddav: None,
+            caldav: None,
+            tiers: None,
+            templates: None,
+            smtp: None,
+            telegram: None,
+            slack: None,
+            profiles: None,
+            kinds: None,
+            timezone: None,
+            theme_frequencies: None,
+            backup: None,
+            git: None,
+            dedupe_entries: None,
+            per_person_path: None,
+            log_globs: None,
+            log_mode: None,
+            journal_mentions: None,
+            strict_encoding: None,
+            date_format: None,
+            read_only: None,
+            per_person: None,
+            summary: None,
+            hooks: None,
+            prompt: None,
+        });
+
+        assert_eq!(parse_config(config_file_content), expected);
+    }
+
+    #[test]
+    fn test_parse_config_with_references() {
+        let config_file_content = r#"
+        people_dir: ~/people
+        references:
+          wikilinks: true
+          wikilink_names: verbatim
+        "#
+        .to_string();
+
+        let expected = Ok(ConfigFile {
+            version: None,
+            people_dir: Box::new(Path::new("~/people").to_path_buf()),
+            include: None,
+            ignore: None,
+            not_people: None,
+            people: None,
+            archived: None,
+            front_matter: None,
+            references: Some(ReferenceSyntax {
+                hashtags: true,
+                wikilinks: true,
+                wikilink_names: WikilinkNames::Verbatim,
+            }),
+            carddav: None,
+            caldav: None,
+            tiers: None,
+            templates: None,
+            smtp: None,
+            telegram: None,
+            slack: None,
+            profiles: None,
+            kinds: None,
+            timezone: None,
+            theme_frequencies: None,
+            backup: None,
+            git: None,
+            dedupe_entries: None,
+            per_person_path: None,
+            log_globs: None,
+            log_mode: None,
+            journal_mentions: None,
+            strict_encoding: None,
+            date_format: None,
+            read_only: None,
+            per_person: None,
+            summary: None,
+            hooks: None,
+            prompt: None,
+        });
+
+        assert_eq!(parse_config(config_file_content), expected);
+    }
+
+    #[test]
+    fn test_replace_people_in_config() {
+        let config_file_content = indoc!(
+            "
+            people_dir: ~/people
+            ignore:
+            - Lucía
+            "
+        );
+
+        let people = vec![Person {
+            name: "FooBar".to_string(),
+            location: "Here".to_string(),
+            birthday: Some(NaiveDate::from_ymd_opt(1990, 5, 1).unwrap()),
+            ..Default::default()
+        }];
+
+        let expected = indoc!(
+            "
+            people_dir: ~/people
+            ignore:
+            - Lucía
+            people:
+            - name: FooBar
+              location: Here
+              birthday: 1990-05-01
+            "
+        )
+        .trim_start();
+
+        assert_eq!(
+            replace_people_in_config(config_file_content, &[("people", &people)]),
+            Ok(expected.to_string())
+        );
+
+        let with_archived = format!("{expected}archived:\n- name: JohnDoe\n");
+        assert_eq!(
+            replace_people_in_config(&with_archived, &[("people", &people), ("archived", &[])]),
+            Ok(expected.to_string())
+        );
+    }
+
+    #[test]
+    fn test_merge_includes() {
+        let read = |path: &Path| {
+            let content = match path.to_str() {
+                Some("/home/me/.config/people/family.yaml") => indoc!(
+                    "
+                    ignore: [Mum]
+                    people:
+                      - name: JaneDoe
+                        aliases: [Jane]
+                    "
+                ),
+                Some("/shared/work.yaml") => "archived:\n  - name: Abu\n",
+                Some("/home/me/.config/people/clash.yaml") => "people:\n  - name: Jane\n",
+                _ => return Err(format!("{path:?} not found")),
+            };
+            Ok(serde_yaml::from_str::<IncludedFile>(content).unwrap())
+        };
+        let main_path = Path::new("/home/me/.config/people/config.yaml");
+        let config_file = |include: &str| {
+            parse_config(format!(
+                "version: 2\npeople_dir: ~/people\ninclude: {include}\npeople:\n  - name: JohnDoe\n"
+            ))
+            .unwrap()
+        };
+
+        let (merged, included) = merge_includes(
+            config_file("[family.yaml, /shared/work.yaml]"),
+            main_path,
+            read,
+        )
+        .unwrap();
+        let config = config_from_file(merged).unwrap();
+
+        let names = |people: &[Person]| -> Vec<PersonName> {
+            people.iter().map(|person| person.name.clone()).collect()
+        };
+        assert_eq!(names(&config.people), vec!["JohnDoe", "JaneDoe"]);
+        assert_eq!(names(&config.archived), vec!["Abu"]);
+        assert!(config.is_ignored("Mum"));
+        assert_eq!(
+            included,
+            BTreeMap::from([
+                ("Abu".to_string(), PathBuf::from("/shared/work.yaml")),
+                (
+                    "JaneDoe".to_string(),
+                    PathBuf::from("/home/me/.config/people/family.yaml")
+                ),
+            ])
+        );
+
+        assert_eq!(
+            merge_includes(config_file("[family.yaml, clash.yaml]"), main_path, read)
+                .map(|_| ()),
+            Err("Jane is in both \"/home/me/.config/people/family.yaml\" and \"/home/me/.config/people/clash.yaml\"".to_string())
+        );
+        assert!(merge_includes(config_file("[missing.yaml]"), main_path, read).is_err());
+    }
+
+    #[test]
+    fn test_replace_people_in_config_keeps_comments() {
+        let config_file_content = indoc!(
+            "
+            # my config
+            people_dir: ~/people  # synced
+            people:
+              # old friends
+              - name: JohnDoe
+
+            # never remind about these
+            ignore:
+              - Lucía
+            archived:
+              - name: Abu
+            "
+        );
+
+        let people = vec![
+            Person {
+                name: "JohnDoe".to_string(),
+                ..Default::default()
+            },
+            Person {
+                name: "JaneDoe".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let expected = indoc!(
+            "
+            # my config
+            people_dir: ~/people  # synced
+            people:
+              # old friends
+              - name: JohnDoe
+              - name: JaneDoe
+
+            # never remind about these
+            ignore:
+              - Lucía
+            "
+        );
+
+        assert_eq!(
+            replace_people_in_config(
+                config_file_content,
+                &[("people", &people), ("archived", &[])]
+            ),
+            Ok(expected.to_string())
+        );
+    }
+
+    #[test]
+    fn test_replace_people_in_config_only_rewrites_changed_people() {
+        let config_file_content = indoc!(
+            "
+            people:
+              # school
+              - name: JohnDoe
+                location: London  # for now
+              # work
+              - {name: JaneDoe, location: Paris}
+              - name: Abu
+            "
+        );
+
+        let people = vec![
+            Person {
+                name: "JohnDoe".to_string(),
+                location: "Berlin".to_string(),
+                ..Default::default()
+            },
+            Person {
+                name: "JaneDoe".to_string(),
+                location: "Paris".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let expected = indoc!(
+            "
+            people:
+              # school
+              - name: JohnDoe
+                location: Berlin
+              # work
+              - {name: JaneDoe, location: Paris}
+            "
+        );
+
+        assert_eq!(
+            replace_people_in_config(config_file_content, &[("people", &people)]),
+            Ok(expected.trim_start().to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_config_with_carddav() {
+        let config_file_content = r#"
+        people_dir: ~/people
+        carddav:
+          url: https://example.com/addressbooks/me/
+          username: me
+          password_command: pass show carddav
+        "#
+        .to_string();
+
+        let expected = Ok(ConfigFile {
+            version: None,
+            people_dir: Box::new(Path::new("~/people").to_path_buf()),
+            include: None,
+            ignore: None,
+            not_people: None,
+            people: None,
+            archived: None,
+            front_matter: None,
+            references: None,
+            carddav: Some(DavConfig {
+                url: "https://example.com/addressbooks/me/".to_string(),
+                username: "me".to_string(),
+                password: None,
+                password_command: Some("pass show carddav".to_string()),
+            }),
+            caldav: None,
+            tiers: None,
+            templates: None,
+            smtp: None,
+            telegram: None,
+            slack: None,
+            profiles: None,
+            kinds: None,
+            timezone: None,
+            theme_frequencies: None,
+            backup: None,
+            git: None,
+            dedupe_entries: None,
+            per_person_path: None,
+            log_globs: None,
+            log_mode: None,
+            journal_mentions: None,
+            strict_encoding: None,
+            date_format: None,
+            read_only: None,
+            per_person: None,
+            summary: None,
+            hooks: None,
+            prompt: None,
         });
 
         assert_eq!(parse_config(config_file_content), expected);
     }
+
+    #[test]
+    fn test_config_from_yaml_str() {
+        let config = Config::from_yaml_str(indoc!(
+            "
+            people_dir: /tmp/people
+            ignore:
+              - JohnDoe
+            front_matter: []
+            "
+        ))
+        .unwrap();
+
+        assert_eq!(config.people_dir, PathBuf::from("/tmp/people"));
+        assert_eq!(config.ignore, patterns(&["JohnDoe"]));
+        assert_eq!(config.front_matter, vec![]);
+        assert_eq!(config.references, ReferenceSyntax::default());
+    }
+
+    #[test]
+    fn test_config_with_templates() {
+        let config = Config::from_yaml_str(indoc!(
+            "
+            people_dir: /tmp/people
+            templates:
+              summary: /tmp/templates/summary.tera
+            "
+        ))
+        .unwrap();
+
+        let expected = Templates {
+            summary: Some(PathBuf::from("/tmp/templates/summary.tera")),
+            per_person: None,
+            reminder: None,
+            notify: None,
+            site_index: None,
+            site_person: None,
+        };
+
+        assert_eq!(config.templates, expected);
+    }
+
+    #[test]
+    fn test_invalid_remind_after_is_rejected_on_load() {
+        let result = Config::from_yaml_str(indoc!(
+            "
+            people_dir: /tmp/people
+            people:
+              - name: FooBar
+                remind_after: 3 fortnights
+            "
+        ));
+
+        let reason = result.unwrap_err();
+        assert!(reason.contains("unsupported unit found: \"fortnights\""));
+        assert!(reason.contains("people[0]"));
+    }
+
+    #[test]
+    fn test_config_from_invalid_yaml_str() {
+        assert!(Config::from_yaml_str("ignore: []").is_err());
+    }
+
+    #[test]
+    fn test_config_builder() {
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .person(Person {
+                name: "JohnDoe".to_string(),
+                ..Default::default()
+            })
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(config.people_dir, PathBuf::from("/tmp/people"));
+        assert_eq!(config.people.len(), 2);
+        assert_eq!(config.front_matter, FrontMatterField::all());
+    }
+
+    #[test]
+    fn test_config_for_profile() {
+        let config = Config::from_yaml_str(indoc!(
+            "
+            people_dir: /tmp/people
+            ignore:
+              - Me
+            profiles:
+              work:
+                people_dir: /tmp/work
+                people:
+                  - name: JohnDoe
+            "
+        ))
+        .unwrap();
+
+        let work = config.for_profile("work").unwrap();
+        assert_eq!(work.people_dir, PathBuf::from("/tmp/work"));
+        assert_eq!(work.ignore, vec![]);
+        assert_eq!(work.people.len(), 1);
+
+        let names: Vec<String> = config
+            .all_profiles()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["default".to_string(), "work".to_string()]);
+
+        let reason = config.for_profile("home").unwrap_err();
+        assert!(reason.contains("expected one of: work"));
+    }
+
+    #[test]
+    fn test_config_with_timezone() {
+        let config = Config::from_yaml_str(indoc!(
+            "
+            people_dir: /tmp/people
+            timezone: Pacific/Auckland
+            "
+        ))
+        .unwrap();
+        assert_eq!(config.timezone, Some(chrono_tz::Pacific::Auckland));
+
+        let result = Config::from_yaml_str("people_dir: /tmp/people\ntimezone: Mars/Olympus");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_per_person_path() {
+        let config = Config::from_yaml_str(indoc!(
+            "
+            people_dir: /tmp/people
+            per_person_path: per-person-logs/{first_letter}/{person}.md
+            "
+        ))
+        .unwrap();
+        assert_eq!(
+            config.get_person_log_path("zoë Doe"),
+            PathBuf::from("/tmp/people/per-person-logs/Z/zoë-Doe.md")
+        );
+        assert_eq!(
+            config.get_people_index_path(),
+            PathBuf::from("/tmp/people/per-person-logs/index.md")
+        );
+
+        let default = Config::builder().people_dir("/tmp/people").build();
+        assert_eq!(
+            default.get_person_log_path("JaneDoe"),
+            PathBuf::from("/tmp/people/per-person-logs/JaneDoe.md")
+        );
+        let own_dirs: PersonPathTemplate = "{person}/index.md".parse().unwrap();
+        assert_eq!(own_dirs.base_dir(), PathBuf::new());
+
+        for invalid in [
+            "logs/{name}.md",
+            "per-person-logs.md",
+            "../{person}.md",
+            "/tmp/{person}.md",
+        ] {
+            assert!(invalid.parse::<PersonPathTemplate>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_log_globs() {
+        let config = Config::from_yaml_str(indoc!(
+            "
+            people_dir: /tmp/[people]
+            log_globs:
+              - log/*.md
+              - /archive/**/*.people.md
+              - notes/*.org
+              - {glob: notes/*.txt, format: org}
+            "
+        ))
+        .unwrap();
+
+        let patterns: Vec<String> = config
+            .log_globs
+            .iter()
+            .map(|glob| glob.pattern(&config.people_dir))
+            .collect();
+        // `/archive` has no drive, so it is relative on Windows
+        let archive = match Path::new("/archive").is_absolute() {
+            true => "/archive/**/*.people.md".to_string(),
+            false => format!("/tmp/[[]people[]]{MAIN_SEPARATOR}/archive/**/*.people.md"),
+        };
+        assert_eq!(
+            patterns,
+            vec![
+                format!("/tmp/[[]people[]]{MAIN_SEPARATOR}log/*.md"),
+                archive,
+                format!("/tmp/[[]people[]]{MAIN_SEPARATOR}notes/*.org"),
+                format!("/tmp/[[]people[]]{MAIN_SEPARATOR}notes/*.txt"),
+            ]
+        );
+        let syntaxes: Vec<LogSyntax> = config.log_globs.iter().map(LogGlob::syntax).collect();
+        assert_eq!(
+            syntaxes,
+            vec![
+                LogSyntax::Markdown,
+                LogSyntax::Markdown,
+                LogSyntax::Org,
+                LogSyntax::Org
+            ]
+        );
+
+        assert!(Config::from_yaml_str("people_dir: /tmp\nlog_globs: []").is_err());
+        assert!("log/***.md".parse::<LogGlob>().is_err());
+    }
+
+    #[test]
+    fn test_config_path_in() {
+        let home = Path::new("home").join("me");
+        assert_eq!(
+            config_path_in(&home),
+            home.join(".config").join("people").join("config.yaml")
+        );
+    }
+
+    #[test]
+    fn test_read_only() {
+        let config = Config::from_yaml_str(indoc!(
+            "
+            version: 2
+            people_dir: /tmp/people
+            read_only: true
+            "
+        ))
+        .unwrap();
+        assert_eq!(
+            config.check_writable(Path::new("/tmp/people/log/2024.md")),
+            Err("not writing \"/tmp/people/log/2024.md\" in read-only mode, see `--read-only` or `read_only` in the config".to_string())
+        );
+
+        let default = Config::builder().people_dir("/tmp/people").build();
+        assert_eq!(default.check_writable(&default.people_dir), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let content = indoc!(
+            "
+            peple_dir: ~/people
+            people:
+              - name: JaneDoe
+                remind_after: 3 fortnights
+              - name: JohnDoe
+                nickname: Johnny
+            archived:
+              - name: JaneDoe
+            summary: {group_by: week}
+            "
+        );
+
+        let problems: Vec<String> = validate_config(content)
+            .into_iter()
+            .map(|problem| match problem.message.split_once(", expected") {
+                Some((start, _)) => format!("{:?}: {start}", problem.line),
+                None => format!("{:?}: {}", problem.line, problem.message),
+            })
+            .collect();
+
+        assert_eq!(
+            problems,
+            vec![
+                "None: missing people_dir",
+                "Some(1): peple_dir: unknown field `peple_dir`",
+                "Some(3): people[0]: failed to parse '3 fortnights', reason: unsupported unit found: \"fortnights\"",
+                "Some(5): people[1]: unknown field `nickname`",
+                "Some(8): JaneDoe is in the config more than once",
+                "Some(9): summary: unknown variant `week`",
+            ]
+        );
+
+        assert_eq!(validate_config("people_dir: /tmp/people\n"), vec![]);
+        assert_eq!(
+            validate_config("people_dir: [\n").len(),
+            1,
+            "a syntax error is the only problem"
+        );
+        assert_eq!(
+            validate_config("version: 3\npeople_dir: /tmp/people\n"),
+            vec![ConfigProblem {
+                line: Some(1),
+                message: "version 3 is newer than 2, the latest this version of people supports"
+                    .to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_summary_config() {
+        let config = Config::from_yaml_str(indoc!(
+            "
+            people_dir: /tmp/people
+            summary:
+              boundaries: [7, 30, 90]
+              labels: [this week, this month, this quarter, older]
+            "
+        ))
+        .unwrap();
+
+        assert_eq!(
+            config.summary,
+            SummaryConfig {
+                boundaries: vec![7, 30, 90],
+                labels: vec![
+                    "this week".to_string(),
+                    "this month".to_string(),
+                    "this quarter".to_string(),
+                    "older".to_string(),
+                ],
+                group_by: SummaryGrouping::DaysAgo,
+                count: MentionLevel::Main,
+            }
+        );
+
+        let invalid = [
+            "summary: {boundaries: [30, 7]}",
+            "summary: {boundaries: [0, 7]}",
+            "summary: {boundaries: [7], labels: [recent]}",
+        ];
+        for summary in invalid {
+            let content = format!("people_dir: /tmp/people\n{summary}");
+            assert!(Config::from_yaml_str(&content).is_err(), "{summary}");
+        }
+    }
+
+    #[test]
+    fn test_ignore_patterns() {
+        let config = Config::from_yaml_str(indoc!(
+            "
+            people_dir: /tmp/people
+            ignore:
+              - Me
+              - Test*
+              - /^tmp_/
+            not_people:
+              - Budget
+            "
+        ))
+        .unwrap();
+
+        assert!(config.is_ignored("Me"));
+        assert!(!config.is_ignored("Meg"));
+        assert!(config.is_ignored("TestUser"));
+        assert!(config.is_ignored("tmp_alice"));
+        assert!(!config.is_ignored("Budget"));
+        assert_eq!(config.not_people, patterns(&["Budget"]));
+
+        let result = Config::from_yaml_str("people_dir: /tmp/people\nignore: ['/(/']");
+        assert!(result.unwrap_err().contains("invalid regex"));
+    }
 }
@@ -0,0 +1,121 @@
+use std::process::Command;
+
+use crate::config::Person;
+
+/// A way to reach someone, e.g. their email
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContactMethod {
+    pub label: &'static str,
+    pub value: String,
+    /// What opens it, e.g. `mailto:jane@example.com`
+    pub url: String,
+}
+
+/// The contact details in the config, preferred first: email, phone, Telegram
+pub fn contact_methods(person: &Person) -> Vec<ContactMethod> {
+    let mut methods: Vec<ContactMethod> = vec![];
+
+    if let Some(email) = &person.email {
+        methods.push(ContactMethod {
+            label: "email",
+            value: email.clone(),
+            url: format!("mailto:{email}"),
+        });
+    }
+
+    if let Some(phone) = &person.phone {
+        let number: String = phone
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == '+')
+            .collect();
+        methods.push(ContactMethod {
+            label: "phone",
+            value: phone.clone(),
+            url: format!("tel:{number}"),
+        });
+    }
+
+    if let Some(telegram) = &person.telegram {
+        let handle = telegram.trim_start_matches('@');
+        methods.push(ContactMethod {
+            label: "telegram",
+            value: format!("@{handle}"),
+            url: format!("https://t.me/{handle}"),
+        });
+    }
+
+    methods
+}
+
+/// How to reach someone in one short line, e.g. for the reminders
+pub fn preferred_contact(person: &Person) -> Option<String> {
+    contact_methods(person)
+        .into_iter()
+        .next()
+        .map(|method| method.value)
+}
+
+#[cfg(target_os = "macos")]
+fn opener(url: &str) -> Command {
+    let mut command = Command::new("open");
+    command.arg(url);
+    command
+}
+
+#[cfg(windows)]
+fn opener(url: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.args(["/C", "start", "", url]);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn opener(url: &str) -> Command {
+    let mut command = Command::new("xdg-open");
+    command.arg(url);
+    command
+}
+
+/// Hand the URL to the system's handler, e.g. the mail client for `mailto:`
+pub fn open_url(url: &str) -> Result<(), String> {
+    match opener(url).status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("failed to open {url:?}, reason: {status}")),
+        Err(reason) => Err(format!("failed to open {url:?}, reason: {reason}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_contact_methods() {
+        let person = Person {
+            name: "JaneDoe".to_string(),
+            phone: Some("+34 600 11 22 33".to_string()),
+            telegram: Some("@janedoe".to_string()),
+            ..Default::default()
+        };
+
+        let urls: Vec<(&str, String)> = contact_methods(&person)
+            .into_iter()
+            .map(|method| (method.label, method.url))
+            .collect();
+
+        assert_eq!(
+            urls,
+            vec![
+                ("phone", "tel:+34600112233".to_string()),
+                ("telegram", "https://t.me/janedoe".to_string()),
+            ]
+        );
+        assert_eq!(
+            preferred_contact(&person),
+            Some("+34 600 11 22 33".to_string())
+        );
+        assert_eq!(preferred_contact(&Person::default()), None);
+    }
+}
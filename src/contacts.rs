@@ -0,0 +1,250 @@
+use chrono::NaiveDate;
+
+use crate::config::{DavConfig, Person};
+use crate::dav;
+use crate::model::PersonName;
+use crate::vcard::{self, Contact, PersonChange};
+
+/// A source of contacts that people metadata can be synced from
+pub trait ContactsProvider {
+    fn fetch_contacts(&self) -> Result<Vec<Contact>, String>;
+}
+
+pub struct CardDavProvider {
+    config: DavConfig,
+}
+
+impl CardDavProvider {
+    pub fn new(config: DavConfig) -> CardDavProvider {
+        CardDavProvider { config }
+    }
+}
+
+static ADDRESSBOOK_QUERY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<C:addressbook-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav">
+  <D:prop>
+    <C:address-data/>
+  </D:prop>
+</C:addressbook-query>"#;
+
+impl ContactsProvider for CardDavProvider {
+    fn fetch_contacts(&self) -> Result<Vec<Contact>, String> {
+        let authorization = dav::authorization(&self.config)?;
+
+        let response = ureq::request("REPORT", &self.config.url)
+            .set("Authorization", &authorization)
+            .set("Depth", "1")
+            .set("Content-Type", "application/xml; charset=utf-8")
+            .send_string(ADDRESSBOOK_QUERY);
+
+        let body = match response {
+            Ok(response) => match response.into_string() {
+                Ok(body) => body,
+                Err(reason) => return Err(format!("failed to read CardDAV response: {reason}")),
+            },
+            Err(reason) => return Err(format!("CardDAV request failed: {reason}")),
+        };
+
+        let vcards: Vec<String> = extract_address_data(&body);
+        Ok(vcard::parse_vcards(&vcards.join("\n")))
+    }
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&#13;", "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Get the content of every `address-data` element in a multistatus response
+fn extract_address_data(body: &str) -> Vec<String> {
+    let mut vcards: Vec<String> = vec![];
+    let mut rest = body;
+
+    while let Some(tag_start) = rest.find('<') {
+        let after = &rest[tag_start + 1..];
+        let tag_end = match after.find('>') {
+            Some(index) => index,
+            None => break,
+        };
+        let tag = &after[..tag_end];
+        rest = &after[tag_end + 1..];
+
+        // skip closing and self-closing tags
+        if tag.starts_with('/') || tag.ends_with('/') {
+            continue;
+        }
+
+        // drop attributes and namespace prefix, e.g. `card:address-data xmlns:...`
+        let name = tag.split_whitespace().next().unwrap_or("");
+        let local_name = name.rsplit(':').next().unwrap_or("");
+        if local_name != "address-data" {
+            continue;
+        }
+
+        let content_end = match rest.find("</") {
+            Some(index) => index,
+            None => break,
+        };
+        vcards.push(unescape_xml(&rest[..content_end]));
+        rest = &rest[content_end..];
+    }
+
+    vcards
+}
+
+/// A birthday that differs between the config and the contacts provider
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub person: PersonName,
+    pub config: NaiveDate,
+    pub provider: NaiveDate,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncReport {
+    pub changes: Vec<PersonChange>,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Compare the names and birthdays of the contacts with the people in the config
+pub fn plan_sync(people: &[Person], contacts: &[Contact]) -> SyncReport {
+    let mut changes: Vec<PersonChange> = vec![];
+    let mut conflicts: Vec<Conflict> = vec![];
+
+    for contact in contacts {
+        let existing = match vcard::find_person(people, contact) {
+            Some(existing) => existing,
+            None => {
+                changes.push(PersonChange::Added(Box::new(Person {
+                    name: contact.person_name(),
+                    birthday: contact.birthday,
+                    ..Default::default()
+                })));
+                continue;
+            }
+        };
+
+        match (existing.birthday, contact.birthday) {
+            (None, Some(birthday)) => {
+                let mut updated = existing.clone();
+                updated.birthday = Some(birthday);
+                changes.push(PersonChange::Updated {
+                    before: Box::new(existing.clone()),
+                    after: Box::new(updated),
+                });
+            }
+            (Some(config), Some(provider)) if config != provider => {
+                conflicts.push(Conflict {
+                    person: existing.name.clone(),
+                    config,
+                    provider,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    SyncReport { changes, conflicts }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_extract_address_data() {
+        let body = indoc!(
+            r#"
+            <d:multistatus xmlns:d="DAV:" xmlns:card="urn:ietf:params:xml:ns:carddav">
+              <d:response>
+                <d:propstat>
+                  <d:prop>
+                    <card:address-data>BEGIN:VCARD&#13;
+            FN:Jane &amp; Co&#13;
+            END:VCARD&#13;
+            </card:address-data>
+                  </d:prop>
+                </d:propstat>
+              </d:response>
+              <d:response>
+                <d:propstat>
+                  <d:prop>
+                    <card:address-data/>
+                  </d:prop>
+                </d:propstat>
+              </d:response>
+            </d:multistatus>
+            "#
+        );
+
+        let expected = vec!["BEGIN:VCARD\nFN:Jane & Co\nEND:VCARD\n".to_string()];
+
+        assert_eq!(extract_address_data(body), expected);
+    }
+
+    #[test]
+    fn test_plan_sync() {
+        let people = vec![
+            Person {
+                name: "JohnDoe".to_string(),
+                ..Default::default()
+            },
+            Person {
+                name: "JaneDoe".to_string(),
+                birthday: Some(d("1990-01-01")),
+                ..Default::default()
+            },
+        ];
+        let contacts = vec![
+            Contact {
+                full_name: "John Doe".to_string(),
+                location: None,
+                birthday: Some(d("1980-01-01")),
+            },
+            Contact {
+                full_name: "Jane Doe".to_string(),
+                location: None,
+                birthday: Some(d("1990-02-02")),
+            },
+            Contact {
+                full_name: "Abu".to_string(),
+                location: Some("Ignored".to_string()),
+                birthday: None,
+            },
+        ];
+
+        let expected = SyncReport {
+            changes: vec![
+                PersonChange::Updated {
+                    before: Box::new(people[0].clone()),
+                    after: Box::new(Person {
+                        name: "JohnDoe".to_string(),
+                        birthday: Some(d("1980-01-01")),
+                        ..Default::default()
+                    }),
+                },
+                PersonChange::Added(Box::new(Person {
+                    name: "Abu".to_string(),
+                    ..Default::default()
+                })),
+            ],
+            conflicts: vec![Conflict {
+                person: "JaneDoe".to_string(),
+                config: d("1990-01-01"),
+                provider: d("1990-02-02"),
+            }],
+        };
+
+        assert_eq!(plan_sync(&people, &contacts), expected);
+    }
+}
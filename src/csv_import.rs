@@ -0,0 +1,380 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::links;
+use crate::log::{self, DatePrecision, Day, Entry, Log, ParseOptions};
+use crate::model::PersonName;
+use crate::operations::Transaction;
+
+/// Which CSV column holds each field, as 1-based positions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnMap {
+    pub date: usize,
+    pub person: usize,
+    pub note: Option<usize>,
+}
+
+impl FromStr for ColumnMap {
+    type Err = String;
+
+    /// Parse `date=1,person=2,note=3`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut date: Option<usize> = None;
+        let mut person: Option<usize> = None;
+        let mut note: Option<usize> = None;
+
+        for pair in s.split(',') {
+            let (field, column_str) = match pair.split_once('=') {
+                Some(parts) => parts,
+                None => return Err(format!("expected 'field=column', but found {pair:?}")),
+            };
+
+            let column: usize = match column_str.trim().parse() {
+                Ok(column) if column > 0 => column,
+                _ => {
+                    return Err(format!(
+                        "expected a column number starting at 1, but found {column_str:?}"
+                    ))
+                }
+            };
+
+            match field.trim() {
+                "date" => date = Some(column),
+                "person" => person = Some(column),
+                "note" => note = Some(column),
+                other => return Err(format!("unsupported field found: {other:?}")),
+            }
+        }
+
+        match (date, person) {
+            (Some(date), Some(person)) => Ok(ColumnMap { date, person, note }),
+            _ => Err("both 'date' and 'person' columns must be mapped".to_string()),
+        }
+    }
+}
+
+fn get_cell(record: &::csv::StringRecord, column: usize) -> Option<&str> {
+    record.get(column - 1).map(|cell| cell.trim())
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    let date = value.get(..10).unwrap_or(value);
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// `Jane Doe` becomes `JaneDoe`, so it can be referenced as `#JaneDoe`
+fn to_person_name(value: &str) -> PersonName {
+    value.split_whitespace().collect::<Vec<&str>>().join("")
+}
+
+pub(crate) fn build_entry(person: PersonName, note: Option<&str>) -> Entry {
+    let mut lines: Vec<String> = vec![format!("- #{person} :")];
+    if let Some(note) = note {
+        for line in note.lines().filter(|line| !line.trim().is_empty()) {
+            lines.push(format!("  - {}", line.trim()));
+        }
+    }
+
+    let content = lines.join("\n");
+
+    Entry {
+        main: [person.clone()].into(),
+        related: [person].into(),
+        links: links::find_links(&content),
+        content,
+        kind: None,
+        fields: BTreeMap::new(),
+        source_file: None,
+        line_range: 0..0,
+    }
+}
+
+/// Convert CSV rows into a log, one entry per row. The first row is skipped
+/// if it looks like a header (i.e. its date cannot be parsed).
+pub fn parse_csv(content: &str, map: &ColumnMap) -> Result<Log, String> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let mut days: HashMap<NaiveDate, Day> = HashMap::new();
+
+    for (index, result) in reader.records().enumerate() {
+        let row_number = index + 1;
+        let record = match result {
+            Ok(record) => record,
+            Err(reason) => return Err(format!("failed to read row {row_number}: {reason}")),
+        };
+
+        let date_cell = get_cell(&record, map.date).unwrap_or("");
+        let date = match parse_date(date_cell) {
+            Some(date) => date,
+            None if row_number == 1 => continue, // header
+            None => {
+                return Err(format!(
+                "failed to parse row {row_number}, reason: unsupported date found: {date_cell:?}"
+            ))
+            }
+        };
+
+        let person = match get_cell(&record, map.person) {
+            Some(person) if !person.is_empty() => to_person_name(person),
+            _ => {
+                return Err(format!(
+                    "failed to parse row {row_number}, reason: no person"
+                ))
+            }
+        };
+
+        let note = map.note.and_then(|column| get_cell(&record, column));
+
+        days.entry(date)
+            .or_insert(Day {
+                date,
+                precision: DatePrecision::Day,
+                entries: vec![],
+                source_file: None,
+                line_range: 0..0,
+            })
+            .entries
+            .push(Arc::new(build_entry(person, note)));
+    }
+
+    let mut days: Vec<Day> = days.into_values().collect();
+    days.sort_by_key(|day| day.date);
+
+    Ok(Log { days })
+}
+
+/// Add entries to the day, skipping those that are already there. Returns the
+/// added ones.
+fn merge_entries(day: &mut Day, entries: Vec<Arc<Entry>>) -> Vec<Arc<Entry>> {
+    let mut added: Vec<Arc<Entry>> = vec![];
+    for entry in entries {
+        if day
+            .entries
+            .iter()
+            .any(|existing| existing.content == entry.content)
+        {
+            continue;
+        }
+        day.entries.push(entry.clone());
+        added.push(entry);
+    }
+    added
+}
+
+/// The log file to add a day to: the first one, by path, that already has the
+/// date, or else its `<year>-people.md` file
+fn destination(files: &BTreeMap<PathBuf, Log>, date: NaiveDate, config: &Config) -> PathBuf {
+    let existing_file = files
+        .iter()
+        .find(|(_, log)| log.days.iter().any(|existing| existing.date == date))
+        .map(|(path, _)| path.clone());
+
+    match existing_file {
+        Some(path) => path,
+        None => config.get_year_log_path(date),
+    }
+}
+
+/// Append imported days to the log file that already has each date, or else to
+/// a `<year>-people.md` file, leaving what the files had as is. Entries already
+/// in the file under the same date are skipped. Returns the amount of entries
+/// added per file.
+pub fn merge_into_log_files(
+    imported: Log,
+    config: &Config,
+    transaction: &Transaction,
+) -> Result<Vec<(PathBuf, usize)>, String> {
+    let options = ParseOptions::from_config(config);
+    let mut contents: BTreeMap<PathBuf, String> = BTreeMap::new();
+    let mut files: BTreeMap<PathBuf, Log> = BTreeMap::new();
+    for path in log::find_log_files(config) {
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+        };
+        let file_log = log::parse_log_file_content_with_options(&content, &options);
+        files.insert(path.clone(), file_log);
+        contents.insert(path, content);
+    }
+
+    let mut appended: BTreeMap<PathBuf, Vec<Day>> = BTreeMap::new();
+
+    for day in imported.days {
+        let path = destination(&files, day.date, config);
+        let file_log = files.entry(path.clone()).or_insert(Log { days: vec![] });
+        let added = match file_log.days.iter_mut().find(|d| d.date == day.date) {
+            Some(existing) => merge_entries(existing, day.entries),
+            None => {
+                file_log.days.push(day.clone());
+                day.entries
+            }
+        };
+        if added.is_empty() {
+            continue;
+        }
+
+        let days = appended.entry(path).or_default();
+        match days.iter_mut().find(|d| d.date == day.date) {
+            Some(existing) => existing.entries.extend(added),
+            None => days.push(Day {
+                entries: added,
+                ..day
+            }),
+        }
+    }
+
+    let mut written: Vec<(PathBuf, usize)> = vec![];
+    for (path, mut days) in appended {
+        days.sort_by_key(|day| day.date);
+        let added = days.iter().map(|day| day.entries.len()).sum();
+        let mut content = contents.remove(&path).unwrap_or_default();
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        if !content.trim().is_empty() {
+            content.push('\n');
+        }
+        content.push_str(&Log { days }.to_string());
+
+        transaction.write(&path, &content)?;
+        written.push((path, added));
+    }
+
+    written.sort();
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::{d, without_provenance};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_column_map() {
+        let expected = Ok(ColumnMap {
+            date: 1,
+            person: 2,
+            note: Some(3),
+        });
+
+        assert_eq!(ColumnMap::from_str("date=1,person=2,note=3"), expected);
+    }
+
+    #[test]
+    fn test_parse_column_map_without_person() {
+        assert!(ColumnMap::from_str("date=1,note=3").is_err());
+        assert!(ColumnMap::from_str("date=0,person=1").is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let content = indoc!(
+            r#"
+            when,who,what
+            2000-01-02,Jane Doe,"went for a walk
+            talked about work"
+            2000-01-01,Abu,
+            2000-01-02,Abu,coffee
+            "#
+        )
+        .trim_start();
+        let map = ColumnMap {
+            date: 1,
+            person: 2,
+            note: Some(3),
+        };
+
+        let expected = without_provenance(log::parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #Abu :
+
+            # 2000-01-02
+
+            - #JaneDoe :
+              - went for a walk
+              - talked about work
+            - #Abu :
+              - coffee
+            "
+        )));
+
+        assert_eq!(parse_csv(content, &map), Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_csv_with_invalid_date() {
+        let content = "2000-01-01,Abu,coffee\nyesterday,Abu,tea\n";
+        let map = ColumnMap {
+            date: 1,
+            person: 2,
+            note: Some(3),
+        };
+
+        assert!(parse_csv(content, &map).is_err());
+    }
+
+    #[test]
+    fn test_merge_entries_skips_existing_ones() {
+        let mut day = Day {
+            date: d("2000-01-01"),
+            precision: DatePrecision::Day,
+            entries: vec![Arc::new(build_entry("Abu".to_string(), Some("coffee")))],
+            source_file: None,
+            line_range: 0..0,
+        };
+
+        let added = merge_entries(
+            &mut day,
+            vec![
+                Arc::new(build_entry("Abu".to_string(), Some("coffee"))),
+                Arc::new(build_entry("Abu".to_string(), Some("tea"))),
+            ],
+        );
+
+        assert_eq!(added.len(), 1);
+        assert_eq!(day.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_destination_is_the_first_log_with_the_date() {
+        let config = Config::builder().people_dir("/tmp/people").build();
+        let log = |content: &str| log::parse_log_file_content(content);
+        let files: BTreeMap<PathBuf, Log> = [
+            (
+                "/tmp/people/log/b-people.md",
+                log("# 2000-01-01\n\n- #Abu : tea\n"),
+            ),
+            (
+                "/tmp/people/log/a-people.md",
+                log("# 2000-01-01\n\n- #Zoe : tea\n"),
+            ),
+        ]
+        .map(|(path, log)| (PathBuf::from(path), log))
+        .into();
+
+        assert_eq!(
+            destination(&files, d("2000-01-01"), &config),
+            PathBuf::from("/tmp/people/log/a-people.md")
+        );
+        assert_eq!(
+            destination(&files, d("2001-01-01"), &config),
+            PathBuf::from("/tmp/people/log/2001-people.md")
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::log::{DatePrecision, Log};
+
+/// How dates are shown to people. Machine formats, like JSON or `--plain`,
+/// always have ISO dates, e.g. `2024-05-01`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateStyle {
+    /// `2024-05-01`, or as `date_format` in the config says
+    #[default]
+    Absolute,
+    /// `3 days ago`, `last Tuesday` or `Mar 2023`
+    Relative,
+}
+
+impl FromStr for DateStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "absolute" => Ok(DateStyle::Absolute),
+            "relative" => Ok(DateStyle::Relative),
+            other => Err(format!(
+                "expected 'absolute' or 'relative', but found {other:?}"
+            )),
+        }
+    }
+}
+
+/// Check a `date_format` of the config, a strftime format like `%d %b %Y`
+pub fn validate_format(format: &str) -> Result<(), String> {
+    let mut formatted = String::new();
+    match write!(formatted, "{}", NaiveDate::MIN.format(format)) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(format!(
+            "expected a date format like '%d %b %Y', but found {format:?}"
+        )),
+    }
+}
+
+/// Dates the way the user wants to read them
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DateDisplay {
+    pub style: DateStyle,
+    /// strftime format of absolute dates, ISO if none
+    pub format: Option<String>,
+}
+
+impl DateDisplay {
+    /// `2024-05-01`, for machine formats
+    pub fn iso() -> DateDisplay {
+        DateDisplay::default()
+    }
+
+    pub fn is_iso(&self) -> bool {
+        self == &DateDisplay::iso()
+    }
+
+    pub fn show(&self, date: NaiveDate, today: NaiveDate) -> String {
+        match (self.style, &self.format) {
+            (DateStyle::Relative, _) => relative(date, today),
+            (DateStyle::Absolute, Some(format)) => date.format(format).to_string(),
+            (DateStyle::Absolute, None) => date.to_string(),
+        }
+    }
+
+    /// The log as markdown, with the days that have a full date shown this way
+    pub fn render_log(&self, log: &Log, today: NaiveDate) -> String {
+        let days: Vec<String> = log
+            .days
+            .iter()
+            .map(|day| {
+                let header = match day.precision {
+                    DatePrecision::Day => self.show(day.date, today),
+                    precision => precision.header(day.date),
+                };
+                let entries: Vec<String> =
+                    day.entries.iter().map(|entry| entry.to_string()).collect();
+                format!("# {header}\n\n{}", entries.join("\n"))
+            })
+            .collect();
+        format!("{}\n", days.join("\n\n"))
+    }
+}
+
+fn plural(amount: i64, unit: &str) -> String {
+    match amount {
+        1 => format!("a {unit}"),
+        amount => format!("{amount} {unit}s"),
+    }
+}
+
+/// `today`, `yesterday`, `3 days ago` in the same week, `last Tuesday` in the
+/// week before, `2 weeks ago` and, from four weeks on, `Mar 2023`
+pub fn relative(date: NaiveDate, today: NaiveDate) -> String {
+    let days = (today - date).num_days();
+    let same_week = date.iso_week() == today.iso_week();
+    match days {
+        0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        -1 => "tomorrow".to_string(),
+        2..=6 if same_week => format!("{days} days ago"),
+        2..=6 => format!("last {}", date.format("%A")),
+        7..=27 => format!("{} ago", plural(days / 7, "week")),
+        -6..=-2 => format!("in {} days", -days),
+        _ => date.format("%b %Y").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_relative() {
+        // a Friday
+        let today = d("2024-05-10");
+        let shown: Vec<String> = [
+            "2024-05-10",
+            "2024-05-09",
+            "2024-05-07",
+            "2024-05-05",
+            "2024-05-01",
+            "2024-04-26",
+            "2024-04-12",
+            "2023-03-15",
+            "2024-05-11",
+            "2024-05-14",
+        ]
+        .iter()
+        .map(|date| relative(d(date), today))
+        .collect();
+
+        assert_eq!(
+            shown,
+            vec![
+                "today",
+                "yesterday",
+                "3 days ago",
+                "last Sunday",
+                "a week ago",
+                "2 weeks ago",
+                "Apr 2024",
+                "Mar 2023",
+                "tomorrow",
+                "in 4 days",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_show() {
+        let today = d("2024-05-10");
+        let display = DateDisplay {
+            style: DateStyle::Absolute,
+            format: Some("%d %b %Y".to_string()),
+        };
+        assert_eq!(display.show(d("2024-05-01"), today), "01 May 2024");
+        assert_eq!(
+            DateDisplay::iso().show(d("2024-05-01"), today),
+            "2024-05-01"
+        );
+
+        assert!(validate_format("%d %b %Y").is_ok());
+        assert!(validate_format("%Q").is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use base64::Engine;
+
+use crate::config::DavConfig;
+use crate::secrets;
+
+/// Value of the `Authorization` header to authenticate against the server
+pub fn authorization(config: &DavConfig) -> Result<String, String> {
+    let password = secrets::resolve_password(&config.password, &config.password_command)?;
+    let credentials = format!("{}:{password}", config.username);
+    let token = base64::engine::general_purpose::STANDARD.encode(credentials);
+
+    Ok(format!("Basic {token}"))
+}
+
+/// Join the collection URL and a resource name, e.g. `.../calendar/` + `foo.ics`
+pub fn resource_url(collection: &str, name: &str) -> String {
+    format!("{}/{name}", collection.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_authorization() {
+        let config = DavConfig {
+            url: "https://example.com".to_string(),
+            username: "me".to_string(),
+            password: Some("secret".to_string()),
+            password_command: None,
+        };
+
+        assert_eq!(authorization(&config), Ok("Basic bWU6c2VjcmV0".to_string()));
+    }
+
+    #[test]
+    fn test_authorization_with_password_command() {
+        let config = DavConfig {
+            url: "https://example.com".to_string(),
+            username: "me".to_string(),
+            password: None,
+            password_command: Some("echo secret".to_string()),
+        };
+
+        assert_eq!(authorization(&config), Ok("Basic bWU6c2VjcmV0".to_string()));
+    }
+
+    #[test]
+    fn test_resource_url() {
+        assert_eq!(
+            resource_url("https://example.com/cal/", "a.ics"),
+            "https://example.com/cal/a.ics"
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::config::{Config, Person};
+use crate::log::Log;
+use crate::model::PersonName;
+
+/// Shortest common start for a name to be taken as the short form of another,
+/// e.g. `Jane` for `JaneDoe`
+const MIN_PREFIX: usize = 4;
+
+/// Tags that likely refer to the same person, to be merged as aliases of
+/// `canonical`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Merge {
+    pub canonical: PersonName,
+    pub aliases: Vec<PersonName>,
+}
+
+impl fmt::Display for Merge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} <- {}", self.canonical, self.aliases.join(", "))
+    }
+}
+
+/// Lowercase and without accents, so that `Lucía` reads as `lucia`
+fn simplify(name: &str) -> String {
+    name.nfkd()
+        .filter(|ch| ch.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Typos allowed between two names, none for very short ones like `Abu`
+fn max_distance(a: &str, b: &str) -> usize {
+    match a.chars().count().min(b.chars().count()) {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
+
+fn look_alike(a: &str, b: &str) -> bool {
+    let (a, b) = (simplify(a), simplify(b));
+    if a == b {
+        return true;
+    }
+
+    let shorter = a.chars().count().min(b.chars().count());
+    if shorter >= MIN_PREFIX && (a.starts_with(&b) || b.starts_with(&a)) {
+        return true;
+    }
+
+    strsim::levenshtein(&a, &b) <= max_distance(&a, &b)
+}
+
+/// Mentions per tag, as entries referring to them
+fn count_mentions(log: &Log) -> BTreeMap<PersonName, usize> {
+    let mut mentions: BTreeMap<PersonName, usize> = BTreeMap::new();
+    for day in log.days.iter() {
+        for entry in day.entries.iter() {
+            for person in entry.related.iter() {
+                *mentions.entry(person.clone()).or_default() += 1;
+            }
+        }
+    }
+    mentions
+}
+
+fn find_root(parents: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[index] = root;
+    root
+}
+
+/// Group the tags in the logs and the config that look alike. The canonical
+/// name is the one in the config, or else the most mentioned one.
+pub fn find_merges(log: &Log, config: &Config) -> Vec<Merge> {
+    let mentions = count_mentions(log);
+    let configured: HashSet<&PersonName> =
+        config.people.iter().map(|person| &person.name).collect();
+
+    let mut names: Vec<&PersonName> = mentions.keys().collect();
+    names.extend(configured.iter().copied());
+    names.sort();
+    names.dedup();
+    names.retain(|name| !config.is_ignored(name));
+
+    let mut parents: Vec<usize> = (0..names.len()).collect();
+    for i in 0..names.len() {
+        for j in (i + 1)..names.len() {
+            // two people in the config are different people, even if alike
+            if configured.contains(names[i]) && configured.contains(names[j]) {
+                continue;
+            }
+            if look_alike(names[i], names[j]) {
+                let (a, b) = (find_root(&mut parents, i), find_root(&mut parents, j));
+                parents[b] = a;
+            }
+        }
+    }
+
+    let mut clusters: BTreeMap<usize, Vec<&PersonName>> = BTreeMap::new();
+    for (index, name) in names.iter().enumerate() {
+        let root = find_root(&mut parents, index);
+        clusters.entry(root).or_default().push(name);
+    }
+
+    let mut merges: Vec<Merge> = clusters
+        .into_values()
+        .filter(|cluster| cluster.len() > 1)
+        .filter_map(|cluster| {
+            let canonical = cluster.iter().max_by(|a, b| {
+                let rank = |name: &PersonName| {
+                    (
+                        configured.contains(name),
+                        mentions.get(name).copied().unwrap_or(0),
+                    )
+                };
+                // on a tie, the first name in alphabetical order wins
+                rank(a).cmp(&rank(b)).then_with(|| b.cmp(a))
+            })?;
+
+            let aliases: Vec<PersonName> = cluster
+                .iter()
+                .filter(|name| *name != canonical && !configured.contains(*name))
+                .map(|name| (*name).clone())
+                .collect();
+
+            match aliases.is_empty() {
+                true => None,
+                false => Some(Merge {
+                    canonical: (*canonical).clone(),
+                    aliases,
+                }),
+            }
+        })
+        .collect();
+
+    merges.sort_by(|a, b| a.canonical.cmp(&b.canonical));
+    merges
+}
+
+/// The config people with the merged tags added as aliases, adding the
+/// canonical people that are not in the config yet
+pub fn apply_merges(people: &[Person], merges: &[Merge]) -> Vec<Person> {
+    let mut people: Vec<Person> = people.to_vec();
+
+    for merge in merges {
+        let person = match people
+            .iter()
+            .position(|person| person.name == merge.canonical)
+        {
+            Some(index) => &mut people[index],
+            None => {
+                people.push(Person {
+                    name: merge.canonical.clone(),
+                    ..Default::default()
+                });
+                people.last_mut().expect("a person was just added")
+            }
+        };
+
+        for alias in merge.aliases.iter() {
+            if !person.aliases.contains(alias) {
+                person.aliases.push(alias.clone());
+            }
+        }
+    }
+
+    people
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+
+    use super::*;
+
+    #[test]
+    fn test_find_merges() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JaneDoe :
+              - coffee
+            - #janedoe, #Jane :
+              - lunch
+            - #Lucía, #Lucia, #Lucia :
+              - dinner
+            - #Abu, #Ana, #JohnDoe, #JhonDoe :
+              - walk
+            - #JohnDoe :
+              - call
+            "
+        ));
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                ..Default::default()
+            })
+            .build();
+
+        let expected = vec![
+            Merge {
+                canonical: "JaneDoe".to_string(),
+                aliases: vec!["Jane".to_string(), "janedoe".to_string()],
+            },
+            Merge {
+                canonical: "JohnDoe".to_string(),
+                aliases: vec!["JhonDoe".to_string()],
+            },
+            Merge {
+                canonical: "Lucia".to_string(),
+                aliases: vec!["Lucía".to_string()],
+            },
+        ];
+
+        assert_eq!(find_merges(&log, &config), expected);
+    }
+
+    #[test]
+    fn test_apply_merges() {
+        let people = vec![Person {
+            name: "JaneDoe".to_string(),
+            aliases: vec!["Jane".to_string()],
+            ..Default::default()
+        }];
+        let merges = vec![
+            Merge {
+                canonical: "JaneDoe".to_string(),
+                aliases: vec!["Jane".to_string(), "janedoe".to_string()],
+            },
+            Merge {
+                canonical: "JohnDoe".to_string(),
+                aliases: vec!["JhonDoe".to_string()],
+            },
+        ];
+
+        let updated = apply_merges(&people, &merges);
+
+        assert_eq!(updated[0].aliases, vec!["Jane", "janedoe"]);
+        assert_eq!(updated[1].name, "JohnDoe");
+        assert_eq!(updated[1].aliases, vec!["JhonDoe"]);
+    }
+}
@@ -0,0 +1,566 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+use crate::config::{Config, SmtpConfig};
+use crate::links;
+use crate::log::{Day, Log};
+use crate::model::PersonName;
+use crate::secrets;
+use crate::state::State;
+use crate::use_cases::{self, LastInteraction, DAYS_IN_A_MONTH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Period {
+    Week,
+    Month,
+}
+
+impl Period {
+    pub(crate) fn days(&self) -> i64 {
+        match self {
+            Period::Week => 7,
+            Period::Month => DAYS_IN_A_MONTH,
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Period::Week => write!(f, "week"),
+            Period::Month => write!(f, "month"),
+        }
+    }
+}
+
+impl FromStr for Period {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            other => Err(format!(
+                "unsupported period found: {other:?}, expected 'week' or 'month'"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DigestFormat {
+    Markdown,
+    Html,
+}
+
+impl FromStr for DigestFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" => Ok(DigestFormat::Markdown),
+            "html" => Ok(DigestFormat::Html),
+            other => Err(format!(
+                "unsupported format found: {other:?}, expected 'markdown' or 'html'"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpcomingBirthday {
+    pub person: PersonName,
+    pub date: NaiveDate,
+    pub age: i32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Digest {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub overdue: Vec<LastInteraction>,
+    pub birthdays: Vec<UpcomingBirthday>,
+    pub days: Vec<Day>,
+}
+
+/// Birthday on the given year, 29th of February becomes 1st of March on
+/// non-leap years
+fn birthday_on(birthday: NaiveDate, year: i32) -> NaiveDate {
+    match birthday.with_year(year) {
+        Some(date) => date,
+        None => NaiveDate::from_ymd_opt(year, 3, 1).expect("1st of March always exists"),
+    }
+}
+
+/// The first anniversary of `date` from `start` until `end`, both included, with
+/// the years since `date`
+fn next_anniversary(date: NaiveDate, start: NaiveDate, end: NaiveDate) -> Option<(NaiveDate, i32)> {
+    let mut next = birthday_on(date, start.year());
+    if next < start {
+        next = birthday_on(date, start.year() + 1);
+    }
+
+    match next <= end {
+        true => Some((next, next.year() - date.year())),
+        false => None,
+    }
+}
+
+/// Birthdays from `start` until `end`, both included
+pub fn upcoming_birthdays(
+    config: &Config,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<UpcomingBirthday> {
+    let mut upcoming: Vec<UpcomingBirthday> = vec![];
+
+    for person in config.people.iter() {
+        let birthday = match person.birthday {
+            Some(birthday) => birthday,
+            None => continue,
+        };
+
+        if let Some((date, age)) = next_anniversary(birthday, start, end) {
+            upcoming.push(UpcomingBirthday {
+                person: person.name.clone(),
+                date,
+                age,
+            });
+        }
+    }
+
+    upcoming.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.person.cmp(&b.person)));
+
+    upcoming
+}
+
+/// Anniversaries of meeting each person, see `get_first_interactions`, from
+/// `start` until `end`, both included. `age` is the years since you met, and
+/// the day you met is not an anniversary.
+pub fn upcoming_metiversaries(
+    first_met: &HashMap<PersonName, NaiveDate>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<UpcomingBirthday> {
+    let mut upcoming: Vec<UpcomingBirthday> = first_met
+        .iter()
+        .filter_map(|(person, met)| {
+            let (date, age) = next_anniversary(*met, start, end)?;
+            match age > 0 {
+                true => Some(UpcomingBirthday {
+                    person: person.clone(),
+                    date,
+                    age,
+                }),
+                false => None,
+            }
+        })
+        .collect();
+
+    upcoming.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.person.cmp(&b.person)));
+
+    upcoming
+}
+
+/// Who is overdue now, whose birthday is in the next period, and what was
+/// logged during the last one
+pub fn build_digest(
+    log: Log,
+    config: &Config,
+    mut state: State,
+    today: NaiveDate,
+    period: Period,
+) -> Result<Digest, String> {
+    let length = Duration::days(period.days() - 1);
+    let start = today - length;
+
+    let interactions = use_cases::get_last_interactions(&log);
+    let interactions = use_cases::apply_acknowledgments(interactions, &mut state);
+    let interactions = use_cases::identify_reachouts(interactions, config, today);
+    // snoozes only silence notifications, the digest is a full picture
+    let overdue = use_cases::due_reminders(interactions, config, &State::default(), today);
+
+    let birthdays = upcoming_birthdays(config, today, today + length);
+
+    let days: Vec<Day> = log
+        .days
+        .into_iter()
+        .filter(|day| start <= day.date && day.date <= today)
+        .filter_map(|mut day| {
+            day.entries
+                .retain(|entry| entry.interacted().any(|name| !config.is_ignored(name)));
+            match day.entries.is_empty() {
+                true => None,
+                false => Some(day),
+            }
+        })
+        .collect();
+
+    Ok(Digest {
+        start,
+        end: today,
+        overdue,
+        birthdays,
+        days,
+    })
+}
+
+fn overdue_line(interaction: &LastInteraction) -> String {
+    let days = interaction.days_beyond_reachout_threshold.unwrap_or(0);
+    format!(
+        "{}: last interaction on {}, {days} days overdue",
+        interaction.person, interaction.last
+    )
+}
+
+fn birthday_line(birthday: &UpcomingBirthday) -> String {
+    format!(
+        "{}: {} (turns {})",
+        birthday.person, birthday.date, birthday.age
+    )
+}
+
+pub fn to_markdown(digest: &Digest) -> String {
+    let mut sections: Vec<String> = vec![format!(
+        "# People digest: {} to {}",
+        digest.start, digest.end
+    )];
+
+    let mut overdue = vec!["## Overdue".to_string(), "".to_string()];
+    if digest.overdue.is_empty() {
+        overdue.push("Nobody is overdue a reach out.".to_string());
+    }
+    for interaction in digest.overdue.iter() {
+        overdue.push(format!("- {}", overdue_line(interaction)));
+    }
+    sections.push(overdue.join("\n"));
+
+    let mut birthdays = vec!["## Upcoming birthdays".to_string(), "".to_string()];
+    if digest.birthdays.is_empty() {
+        birthdays.push("No birthdays coming up.".to_string());
+    }
+    for birthday in digest.birthdays.iter() {
+        birthdays.push(format!("- {}", birthday_line(birthday)));
+    }
+    sections.push(birthdays.join("\n"));
+
+    let mut interactions = vec!["## Interactions".to_string()];
+    if digest.days.is_empty() {
+        interactions.push("".to_string());
+        interactions.push("Nothing logged.".to_string());
+    }
+    for day in digest.days.iter() {
+        interactions.push("".to_string());
+        // nest the day headings under the section
+        interactions.push(format!("##{day}"));
+    }
+    sections.push(interactions.join("\n"));
+
+    format!("{}\n", sections.join("\n\n"))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escape the text and turn its URLs into clickable links
+fn linkify_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut last = 0;
+
+    for span in links::find_link_spans(text) {
+        html.push_str(&escape_html(&text[last..span.start]));
+        let url = escape_html(&text[span.clone()]);
+        html.push_str(&format!("<a href=\"{url}\">{url}</a>"));
+        last = span.end;
+    }
+    html.push_str(&escape_html(&text[last..]));
+
+    html
+}
+
+fn html_list(items: Vec<String>, when_empty: &str) -> String {
+    if items.is_empty() {
+        return format!("<p>{when_empty}</p>");
+    }
+
+    let items: Vec<String> = items
+        .iter()
+        .map(|item| format!("<li>{}</li>", escape_html(item)))
+        .collect();
+    format!("<ul>\n{}\n</ul>", items.join("\n"))
+}
+
+pub fn to_html(digest: &Digest) -> String {
+    let mut html: Vec<String> = vec![
+        "<html>".to_string(),
+        "<body>".to_string(),
+        format!("<h1>People digest: {} to {}</h1>", digest.start, digest.end),
+        "<h2>Overdue</h2>".to_string(),
+        html_list(
+            digest.overdue.iter().map(overdue_line).collect(),
+            "Nobody is overdue a reach out.",
+        ),
+        "<h2>Upcoming birthdays</h2>".to_string(),
+        html_list(
+            digest.birthdays.iter().map(birthday_line).collect(),
+            "No birthdays coming up.",
+        ),
+        "<h2>Interactions</h2>".to_string(),
+    ];
+
+    if digest.days.is_empty() {
+        html.push("<p>Nothing logged.</p>".to_string());
+    }
+    for day in digest.days.iter() {
+        html.push(format!("<h3>{}</h3>", day.date));
+        for entry in day.entries.iter() {
+            html.push(format!("<pre>{}</pre>", linkify_html(&entry.content)));
+        }
+    }
+
+    html.push("</body>".to_string());
+    html.push("</html>".to_string());
+
+    format!("{}\n", html.join("\n"))
+}
+
+pub fn render(digest: &Digest, format: DigestFormat) -> String {
+    match format {
+        DigestFormat::Markdown => to_markdown(digest),
+        DigestFormat::Html => to_html(digest),
+    }
+}
+
+pub fn subject(digest: &Digest) -> String {
+    format!("People digest: {} to {}", digest.start, digest.end)
+}
+
+pub fn send(
+    smtp: &SmtpConfig,
+    subject: &str,
+    body: String,
+    format: DigestFormat,
+) -> Result<(), String> {
+    let from = match smtp.from.parse() {
+        Ok(from) => from,
+        Err(reason) => return Err(format!("invalid sender {:?}: {reason}", smtp.from)),
+    };
+    let to = match smtp.to.parse() {
+        Ok(to) => to,
+        Err(reason) => return Err(format!("invalid recipient {:?}: {reason}", smtp.to)),
+    };
+    let content_type = match format {
+        DigestFormat::Markdown => ContentType::TEXT_PLAIN,
+        DigestFormat::Html => ContentType::TEXT_HTML,
+    };
+
+    let email = match Message::builder()
+        .from(from)
+        .to(to)
+        .subject(subject)
+        .header(content_type)
+        .body(body)
+    {
+        Ok(email) => email,
+        Err(reason) => return Err(format!("failed to build the email, reason: {reason}")),
+    };
+
+    let password = secrets::resolve_password(&smtp.password, &smtp.password_command)?;
+    let transport = match SmtpTransport::relay(&smtp.host) {
+        Ok(builder) => builder
+            .port(smtp.port.unwrap_or(465))
+            .credentials(Credentials::new(smtp.username.clone(), password))
+            .build(),
+        Err(reason) => return Err(format!("failed to connect to {}: {reason}", smtp.host)),
+    };
+
+    match transport.send(&email) {
+        Ok(_) => Ok(()),
+        Err(reason) => Err(format!("failed to send the digest, reason: {reason}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Person;
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_upcoming_birthdays() {
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .person(Person {
+                name: "NewYear".to_string(),
+                birthday: Some(d("1990-01-02")),
+                ..Default::default()
+            })
+            .person(Person {
+                name: "LeapDay".to_string(),
+                birthday: Some(d("1992-02-29")),
+                ..Default::default()
+            })
+            .person(Person {
+                name: "Later".to_string(),
+                birthday: Some(d("1990-06-01")),
+                ..Default::default()
+            })
+            .build();
+
+        let expected = vec![
+            UpcomingBirthday {
+                person: "LeapDay".to_string(),
+                date: d("2001-03-01"),
+                age: 9,
+            },
+            UpcomingBirthday {
+                person: "Later".to_string(),
+                date: d("2001-06-01"),
+                age: 11,
+            },
+            UpcomingBirthday {
+                person: "NewYear".to_string(),
+                date: d("2002-01-02"),
+                age: 12,
+            },
+        ];
+
+        assert_eq!(
+            upcoming_birthdays(&config, d("2001-02-27"), d("2002-01-05")),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_upcoming_metiversaries() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 1999-03
+
+            - #JohnDoe :
+
+            # 1999-03-10
+
+            - #JaneDoe :
+            - #JohnDoe :
+
+            # 2001-03-05
+
+            - #JaneDoe :
+            - #Abu :
+            "
+        ));
+        let first_met = use_cases::get_first_interactions(&log);
+        assert_eq!(first_met["JohnDoe"], d("1999-03-01"));
+
+        let expected = vec![
+            UpcomingBirthday {
+                person: "JohnDoe".to_string(),
+                date: d("2001-03-01"),
+                age: 2,
+            },
+            UpcomingBirthday {
+                person: "JaneDoe".to_string(),
+                date: d("2001-03-10"),
+                age: 2,
+            },
+        ];
+
+        assert_eq!(
+            upcoming_metiversaries(&first_met, d("2001-03-01"), d("2001-03-31")),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_digest_to_markdown() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - too old
+
+            # 2000-01-09
+
+            - #Mum : call
+
+            # 2000-01-10
+
+            - #JohnDoe :
+              - stuff: blah
+            - #Mum : dinner
+            "
+        ));
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .ignore(vec!["Mum".to_string()])
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                birthday: Some(d("1990-01-12")),
+                ..Default::default()
+            })
+            .build();
+
+        let digest = build_digest(
+            log,
+            &config,
+            State::default(),
+            d("2000-01-10"),
+            Period::Week,
+        )
+        .unwrap();
+
+        let expected = indoc!(
+            "
+            # People digest: 2000-01-04 to 2000-01-10
+
+            ## Overdue
+
+            Nobody is overdue a reach out.
+
+            ## Upcoming birthdays
+
+            - JaneDoe: 2000-01-12 (turns 10)
+
+            ## Interactions
+
+            ### 2000-01-10
+
+            - #JohnDoe :
+              - stuff: blah
+            "
+        )
+        .trim_start();
+
+        assert_eq!(to_markdown(&digest), expected);
+    }
+
+    #[test]
+    fn test_linkify_html() {
+        assert_eq!(
+            linkify_html("<3 https://a.io/?x=1&y=2."),
+            "&lt;3 <a href=\"https://a.io/?x=1&amp;y=2\">https://a.io/?x=1&amp;y=2</a>."
+        );
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("<b>&</b>"), "&lt;b&gt;&amp;&lt;/b&gt;");
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::log::{find_log_files, parse_log_file_iter, DatePrecision, Day, ParseOptions};
+use crate::operations::Transaction;
+
+type EntryKey = (NaiveDate, DatePrecision, String);
+
+/// An entry with the same date and content as an earlier one, e.g. copied
+/// between devices
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateEntry {
+    pub date: NaiveDate,
+    pub precision: DatePrecision,
+    pub content: String,
+    pub path: PathBuf,
+    /// File with the first copy, which can be `path` itself
+    pub original: PathBuf,
+    /// Lines of the copy in `path`, like `Entry::line_range`
+    pub line_range: Range<usize>,
+}
+
+impl DuplicateEntry {
+    pub fn first_line(&self) -> &str {
+        self.content.lines().next().unwrap_or("")
+    }
+}
+
+/// Drop the entries seen before on the same day, in this or an earlier day.
+/// Returns the dropped ones.
+pub fn dedupe_days(days: &mut [Day]) -> Vec<(NaiveDate, String)> {
+    let mut seen: HashSet<EntryKey> = HashSet::new();
+    let mut dropped: Vec<(NaiveDate, String)> = vec![];
+
+    for day in days.iter_mut() {
+        day.entries.retain(|entry| {
+            let key = (day.date, day.precision, entry.content.clone());
+            if seen.insert(key) {
+                return true;
+            }
+            dropped.push((day.date, entry.content.clone()));
+            false
+        });
+    }
+
+    dropped
+}
+
+/// Every copy of an entry after the first, going through the files in order
+pub fn find_duplicates(files: &[(PathBuf, String)], options: &ParseOptions) -> Vec<DuplicateEntry> {
+    let mut seen: HashMap<EntryKey, &PathBuf> = HashMap::new();
+    let mut duplicates: Vec<DuplicateEntry> = vec![];
+
+    for (path, content) in files.iter() {
+        for day in parse_log_file_iter(content, options) {
+            for entry in day.entries {
+                let entry = Arc::unwrap_or_clone(entry);
+                let key = (day.date, day.precision, entry.content);
+                match seen.get(&key) {
+                    Some(original) => duplicates.push(DuplicateEntry {
+                        date: day.date,
+                        precision: day.precision,
+                        content: key.2,
+                        path: path.clone(),
+                        original: (*original).clone(),
+                        line_range: entry.line_range,
+                    }),
+                    None => {
+                        seen.insert(key, path);
+                    }
+                }
+            }
+        }
+    }
+
+    duplicates
+}
+
+fn is_blank(line: Option<&str>) -> bool {
+    line.is_some_and(|line| line.trim().is_empty())
+}
+
+/// The content of a log file without the lines of its duplicates, and
+/// everything else as is
+pub fn remove_duplicates(content: &str, duplicates: &[&DuplicateEntry]) -> String {
+    let mut lines: Vec<Option<&str>> = content.split_inclusive('\n').map(Some).collect();
+    for duplicate in duplicates {
+        let range = duplicate.line_range.start - 1..duplicate.line_range.end - 1;
+        for line in lines.iter_mut().take(range.end).skip(range.start) {
+            *line = None;
+        }
+
+        // the blank lines around the entry would end up together
+        let before = lines[..range.start].iter().rev().find_map(|line| *line);
+        if is_blank(before) && is_blank(lines.get(range.end).copied().flatten()) {
+            lines[range.end] = None;
+        }
+    }
+
+    lines.into_iter().flatten().collect()
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+    }
+}
+
+/// The duplicate entries across the log files
+pub fn find_duplicates_in_logs(
+    config: &Config,
+    options: &ParseOptions,
+) -> Result<Vec<DuplicateEntry>, String> {
+    let mut files: Vec<(PathBuf, String)> = vec![];
+    for path in find_log_files(config) {
+        let content = read(&path)?;
+        files.push((path, content));
+    }
+
+    Ok(find_duplicates(&files, options))
+}
+
+/// Remove the duplicates from the files they are in
+pub fn fix_duplicates(
+    duplicates: &[DuplicateEntry],
+    transaction: &Transaction,
+) -> Result<(), String> {
+    let mut per_file: HashMap<&PathBuf, Vec<&DuplicateEntry>> = HashMap::new();
+    for duplicate in duplicates.iter() {
+        per_file.entry(&duplicate.path).or_default().push(duplicate);
+    }
+
+    for (path, duplicates) in per_file {
+        let content = read(path)?;
+        let fixed = remove_duplicates(&content, &duplicates);
+        transaction.write(path, &fixed)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    fn files() -> Vec<(PathBuf, String)> {
+        let laptop = indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - blah
+            - #JaneDoe :
+            "
+        );
+        let phone = indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - blah
+
+            # 2000-01-02
+
+            - #JohnDoe :
+              - blah
+            "
+        );
+
+        vec![
+            (PathBuf::from("laptop-people.md"), laptop.to_string()),
+            (PathBuf::from("phone-people.md"), phone.to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let duplicates = find_duplicates(&files(), &ParseOptions::default());
+
+        assert_eq!(
+            duplicates,
+            vec![DuplicateEntry {
+                date: d("2000-01-01"),
+                precision: DatePrecision::Day,
+                content: "- #JohnDoe :\n  - blah".to_string(),
+                path: PathBuf::from("phone-people.md"),
+                original: PathBuf::from("laptop-people.md"),
+                line_range: 3..5,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_remove_duplicates() {
+        let options = ParseOptions::default();
+        let mut files = files();
+        files[1].1 = format!("Synced from the phone.\n\n{}", files[1].1);
+        let duplicates = find_duplicates(&files, &options);
+
+        let fixed = remove_duplicates(&files[1].1, &[&duplicates[0]]);
+
+        assert_eq!(
+            fixed,
+            indoc!(
+                "
+                Synced from the phone.
+
+                # 2000-01-01
+
+                # 2000-01-02
+
+                - #JohnDoe :
+                  - blah
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn test_dedupe_days() {
+        let mut days: Vec<Day> = files()
+            .iter()
+            .flat_map(|(_, content)| parse_log_file_content(content).days)
+            .collect();
+
+        let dropped = dedupe_days(&mut days);
+
+        assert_eq!(
+            dropped,
+            vec![(d("2000-01-01"), "- #JohnDoe :\n  - blah".to_string())]
+        );
+        let entries: Vec<usize> = days.iter().map(|day| day.entries.len()).collect();
+        assert_eq!(entries, vec![2, 0, 1]);
+    }
+}
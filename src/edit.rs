@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::log::{parse_header_date, DatePrecision, Log};
+use crate::model::PersonName;
+use crate::operations::Transaction;
+use crate::show;
+
+/// What `people edit` opens: a day, or the latest entry with a person
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EditTarget {
+    Date(NaiveDate),
+    Person(PersonName),
+}
+
+impl EditTarget {
+    /// A date like `2024-05-01`, or else a person
+    pub fn parse(text: &str) -> EditTarget {
+        match text.parse::<NaiveDate>() {
+            Ok(date) => EditTarget::Date(date),
+            Err(_) => EditTarget::Person(text.to_string()),
+        }
+    }
+}
+
+/// File and line of the most recent entry with the person, as `people show`
+/// lists them
+pub fn latest_entry_location(
+    log: Log,
+    person: &PersonName,
+    config: &Config,
+) -> Option<(PathBuf, usize)> {
+    let history = show::person_history(log, person, config, None, Some(1))?;
+    let entry = history.days.last()?.entries.last()?;
+    let path = entry.source_file.clone()?;
+    Some((path, entry.line_range.start))
+}
+
+/// Line of the `# <date>` header in the content, starting at 1
+fn find_header(content: &str, date: NaiveDate) -> Option<usize> {
+    content
+        .lines()
+        .position(|line| {
+            let header = line.strip_prefix("# ").map(str::trim_end);
+            header.and_then(parse_header_date) == Some((date, DatePrecision::Day))
+        })
+        .map(|index| index + 1)
+}
+
+/// The content with a `# <date>` header appended, and the line to start
+/// writing the day's entries at
+pub fn append_header(content: &str, date: NaiveDate) -> (String, usize) {
+    let mut updated = content.trim_end().to_string();
+    if !updated.is_empty() {
+        updated.push_str("\n\n");
+    }
+    updated.push_str(&format!("# {date}\n\n"));
+
+    let header_line = updated.lines().count() - 1;
+    (updated, header_line + 1)
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+    }
+}
+
+/// File and line of the day in the logs. If no log has it, the header is
+/// added to the year's log file, which is created if missing.
+pub fn day_location(
+    config: &Config,
+    log: &Log,
+    date: NaiveDate,
+    transaction: &Transaction,
+) -> Result<(PathBuf, usize), String> {
+    let logged = log.days.iter().find(|day| {
+        day.date == date && day.precision == DatePrecision::Day && day.source_file.is_some()
+    });
+    if let Some(day) = logged {
+        let path = day.source_file.clone().expect("filtered above");
+        return Ok((path, day.line_range.start));
+    }
+
+    let path = config.get_year_log_path(date);
+    let content = match path.exists() {
+        true => read(&path)?,
+        false => String::new(),
+    };
+
+    // a header without entries yet is not a day in the log
+    if let Some(line) = find_header(&content, date) {
+        return Ok((path, line + 1));
+    }
+    let (updated, line) = append_header(&content, date);
+    transaction.write(&path, &updated)?;
+    Ok((path, line))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_append_header() {
+        let content = indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - blah
+
+            "
+        );
+
+        let (updated, line) = append_header(content, d("2000-01-02"));
+
+        assert_eq!(
+            updated,
+            indoc!(
+                "
+                # 2000-01-01
+
+                - #JohnDoe :
+                  - blah
+
+                # 2000-01-02
+
+                "
+            )
+        );
+        assert_eq!(line, 7);
+        assert_eq!(find_header(&updated, d("2000-01-02")), Some(6));
+        assert_eq!(
+            append_header("", d("2000-01-02")),
+            ("# 2000-01-02\n\n".to_string(), 2)
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+const DEFAULT_EDITOR: &str = "vi";
+
+/// `$VISUAL`, then `$EDITOR`, then `vi`. It can have arguments, e.g. `code -w`.
+fn editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// The program and its arguments to open `path` at `line`, with the `+<line>`
+/// argument that vi, nano, emacs and most terminal editors understand
+pub fn editor_command(editor: &str, path: &Path, line: usize) -> (String, Vec<String>) {
+    let mut words = editor.split_whitespace().map(String::from);
+    let program = words.next().unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+
+    let mut args: Vec<String> = words.collect();
+    args.push(format!("+{line}"));
+    args.push(path.to_string_lossy().to_string());
+    (program, args)
+}
+
+/// Open `path` at `line` in the user's editor and wait until it exits
+pub fn open(path: &Path, line: usize) -> Result<(), String> {
+    let (program, args) = editor_command(&editor(), path, line);
+
+    let status = match Command::new(&program).args(&args).status() {
+        Ok(status) => status,
+        Err(reason) => return Err(format!("failed to run {program:?}, reason: {reason}")),
+    };
+
+    match status.success() {
+        true => Ok(()),
+        false => Err(format!("{program:?} exited with {status}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_editor_command() {
+        let path = Path::new("/tmp/people/log/2000-people.md");
+
+        assert_eq!(
+            editor_command("nvim", path, 12),
+            (
+                "nvim".to_string(),
+                vec!["+12".to_string(), path.display().to_string()]
+            )
+        );
+        assert_eq!(
+            editor_command("emacsclient -t", path, 1).1,
+            vec![
+                "-t".to_string(),
+                "+1".to_string(),
+                path.display().to_string()
+            ]
+        );
+    }
+}
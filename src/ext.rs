@@ -0,0 +1,137 @@
+use std::ffi::OsStr;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+
+use serde::Serialize;
+
+use crate::log::Log;
+use crate::render::EntryContext;
+
+/// Environment variable with the path of the config file in use
+pub static CONFIG_PATH_VAR: &str = "PEOPLE_CONFIG";
+
+/// Name of the executable that provides `people ext <name>`
+pub fn executable_name(name: &str) -> String {
+    format!("people-{name}")
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    match path.metadata() {
+        Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// First `people-<name>` executable in the directories of `path_var` (e.g. the
+/// value of `PATH`), like git does for its subcommands
+pub fn find_extension(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(executable_name(name)))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[derive(Serialize)]
+struct LogDump {
+    entries: Vec<EntryContext>,
+}
+
+/// The parsed log as extensions get it on stdin: `{"entries": [...]}`, oldest first
+pub fn log_to_json(log: &Log) -> String {
+    let entries = log
+        .days
+        .iter()
+        .flat_map(|day| {
+            day.entries
+                .iter()
+                .map(|entry| EntryContext::new(day.date, entry))
+        })
+        .collect();
+
+    serde_json::to_string(&LogDump { entries }).expect("the log should be serializable")
+}
+
+/// Run an extension with `args`, the config path in `PEOPLE_CONFIG` and the
+/// log as JSON on stdin, and wait for it to finish
+pub fn run(
+    executable: &Path,
+    args: &[String],
+    config_path: &Path,
+    log: &Log,
+) -> Result<ExitStatus, String> {
+    let mut child = match Command::new(executable)
+        .args(args)
+        .env(CONFIG_PATH_VAR, config_path)
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(reason) => return Err(format!("failed to run {executable:?}, reason: {reason}")),
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // extensions that don't read stdin close it early, that's fine
+        let _ = stdin.write_all(log_to_json(log).as_bytes());
+    }
+
+    match child.wait() {
+        Ok(status) => Ok(status),
+        Err(reason) => Err(format!(
+            "failed to wait for {executable:?}, reason: {reason}"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use crate::log::parse_log_file_content;
+
+    use super::*;
+
+    #[test]
+    fn test_log_to_json() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - coffee
+            "
+        ));
+
+        let expected = json!({
+            "entries": [{
+                "date": "2000-01-01",
+                "main": ["JohnDoe"],
+                "related": ["JohnDoe"],
+                "content": "- #JohnDoe :\n  - coffee",
+                "links": [],
+            }]
+        });
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&log_to_json(&log)).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_find_missing_extension() {
+        assert_eq!(
+            find_extension("does-not-exist", OsStr::new("/nonexistent/bin")),
+            None
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::log::Log;
+use crate::model::PersonName;
+
+/// Keys of the fields collected by `people gift-ideas`
+pub static GIFT_KEYS: [&str; 3] = ["gift", "gift-idea", "gift-ideas"];
+
+/// A value of an entry field, e.g. a gift idea, with who and when it was about
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct FieldNote {
+    pub person: PersonName,
+    pub date: NaiveDate,
+    pub key: String,
+    pub value: String,
+}
+
+/// Every value of the keys in the entries about the person, or about anyone,
+/// sorted by person and then oldest first
+pub fn field_notes(log: &Log, keys: &[&str], person: Option<&PersonName>) -> Vec<FieldNote> {
+    let mut notes: Vec<FieldNote> = vec![];
+    for day in log.days.iter() {
+        for entry in day.entries.iter() {
+            let fields: Vec<(&String, &String)> = entry
+                .fields
+                .iter()
+                .filter(|(key, _)| keys.contains(&key.as_str()))
+                .collect();
+
+            let people = entry
+                .main
+                .iter()
+                .filter(|name| person.is_none_or(|person| person == *name));
+            for name in people {
+                for (key, value) in fields.iter() {
+                    notes.push(FieldNote {
+                        person: name.clone(),
+                        date: day.date,
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    // stable, so notes on the same day stay in the order they were logged
+    notes.sort_by(|a, b| (&a.person, a.date).cmp(&(&b.person, b.date)));
+    notes
+}
+
+/// The latest value of an entry field for a person, e.g. `kids: 2`
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct Fact {
+    pub key: String,
+    pub value: String,
+    /// Day of the entry the value comes from
+    pub date: NaiveDate,
+}
+
+/// The latest value of each field in the entries about the person, sorted by
+/// key. Later entries win over earlier ones on the same day.
+pub fn facts(log: &Log, person: &PersonName) -> Vec<Fact> {
+    let mut latest: BTreeMap<&str, (NaiveDate, &str)> = BTreeMap::new();
+    for day in log.days.iter() {
+        let entries = day
+            .entries
+            .iter()
+            .filter(|entry| entry.main.contains(person));
+        for entry in entries {
+            for (key, value) in entry.fields.iter() {
+                let is_newer = match latest.get(key.as_str()) {
+                    Some((date, _)) => *date <= day.date,
+                    None => true,
+                };
+                if is_newer {
+                    latest.insert(key, (day.date, value));
+                }
+            }
+        }
+    }
+
+    latest
+        .into_iter()
+        .map(|(key, (date, value))| Fact {
+            key: key.to_string(),
+            value: value.to_string(),
+            date,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_field_notes() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-03
+
+            - #JaneDoe, #JohnDoe :
+              - gift: board game
+
+            # 2000-01-01
+
+            - #JaneDoe :
+              - gift-idea: fountain pen
+              - kids: 1
+            "
+        ));
+
+        let note = |person: &str, date: &str, key: &str, value: &str| FieldNote {
+            person: person.to_string(),
+            date: d(date),
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        assert_eq!(
+            field_notes(&log, &GIFT_KEYS, None),
+            vec![
+                note("JaneDoe", "2000-01-01", "gift-idea", "fountain pen"),
+                note("JaneDoe", "2000-01-03", "gift", "board game"),
+                note("JohnDoe", "2000-01-03", "gift", "board game"),
+            ]
+        );
+        assert_eq!(
+            field_notes(&log, &["kids"], Some(&"JohnDoe".to_string())),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_facts() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-03
+
+            - #JaneDoe :
+              - kids: 2
+
+            # 2000-01-01
+
+            - #JaneDoe :
+              - kids: 1
+              - gift-idea: fountain pen
+            - #JohnDoe :
+              - gift-idea: a book for #JaneDoe
+            "
+        ));
+
+        let fact = |key: &str, value: &str, date: &str| Fact {
+            key: key.to_string(),
+            value: value.to_string(),
+            date: d(date),
+        };
+        assert_eq!(
+            facts(&log, &"JaneDoe".to_string()),
+            vec![
+                fact("gift-idea", "fountain pen", "2000-01-01"),
+                fact("kids", "2", "2000-01-03"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::{self, Utf8Error};
+
+use tracing::warn;
+
+/// Some editors, mostly on Windows, start UTF-8 files with it
+static BYTE_ORDER_MARK: &[u8] = b"\xEF\xBB\xBF";
+
+pub(crate) fn temporary_path(path: &Path) -> PathBuf {
+    let file_name = match path.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => "people".to_string(),
+    };
+
+    path.with_file_name(format!(".{file_name}.tmp-{}", process::id()))
+}
+
+/// A name that is safe as a file name on every platform: whitespace and
+/// characters that mean something in paths become `-`, and it can't start
+/// with a dot
+pub fn slug(name: &str) -> String {
+    let slug: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_whitespace() || c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+
+    match slug.strip_prefix('.') {
+        Some(rest) => format!("_{rest}"),
+        None => slug,
+    }
+}
+
+/// Write to a temporary file next to `path` and then rename it into place, so
+/// that readers (e.g. sync tools) never see a half-written file
+pub fn write_atomically(path: &Path, content: &str) -> Result<(), String> {
+    let temporary = temporary_path(path);
+    if let Err(reason) = fs::write(&temporary, content) {
+        return Err(format!("failed to write {temporary:?}, reason: {reason}"));
+    }
+
+    if let Err(reason) = fs::rename(&temporary, path) {
+        let _ = fs::remove_file(&temporary);
+        return Err(format!("failed to write {path:?}, reason: {reason}"));
+    }
+
+    Ok(())
+}
+
+/// Create the directory a file goes in, and the ones above it, if missing
+pub fn create_parent_dir(path: &Path) -> Result<(), String> {
+    let dir = match path.parent() {
+        Some(dir) => dir,
+        None => return Ok(()),
+    };
+    match fs::create_dir_all(dir) {
+        Ok(()) => Ok(()),
+        Err(reason) => Err(format!("failed to create {dir:?}, reason: {reason}")),
+    }
+}
+
+/// A path relative to the home directory if it starts with `~`, e.g.
+/// `~/people`
+pub fn expand_home(path: &str) -> Result<PathBuf, String> {
+    expand_home_in(path, home::home_dir().as_deref())
+}
+
+fn expand_home_in(path: &str, home: Option<&Path>) -> Result<PathBuf, String> {
+    // `~/` on every platform, and also `~\` on Windows
+    let rest = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => rest,
+        _ => return Ok(PathBuf::from(path)),
+    };
+
+    let home = match home {
+        Some(home) => home,
+        None => {
+            return Err(format!(
+                "failed to expand {path:?}, reason: home directory not found"
+            ))
+        }
+    };
+    match rest.trim_start_matches(['/', '\\']) {
+        "" => Ok(home.to_path_buf()),
+        rest => Ok(home.join(rest)),
+    }
+}
+
+/// The text of a file's bytes without the byte order mark, or why it is not
+/// valid UTF-8 along with the text with the invalid bytes replaced by `�`
+fn decode_text(bytes: &[u8]) -> Result<String, (Utf8Error, String)> {
+    let bytes = bytes.strip_prefix(BYTE_ORDER_MARK).unwrap_or(bytes);
+    match str::from_utf8(bytes) {
+        Ok(text) => Ok(text.to_string()),
+        Err(reason) => Err((reason, String::from_utf8_lossy(bytes).into_owned())),
+    }
+}
+
+/// Read a text file, e.g. a log. Invalid UTF-8 is replaced with a warning,
+/// unless `strict`, where it is an error.
+pub fn read_text(path: &Path, strict: bool) -> Result<String, String> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+
+    match decode_text(&bytes) {
+        Ok(text) => Ok(text),
+        Err((reason, _)) if strict => Err(format!("failed to read {path:?}, reason: {reason}")),
+        Err((reason, text)) => {
+            warn!(
+                "{path:?} is not valid UTF-8, reading it with the invalid bytes replaced: {reason}"
+            );
+            Ok(text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("JaneDoe"), "JaneDoe");
+        assert_eq!(slug("Jane Doe"), "Jane-Doe");
+        assert_eq!(slug("AC/DC: live?"), "AC-DC--live-");
+        assert_eq!(slug("..hidden"), "_.hidden");
+        assert_eq!(slug("Zoë"), "Zoë");
+    }
+
+    #[test]
+    fn test_decode_text() {
+        assert_eq!(
+            decode_text(b"\xEF\xBB\xBF# 2024-01-01\n"),
+            Ok("# 2024-01-01\n".to_string())
+        );
+
+        let (reason, text) = decode_text(b"- #Zo\xEB :\n").unwrap_err();
+        assert_eq!(reason.valid_up_to(), 5);
+        assert_eq!(text, "- #Zo\u{FFFD} :\n");
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = Path::new("home").join("me");
+        let expand = |path: &str| expand_home_in(path, Some(&home));
+
+        assert_eq!(expand("~"), Ok(home.clone()));
+        assert_eq!(expand("~/people"), Ok(home.join("people")));
+        assert_eq!(expand("~\\people"), Ok(home.join("people")));
+        assert_eq!(expand("people/~"), Ok(PathBuf::from("people/~")));
+        assert_eq!(expand("~jane/people"), Ok(PathBuf::from("~jane/people")));
+        assert!(expand_home_in("~/people", None).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeSet;
+
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::log::{self, Log, ParseOptions};
+use crate::model::PersonName;
+
+/// `- ? ask #JaneDoe about the surgery result`
+static QUESTION_PATTERN: &str = r"^(?:[-*+]\s+)?\?\s+(\S.*)$";
+/// `- ✓ surgery result` or `- done: surgery result`
+static ANSWER_PATTERN: &str = r"^(?:[-*+]\s+)?(?:✓|done:)\s*(.*)$";
+
+/// Something to ask or check with someone next time
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct Followup {
+    /// The people of the entry, and the ones mentioned in the question
+    pub people: BTreeSet<PersonName>,
+    pub date: NaiveDate,
+    pub question: String,
+    /// Day of the first `✓` or `done:` line that matches it
+    pub answered: Option<NaiveDate>,
+}
+
+impl Followup {
+    /// An answer closes a follow-up about any of its people whose question
+    /// contains its text, ignoring case. An answer without text closes any.
+    fn is_closed_by(&self, people: &BTreeSet<PersonName>, answer: &str) -> bool {
+        self.answered.is_none()
+            && !self.people.is_disjoint(people)
+            && self
+                .question
+                .to_lowercase()
+                .contains(&answer.to_lowercase())
+    }
+}
+
+/// Every follow-up in the logs, oldest first, answered by the first later
+/// answer that matches it
+pub fn followups(log: &Log, options: &ParseOptions) -> Vec<Followup> {
+    let question_re = Regex::new(QUESTION_PATTERN).unwrap();
+    let answer_re = Regex::new(ANSWER_PATTERN).unwrap();
+
+    let mut days: Vec<&log::Day> = log.days.iter().collect();
+    days.sort_by_key(|day| day.date);
+
+    let mut followups: Vec<Followup> = vec![];
+    for day in days {
+        for entry in day.entries.iter() {
+            for line in entry.content.lines().map(str::trim) {
+                let mut people: BTreeSet<PersonName> = entry.main.iter().cloned().collect();
+                people.extend(log::parse_line_people(line, options));
+
+                if let Some(cap) = question_re.captures(line) {
+                    followups.push(Followup {
+                        people,
+                        date: day.date,
+                        question: cap[1].to_string(),
+                        answered: None,
+                    });
+                } else if let Some(cap) = answer_re.captures(line) {
+                    let answer = cap[1].trim();
+                    let open = followups
+                        .iter_mut()
+                        .find(|followup| followup.is_closed_by(&people, answer));
+                    if let Some(followup) = open {
+                        followup.answered = Some(day.date);
+                    }
+                }
+            }
+        }
+    }
+
+    followups
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_followups() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-05
+
+            - #JaneDoe :
+              - done: Surgery
+            - #Abu :
+              - ✓
+
+            # 2000-01-01
+
+            - ? ask #JaneDoe about the surgery result
+            - #JohnDoe :
+              - ? how did the move go
+            - #Abu :
+              - ? lend him the drill
+            "
+        ));
+
+        let followups = followups(&log, &ParseOptions::default());
+        let answered: Vec<(&str, Option<NaiveDate>)> = followups
+            .iter()
+            .map(|followup| (followup.question.as_str(), followup.answered))
+            .collect();
+
+        assert_eq!(
+            answered,
+            vec![
+                (
+                    "ask #JaneDoe about the surgery result",
+                    Some(d("2000-01-05"))
+                ),
+                ("how did the move go", None),
+                ("lend him the drill", Some(d("2000-01-05"))),
+            ]
+        );
+    }
+}
@@ -0,0 +1,577 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::files;
+use crate::front_matter::strip_front_matter;
+use crate::log::{line_header_date, parse_log_file_iter, DatePrecision, Day, ParseOptions};
+use crate::operations::Transaction;
+
+/// `* item` and `+ item` become `- item`, and trailing whitespace goes away
+fn normalize_line(line: &str) -> String {
+    let line = line.trim_end();
+    let indentation = line.len() - line.trim_start().len();
+    let (indent, rest) = line.split_at(indentation);
+
+    match rest.strip_prefix("* ").or_else(|| rest.strip_prefix("+ ")) {
+        Some(item) => format!("{indent}- {item}"),
+        None => line.to_string(),
+    }
+}
+
+fn normalize_content(content: &str) -> String {
+    content
+        .lines()
+        .map(normalize_line)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// The lines of the days, normalized, by date. Days with the same date are
+/// merged, keeping the order of their lines.
+type DayLines = BTreeMap<(NaiveDate, DatePrecision), Vec<String>>;
+
+/// Split the body into what comes before the first day header, kept as is,
+/// and the lines of each day
+fn split_days(body: &str) -> Option<(&str, DayLines)> {
+    let mut lines = body.split_inclusive('\n');
+    let mut offset = 0;
+    let mut date = loop {
+        let line = lines.next()?;
+        if let Some(date) = line_header_date(line) {
+            break date;
+        }
+        offset += line.len();
+    };
+    let intro = &body[..offset];
+
+    let mut days: DayLines = BTreeMap::new();
+    days.entry(date).or_default();
+    for line in lines {
+        match line_header_date(line) {
+            Some(header) => {
+                date = header;
+                days.entry(date).or_default();
+            }
+            None => days
+                .entry(date)
+                .or_default()
+                .push(normalize_line(&line.replace('\t', "  "))),
+        }
+    }
+
+    Some((intro, days))
+}
+
+/// A day without blank lines around its lines, and only one between them
+fn render_day((date, precision): (NaiveDate, DatePrecision), lines: &[String]) -> String {
+    let mut body: Vec<&str> = vec![];
+    for line in lines {
+        let after_blank = body.last().is_none_or(|last| last.is_empty());
+        if !(line.is_empty() && after_blank) {
+            body.push(line);
+        }
+    }
+    if body.last().is_some_and(|last| last.is_empty()) {
+        body.pop();
+    }
+
+    let header = format!("# {}", precision.header(date));
+    match body.is_empty() {
+        true => header,
+        false => format!("{header}\n\n{}", body.join("\n")),
+    }
+}
+
+fn format_once(content: &str) -> String {
+    let body = strip_front_matter(content);
+    let front_matter = &content[..content.len() - body.len()];
+    let (intro, days) = match split_days(body) {
+        Some(split) => split,
+        None => return content.to_string(),
+    };
+
+    // blank lines alone before the first day are not worth keeping
+    let intro = match intro.trim().is_empty() {
+        true => "",
+        false => intro,
+    };
+    let days: Vec<String> = days
+        .iter()
+        .map(|(date, lines)| render_day(*date, lines))
+        .collect();
+
+    format!("{front_matter}{intro}{}\n", days.join("\n\n"))
+}
+
+/// The lines worth comparing before and after formatting: not blank, and not
+/// day headers, as days with the same date are merged
+fn comparable_lines(content: &str) -> impl Iterator<Item = String> + '_ {
+    content
+        .lines()
+        .filter(|line| line_header_date(line).is_none())
+        .map(|line| normalize_line(&line.replace('\t', "  ")).trim().to_string())
+        .filter(|line| !line.is_empty())
+}
+
+/// A line of `content` that `formatted` does not have, if any
+fn lost_line(content: &str, formatted: &str) -> Option<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for line in comparable_lines(formatted) {
+        *counts.entry(line).or_default() += 1;
+    }
+
+    comparable_lines(content).find(|line| match counts.get_mut(line) {
+        Some(count) if *count > 0 => {
+            *count -= 1;
+            false
+        }
+        _ => true,
+    })
+}
+
+/// Merge two versions of a log file, e.g. edited on different machines:
+/// `ours` as is, plus the entries of `theirs` that are neither in `ours` nor
+/// in `base`, the version both started from, as those were removed on our
+/// side. Entries are the same if their content is, once formatted. New
+/// entries go at the end of their day in `ours`, or in a new day. The front
+/// matter of `ours` wins, if it has one.
+pub fn merge_contents(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    options: &ParseOptions,
+) -> Result<String, String> {
+    let our_days: Vec<Day> = parse_log_file_iter(ours, options).collect();
+    let their_days: Vec<Day> = parse_log_file_iter(theirs, options).collect();
+    if our_days.is_empty() || their_days.is_empty() {
+        return Err("both versions must have days to merge".to_string());
+    }
+
+    let mut known: HashSet<((NaiveDate, DatePrecision), String)> = HashSet::new();
+    for day in parse_log_file_iter(base, options).chain(our_days.iter().cloned()) {
+        for entry in day.entries {
+            known.insert(((day.date, day.precision), normalize_content(&entry.content)));
+        }
+    }
+    let mut new_entries: DayLines = BTreeMap::new();
+    for day in their_days {
+        for entry in day.entries {
+            let content = normalize_content(&entry.content);
+            if known.insert(((day.date, day.precision), content.clone())) {
+                new_entries
+                    .entry((day.date, day.precision))
+                    .or_default()
+                    .push(content);
+            }
+        }
+    }
+
+    // what to insert before each line of `ours`, by index
+    let mut insertions: Vec<(usize, String)> = vec![];
+    for ((date, precision), entries) in new_entries {
+        let entries = entries.join("\n");
+        let same_day = our_days
+            .iter()
+            .rfind(|day| (day.date, day.precision) == (date, precision));
+        let later_day = our_days
+            .iter()
+            .find(|day| (day.date, day.precision) > (date, precision));
+        let header = precision.header(date);
+        insertions.push(match (same_day, later_day) {
+            (Some(day), _) => (day.line_range.end - 1, format!("{entries}\n")),
+            (None, Some(day)) => (
+                day.line_range.start - 1,
+                format!("# {header}\n\n{entries}\n\n"),
+            ),
+            (None, None) => (usize::MAX, format!("\n# {header}\n\n{entries}\n")),
+        });
+    }
+    insertions.sort_by_key(|(index, _)| *index);
+
+    let our_body = strip_front_matter(ours);
+    let mut merged = match our_body.len() == ours.len() {
+        true => theirs[..theirs.len() - strip_front_matter(theirs).len()].to_string(),
+        false => String::new(),
+    };
+    let mut insertions = insertions.into_iter().peekable();
+    for (index, line) in ours.split_inclusive('\n').enumerate() {
+        while let Some((_, text)) = insertions.next_if(|(at, _)| *at <= index) {
+            merged.push_str(&text);
+        }
+        merged.push_str(line);
+    }
+    if !merged.ends_with('\n') {
+        merged.push('\n');
+    }
+    for (_, text) in insertions {
+        merged.push_str(&text);
+    }
+
+    if let Some(line) = lost_line(ours, &merged) {
+        return Err(format!("merging would lose our line {line:?}"));
+    }
+    // lines removed on our side are not lost
+    if let Some(line) = lost_line(theirs, &format!("{merged}\n{base}")) {
+        return Err(format!("merging would lose their line {line:?}"));
+    }
+
+    Ok(merged)
+}
+
+/// Merge two log files into `output`, which can be one of them, as git merge
+/// drivers do. Without a `base`, every entry only in `theirs` is added.
+pub fn merge_files(
+    base: Option<&Path>,
+    ours: &Path,
+    theirs: &Path,
+    output: &Path,
+) -> Result<(), String> {
+    let base = match base {
+        Some(path) => read(path)?,
+        None => String::new(),
+    };
+    let merged = match merge_contents(
+        &base,
+        &read(ours)?,
+        &read(theirs)?,
+        &ParseOptions::default(),
+    ) {
+        Ok(merged) => merged,
+        Err(reason) => {
+            return Err(format!(
+                "failed to merge {ours:?} and {theirs:?}, reason: {reason}"
+            ))
+        }
+    };
+    files::write_atomically(output, &merged)
+}
+
+/// Normalize a log file: tabs become two spaces, bullets are `- `, days are
+/// sorted and separated by one blank line, blank lines within a day are
+/// collapsed into one, and trailing whitespace is trimmed. The front matter
+/// and anything before the first day are kept as is.
+pub fn format_content(content: &str) -> Result<String, String> {
+    let formatted = format_once(content);
+
+    // formatting only moves lines around, so a missing one is a bug
+    if let Some(line) = lost_line(content, &formatted) {
+        return Err(format!(
+            "formatting would lose the line {line:?}, please report it"
+        ));
+    }
+
+    // formatting twice must not change anything, or the parser and the
+    // formatter disagree and the file is left alone
+    if format_once(&formatted) != formatted {
+        return Err("formatting is not stable, please report it".to_string());
+    }
+
+    Ok(formatted)
+}
+
+/// A log file whose content would change once formatted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unformatted {
+    pub path: PathBuf,
+    pub formatted: String,
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+    }
+}
+
+/// The files that are not formatted yet
+pub fn check_files(paths: &[PathBuf]) -> Result<Vec<Unformatted>, String> {
+    let mut unformatted: Vec<Unformatted> = vec![];
+    for path in paths {
+        let content = read(path)?;
+        let formatted = match format_content(&content) {
+            Ok(formatted) => formatted,
+            Err(reason) => return Err(format!("failed to format {path:?}, reason: {reason}")),
+        };
+
+        if formatted != content {
+            unformatted.push(Unformatted {
+                path: path.clone(),
+                formatted,
+            });
+        }
+    }
+
+    Ok(unformatted)
+}
+
+pub fn write_formatted(
+    unformatted: &[Unformatted],
+    transaction: &Transaction,
+) -> Result<(), String> {
+    for file in unformatted {
+        transaction.write(&file.path, &file.formatted)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::without_provenance;
+
+    use super::*;
+
+    #[test]
+    fn test_format_content() {
+        let content = indoc!(
+            "
+            ---
+            title: 2000
+            ---
+            # 2000-01-02
+
+
+
+            - #JaneDoe :
+            \t* stuff   
+            # 2000-01-01
+            + #JohnDoe :
+              - blah
+
+            # 2000-01-02
+            - #Abu :
+            "
+        )
+        .trim_start();
+
+        let expected = indoc!(
+            "
+            ---
+            title: 2000
+            ---
+            # 2000-01-01
+
+            - #JohnDoe :
+              - blah
+
+            # 2000-01-02
+
+            - #JaneDoe :
+              - stuff
+            - #Abu :
+            "
+        )
+        .trim_start();
+
+        let formatted = format_content(content).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(format_content(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_keeps_the_parsed_log() {
+        let content = "# 2000-01-01\n\n- #JohnDoe :\n\t- stuff https://a.io\n\n\n- #Abu\n";
+
+        let formatted = format_content(content).unwrap();
+
+        assert_eq!(
+            without_provenance(parse_log_file_content(&formatted)),
+            without_provenance(parse_log_file_content(content))
+        );
+    }
+
+    #[test]
+    fn test_merge_contents() {
+        let ours = indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - blah
+
+            # 2000-01-03
+
+            - #Abu :
+            "
+        );
+        let theirs = indoc!(
+            "
+            ---
+            title: 2000
+            ---
+            # 2000-01-01
+
+            - #JaneDoe :
+            * #JohnDoe :
+              - blah
+
+            # 2000-01-02
+
+            - #Abu : new
+            "
+        );
+
+        let expected = indoc!(
+            "
+            ---
+            title: 2000
+            ---
+            # 2000-01-01
+
+            - #JohnDoe :
+              - blah
+            - #JaneDoe :
+
+            # 2000-01-02
+
+            - #Abu : new
+
+            # 2000-01-03
+
+            - #Abu :
+            "
+        );
+
+        let options = ParseOptions::default();
+        assert_eq!(
+            merge_contents("", ours, theirs, &options),
+            Ok(expected.to_string())
+        );
+    }
+
+    #[test]
+    fn test_merge_contents_with_base() {
+        let base = indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe : lunch
+            - #Abu : call
+            "
+        );
+        // we removed the call and added a note before the first day
+        let ours = indoc!(
+            "
+            Notes on people.
+
+            # 2000-01-01
+
+            - #JohnDoe : lunch
+
+              It rained.
+            "
+        );
+        let theirs = indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe : lunch
+            - #Abu : call
+            - #Zoe : coffee
+            "
+        );
+
+        let expected = indoc!(
+            "
+            Notes on people.
+
+            # 2000-01-01
+
+            - #JohnDoe : lunch
+
+              It rained.
+            - #Zoe : coffee
+            "
+        );
+
+        let options = ParseOptions::default();
+        assert_eq!(
+            merge_contents(base, ours, theirs, &options),
+            Ok(expected.to_string())
+        );
+        assert_eq!(
+            merge_contents(base, ours, &format!("Their notes.\n{theirs}"), &options),
+            Err("merging would lose their line \"Their notes.\"".to_string())
+        );
+        assert_eq!(
+            merge_contents("", ours, "* TODO call Abu\n", &options),
+            Err("both versions must have days to merge".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_keeps_prose() {
+        let content = indoc!(
+            "
+            Notes from the trip.
+
+            Nothing about people here.
+            # 2000-01-02
+            - #JaneDoe : met
+
+            \tsome thoughts on it
+
+
+            - #Abu : call
+            # 2000-01-01
+            Just a paragraph.
+            "
+        )
+        .trim_start();
+
+        let expected = indoc!(
+            "
+            Notes from the trip.
+
+            Nothing about people here.
+            # 2000-01-01
+
+            Just a paragraph.
+
+            # 2000-01-02
+
+            - #JaneDoe : met
+
+              some thoughts on it
+
+            - #Abu : call
+            "
+        )
+        .trim_start();
+
+        assert_eq!(format_content(content), Ok(expected.to_string()));
+        assert_eq!(
+            lost_line(content, "# 2000-01-01\n"),
+            Some("Notes from the trip.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_empty_content() {
+        assert_eq!(format_content(""), Ok("".to_string()));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_format_any_content(content in crate::test_utils::log_content()) {
+            let formatted = format_content(&content);
+            proptest::prop_assert!(formatted.is_ok(), "{:?}", formatted);
+
+            // formatting keeps every line, and what they parse to
+            let formatted = formatted.unwrap();
+            proptest::prop_assert_eq!(lost_line(&content, &formatted), None);
+            proptest::prop_assert_eq!(lost_line(&formatted, &content), None);
+            proptest::prop_assert_eq!(
+                without_provenance(parse_log_file_content(&format_once(&formatted))),
+                without_provenance(parse_log_file_content(&formatted))
+            );
+        }
+    }
+}
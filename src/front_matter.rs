@@ -0,0 +1,201 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::config::{Config, FrontMatterField};
+use crate::log::Log;
+use crate::migrations;
+use crate::model::PersonName;
+
+static DELIMITER: &str = "---";
+
+/// Remove the YAML front matter block (if any) at the top of a log file
+pub fn strip_front_matter(content: &str) -> &str {
+    let mut lines = content.split_inclusive('\n');
+
+    let opening = match lines.next() {
+        Some(line) if line.trim_end() == DELIMITER => line,
+        _ => return content,
+    };
+
+    let mut offset = opening.len();
+    for line in lines {
+        offset += line.len();
+        if line.trim_end() == DELIMITER {
+            return &content[offset..];
+        }
+    }
+
+    // no closing delimiter found, so this is not front matter
+    content
+}
+
+fn last_interaction(log: &Log) -> Option<String> {
+    log.days
+        .iter()
+        .map(|day| day.date)
+        .max()
+        .map(|date| date.to_string())
+}
+
+fn total_entries(log: &Log) -> usize {
+    log.days.iter().map(|day| day.entries.len()).sum()
+}
+
+/// Build the YAML front matter block to prepend to a person's log
+pub fn render_front_matter(person: &PersonName, log: &Log, config: &Config) -> String {
+    if config.front_matter.is_empty() {
+        return "".to_string();
+    }
+
+    let metadata = config.get_person(person);
+
+    let mut mapping = Mapping::new();
+    for field in config.front_matter.iter() {
+        let value: Option<Value> = match field {
+            FrontMatterField::Name => Some(Value::from(person.clone())),
+            FrontMatterField::Aliases => metadata
+                .filter(|person| !person.aliases.is_empty())
+                .map(|person| Value::from(person.aliases.clone())),
+            FrontMatterField::Location => {
+                metadata.map(|person| Value::from(person.location.clone()))
+            }
+            FrontMatterField::Themes => metadata
+                .filter(|person| !person.themes.is_empty())
+                .map(|person| Value::from(person.themes.clone())),
+            FrontMatterField::LastInteraction => last_interaction(log).map(Value::from),
+            FrontMatterField::TotalEntries => Some(Value::from(total_entries(log))),
+            FrontMatterField::Version => Some(Value::from(migrations::LOG_VERSION)),
+        };
+
+        if let Some(value) = value {
+            mapping.insert(Value::from(field.key()), value);
+        }
+    }
+
+    let yaml = serde_yaml::to_string(&mapping).expect("front matter should be serializable");
+
+    format!("{DELIMITER}\n{yaml}{DELIMITER}\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Person;
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::without_provenance;
+
+    use super::*;
+
+    fn config_with(front_matter: Vec<FrontMatterField>) -> Config {
+        Config::builder()
+            .people_dir("/tmp/people")
+            .person(Person {
+                name: "JohnDoe".to_string(),
+                aliases: vec!["Johnny".to_string()],
+                location: "London".to_string(),
+                themes: vec!["climbing".to_string()],
+                ..Default::default()
+            })
+            .front_matter(front_matter)
+            .build()
+    }
+
+    #[test]
+    fn test_strip_front_matter() {
+        let content = indoc!(
+            "
+            ---
+            name: JohnDoe
+            ---
+
+            # 2000-01-01
+            "
+        )
+        .trim_start();
+
+        assert_eq!(strip_front_matter(content), "\n# 2000-01-01\n");
+    }
+
+    #[test]
+    fn test_strip_front_matter_when_there_is_none() {
+        let content = "# 2000-01-01\n\n- #JohnDoe :\n";
+
+        assert_eq!(strip_front_matter(content), content);
+    }
+
+    #[test]
+    fn test_render_front_matter() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - stuff: blah
+
+            # 2000-01-02
+
+            - #JohnDoe :
+              - stuff: bleh
+            "
+        ));
+        let config = config_with(FrontMatterField::all());
+
+        let expected = indoc!(
+            "
+            ---
+            name: JohnDoe
+            aliases:
+            - Johnny
+            location: London
+            themes:
+            - climbing
+            last_interaction: 2000-01-02
+            total_entries: 2
+            version: 1
+            ---
+
+            "
+        )
+        .trim_start();
+
+        assert_eq!(
+            render_front_matter(&"JohnDoe".to_string(), &log, &config),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_render_no_front_matter_when_no_fields_configured() {
+        let log = Log { days: vec![] };
+        let config = config_with(vec![]);
+
+        assert_eq!(
+            render_front_matter(&"JohnDoe".to_string(), &log, &config),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_front_matter_is_ignored_on_read() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - stuff: blah
+            "
+        ));
+        let config = config_with(FrontMatterField::all());
+
+        let written = format!(
+            "{}{log}",
+            render_front_matter(&"JohnDoe".to_string(), &log, &config)
+        );
+
+        assert_eq!(
+            without_provenance(parse_log_file_content(&written)),
+            without_provenance(log)
+        );
+    }
+}
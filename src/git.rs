@@ -0,0 +1,367 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::{DateTime, Local, NaiveDate};
+use chrono_tz::Tz;
+
+use crate::config::Config;
+use crate::log::{self, find_log_files, parse_header_date, ParseOptions};
+use crate::model::PersonName;
+use crate::use_cases::LogWritten;
+
+/// `git blame` shows lines not committed yet as coming from this commit
+const UNCOMMITTED: &str = "0000000000000000000000000000000000000000";
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = match Command::new("git").arg("-C").arg(dir).args(args).output() {
+        Ok(output) => output,
+        Err(reason) => return Err(format!("failed to run git, reason: {reason}")),
+    };
+
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).to_string()),
+        false => Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// `git <args> -- <paths>`
+fn git_with_paths(dir: &Path, args: &[&str], paths: &[String]) -> Result<String, String> {
+    let mut all: Vec<&str> = args.to_vec();
+    all.push("--");
+    all.extend(paths.iter().map(String::as_str));
+    git(dir, &all)
+}
+
+pub fn is_repository(dir: &Path) -> bool {
+    git(dir, &["rev-parse", "--is-inside-work-tree"]).is_ok_and(|output| output.trim() == "true")
+}
+
+fn relative_paths(paths: &[&PathBuf], people_dir: &Path) -> Vec<String> {
+    let mut relative: Vec<String> = paths
+        .iter()
+        .map(|path| path.strip_prefix(people_dir).unwrap_or(path))
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    relative.sort();
+    relative
+}
+
+fn changed_paths(results: &[LogWritten]) -> (Vec<&PathBuf>, Vec<&PathBuf>) {
+    let mut updated: Vec<&PathBuf> = vec![];
+    let mut deleted: Vec<&PathBuf> = vec![];
+    for result in results.iter() {
+        match result {
+            LogWritten::Written(path) => updated.push(path),
+            LogWritten::Deleted(path) => deleted.push(path),
+            _ => {}
+        }
+    }
+    (updated, deleted)
+}
+
+/// A summary line, then a line per kind of change with the files affected,
+/// relative to `people_dir`
+pub fn commit_message(results: &[LogWritten], people_dir: &Path) -> String {
+    let (updated, deleted) = changed_paths(results);
+
+    let mut message = String::from("people: regenerate per-person logs\n");
+    if !updated.is_empty() {
+        let updated = relative_paths(&updated, people_dir).join(", ");
+        message.push_str(&format!("\nUpdated: {updated}"));
+    }
+    if !deleted.is_empty() {
+        let deleted = relative_paths(&deleted, people_dir).join(", ");
+        message.push_str(&format!("\nDeleted: {deleted}"));
+    }
+    message + "\n"
+}
+
+/// Commit the per-person logs written or deleted if `git.auto_commit` is set
+/// and `people_dir` is a git repository. Other changes in the repository are
+/// left alone. Returns whether there was anything to commit.
+pub fn commit_per_person_logs(config: &Config, results: &[LogWritten]) -> Result<bool, String> {
+    if !config.git.auto_commit || !is_repository(&config.people_dir) {
+        return Ok(false);
+    }
+
+    let (updated, deleted) = changed_paths(results);
+    let dir = &config.people_dir;
+    let updated = relative_paths(&updated, dir);
+    let deleted = relative_paths(&deleted, dir);
+    if !updated.is_empty() {
+        git_with_paths(dir, &["add"], &updated)?;
+    }
+    // files deleted before they were ever committed are not in the index
+    if !deleted.is_empty() {
+        let args = ["rm", "--cached", "--quiet", "--ignore-unmatch"];
+        git_with_paths(dir, &args, &deleted)?;
+    }
+
+    let all: Vec<String> = updated.into_iter().chain(deleted).collect();
+    if all.is_empty() {
+        return Ok(false);
+    }
+    let args = ["diff", "--cached", "--name-only", "--relative"];
+    let staged = git_with_paths(dir, &args, &all)?;
+    let staged: Vec<String> = staged.lines().map(String::from).collect();
+    if staged.is_empty() {
+        return Ok(false);
+    }
+
+    let message = commit_message(results, dir);
+    git_with_paths(dir, &["commit", "--quiet", "--message", &message], &staged)?;
+    Ok(true)
+}
+
+/// A line of a file with the commit that last changed it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlameLine {
+    pub commit: String,
+    /// Seconds since the epoch
+    pub author_time: i64,
+    pub content: String,
+}
+
+impl BlameLine {
+    fn is_committed(&self) -> bool {
+        self.commit != UNCOMMITTED
+    }
+}
+
+/// Parse the output of `git blame --line-porcelain`
+pub fn parse_blame(output: &str) -> Vec<BlameLine> {
+    let mut lines: Vec<BlameLine> = vec![];
+    let mut commit = String::new();
+    let mut author_time = 0;
+
+    for line in output.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            lines.push(BlameLine {
+                commit: commit.clone(),
+                author_time,
+                content: content.to_string(),
+            });
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            author_time = time.parse().unwrap_or(0);
+        } else if let Some((hash, _)) = line.split_once(' ') {
+            if hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                commit = hash.to_string();
+            }
+        }
+    }
+
+    lines
+}
+
+/// Lines of a file not tracked yet count as not committed
+fn blame(repository: &Path, path: &Path) -> Result<Vec<BlameLine>, String> {
+    let path_str = path.to_string_lossy();
+    let is_tracked = git(
+        repository,
+        &["ls-files", "--error-unmatch", "--", &path_str],
+    )
+    .is_ok();
+    if is_tracked {
+        let output = git(repository, &["blame", "--line-porcelain", "--", &path_str])?;
+        return Ok(parse_blame(&output));
+    }
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+    let lines = content
+        .lines()
+        .map(|line| BlameLine {
+            commit: UNCOMMITTED.to_string(),
+            author_time: 0,
+            content: line.to_string(),
+        })
+        .collect();
+    Ok(lines)
+}
+
+/// When an entry was stated to happen and when it was written down
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedEntry {
+    pub date: NaiveDate,
+    /// None if the entry is not committed yet
+    pub recorded: Option<NaiveDate>,
+    pub commit: Option<String>,
+    /// First line of the entry
+    pub line: String,
+}
+
+impl RecordedEntry {
+    /// Days between the interaction and writing it down
+    pub fn lag(&self) -> Option<i64> {
+        self.recorded
+            .map(|recorded| (recorded - self.date).num_days())
+    }
+}
+
+fn local_date(timestamp: i64, timezone: Option<Tz>) -> Option<NaiveDate> {
+    let utc = DateTime::from_timestamp(timestamp, 0)?;
+    Some(match timezone {
+        Some(timezone) => utc.with_timezone(&timezone).date_naive(),
+        None => utc.with_timezone(&Local).date_naive(),
+    })
+}
+
+/// The entries with the person in the blamed lines of a log file, dated with
+/// the commit that added their first line
+pub fn recorded_entries(
+    lines: &[BlameLine],
+    person: &PersonName,
+    options: &ParseOptions,
+    timezone: Option<Tz>,
+) -> Vec<RecordedEntry> {
+    let mut entries: Vec<RecordedEntry> = vec![];
+    let mut date: Option<NaiveDate> = None;
+
+    for line in lines.iter() {
+        let is_top_level = !line.content.starts_with([' ', '\t']);
+        if !is_top_level || line.content.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.content.strip_prefix("# ") {
+            if let Some((header_date, _)) = parse_header_date(header.trim_end()) {
+                date = Some(header_date);
+                continue;
+            }
+        }
+
+        // lines before the first day are the front matter
+        let Some(date) = date else {
+            continue;
+        };
+
+        if !log::parse_line_people(&line.content, options).contains(person) {
+            continue;
+        }
+
+        let committed = line.is_committed();
+        entries.push(RecordedEntry {
+            date,
+            recorded: match committed {
+                true => local_date(line.author_time, timezone),
+                false => None,
+            },
+            commit: committed.then(|| line.commit.clone()),
+            line: line.content.clone(),
+        });
+    }
+
+    entries
+}
+
+/// Every entry with the person across the log files, oldest first
+pub fn history(config: &Config, person: &PersonName) -> Result<Vec<RecordedEntry>, String> {
+    if !is_repository(&config.people_dir) {
+        return Err(format!("{:?} is not a git repository", config.people_dir));
+    }
+
+    let options = ParseOptions::from_config(config);
+    let mut entries: Vec<RecordedEntry> = vec![];
+    for path in find_log_files(config) {
+        let lines = blame(&config.people_dir, &path)?;
+        entries.extend(recorded_entries(&lines, person, &options, config.timezone));
+    }
+
+    entries.sort_by_key(|entry| entry.date);
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_commit_message() {
+        let results = vec![
+            LogWritten::Written(PathBuf::from("/tmp/people/per-person-logs/JohnDoe.md")),
+            LogWritten::Unchanged(PathBuf::from("/tmp/people/per-person-logs/Abu.md")),
+            LogWritten::Written(PathBuf::from("/tmp/people/per-person-logs/JaneDoe.md")),
+            LogWritten::Deleted(PathBuf::from("/tmp/people/per-person-logs/Old.md")),
+        ];
+
+        assert_eq!(
+            commit_message(&results, Path::new("/tmp/people")),
+            indoc!(
+                "
+                people: regenerate per-person logs
+
+                Updated: per-person-logs/JaneDoe.md, per-person-logs/JohnDoe.md
+                Deleted: per-person-logs/Old.md
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn test_recorded_entries() {
+        let blame = indoc!(
+            "
+            1111111111111111111111111111111111111111 1 1 3
+            author Jane
+            author-time 946771200
+            \t# 2000-01-01
+            1111111111111111111111111111111111111111 2 2
+            author Jane
+            author-time 946771200
+            \t
+            1111111111111111111111111111111111111111 3 3
+            author Jane
+            author-time 946771200
+            \t- #JohnDoe :
+            0000000000000000000000000000000000000000 4 4 1
+            author Not Committed Yet
+            author-time 947000000
+            \t- #JohnDoe, #JaneDoe : again
+            2222222222222222222222222222222222222222 5 5 1
+            author Jane
+            author-time 947000000
+            \t  - #JohnDoe was there too
+            "
+        );
+        let lines = parse_blame(blame);
+        assert_eq!(lines.len(), 5);
+
+        let entries = recorded_entries(
+            &lines,
+            &"JohnDoe".to_string(),
+            &ParseOptions::default(),
+            Some(Tz::UTC),
+        );
+
+        assert_eq!(
+            entries,
+            vec![
+                RecordedEntry {
+                    date: d("2000-01-01"),
+                    recorded: Some(d("2000-01-02")),
+                    commit: Some("1111111111111111111111111111111111111111".to_string()),
+                    line: "- #JohnDoe :".to_string(),
+                },
+                RecordedEntry {
+                    date: d("2000-01-01"),
+                    recorded: None,
+                    commit: None,
+                    line: "- #JohnDoe, #JaneDoe : again".to_string(),
+                },
+            ]
+        );
+        assert_eq!(entries[0].lag(), Some(1));
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::aggregate::{self, Window};
+use crate::config::Config;
+use crate::log::Log;
+use crate::model::PersonName;
+
+/// Weeks shown, like GitHub's contribution graph
+pub const WEEKS: i64 = 53;
+
+/// Darkest last, from no interactions to the busiest days
+static BLOCKS: [char; 5] = ['·', '░', '▒', '▓', '█'];
+static COLORS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+const CELL: usize = 10;
+const GAP: usize = 2;
+const MARGIN: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeatmapFormat {
+    Text,
+    Svg,
+}
+
+impl FromStr for HeatmapFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(HeatmapFormat::Text),
+            "svg" => Ok(HeatmapFormat::Svg),
+            other => Err(format!(
+                "unsupported format found: {other:?}, expected 'text' or 'svg'"
+            )),
+        }
+    }
+}
+
+/// Interactions per day, from the Monday `WEEKS` weeks ago until `end`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Heatmap {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub counts: BTreeMap<NaiveDate, usize>,
+}
+
+impl Heatmap {
+    fn count(&self, date: NaiveDate) -> usize {
+        self.counts.get(&date).copied().unwrap_or(0)
+    }
+
+    /// 0 for no interactions, up to 4 for the busiest days
+    fn level(&self, date: NaiveDate) -> usize {
+        let max = self.counts.values().max().copied().unwrap_or(0);
+        match self.count(date) {
+            0 => 0,
+            count => (count * 4).div_ceil(max).clamp(1, 4),
+        }
+    }
+
+    /// The Monday of every week shown, oldest first
+    fn weeks(&self) -> Vec<NaiveDate> {
+        let mut weeks: Vec<NaiveDate> = vec![];
+        let mut monday = self.start;
+        while monday <= self.end {
+            weeks.push(monday);
+            monday += Duration::weeks(1);
+        }
+        weeks
+    }
+}
+
+/// Count the entries with the person (or with anyone not ignored) per day
+pub fn build_heatmap(
+    log: &Log,
+    config: &Config,
+    person: Option<&PersonName>,
+    today: NaiveDate,
+) -> Heatmap {
+    let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let start = this_monday - Duration::weeks(WEEKS - 1);
+
+    let window = Window { start, end: today };
+
+    Heatmap {
+        start,
+        end: today,
+        counts: aggregate::count_per_day(log, config, person, window),
+    }
+}
+
+/// A row per weekday and a column per week, with the months on top
+pub fn to_text(heatmap: &Heatmap) -> String {
+    let weeks = heatmap.weeks();
+
+    // a label takes the columns of the following weeks too, and labels are
+    // kept a column apart so that they don't run into each other
+    let mut months = String::from("    ");
+    let mut previous_month: Option<u32> = None;
+    let mut skip = 0;
+    let mut after_label = false;
+    for monday in weeks.iter() {
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+        if previous_month != Some(monday.month()) && !after_label {
+            let label = monday.format("%b").to_string();
+            skip = label.chars().count() - 1;
+            months.push_str(&label);
+            previous_month = Some(monday.month());
+            after_label = true;
+        } else {
+            months.push(' ');
+            after_label = false;
+        }
+    }
+
+    let mut lines: Vec<String> = vec![months.trim_end().to_string()];
+    for (weekday, label) in ["Mon", "", "Wed", "", "Fri", "", "Sun"].iter().enumerate() {
+        let mut line = format!("{label:<4}");
+        for monday in weeks.iter() {
+            let date = *monday + Duration::days(weekday as i64);
+            if date > heatmap.end {
+                break;
+            }
+            line.push(BLOCKS[heatmap.level(date)]);
+        }
+        lines.push(line);
+    }
+
+    let total: usize = heatmap.counts.values().sum();
+    let legend: String = BLOCKS.iter().collect();
+    lines.push(format!(
+        "\n{total} interactions since {}    Less {legend} More",
+        heatmap.start
+    ));
+
+    lines.join("\n") + "\n"
+}
+
+pub fn to_svg(heatmap: &Heatmap) -> String {
+    let weeks = heatmap.weeks();
+    let step = CELL + GAP;
+    let width = MARGIN * 2 + weeks.len() * step;
+    let height = MARGIN * 2 + 7 * step;
+
+    let mut cells: Vec<String> = vec![];
+    for (column, monday) in weeks.iter().enumerate() {
+        for weekday in 0..7 {
+            let date = *monday + Duration::days(weekday as i64);
+            if date > heatmap.end {
+                break;
+            }
+            cells.push(format!(
+                r#"  <rect x="{}" y="{}" width="{CELL}" height="{CELL}" rx="2" fill="{}"><title>{date}: {}</title></rect>"#,
+                MARGIN + column * step,
+                MARGIN + weekday * step,
+                COLORS[heatmap.level(date)],
+                heatmap.count(date),
+            ));
+        }
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\">\n{}\n</svg>\n",
+        cells.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_build_heatmap() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 1998-01-01
+
+            - #JohnDoe :
+
+            # 2000-01-03
+
+            - #JohnDoe :
+            - #JohnDoe, #JaneDoe :
+            - #Ignored :
+
+            # 2000-01-05
+
+            - #JaneDoe :
+            "
+        ));
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .ignore(vec!["Ignored".to_string()])
+            .build();
+
+        let heatmap = build_heatmap(&log, &config, None, d("2000-01-05"));
+        assert_eq!(heatmap.start, d("1999-01-04"));
+        assert_eq!(
+            heatmap.counts,
+            [(d("2000-01-03"), 2), (d("2000-01-05"), 1)].into()
+        );
+        assert_eq!(heatmap.level(d("2000-01-03")), 4);
+        assert_eq!(heatmap.level(d("2000-01-05")), 2);
+        assert_eq!(heatmap.level(d("2000-01-04")), 0);
+
+        let jane = "JaneDoe".to_string();
+        let heatmap = build_heatmap(&log, &config, Some(&jane), d("2000-01-05"));
+        assert_eq!(
+            heatmap.counts,
+            [(d("2000-01-03"), 1), (d("2000-01-05"), 1)].into()
+        );
+    }
+
+    #[test]
+    fn test_to_text() {
+        let heatmap = Heatmap {
+            start: d("2000-01-03"),
+            end: d("2000-01-12"),
+            counts: [(d("2000-01-03"), 1), (d("2000-01-11"), 2)].into(),
+        };
+
+        let expected = indoc!(
+            "
+                Jan
+            Mon ▒·
+                ·█
+            Wed ··
+                ·
+            Fri ·
+                ·
+            Sun ·
+
+            3 interactions since 2000-01-03    Less ·░▒▓█ More
+            "
+        );
+
+        assert_eq!(to_text(&heatmap), expected);
+    }
+}
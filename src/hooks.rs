@@ -0,0 +1,208 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::config::HooksConfig;
+use crate::model::{DaysAgo, PersonName};
+use crate::state::HooksSnapshot;
+use crate::use_cases::LastInteraction;
+
+/// A change since the last `people hooks run`, sent to the hook as JSON
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HookEvent {
+    Overdue {
+        person: PersonName,
+        last: NaiveDate,
+        days_overdue: DaysAgo,
+    },
+    ContactLogged {
+        person: PersonName,
+        date: NaiveDate,
+        /// The last interaction before this one, if any
+        previous: Option<NaiveDate>,
+    },
+}
+
+impl HookEvent {
+    pub fn person(&self) -> &PersonName {
+        match self {
+            HookEvent::Overdue { person, .. } => person,
+            HookEvent::ContactLogged { person, .. } => person,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::Overdue { .. } => "overdue",
+            HookEvent::ContactLogged { .. } => "contact_logged",
+        }
+    }
+
+    /// The command configured for this kind of event
+    pub fn command<'a>(&self, hooks: &'a HooksConfig) -> Option<&'a str> {
+        match self {
+            HookEvent::Overdue { .. } => hooks.on_overdue.as_deref(),
+            HookEvent::ContactLogged { .. } => hooks.on_contact_logged.as_deref(),
+        }
+    }
+}
+
+/// The last interactions, with their reach outs assessed, as of `today`
+pub fn snapshot(interactions: &[LastInteraction], today: NaiveDate) -> HooksSnapshot {
+    HooksSnapshot {
+        checked: today,
+        last_contacts: interactions
+            .iter()
+            .map(|interaction| (interaction.person.clone(), interaction.last))
+            .collect(),
+        overdue: interactions
+            .iter()
+            .filter(|interaction| interaction.days_beyond_reachout_threshold.is_some())
+            .map(|interaction| interaction.person.clone())
+            .collect(),
+    }
+}
+
+/// Who was logged or went overdue since the `previous` snapshot. Nothing
+/// happened if there is no previous snapshot: the first run only takes one.
+pub fn events(
+    previous: Option<&HooksSnapshot>,
+    interactions: &[LastInteraction],
+) -> Vec<HookEvent> {
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return vec![],
+    };
+
+    let mut events: Vec<HookEvent> = vec![];
+    for interaction in interactions {
+        let person = &interaction.person;
+
+        let before = previous.last_contacts.get(person).copied();
+        if before.is_none_or(|before| before < interaction.last) {
+            events.push(HookEvent::ContactLogged {
+                person: person.clone(),
+                date: interaction.last,
+                previous: before,
+            });
+        }
+
+        if let Some(days_overdue) = interaction.days_beyond_reachout_threshold {
+            if !previous.overdue.contains(person) {
+                events.push(HookEvent::Overdue {
+                    person: person.clone(),
+                    last: interaction.last,
+                    days_overdue,
+                });
+            }
+        }
+    }
+
+    events
+}
+
+/// Run the command with `sh -c`, the event as JSON on stdin, and the event
+/// name and person in `PEOPLE_EVENT` and `PEOPLE_PERSON`
+pub fn run_hook(command: &str, event: &HookEvent) -> Result<(), String> {
+    let payload = serde_json::to_string(event).expect("events should be serializable");
+
+    let mut child = match Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("PEOPLE_EVENT", event.name())
+        .env("PEOPLE_PERSON", event.person())
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(reason) => return Err(format!("failed to run {command:?}, reason: {reason}")),
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // hooks that don't read stdin close it early, that's fine
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("{command:?} exited with {status}")),
+        Err(reason) => Err(format!("failed to wait for {command:?}, reason: {reason}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    fn interaction(person: &str, last: &str, overdue: Option<DaysAgo>) -> LastInteraction {
+        LastInteraction {
+            person: person.to_string(),
+            last: d(last),
+            days_beyond_reachout_threshold: overdue,
+            kind: None,
+        }
+    }
+
+    #[test]
+    fn test_events() {
+        let before = vec![
+            interaction("JaneDoe", "2000-01-01", None),
+            interaction("JohnDoe", "2000-01-01", Some(2)),
+        ];
+        let previous = snapshot(&before, d("2000-02-01"));
+        let now = vec![
+            interaction("JaneDoe", "2000-01-01", Some(1)),
+            interaction("JohnDoe", "2000-02-02", None),
+            interaction("Abu", "2000-02-02", None),
+        ];
+
+        assert_eq!(
+            events(Some(&previous), &now),
+            vec![
+                HookEvent::Overdue {
+                    person: "JaneDoe".to_string(),
+                    last: d("2000-01-01"),
+                    days_overdue: 1,
+                },
+                HookEvent::ContactLogged {
+                    person: "JohnDoe".to_string(),
+                    date: d("2000-02-02"),
+                    previous: Some(d("2000-01-01")),
+                },
+                HookEvent::ContactLogged {
+                    person: "Abu".to_string(),
+                    date: d("2000-02-02"),
+                    previous: None,
+                },
+            ]
+        );
+        assert_eq!(events(None, &now), vec![]);
+    }
+
+    #[test]
+    fn test_event_payload() {
+        let event = HookEvent::Overdue {
+            person: "JaneDoe".to_string(),
+            last: d("2000-01-01"),
+            days_overdue: 1,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({
+                "event": "overdue",
+                "person": "JaneDoe",
+                "last": "2000-01-01",
+                "days_overdue": 1,
+            })
+        );
+    }
+}
@@ -0,0 +1,371 @@
+use core::fmt;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::Arc;
+
+use chrono::{NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::config::Config;
+use crate::links;
+use crate::log::{DatePrecision, Day, Entry, Log};
+use crate::model::PersonName;
+use crate::suggest_tags::{self, Spellings};
+use crate::vcard::{unescape, unfold};
+
+/// Someone invited to an event, as `ATTENDEE;CN=Jane Doe:mailto:jane@example.com`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Attendee {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// The bits of a `VEVENT` that tell who was met and when
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub date: NaiveDate,
+    pub summary: String,
+    pub attendees: Vec<Attendee>,
+}
+
+/// `20240105`, `20240105T100000` or `20240105T090000Z`. UTC times are moved to
+/// `timezone`, other times are taken as written.
+fn parse_date(value: &str, timezone: Option<Tz>) -> Option<NaiveDate> {
+    if let (Some(utc), Some(timezone)) = (value.strip_suffix('Z'), timezone) {
+        if let Ok(time) = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S") {
+            return Some(
+                Utc.from_utc_datetime(&time)
+                    .with_timezone(&timezone)
+                    .date_naive(),
+            );
+        }
+    }
+
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+fn parse_attendee(parameters: &str, value: &str) -> Attendee {
+    let name = parameters
+        .split(';')
+        .find_map(|parameter| parameter.strip_prefix("CN="))
+        .map(|name| unescape(name.trim_matches('"')).trim().to_string())
+        .filter(|name| !name.is_empty());
+
+    let email = value
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map(|_| value[7..].trim().to_lowercase());
+
+    Attendee { name, email }
+}
+
+/// Every event with a start date, in the order of the file
+pub fn parse_events(content: &str, timezone: Option<Tz>) -> Vec<Event> {
+    let mut events: Vec<Event> = vec![];
+    let mut current: Option<(Option<NaiveDate>, String, Vec<Attendee>)> = None;
+
+    for line in unfold(content) {
+        let (property, value) = match line.split_once(':') {
+            Some(parts) => parts,
+            None => continue,
+        };
+        let (name, parameters) = property.split_once(';').unwrap_or((property, ""));
+
+        match (name.to_uppercase().as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some((None, String::new(), vec![]));
+            }
+            ("END", Some((date, summary, attendees))) if value.eq_ignore_ascii_case("VEVENT") => {
+                if let Some(date) = date {
+                    events.push(Event {
+                        date: *date,
+                        summary: summary.clone(),
+                        attendees: attendees.clone(),
+                    });
+                }
+                current = None;
+            }
+            ("DTSTART", Some((date, _, _))) => *date = parse_date(value, timezone),
+            ("SUMMARY", Some((_, summary, _))) => *summary = unescape(value).trim().to_string(),
+            ("ATTENDEE", Some((_, _, attendees))) => {
+                attendees.push(parse_attendee(parameters, value))
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
+
+/// An event with the known people in it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Meeting {
+    pub date: NaiveDate,
+    pub summary: String,
+    pub people: BTreeSet<PersonName>,
+}
+
+impl fmt::Display for Meeting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let tags: Vec<String> = self
+            .people
+            .iter()
+            .map(|person| format!("#{person}"))
+            .collect();
+        write!(f, "{} {}: {}", self.date, self.summary, tags.join(" "))
+    }
+}
+
+/// The known people of an event: attendees whose email is a person's `email`
+/// or whose name is a person's name or alias, and people mentioned by name or
+/// alias in the title, e.g. `Lunch with Jane Doe`
+fn event_people(event: &Event, spellings: &Spellings, config: &Config) -> BTreeSet<PersonName> {
+    let untagged: HashSet<PersonName> = HashSet::new();
+    let mut people: BTreeSet<PersonName> = BTreeSet::new();
+
+    for attendee in event.attendees.iter() {
+        let by_email = attendee.email.as_ref().and_then(|email| {
+            config.people.iter().find(|person| {
+                person
+                    .email
+                    .as_ref()
+                    .is_some_and(|known| known.eq_ignore_ascii_case(email))
+            })
+        });
+        match (by_email, &attendee.name) {
+            (Some(person), _) => {
+                people.insert(person.name.clone());
+            }
+            (None, Some(name)) => {
+                for (_, person) in suggest_tags::find_mentions(name, spellings, &untagged) {
+                    people.insert(person.clone());
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    for (_, person) in suggest_tags::find_mentions(&event.summary, spellings, &untagged) {
+        people.insert(person.clone());
+    }
+
+    people.retain(|person| !config.is_ignored(person));
+    people
+}
+
+/// The events since `since` with at least one known person
+pub fn match_events(
+    events: &[Event],
+    spellings: &Spellings,
+    config: &Config,
+    since: Option<NaiveDate>,
+) -> Vec<Meeting> {
+    let mut meetings: Vec<Meeting> = events
+        .iter()
+        .filter(|event| since.is_none_or(|since| event.date >= since))
+        .filter_map(|event| {
+            let people = event_people(event, spellings, config);
+            match people.is_empty() {
+                true => None,
+                false => Some(Meeting {
+                    date: event.date,
+                    summary: event.summary.clone(),
+                    people,
+                }),
+            }
+        })
+        .collect();
+    meetings.sort_by_key(|meeting| meeting.date);
+    meetings
+}
+
+/// The meetings with the people that have no entry on that date
+pub fn unlogged(meetings: Vec<Meeting>, log: &Log) -> Vec<Meeting> {
+    let mut logged: BTreeMap<NaiveDate, HashSet<&PersonName>> = BTreeMap::new();
+    for day in log.days.iter() {
+        if day.precision != DatePrecision::Day {
+            continue;
+        }
+        let people = logged.entry(day.date).or_default();
+        for entry in day.entries.iter() {
+            people.extend(entry.related.iter());
+        }
+    }
+
+    meetings
+        .into_iter()
+        .filter_map(|mut meeting| {
+            if let Some(people) = logged.get(&meeting.date) {
+                meeting.people.retain(|person| !people.contains(person));
+            }
+            match meeting.people.is_empty() {
+                true => None,
+                false => Some(meeting),
+            }
+        })
+        .collect()
+}
+
+fn stub_entry(meeting: &Meeting) -> Entry {
+    let tags: Vec<String> = meeting
+        .people
+        .iter()
+        .map(|person| format!("#{person}"))
+        .collect();
+    let mut content = format!("- {} :", tags.join(" "));
+    if !meeting.summary.is_empty() {
+        content.push_str(&format!("\n  - {}", meeting.summary));
+    }
+
+    Entry {
+        main: meeting.people.iter().cloned().collect(),
+        related: meeting.people.iter().cloned().collect(),
+        links: links::find_links(&content),
+        content,
+        kind: None,
+        fields: BTreeMap::new(),
+        source_file: None,
+        line_range: 0..0,
+    }
+}
+
+/// A log with an entry per meeting, to fill in later
+pub fn stubs(meetings: &[Meeting]) -> Log {
+    let mut days: BTreeMap<NaiveDate, Day> = BTreeMap::new();
+    for meeting in meetings {
+        days.entry(meeting.date)
+            .or_insert(Day {
+                date: meeting.date,
+                precision: DatePrecision::Day,
+                entries: vec![],
+                source_file: None,
+                line_range: 0..0,
+            })
+            .entries
+            .push(Arc::new(stub_entry(meeting)));
+    }
+
+    Log {
+        days: days.into_values().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Person;
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    static CALENDAR: &str = indoc!(
+        "
+        BEGIN:VCALENDAR
+        VERSION:2.0
+        BEGIN:VEVENT
+        DTSTART:20240105T233000Z
+        SUMMARY:Lunch with John Doe\\, finally
+        END:VEVENT
+        BEGIN:VEVENT
+        DTSTART;VALUE=DATE:20240110
+        SUMMARY:Planning
+        ATTENDEE;CN=\"Someone Else\":mailto:JANE@example.com
+        ATTENDEE;CN=Abu;ROLE=REQ-PARTICIPANT:mailto:abu@exam
+         ple.com
+        END:VEVENT
+        BEGIN:VEVENT
+        SUMMARY:No date
+        END:VEVENT
+        END:VCALENDAR
+        "
+    );
+
+    #[test]
+    fn test_parse_events() {
+        assert_eq!(
+            parse_events(CALENDAR, Some(chrono_tz::Europe::Madrid)),
+            vec![
+                Event {
+                    date: d("2024-01-06"),
+                    summary: "Lunch with John Doe, finally".to_string(),
+                    attendees: vec![],
+                },
+                Event {
+                    date: d("2024-01-10"),
+                    summary: "Planning".to_string(),
+                    attendees: vec![
+                        Attendee {
+                            name: Some("Someone Else".to_string()),
+                            email: Some("jane@example.com".to_string()),
+                        },
+                        Attendee {
+                            name: Some("Abu".to_string()),
+                            email: Some("abu@example.com".to_string()),
+                        },
+                    ],
+                },
+            ]
+        );
+        assert_eq!(parse_events(CALENDAR, None)[0].date, d("2024-01-05"));
+    }
+
+    #[test]
+    fn test_unlogged_meetings() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2024-01-10
+
+            - #Abu : planning
+            "
+        ));
+        let config = Config::builder()
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                email: Some("jane@example.com".to_string()),
+                ..Default::default()
+            })
+            .person(Person {
+                name: "JohnDoe".to_string(),
+                ..Default::default()
+            })
+            .build();
+        let spellings = Spellings::new(&log, &config);
+        let events = parse_events(CALENDAR, None);
+
+        let meetings = match_events(&events, &spellings, &config, Some(d("2024-01-01")));
+        assert_eq!(
+            meetings
+                .iter()
+                .map(|meeting| meeting.to_string())
+                .collect::<Vec<String>>(),
+            vec![
+                "2024-01-05 Lunch with John Doe, finally: #JohnDoe",
+                "2024-01-10 Planning: #Abu #JaneDoe",
+            ]
+        );
+        assert_eq!(
+            match_events(&events, &spellings, &config, Some(d("2024-01-06"))).len(),
+            1
+        );
+
+        let meetings = unlogged(meetings, &log);
+        assert_eq!(
+            meetings
+                .iter()
+                .map(|meeting| meeting.to_string())
+                .collect::<Vec<String>>(),
+            vec![
+                "2024-01-05 Lunch with John Doe, finally: #JohnDoe",
+                "2024-01-10 Planning: #JaneDoe",
+            ]
+        );
+
+        let stubs = stubs(&meetings);
+        assert_eq!(
+            stubs.days[1].entries[0].content,
+            "- #JaneDoe :\n  - Planning"
+        );
+    }
+}
@@ -1,5 +1,69 @@
+pub mod aggregate;
+pub mod anonymize;
+pub mod archive;
+pub mod backup;
+pub mod bench;
+pub mod caldav;
+pub mod chat_import;
+pub mod co_mentions;
+pub mod completions;
 pub mod config;
+pub mod contact;
+pub mod contacts;
+pub mod csv_import;
+pub mod dates;
+pub mod dav;
+pub mod dedupe;
+pub mod digest;
+pub mod duplicates;
+pub mod edit;
+pub mod editor;
+pub mod ext;
+pub mod facts;
+pub mod files;
+pub mod followups;
+pub mod formatter;
+pub mod front_matter;
+pub mod git;
+pub mod heatmap;
+pub mod hooks;
+pub mod ics;
+pub mod links;
+pub mod listing;
+pub mod locations;
 pub mod log;
+pub mod log_format;
+pub mod logging;
+pub mod mail;
+pub mod metrics;
+pub mod migrations;
 pub mod model;
+pub mod ndjson;
+pub mod notifications;
+pub mod notifiers;
+pub mod operations;
+pub mod people_index;
+pub mod pick;
+pub mod plan;
+pub mod profile;
+pub mod prompt;
+pub mod rename;
+pub mod render;
+pub mod resolve;
+pub mod review;
+pub mod rollup;
+pub mod roster;
+pub mod secrets;
+pub mod server;
+pub mod show;
+pub mod site;
+pub mod state;
+pub mod suggest_tags;
+pub mod summary;
 pub mod test_utils;
+pub mod tiers;
+pub mod trip;
 pub mod use_cases;
+pub mod vcard;
+pub mod verify_names;
+pub mod workspace;
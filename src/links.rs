@@ -0,0 +1,281 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use chrono::NaiveDate;
+use regex::Regex;
+
+use crate::config::Config;
+use crate::log::Log;
+use crate::model::PersonName;
+
+static URL_PATTERN: &str = r#"https?://[^\s<>\[\]"]+"#;
+/// `[text](target)` or `![alt](target "title")`
+static MARKDOWN_LINK_PATTERN: &str = r#"!?\[[^\[\]]*\]\(([^()\s]+)(?:\s+"[^"]*")?\)"#;
+
+// compiled once, as links are looked for in every entry
+static URL_REGEX: OnceLock<Regex> = OnceLock::new();
+static MARKDOWN_LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Drop the trailing punctuation that ends a sentence or wraps the URL, e.g.
+/// the `)` of a markdown link, but keep balanced parentheses
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'']);
+        let trimmed = if trimmed.ends_with(')')
+            && trimmed.matches(')').count() > trimmed.matches('(').count()
+        {
+            &trimmed[..trimmed.len() - 1]
+        } else {
+            trimmed
+        };
+
+        if trimmed == url {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+/// Byte ranges of the URLs in the text
+pub fn find_link_spans(text: &str) -> Vec<Range<usize>> {
+    let re = URL_REGEX.get_or_init(|| Regex::new(URL_PATTERN).unwrap());
+
+    re.find_iter(text)
+        .map(|found| {
+            let url = trim_url(found.as_str());
+            found.start()..found.start() + url.len()
+        })
+        .collect()
+}
+
+/// A link target without a scheme, e.g. `photos/beach.jpg`, is a file
+/// relative to the people directory
+pub fn is_attachment(link: &str) -> bool {
+    !link.contains("://") && !link.starts_with("mailto:") && !link.starts_with('#')
+}
+
+/// Byte ranges of the files linked with markdown links or images
+fn find_attachment_spans(text: &str) -> Vec<Range<usize>> {
+    let re = MARKDOWN_LINK_REGEX.get_or_init(|| Regex::new(MARKDOWN_LINK_PATTERN).unwrap());
+
+    re.captures_iter(text)
+        .filter_map(|cap| cap.get(1))
+        .filter(|target| is_attachment(target.as_str()))
+        .map(|target| target.range())
+        .collect()
+}
+
+/// URLs and attached files, in order of appearance
+pub fn find_links(text: &str) -> Vec<String> {
+    let mut spans = find_link_spans(text);
+    spans.extend(find_attachment_spans(text));
+    spans.sort_by_key(|span| span.start);
+
+    spans
+        .into_iter()
+        .map(|span| text[span].to_string())
+        .collect()
+}
+
+/// Whether the attachment is a path under `people_dir`, i.e. neither absolute
+/// nor going up with `..`
+fn is_under_people_dir(link: &str) -> bool {
+    Path::new(link)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Attached files that don't exist under `people_dir`, with the day they were
+/// mentioned. Attachments outside of `people_dir` are never looked for, and
+/// count as missing.
+pub fn missing_attachments(log: &Log, people_dir: &Path) -> Vec<(NaiveDate, PathBuf)> {
+    let mut missing: Vec<(NaiveDate, PathBuf)> = vec![];
+
+    for day in log.days.iter() {
+        for entry in day.entries.iter() {
+            for link in entry.links.iter().filter(|link| is_attachment(link)) {
+                let path = people_dir.join(link);
+                let exists = is_under_people_dir(link) && path.exists();
+                if !exists && !missing.iter().any(|(_, known)| known == &path) {
+                    missing.push((day.date, path));
+                }
+            }
+        }
+    }
+
+    missing
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SharedLink {
+    pub date: NaiveDate,
+    pub url: String,
+}
+
+/// Links per person, most recent first. Links mentioned more than once on the
+/// same day are listed once.
+pub fn collect_links(
+    log: &Log,
+    config: &Config,
+    person: Option<&PersonName>,
+) -> BTreeMap<PersonName, Vec<SharedLink>> {
+    let mut links: BTreeMap<PersonName, Vec<SharedLink>> = BTreeMap::new();
+
+    for day in log.days.iter() {
+        for entry in day.entries.iter() {
+            for name in entry.main.iter() {
+                if config.is_ignored(name) || person.is_some_and(|person| person != name) {
+                    continue;
+                }
+
+                let person_links = links.entry(name.clone()).or_default();
+                for url in entry.links.iter() {
+                    let link = SharedLink {
+                        date: day.date,
+                        url: url.clone(),
+                    };
+                    if !person_links.contains(&link) {
+                        person_links.push(link);
+                    }
+                }
+            }
+        }
+    }
+
+    links.retain(|_, person_links| !person_links.is_empty());
+    for person_links in links.values_mut() {
+        person_links.sort_by_key(|link| Reverse(link.date));
+    }
+
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_find_links() {
+        let text = indoc!(
+            "
+            - #Abu :
+              - sent me https://example.com/article?id=1&x=2.
+              - see [this](https://en.wikipedia.org/wiki/Foo_(bar)) and http://a.io/b,
+            "
+        );
+
+        let expected = vec![
+            "https://example.com/article?id=1&x=2".to_string(),
+            "https://en.wikipedia.org/wiki/Foo_(bar)".to_string(),
+            "http://a.io/b".to_string(),
+        ];
+
+        assert_eq!(find_links(text), expected);
+    }
+
+    #[test]
+    fn test_find_attachments() {
+        let text = indoc!(
+            "
+            - #Abu :
+              - ![beach](photos/beach.jpg \"Sunset\") and [the plan](docs/plan.pdf)
+              - [site](https://a.io), [mail](mailto:abu@a.io) and [[Jane Doe]]
+            "
+        );
+
+        let expected = vec![
+            "photos/beach.jpg".to_string(),
+            "docs/plan.pdf".to_string(),
+            "https://a.io".to_string(),
+        ];
+
+        assert_eq!(find_links(text), expected);
+    }
+
+    #[test]
+    fn test_missing_attachments() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #Abu :
+              - ![beach](photos/beach.jpg) at https://example.com
+            - #JaneDoe :
+              - ![beach](photos/beach.jpg) again
+            - #Zoe :
+              - [hosts](/etc/hosts) and [up](../../etc/hosts)
+            "
+        ));
+
+        assert_eq!(
+            missing_attachments(&log, Path::new("/nonexistent")),
+            vec![
+                (
+                    d("2000-01-01"),
+                    PathBuf::from("/nonexistent/photos/beach.jpg")
+                ),
+                (d("2000-01-01"), PathBuf::from("/etc/hosts")),
+                (
+                    d("2000-01-01"),
+                    PathBuf::from("/nonexistent/../../etc/hosts")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_links() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #Abu :
+              - video: https://example.com/video
+
+            # 2000-01-02
+
+            - #Abu, #JohnDoe :
+              - article: https://example.com/article
+            - #JaneDoe :
+              - nothing shared
+            "
+        ));
+        let config = Config::builder().people_dir("/tmp/people").build();
+
+        let abu = "Abu".to_string();
+        let links = collect_links(&log, &config, Some(&abu));
+
+        let expected: BTreeMap<PersonName, Vec<SharedLink>> = [(
+            abu.clone(),
+            vec![
+                SharedLink {
+                    date: d("2000-01-02"),
+                    url: "https://example.com/article".to_string(),
+                },
+                SharedLink {
+                    date: d("2000-01-01"),
+                    url: "https://example.com/video".to_string(),
+                },
+            ],
+        )]
+        .into();
+
+        assert_eq!(links, expected);
+        assert_eq!(
+            collect_links(&log, &config, None)
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["Abu", "JohnDoe"]
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use clap::Args;
+use tabular::{Row, Table};
+
+/// Paging and plain output flags of the commands that list many rows
+#[derive(Args, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ListingArgs {
+    /// Show at most this many rows
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+    /// Skip this many rows first
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub offset: usize,
+    /// Print tab-separated values without header or alignment, e.g. for `cut`,
+    /// `awk` or `fzf`
+    #[arg(long)]
+    pub plain: bool,
+}
+
+impl ListingArgs {
+    /// The items after `offset`, at most `limit` of them
+    pub fn page<T>(&self, items: Vec<T>) -> Vec<T> {
+        items
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+/// The cells separated by tabs, with the tabs and line breaks in them replaced
+/// by spaces so each row stays a single line
+pub fn plain_row(cells: &[String]) -> String {
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|cell| cell.replace(['\t', '\n', '\r'], " "))
+        .collect();
+    cells.join("\t")
+}
+
+/// A table printed with a header and aligned columns, or as plain rows
+pub struct Listing {
+    spec: String,
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Listing {
+    /// `spec` as in `tabular::Table::new`, e.g. `{:<}  {:>}`
+    pub fn new(spec: &str, header: impl IntoIterator<Item = impl ToString>) -> Listing {
+        Listing {
+            spec: spec.to_string(),
+            header: header.into_iter().map(|title| title.to_string()).collect(),
+            rows: vec![],
+        }
+    }
+
+    pub fn add_row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    pub fn render(&self, plain: bool) -> String {
+        if plain {
+            return self
+                .rows
+                .iter()
+                .map(|cells| format!("{}\n", plain_row(cells)))
+                .collect();
+        }
+
+        let mut table = Table::new(&self.spec);
+        for cells in std::iter::once(&self.header).chain(&self.rows) {
+            let mut row = Row::new();
+            for cell in cells {
+                row.add_cell(cell);
+            }
+            table.add_row(row);
+        }
+        format!("{table}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_page() {
+        let args = ListingArgs {
+            limit: Some(2),
+            offset: 1,
+            plain: false,
+        };
+
+        assert_eq!(args.page(vec![1, 2, 3, 4]), vec![2, 3]);
+        assert_eq!(ListingArgs::default().page(vec![1, 2]), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let mut listing = Listing::new("{:<}  {:<}", ["PERSON", "NOTE"]);
+        listing.add_row(vec!["JaneDoe".into(), "pen\tand\npaper".into()]);
+        listing.add_row(vec!["Abu".into(), "".into()]);
+
+        assert_eq!(listing.render(true), "JaneDoe\tpen and paper\nAbu\t\n");
+        assert_eq!(listing.render(false).lines().next(), Some("PERSON   NOTE"));
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use regex::Regex;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::config::Config;
+use crate::log::Log;
+use crate::model::{Location, PersonName};
+
+static MOVED_TO_PATTERN: &str = r"moved-to:\s*(.+)$";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocationChange {
+    pub date: NaiveDate,
+    pub location: Location,
+}
+
+/// Where someone is, according to the logs or else the config
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CurrentLocation {
+    /// Last `moved-to:` in the logs
+    Logged(LocationChange),
+    Configured(Location),
+}
+
+impl CurrentLocation {
+    pub fn location(&self) -> &Location {
+        match self {
+            CurrentLocation::Logged(change) => &change.location,
+            CurrentLocation::Configured(location) => location,
+        }
+    }
+}
+
+/// Location changes per person, oldest first, from lines like
+/// `- #JaneDoe moved-to: Berlin`. A change applies to the main people of the
+/// entry it is in.
+pub fn location_timelines(log: &Log, config: &Config) -> BTreeMap<PersonName, Vec<LocationChange>> {
+    let re = Regex::new(MOVED_TO_PATTERN).unwrap();
+    let mut timelines: BTreeMap<PersonName, Vec<LocationChange>> = BTreeMap::new();
+
+    for day in log.days.iter() {
+        for entry in day.entries.iter() {
+            for line in entry.content.lines() {
+                let location = match re.captures(line) {
+                    Some(captures) => captures[1].trim().to_string(),
+                    None => continue,
+                };
+
+                for person in entry
+                    .main
+                    .iter()
+                    .filter(|person| !config.is_ignored(person))
+                {
+                    timelines
+                        .entry(person.clone())
+                        .or_default()
+                        .push(LocationChange {
+                            date: day.date,
+                            location: location.clone(),
+                        });
+                }
+            }
+        }
+    }
+
+    for timeline in timelines.values_mut() {
+        // keep the order within a day, the last line wins
+        timeline.sort_by_key(|change| change.date);
+    }
+
+    timelines
+}
+
+pub fn current_location(
+    person: &PersonName,
+    config: &Config,
+    timelines: &BTreeMap<PersonName, Vec<LocationChange>>,
+) -> Option<CurrentLocation> {
+    if let Some(change) = timelines.get(person).and_then(|timeline| timeline.last()) {
+        return Some(CurrentLocation::Logged(change.clone()));
+    }
+
+    match config.get_person(person) {
+        Some(metadata) if !metadata.location.is_empty() => {
+            Some(CurrentLocation::Configured(metadata.location.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Lowercase, without accents or punctuation, so that `Zürich` reads as `zurich`
+pub fn normalize_location(location: &str) -> String {
+    let simplified: String = location
+        .nfkd()
+        .filter(|ch| ch.is_alphanumeric() || ch.is_whitespace() || *ch == ',')
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    simplified
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Typos allowed before a place stops matching, e.g. 1 for `Berln`
+fn max_distance(query: &str) -> usize {
+    (query.chars().count() / 4).max(1)
+}
+
+/// Whether `location` is or contains the place in `query`, e.g. `Berlin,
+/// Germany` matches `berlin` and `berln`
+pub fn location_matches(location: &str, query: &str) -> bool {
+    let location = normalize_location(location);
+    let query = normalize_location(query);
+    if query.is_empty() {
+        return false;
+    }
+
+    if location.contains(&query) {
+        return true;
+    }
+
+    location
+        .split(',')
+        .map(str::trim)
+        .any(|part| strsim::levenshtein(part, &query) <= max_distance(&query))
+}
+
+/// People whose current location matches `location` (see `location_matches`),
+/// sorted by name
+pub fn people_near(
+    location: &str,
+    config: &Config,
+    timelines: &BTreeMap<PersonName, Vec<LocationChange>>,
+) -> Vec<(PersonName, CurrentLocation)> {
+    let mut people: Vec<&PersonName> = timelines.keys().collect();
+    people.extend(config.people.iter().map(|person| &person.name));
+    people.sort();
+    people.dedup();
+
+    people
+        .into_iter()
+        .filter(|person| !config.is_ignored(person))
+        .filter_map(|person| {
+            let current = current_location(person, config, timelines)?;
+            match location_matches(current.location(), location) {
+                true => Some((person.clone(), current)),
+                false => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Person;
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    fn config() -> Config {
+        Config::builder()
+            .people_dir("/tmp/people")
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                location: "London".to_string(),
+                ..Default::default()
+            })
+            .person(Person {
+                name: "JohnDoe".to_string(),
+                location: "Berlin".to_string(),
+                ..Default::default()
+            })
+            .build()
+    }
+
+    #[test]
+    fn test_location_timelines() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-03-01
+
+            - #JaneDoe moved-to: Berlin
+
+            # 2000-01-01
+
+            - #JaneDoe :
+              - moved-to: Paris
+            - #Abu :
+              - coffee
+            "
+        ));
+        let config = config();
+        let timelines = location_timelines(&log, &config);
+
+        let expected = vec![
+            LocationChange {
+                date: d("2000-01-01"),
+                location: "Paris".to_string(),
+            },
+            LocationChange {
+                date: d("2000-03-01"),
+                location: "Berlin".to_string(),
+            },
+        ];
+        assert_eq!(timelines.get("JaneDoe"), Some(&expected));
+        assert_eq!(timelines.get("Abu"), None);
+
+        assert_eq!(
+            current_location(&"JohnDoe".to_string(), &config, &timelines),
+            Some(CurrentLocation::Configured("Berlin".to_string()))
+        );
+
+        let near: Vec<PersonName> = people_near("berlin", &config, &timelines)
+            .into_iter()
+            .map(|(person, _)| person)
+            .collect();
+        assert_eq!(near, vec!["JaneDoe".to_string(), "JohnDoe".to_string()]);
+    }
+
+    #[test]
+    fn test_location_matches() {
+        assert!(location_matches("Zürich", "zurich"));
+        assert!(location_matches("Berlin, Germany", "berlin"));
+        assert!(location_matches("Berlin, Germany", "Berln"));
+        assert!(location_matches("San Sebastián", "san  sebastian"));
+        assert!(!location_matches("Bern", "berlin"));
+        assert!(!location_matches("London", ""));
+    }
+}
@@ -1,22 +1,125 @@
 use core::fmt;
 use glob::glob;
 use regex::Regex;
-use std::{collections::HashSet, fs, path::PathBuf};
-use textwrap::dedent;
-
-use crate::model::PersonName;
-use chrono::NaiveDate;
+use serde::{Deserialize, Serialize, Serializer};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs,
+    io::{self, Read},
+    iter::Enumerate,
+    ops::Range,
+    path::{Path, PathBuf},
+    str::Split,
+    sync::{Arc, OnceLock},
+};
+use tracing::info;
+
+use crate::config::{
+    self, Config, IgnorePattern, LogMode, LogSyntax, MentionLevel, ReferenceSyntax, WikilinkNames,
+};
+use crate::duplicates;
+use crate::files;
+use crate::front_matter::strip_front_matter;
+use crate::links;
+use crate::log_format;
+use crate::model::{InteractionKind, PersonName};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// `#JaneDoe`
+pub(crate) static HASHTAG_PATTERN: &str = r"\#([\p{L}\p{M}]+)";
+/// `[[Jane Doe]]` or `[[Jane Doe|Jane]]`
+pub(crate) static WIKILINK_PATTERN: &str = r"\[\[([^\[\]|#]+)(?:\|[^\[\]]*)?\]\]";
+/// `gift-idea: fountain pen`, optionally as a list item
+static FIELD_PATTERN: &str = r"^(?:[-*+]\s+)?([a-z][a-z0-9_-]*):\s+(\S.*)$";
+/// `(call)` or `:call:`
+static KIND_PATTERN: &str = r"\((\w+)\)|:(\w+):";
+
+// Compiling a pattern takes far longer than matching a line, so each is
+// compiled once, on first use
+static HASHTAG_REGEX: OnceLock<Regex> = OnceLock::new();
+static WIKILINK_REGEX: OnceLock<Regex> = OnceLock::new();
+static KIND_REGEX: OnceLock<Regex> = OnceLock::new();
+static FIELD_REGEX: OnceLock<Regex> = OnceLock::new();
 
 static TAB: &str = "	";
+/// Left by some editors at the start of a file
+static BYTE_ORDER_MARK: char = '\u{FEFF}';
 static TWO_SPACES: &str = "  ";
 
 type EntryContent = String;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Entry {
+    #[serde(serialize_with = "serialize_sorted")]
     pub main: HashSet<PersonName>,
+    #[serde(serialize_with = "serialize_sorted")]
     pub related: HashSet<PersonName>,
+    /// The lines of the entry, dedented. Unlike the lines being parsed, it
+    /// outlives the file content, so it is owned.
     pub content: EntryContent,
+    /// URLs mentioned anywhere in the entry, in order of appearance
+    pub links: Vec<String>,
+    /// How the interaction happened, e.g. `- #JaneDoe (call):`
+    pub kind: Option<InteractionKind>,
+    /// `key: value` lines after the first one, e.g. `- gift-idea: fountain pen`.
+    /// The last value wins if a key is repeated.
+    pub fields: BTreeMap<String, String>,
+    /// File the entry was read from, if any
+    pub source_file: Option<PathBuf>,
+    /// Lines of the entry in its file, starting at 1 and end excluded. Empty
+    /// if the entry was not parsed from a log.
+    pub line_range: Range<usize>,
+}
+
+/// Names in alphabetical order, so that serialized entries are stable
+fn serialize_sorted<S: Serializer>(
+    names: &HashSet<PersonName>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    names
+        .iter()
+        .collect::<BTreeSet<&PersonName>>()
+        .serialize(serializer)
+}
+
+/// Marks an entry that mentions people without being in touch with them, e.g.
+/// `- #JaneDoe : saw a photo of her wedding @no-contact`
+pub static NO_CONTACT_MARKER: &str = "@no-contact";
+
+impl Entry {
+    /// The main people, unless the first line has the `NO_CONTACT_MARKER`:
+    /// the people the entry counts as an interaction with
+    pub fn interacted(&self) -> impl Iterator<Item = &PersonName> {
+        self.interacted_at(MentionLevel::Main)
+    }
+
+    /// Like `interacted`, but with the people mentioned at `level`
+    pub fn interacted_at(&self, level: MentionLevel) -> impl Iterator<Item = &PersonName> {
+        let first_line = self.content.lines().next().unwrap_or("");
+        let no_contact = first_line
+            .split_whitespace()
+            .any(|word| word == NO_CONTACT_MARKER);
+        self.related.iter().filter(move |person| {
+            !no_contact
+                && match level {
+                    MentionLevel::Main => self.main.contains(*person),
+                    MentionLevel::Related => !self.main.contains(*person),
+                    MentionLevel::Both => true,
+                }
+        })
+    }
+
+    /// Whether `person` is only mentioned after the first line
+    pub fn mentions_in_passing(&self, person: &PersonName) -> bool {
+        !self.main.contains(person) && self.related.contains(person)
+    }
+
+    /// `path:line` of the first line of the entry, e.g. to open it in an editor
+    pub fn location(&self) -> Option<String> {
+        let path = self.source_file.as_ref()?;
+        Some(format!("{}:{}", path.display(), self.line_range.start))
+    }
 }
 
 impl fmt::Display for Entry {
@@ -25,15 +128,101 @@ impl fmt::Display for Entry {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// How much of the date a day header gives, e.g. `# 2023-03` for "sometime in
+/// March 2023"
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DatePrecision {
+    /// `# 2023-03-21`
+    #[default]
+    Day,
+    /// `# 2023-W12`, dated on its Monday
+    Week,
+    /// `# 2023-03`, dated on its first day
+    Month,
+}
+
+impl DatePrecision {
+    /// The header of the period that starts on `date`
+    pub fn header(&self, date: NaiveDate) -> String {
+        match self {
+            DatePrecision::Day => date.format("%Y-%m-%d").to_string(),
+            DatePrecision::Week => date.format("%G-W%V").to_string(),
+            DatePrecision::Month => date.format("%Y-%m").to_string(),
+        }
+    }
+
+    /// Last day of the period that starts on `date`
+    pub fn end(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            DatePrecision::Day => date,
+            DatePrecision::Week => date + Duration::days(6),
+            DatePrecision::Month => {
+                let (year, month) = match date.month() {
+                    12 => (date.year() + 1, 1),
+                    month => (date.year(), month + 1),
+                };
+                NaiveDate::from_ymd_opt(year, month, 1).expect("first of month is a valid date")
+                    - Duration::days(1)
+            }
+        }
+    }
+}
+
+/// `2023-03-21`, `2023-W12` or `2023-03`
+pub fn parse_header_date(header: &str) -> Option<(NaiveDate, DatePrecision)> {
+    if let Ok(date) = NaiveDate::parse_from_str(header, "%Y-%m-%d") {
+        return Some((date, DatePrecision::Day));
+    }
+
+    let (year, rest) = header.split_once('-')?;
+    if year.len() != 4 || !year.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let year: i32 = year.parse().ok()?;
+
+    if let Some(week) = rest.strip_prefix('W') {
+        if week.len() != 2 || !week.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let date = NaiveDate::from_isoywd_opt(year, week.parse().ok()?, Weekday::Mon)?;
+        return Some((date, DatePrecision::Week));
+    }
+
+    if rest.len() != 2 || !rest.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let date = NaiveDate::from_ymd_opt(year, rest.parse().ok()?, 1)?;
+    Some((date, DatePrecision::Month))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Day {
+    /// First day of the period the header refers to
     pub date: NaiveDate,
-    pub entries: Vec<Entry>,
+    pub precision: DatePrecision,
+    /// Shared, so that a day can be cloned, e.g. into the logs of everyone in
+    /// it, without copying its entries
+    pub entries: Vec<Arc<Entry>>,
+    /// File the day was read from, if any
+    pub source_file: Option<PathBuf>,
+    /// Lines from the header to the last entry, like `Entry::line_range`
+    pub line_range: Range<usize>,
+}
+
+impl Day {
+    /// Last day of the period the header refers to, the same as `date` unless
+    /// the header is a week or a month
+    pub fn last_date(&self) -> NaiveDate {
+        self.precision.end(self.date)
+    }
 }
 
 impl fmt::Display for Day {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let date = self.date;
+        let date = self.precision.header(self.date);
         let entries: Vec<String> = self.entries.iter().map(|entry| entry.to_string()).collect();
         let fmt_entries = entries.join("\n");
         let content = format!("# {date}\n\n{fmt_entries}");
@@ -41,7 +230,7 @@ impl fmt::Display for Day {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Log {
     pub days: Vec<Day>,
 }
@@ -50,33 +239,138 @@ impl fmt::Display for Log {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let days: Vec<String> = self.days.iter().map(|day| day.to_string()).collect();
         let content = days.join("\n\n");
-        write!(f, "{content}\n")
+        writeln!(f, "{content}")
+    }
+}
+
+impl Log {
+    /// Only the entries of the given kind, e.g. `met`
+    pub fn with_kind(self, kind: &str) -> Log {
+        let days = self
+            .days
+            .into_iter()
+            .filter_map(|day| {
+                let entries: Vec<Arc<Entry>> = day
+                    .entries
+                    .into_iter()
+                    .filter(|entry| entry.kind.as_deref() == Some(kind))
+                    .collect();
+                match entries.is_empty() {
+                    true => None,
+                    false => Some(Day { entries, ..day }),
+                }
+            })
+            .collect();
+
+        Log { days }
     }
 }
 
+/// Settings that control how people are recognised while parsing logs
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    pub references: ReferenceSyntax,
+    /// Maps every known name and alias to the canonical person name
+    pub aliases: HashMap<String, PersonName>,
+    pub kinds: Vec<InteractionKind>,
+    /// Hashtags and links that don't refer to people
+    pub not_people: Vec<IgnorePattern>,
+    /// Drop entries with the same date and content as an earlier one
+    pub dedupe_entries: bool,
+    pub mode: LogMode,
+    /// Where entries of a journal must mention someone to be read
+    pub journal_mentions: MentionLevel,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            references: ReferenceSyntax::default(),
+            aliases: HashMap::new(),
+            kinds: config::default_kinds(),
+            not_people: vec![],
+            dedupe_entries: false,
+            mode: LogMode::default(),
+            journal_mentions: MentionLevel::default(),
+        }
+    }
+}
+
+impl ParseOptions {
+    pub fn from_config(config: &Config) -> ParseOptions {
+        let mut aliases: HashMap<String, PersonName> = HashMap::new();
+        for person in config.all_people() {
+            aliases.insert(person.name.clone(), person.name.clone());
+            for alias in person.aliases.iter() {
+                aliases.insert(alias.clone(), person.name.clone());
+            }
+        }
+
+        ParseOptions {
+            references: config.references.clone(),
+            aliases,
+            kinds: config.kinds.clone(),
+            not_people: config.not_people.clone(),
+            dedupe_entries: config.dedupe_entries,
+            mode: config.log_mode,
+            journal_mentions: config.journal_mentions,
+        }
+    }
+
+    /// Whether an entry is read, which in a journal depends on who it mentions
+    fn keeps(&self, entry: &Entry) -> bool {
+        match (self.mode, self.journal_mentions) {
+            (LogMode::People, _) => true,
+            (LogMode::Journal, MentionLevel::Main) => !entry.main.is_empty(),
+            (LogMode::Journal, MentionLevel::Related) => entry.related.len() > entry.main.len(),
+            (LogMode::Journal, MentionLevel::Both) => !entry.related.is_empty(),
+        }
+    }
+
+    fn canonical_name(&self, name: &str) -> PersonName {
+        match self.aliases.get(name) {
+            Some(canonical) => canonical.clone(),
+            None => name.to_string(),
+        }
+    }
+
+    pub(crate) fn wikilink_to_name(&self, target: &str) -> PersonName {
+        let target = target.trim();
+        if let Some(canonical) = self.aliases.get(target) {
+            return canonical.clone();
+        }
+
+        match self.references.wikilink_names {
+            WikilinkNames::Join => target.split_whitespace().collect::<Vec<&str>>().join(""),
+            WikilinkNames::Verbatim => target.to_string(),
+        }
+    }
+}
+
+/// A line of the log, borrowed from the input unless its tabs had to be replaced
 #[derive(Debug, Clone)]
-struct Token {
+struct Token<'a> {
     line_number: usize,
     indentation: usize, // amount of spaces
-    content: String,
+    content: Cow<'a, str>,
 }
 
-impl Into<Line> for Token {
-    fn into(self) -> Line {
-        if token_is_empty_line(&self) {
+impl<'a> From<Token<'a>> for Line<'a> {
+    fn from(token: Token<'a>) -> Line<'a> {
+        if token_is_empty_line(&token) {
             return Line::Empty;
         }
 
-        if let Ok(date) = self.try_into_date() {
+        if let Ok(date) = token.try_into_date() {
             return Line::Date(date);
         }
 
-        return Line::Record(self);
+        Line::Record(token)
     }
 }
 
-impl Token {
-    fn try_into_date(self: &Token) -> Result<Date, ()> {
+impl Token<'_> {
+    fn try_into_date(&self) -> Result<Date, ()> {
         if self.indentation != 0 {
             return Err(());
         }
@@ -87,41 +381,34 @@ impl Token {
 
         let date_str = &self.content[2..].trim_end();
 
-        let date = match NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-            Ok(date) => date,
-            Err(_) => return Err(()),
+        let (date, precision) = match parse_header_date(date_str) {
+            Some(parsed) => parsed,
+            None => return Err(()),
         };
 
         Ok(Date {
             line_number: self.line_number,
             value: date,
+            precision,
         })
     }
 }
 
 #[derive(Debug, Clone)]
 struct Date {
-    #[allow(dead_code)]
     line_number: usize,
     value: NaiveDate,
-}
-
-impl From<Line> for Date {
-    fn from(line: Line) -> Date {
-        match line {
-            Line::Date(date) => return date,
-            _ => panic!("you should have never reached this point"),
-        }
-    }
+    precision: DatePrecision,
 }
 
 #[derive(Debug, Clone)]
-enum Line {
+enum Line<'a> {
     Empty,
     Date(Date),
-    Record(Token),
+    Record(Token<'a>),
 }
 
+/// Byte offset of the first non-whitespace character, to slice the line with
 fn find_first_non_space(input: &str) -> usize {
     input
         .char_indices()
@@ -130,166 +417,406 @@ fn find_first_non_space(input: &str) -> usize {
         .unwrap_or(0)
 }
 
-fn tokenize_line(line: String, line_number: usize) -> Token {
+/// Indentation is counted in characters, not bytes, as some whitespace takes
+/// more than one byte, e.g. `\u{3000}`
+fn tokenize_line(line: &str, line_number: usize) -> Token<'_> {
+    // files written on Windows end their lines with `\r\n`
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    if !line.contains(TAB) {
+        let start = find_first_non_space(line);
+        return Token {
+            line_number,
+            indentation: line[..start].chars().count(),
+            content: Cow::Borrowed(&line[start..]),
+        };
+    }
+
     let line_no_tabs = line.replace(TAB, TWO_SPACES);
-    let indentation = find_first_non_space(&line_no_tabs);
-    let content = &line_no_tabs[indentation..];
+    let start = find_first_non_space(&line_no_tabs);
 
-    let token = Token {
+    Token {
         line_number,
-        indentation,
-        content: content.to_string(),
-    };
-
-    token
+        indentation: line_no_tabs[..start].chars().count(),
+        content: Cow::Owned(line_no_tabs[start..].to_string()),
+    }
 }
 
-fn tokenize(content: &str) -> Vec<Token> {
-    let lines = content.split("\n");
-
-    let mut tokens: Vec<Token> = vec![];
-    for (line_number, line) in lines.into_iter().enumerate() {
-        let token = tokenize_line(line.to_string(), line_number);
-        tokens.push(token);
-    }
+/// The date of a day header, e.g. `# 2023-03-21`, recognized like the parser
+/// does
+pub(crate) fn line_header_date(line: &str) -> Option<(NaiveDate, DatePrecision)> {
+    let date = tokenize_line(line, 0).try_into_date().ok()?;
+    Some((date.value, date.precision))
+}
 
-    tokens
+/// How many lines have content, tokenizing every line like the parser does.
+/// Only useful to time tokenization on its own.
+pub fn tokenize(content: &str) -> usize {
+    content
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| tokenize_line(line, index + 1))
+        .filter(|token| !token_is_empty_line(token))
+        .count()
 }
 
+/// Blank or only whitespace, which would otherwise start an entry of its own
 fn token_is_empty_line(token: &Token) -> bool {
-    token.indentation == 0 && token.content.is_empty()
+    token.content.trim().is_empty()
 }
 
-fn parse_people(token: &Token) -> HashSet<PersonName> {
-    let pattern = r"\#([A-Za-zñáéíóúç]+)"; // TODO: make it constant
-    let re = Regex::new(pattern).unwrap();
+/// A markdown heading other than a date, e.g. `## Work`
+fn token_is_heading(token: &Token) -> bool {
+    let after_hashes = token.content.trim_start_matches('#');
+    token.indentation == 0
+        && token.content.starts_with('#')
+        && (after_hashes.is_empty() || after_hashes.starts_with(' '))
+}
 
-    let people_in_token: HashSet<PersonName> = re
-        .captures_iter(&token.content)
-        .map(|cap| cap[1].to_string())
-        .collect();
+/// `HASHTAG_PATTERN`, compiled once
+pub(crate) fn hashtag_regex() -> &'static Regex {
+    HASHTAG_REGEX.get_or_init(|| Regex::new(HASHTAG_PATTERN).unwrap())
+}
 
+fn parse_people(token: &Token, options: &ParseOptions) -> HashSet<PersonName> {
     let mut people: HashSet<PersonName> = HashSet::new();
-    people.extend(people_in_token);
 
+    if options.references.hashtags {
+        let re = hashtag_regex();
+
+        let people_in_token: HashSet<PersonName> = re
+            .captures_iter(&token.content)
+            .map(|cap| options.canonical_name(&cap[1]))
+            .collect();
+
+        people.extend(people_in_token);
+    }
+
+    if options.references.wikilinks {
+        let re = WIKILINK_REGEX.get_or_init(|| Regex::new(WIKILINK_PATTERN).unwrap());
+
+        let people_in_token: HashSet<PersonName> = re
+            .captures_iter(&token.content)
+            .map(|cap| options.wikilink_to_name(&cap[1]))
+            .collect();
+
+        people.extend(people_in_token);
+    }
+
+    people.retain(|person| !options.not_people.iter().any(|topic| topic.matches(person)));
     people
 }
 
-fn parse_entry(tokens: Vec<Token>) -> Entry {
+/// People referenced in a single line, e.g. the first line of an entry
+pub fn parse_line_people(line: &str, options: &ParseOptions) -> HashSet<PersonName> {
+    parse_people(&tokenize_line(line, 0), options)
+}
+
+/// First recognised kind in `(call)` or `:call:` form
+fn parse_kind(token: &Token, options: &ParseOptions) -> Option<InteractionKind> {
+    let re = KIND_REGEX.get_or_init(|| Regex::new(KIND_PATTERN).unwrap());
+
+    re.captures_iter(&token.content)
+        .filter_map(|cap| cap.get(1).or_else(|| cap.get(2)))
+        .map(|kind| kind.as_str().to_lowercase())
+        .find(|kind| options.kinds.contains(kind))
+}
+
+fn parse_fields(tokens: &[Token]) -> BTreeMap<String, String> {
+    let re = FIELD_REGEX.get_or_init(|| Regex::new(FIELD_PATTERN).unwrap());
+
+    tokens
+        .iter()
+        .skip(1)
+        .filter_map(|token| re.captures(token.content.trim_end()))
+        // `done: ...` closes a follow-up instead, see `followups`
+        .filter(|cap| &cap[1] != "done")
+        .map(|cap| (cap[1].to_string(), cap[2].to_string()))
+        .collect()
+}
+
+fn parse_entry(tokens: Vec<Token>, options: &ParseOptions) -> Entry {
     let first_token = &tokens[0];
-    let main: HashSet<PersonName> = parse_people(first_token);
+    let main: HashSet<PersonName> = parse_people(first_token, options);
+    let kind = parse_kind(first_token, options);
+    let fields = parse_fields(&tokens);
 
     let mut related: HashSet<PersonName> = HashSet::new();
-    let mut content_lines: Vec<String> = vec![];
+    let mut content = String::new();
 
-    for token in tokens {
-        let people_in_token = parse_people(&token);
+    for (index, token) in tokens.iter().enumerate() {
+        let people_in_token = parse_people(token, options);
         related.extend(people_in_token);
 
-        let indendation = " ".repeat(token.indentation);
-        let content_line = vec![indendation, token.content].join("");
-        content_lines.push(content_line);
+        // relative to the first line, which is indented if the whole day is.
+        // The other lines stay indented, or they would be entries of their own.
+        let indentation = match index {
+            0 => 0,
+            _ => token
+                .indentation
+                .saturating_sub(first_token.indentation)
+                .max(1),
+        };
+        if index > 0 {
+            content.push('\n');
+        }
+        content.push_str(&" ".repeat(indentation));
+        content.push_str(&token.content);
     }
 
+    let last_token = tokens.last().expect("entries have at least one line");
+
     Entry {
         main,
         related,
-        content: dedent(&content_lines.join("\n")),
+        links: links::find_links(&content),
+        content,
+        kind,
+        fields,
+        source_file: None,
+        line_range: first_token.line_number..last_token.line_number + 1,
     }
 }
 
-fn parse_day(date: Date, lines: Vec<Token>) -> Day {
+fn parse_day(date: Date, lines: Vec<Token>, options: &ParseOptions) -> Day {
     let mut entries: Vec<Entry> = vec![];
 
     let mut buffer: Vec<Token> = vec![];
 
-    for token in lines.iter() {
+    for token in lines {
         let is_top_level = token.indentation == 0;
         if is_top_level && !buffer.is_empty() {
-            entries.push(parse_entry(buffer));
+            entries.push(parse_entry(buffer, options));
             buffer = vec![];
         }
-        buffer.push(token.clone());
+        buffer.push(token);
     }
 
     if !buffer.is_empty() {
-        entries.push(parse_entry(buffer));
+        entries.push(parse_entry(buffer, options));
     }
+    entries.retain(|entry| options.keeps(entry));
+
+    let end = match entries.last() {
+        Some(entry) => entry.line_range.end,
+        None => date.line_number + 1,
+    };
 
     Day {
         date: date.value,
-        entries,
+        precision: date.precision,
+        entries: entries.into_iter().map(Arc::new).collect(),
+        source_file: None,
+        line_range: date.line_number..end,
     }
 }
 
-pub fn parse_log_file_content(content: &str) -> Log {
-    let tokens = tokenize(content);
+/// Days parsed one at a time from the content of a log file, see
+/// `parse_log_file_iter`
+pub struct DayIter<'a> {
+    lines: Enumerate<Split<'a, char>>,
+    /// Line number of the first line after the front matter
+    first_line: usize,
+    options: &'a ParseOptions,
+    date: Option<Date>,
+}
 
-    let mut buffered_date: Option<Date> = None;
-    let mut buffered_lines: Vec<Token> = vec![];
-    let mut days: Vec<Day> = vec![];
-    for token in tokens {
-        match Into::<Line>::into(token) {
-            Line::Empty => {} // skip
-            Line::Date(date) => {
-                if !buffered_lines.is_empty() {
-                    let day = parse_day(
-                        buffered_date.expect("expected some date when lines are buffered"),
-                        buffered_lines,
-                    );
-                    days.push(day);
-
-                    buffered_lines = vec![];
-                }
-                buffered_date = Some(date);
-            }
-            Line::Record(token) => {
-                buffered_lines.push(token);
+impl Iterator for DayIter<'_> {
+    type Item = Day;
+
+    /// Days of a journal without entries about people are skipped
+    fn next(&mut self) -> Option<Day> {
+        loop {
+            let day = self.next_day()?;
+            if self.options.mode == LogMode::People || !day.entries.is_empty() {
+                return Some(day);
             }
         }
     }
+}
+
+impl DayIter<'_> {
+    fn next_day(&mut self) -> Option<Day> {
+        let mut buffered_lines: Vec<Token> = vec![];
+        let is_journal = self.options.mode == LogMode::Journal;
+
+        for (index, line) in self.lines.by_ref() {
+            match Line::from(tokenize_line(line, self.first_line + index)) {
+                Line::Empty => {} // skip
+                Line::Record(token) if self.date.is_none() => {
+                    // not in any day, e.g. a title or notes at the top of a journal
+                    if !is_journal {
+                        info!(
+                            "skipping line {} as it comes before the first date",
+                            token.line_number
+                        );
+                    }
+                }
+                Line::Record(token) if is_journal && token_is_heading(&token) => {} // skip
+                Line::Date(date) => {
+                    let buffered_date = self.date.replace(date);
+                    if !buffered_lines.is_empty() {
+                        let date =
+                            buffered_date.expect("expected some date when lines are buffered");
+                        return Some(parse_day(date, buffered_lines, self.options));
+                    }
+                }
+                Line::Record(token) => buffered_lines.push(token),
+            }
+        }
 
-    if !buffered_lines.is_empty() {
-        if buffered_date.is_none() {
-            panic!("expected some date when lines are buffered")
+        if buffered_lines.is_empty() {
+            return None;
         }
-        let day = parse_day(buffered_date.unwrap(), buffered_lines);
-        days.push(day);
+
+        let date = self
+            .date
+            .take()
+            .expect("expected some date when lines are buffered");
+        Some(parse_day(date, buffered_lines, self.options))
     }
+}
 
-    Log { days }
+/// Parse the days lazily, so that only one day's lines are held at a time.
+/// Lines are borrowed from `content` until they become an `Entry`.
+pub fn parse_log_file_iter<'a>(content: &'a str, options: &'a ParseOptions) -> DayIter<'a> {
+    let content = content.strip_prefix(BYTE_ORDER_MARK).unwrap_or(content);
+    let body = strip_front_matter(content);
+    let front_matter = &content[..content.len() - body.len()];
+
+    DayIter {
+        lines: body.split('\n').enumerate(),
+        first_line: front_matter.matches('\n').count() + 1,
+        options,
+        date: None,
+    }
 }
 
-fn find_log_files(people_dir: &PathBuf) -> Vec<PathBuf> {
-    let base = people_dir.to_string_lossy();
-    let pattern = format!("{base}/log/*people.md");
+pub fn parse_log_file_content(content: &str) -> Log {
+    parse_log_file_content_with_options(content, &ParseOptions::default())
+}
 
-    let mut files: Vec<PathBuf> = vec![];
-    for entry in glob(&pattern).expect("Failed to read glob pattern") {
-        match entry {
-            Ok(path) => files.push(path),
-            _ => (),
-        }
+pub fn parse_log_file_content_with_options(content: &str, options: &ParseOptions) -> Log {
+    Log {
+        days: parse_log_file_iter(content, options).collect(),
     }
+}
 
-    files.sort();
+/// Every file matching the `log_globs` of the config, sorted by path
+pub fn find_log_files(config: &Config) -> Vec<PathBuf> {
+    find_log_files_with_syntax(config)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect()
+}
 
-    return files;
+/// Like `find_log_files`, with the syntax of the first glob each file matches
+pub fn find_log_files_with_syntax(config: &Config) -> Vec<(PathBuf, LogSyntax)> {
+    let mut files: BTreeMap<PathBuf, LogSyntax> = BTreeMap::new();
+    for log_glob in config.log_globs.iter() {
+        let pattern = log_glob.pattern(&config.people_dir);
+        let paths = glob(&pattern).expect("log globs are validated when loading the config");
+        for path in paths.flatten().filter(|path| path.is_file()) {
+            files.entry(path).or_insert(log_glob.syntax());
+        }
+    }
+
+    files.into_iter().collect()
 }
 
-pub fn read_logs(people_dir: &PathBuf) -> Log {
+/// Parse the content of each log file, tagging the days with the file they
+/// come from if there is one
+fn parse_logs<'a>(
+    contents: impl IntoIterator<Item = (Option<&'a Path>, &'a str, LogSyntax)>,
+    options: &ParseOptions,
+) -> Log {
     let mut days: Vec<Day> = vec![];
+    for (path, content, syntax) in contents {
+        let format = log_format::for_syntax(syntax);
+        days.extend(format.parse(content, options).into_iter().map(|mut day| {
+            day.source_file = path.map(Path::to_path_buf);
+            for entry in day.entries.iter_mut() {
+                // just parsed, so not shared yet and not copied
+                Arc::make_mut(entry).source_file = day.source_file.clone();
+            }
+            day
+        }));
+    }
 
-    let files = find_log_files(people_dir);
-    for path in files {
-        let content = fs::read_to_string(&path).unwrap();
-        let log = parse_log_file_content(&content);
-        days.extend(log.days);
+    if options.dedupe_entries {
+        for (date, content) in duplicates::dedupe_days(&mut days) {
+            let first_line = content.lines().next().unwrap_or("");
+            info!("dropped duplicate entry on {date}: {first_line}");
+        }
     }
 
     Log { days }
 }
 
+pub fn read_logs(config: &Config, options: &ParseOptions) -> Result<Log, String> {
+    let mut contents: Vec<(PathBuf, String, LogSyntax)> = vec![];
+    for (path, syntax) in find_log_files_with_syntax(config) {
+        let content = files::read_text(&path, config.strict_encoding)?;
+        contents.push((path, content, syntax));
+    }
+
+    let contents = contents
+        .iter()
+        .map(|(path, content, syntax)| (Some(path.as_path()), content.as_str(), *syntax));
+    Ok(parse_logs(contents, options))
+}
+
+/// A single log file given with `--input`, read upfront so that stdin can be
+/// parsed more than once
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogInput {
+    /// None for stdin
+    pub path: Option<PathBuf>,
+    pub content: String,
+}
+
+impl LogInput {
+    /// Read the file at `path`, or stdin if it is `-`
+    pub fn read(path: &Path) -> Result<LogInput, String> {
+        if path == Path::new("-") {
+            let mut content = String::new();
+            return match io::stdin().read_to_string(&mut content) {
+                Ok(_) => Ok(LogInput {
+                    path: None,
+                    content,
+                }),
+                Err(reason) => Err(format!("failed to read stdin, reason: {reason}")),
+            };
+        }
+
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(LogInput {
+                path: Some(path.to_path_buf()),
+                content,
+            }),
+            Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+        }
+    }
+}
+
+/// The logs the commands work on: the `--input` log if there is one,
+/// otherwise the log files in `people_dir`
+pub fn read_config_logs(config: &Config) -> Result<Log, String> {
+    let options = ParseOptions::from_config(config);
+    match &config.input {
+        Some(input) => {
+            let syntax = match &input.path {
+                Some(path) => LogSyntax::from_path(&path.to_string_lossy()),
+                None => LogSyntax::Markdown,
+            };
+            Ok(parse_logs(
+                [(input.path.as_deref(), input.content.as_str(), syntax)],
+                &options,
+            ))
+        }
+        None => read_logs(config, &options),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use indoc::indoc;
@@ -309,6 +836,15 @@ mod tests {
         assert_eq!(find_first_non_space("  foo"), 2);
     }
 
+    #[test]
+    fn test_indentation_is_counted_in_characters() {
+        let token = tokenize_line(" \u{3000}- ŁukaszNowak", 0);
+
+        assert_eq!(find_first_non_space(" \u{3000}- ŁukaszNowak"), 4);
+        assert_eq!(token.indentation, 2);
+        assert_eq!(token.content, "- ŁukaszNowak");
+    }
+
     #[test]
     fn test_parse_log_file() {
         let content = indoc!(
@@ -333,33 +869,224 @@ mod tests {
             days: vec![
                 Day {
                     date: d("2000-01-01"),
-                    entries: vec![Entry {
+                    precision: DatePrecision::Day,
+                    entries: vec![Arc::new(Entry {
                         main: ["JohnDoe".to_string()].into(),
                         related: ["JohnDoe".to_string()].into(),
                         content: "- #JohnDoe :\n  - stuff: blah".to_string(),
-                    }],
+                        links: vec![],
+                        kind: None,
+                        fields: [("stuff".to_string(), "blah".to_string())].into(),
+                        source_file: None,
+                        line_range: 3..5,
+                    })],
+                    source_file: None,
+                    line_range: 1..5,
                 },
                 Day {
                     date: d("2000-01-02"),
+                    precision: DatePrecision::Day,
                     entries: vec![
-                        Entry {
+                        Arc::new(Entry {
                             main: ["JohnDoe".to_string()].into(),
                             related: ["JohnDoe".to_string(), "Bleh".to_string()].into(),
                             content: "- #JohnDoe :\n  - stuff: blah\n  - other: bleh #Bleh"
                                 .to_string(),
-                        },
-                        Entry {
+                            links: vec![],
+                            kind: None,
+                            fields: [
+                                ("stuff".to_string(), "blah".to_string()),
+                                ("other".to_string(), "bleh #Bleh".to_string()),
+                            ]
+                            .into(),
+                            source_file: None,
+                            line_range: 8..11,
+                        }),
+                        Arc::new(Entry {
                             main: ["JaneDoe".to_string(), "Abu".to_string()].into(),
                             related: ["JaneDoe".to_string(), "Abu".to_string()].into(),
                             content: "- #JaneDoe, #Abu :\n  - meet at foo\n    - nested stuff"
                                 .to_string(),
-                        },
+                            links: vec![],
+                            kind: None,
+                            fields: BTreeMap::new(),
+                            source_file: None,
+                            line_range: 11..14,
+                        }),
                     ],
+                    source_file: None,
+                    line_range: 6..14,
                 },
             ],
         };
 
-        assert_eq!(parse_log_file_content(&content), expected);
+        assert_eq!(parse_log_file_content(content), expected);
+    }
+
+    #[test]
+    fn test_parse_entry_kind() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe (call):
+              - stuff
+            - #JaneDoe :Met:
+              - (message) is only recognised in the first line
+            - #Abu (lunch) at 14:30:
+              - unknown kinds are ignored
+            "
+        ));
+
+        let kinds: Vec<Option<&str>> = log.days[0]
+            .entries
+            .iter()
+            .map(|entry| entry.kind.as_deref())
+            .collect();
+        assert_eq!(kinds, vec![Some("call"), Some("met"), None]);
+
+        let met = log.with_kind("met");
+        assert_eq!(met.days[0].entries.len(), 1);
+        assert!(met.days[0].entries[0].main.contains("JaneDoe"));
+    }
+
+    #[test]
+    fn test_parse_header_date() {
+        assert_eq!(
+            parse_header_date("2023-03-21"),
+            Some((d("2023-03-21"), DatePrecision::Day))
+        );
+        assert_eq!(
+            parse_header_date("2023-W12"),
+            Some((d("2023-03-20"), DatePrecision::Week))
+        );
+        assert_eq!(
+            parse_header_date("2023-03"),
+            Some((d("2023-03-01"), DatePrecision::Month))
+        );
+        assert_eq!(parse_header_date("2023-W54"), None);
+        assert_eq!(parse_header_date("2023-13"), None);
+        assert_eq!(parse_header_date("2023-3"), None);
+        assert_eq!(parse_header_date("Notes"), None);
+    }
+
+    #[test]
+    fn test_period_headers_round_trip() {
+        let content = "# 2023-W01\n\n- #JohnDoe :\n\n# 2023-12\n\n- #JaneDoe :\n";
+
+        let log = parse_log_file_content(content);
+
+        assert_eq!(log.days[0].date, d("2023-01-02"));
+        assert_eq!(log.days[0].last_date(), d("2023-01-08"));
+        assert_eq!(log.days[1].date, d("2023-12-01"));
+        assert_eq!(log.days[1].last_date(), d("2023-12-31"));
+        assert_eq!(log.to_string(), content);
+    }
+
+    #[test]
+    fn test_not_people_are_not_parsed_as_people() {
+        let options = ParseOptions {
+            not_people: vec!["Budget".parse().unwrap()],
+            ..Default::default()
+        };
+        let log = parse_log_file_content_with_options(
+            "# 2000-01-01\n\n- #JohnDoe, #Budget :\n  - #Budget review\n",
+            &options,
+        );
+
+        let entry = &log.days[0].entries[0];
+        assert_eq!(entry.main, ["JohnDoe".to_string()].into());
+        assert_eq!(entry.related, ["JohnDoe".to_string()].into());
+    }
+
+    #[test]
+    fn test_parse_journal() {
+        let content = indoc!(
+            "
+            My journal, started in 2000.
+
+            # 2000-01-01
+
+            ## Morning
+
+            - went for a run
+            - #JaneDoe : coffee
+              - she got the job
+
+            ## Evening
+
+            - read a book
+              - recommended by #JohnDoe
+
+            # 2000-01-02
+
+            - groceries
+            "
+        );
+        let options = ParseOptions {
+            mode: LogMode::Journal,
+            ..Default::default()
+        };
+
+        let log = parse_log_file_content_with_options(content, &options);
+        assert_eq!(
+            log.to_string(),
+            "# 2000-01-01\n\n- #JaneDoe : coffee\n  - she got the job\n"
+        );
+        assert_eq!(log.days[0].entries[0].line_range, 8..10);
+
+        let options = ParseOptions {
+            journal_mentions: MentionLevel::Both,
+            ..options
+        };
+        let log = parse_log_file_content_with_options(content, &options);
+        assert_eq!(log.days[0].entries.len(), 2);
+        assert_eq!(log.days.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_log_file_iter() {
+        let content =
+            "# 2000-01-01\n\n- #JohnDoe :\n\t- with tabs\n\n# 2000-01-02\n\n- #JaneDoe :\n";
+        let options = ParseOptions::default();
+
+        let mut days = parse_log_file_iter(content, &options);
+
+        let first = days.next().unwrap();
+        assert_eq!(first.date, d("2000-01-01"));
+        assert_eq!(first.entries[0].content, "- #JohnDoe :\n  - with tabs");
+        assert_eq!(days.next().unwrap().date, d("2000-01-02"));
+        assert_eq!(days.next(), None);
+    }
+
+    #[test]
+    fn test_line_ranges_count_the_front_matter() {
+        let content = indoc!(
+            "
+            ---
+            name: JohnDoe
+            ---
+            # 2000-01-01
+
+            - #JohnDoe :
+              - stuff
+
+            "
+        );
+        let path = PathBuf::from("/tmp/people/log/2000-people.md");
+
+        let log = parse_logs(
+            [(Some(path.as_path()), content, LogSyntax::Markdown)],
+            &ParseOptions::default(),
+        );
+
+        let day = &log.days[0];
+        assert_eq!(day.line_range, 4..8);
+        assert_eq!(day.entries[0].line_range, 6..8);
+        assert_eq!(
+            day.entries[0].location(),
+            Some("/tmp/people/log/2000-people.md:6".to_string())
+        );
     }
 
     #[test]
@@ -376,15 +1103,59 @@ mod tests {
         let expected = Log {
             days: vec![Day {
                 date: d("2000-01-01"),
-                entries: vec![Entry {
+                precision: DatePrecision::Day,
+                entries: vec![Arc::new(Entry {
                     main: ["Lucía".to_string()].into(),
                     related: ["Lucía".to_string()].into(),
                     content: "- #Lucía:\n  - stuff: blah".to_string(),
-                }],
+                    links: vec![],
+                    kind: None,
+                    fields: [("stuff".to_string(), "blah".to_string())].into(),
+                    source_file: None,
+                    line_range: 3..5,
+                })],
+                source_file: None,
+                line_range: 1..5,
             }],
         };
 
-        assert_eq!(parse_log_file_content(&content), expected);
+        assert_eq!(parse_log_file_content(content), expected);
+    }
+
+    #[test]
+    fn test_support_names_in_any_script() {
+        let content = indoc!(
+            "
+            # 2000-01-01
+
+            - #IñakiEtxeberria, #JürgenMüller, #ŁukaszNowak :
+              - with #Дмитрий, #山田太郎 and #Jose\u{301}
+            "
+        );
+
+        let log = parse_log_file_content(content);
+
+        let entry = &log.days[0].entries[0];
+        assert_eq!(
+            entry.main,
+            ["IñakiEtxeberria", "JürgenMüller", "ŁukaszNowak"]
+                .map(String::from)
+                .into()
+        );
+        // `José` with a combining acute accent, as some keyboards type it
+        assert_eq!(
+            entry.related,
+            [
+                "IñakiEtxeberria",
+                "JürgenMüller",
+                "ŁukaszNowak",
+                "Дмитрий",
+                "山田太郎",
+                "Jose\u{301}",
+            ]
+            .map(String::from)
+            .into()
+        );
     }
 
     #[test]
@@ -402,15 +1173,133 @@ mod tests {
         let expected = Log {
             days: vec![Day {
                 date: d("2000-01-01"),
-                entries: vec![Entry {
+                precision: DatePrecision::Day,
+                entries: vec![Arc::new(Entry {
                     main: ["Lucía".to_string()].into(),
                     related: ["Lucía".to_string()].into(),
                     content: "- #Lucía:\n  - stuff: blah".to_string(),
-                }],
+                    links: vec![],
+                    kind: None,
+                    fields: [("stuff".to_string(), "blah".to_string())].into(),
+                    source_file: None,
+                    line_range: 3..5,
+                })],
+                source_file: None,
+                line_range: 1..5,
             }],
         };
 
-        assert_eq!(parse_log_file_content(&content), expected);
+        assert_eq!(parse_log_file_content(content), expected);
+    }
+
+    fn wikilink_options(wikilink_names: WikilinkNames) -> ParseOptions {
+        ParseOptions {
+            references: ReferenceSyntax {
+                hashtags: true,
+                wikilinks: true,
+                wikilink_names,
+            },
+            aliases: HashMap::new(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_wikilinks_like_hashtags() {
+        let hashtags = parse_log_file_content_with_options(
+            indoc!(
+                "
+                # 2000-01-01
+
+                - #JaneDoe :
+                  - met #Abu
+                "
+            ),
+            &wikilink_options(WikilinkNames::Join),
+        );
+        let wikilinks = parse_log_file_content_with_options(
+            indoc!(
+                "
+                # 2000-01-01
+
+                - [[Jane Doe]] :
+                  - met [[Abu|the neighbour]]
+                "
+            ),
+            &wikilink_options(WikilinkNames::Join),
+        );
+
+        let main = |log: &Log| log.days[0].entries[0].main.clone();
+        let related = |log: &Log| log.days[0].entries[0].related.clone();
+
+        assert_eq!(main(&wikilinks), main(&hashtags));
+        assert_eq!(related(&wikilinks), related(&hashtags));
+    }
+
+    #[test]
+    fn test_parse_wikilinks_verbatim() {
+        let log = parse_log_file_content_with_options(
+            "# 2000-01-01\n\n- [[Jane Doe]] :\n",
+            &wikilink_options(WikilinkNames::Verbatim),
+        );
+
+        assert_eq!(log.days[0].entries[0].main, ["Jane Doe".to_string()].into());
+    }
+
+    #[test]
+    fn test_parse_wikilinks_mapped_through_aliases() {
+        let mut options = wikilink_options(WikilinkNames::Join);
+        options
+            .aliases
+            .insert("Jane".to_string(), "JaneDoe".to_string());
+
+        let log = parse_log_file_content_with_options(
+            "# 2000-01-01\n\n- [[Jane]] and #Jane :\n",
+            &options,
+        );
+
+        assert_eq!(log.days[0].entries[0].main, ["JaneDoe".to_string()].into());
+    }
+
+    #[test]
+    fn test_ignore_wikilinks_by_default() {
+        let log = parse_log_file_content("# 2000-01-01\n\n- [[Jane Doe]] #Abu :\n");
+
+        assert_eq!(log.days[0].entries[0].main, ["Abu".to_string()].into());
+    }
+
+    #[test]
+    fn test_parse_empty_log_file() {
+        assert_eq!(parse_log_file_content(""), Log { days: vec![] });
+        assert_eq!(parse_log_file_content("\n\n"), Log { days: vec![] });
+    }
+
+    #[test]
+    fn test_parse_log_file_with_windows_line_endings() {
+        let content = "# 2024-01-01\r\n\r\n- #JaneDoe : lunch\r\n  - dessert\r\n";
+
+        let log = parse_log_file_content(content);
+
+        assert_eq!(log.to_string(), content.replace('\r', ""));
+        assert_eq!(log.days[0].entries[0].line_range, 3..5);
+    }
+
+    #[test]
+    fn test_parse_log_file_with_byte_order_mark() {
+        let log = parse_log_file_content("\u{FEFF}# 2024-01-01\n\n- #JaneDoe :\n");
+
+        assert_eq!(log.days[0].date, d("2024-01-01"));
+        assert_eq!(log.days[0].entries[0].line_range, 3..4);
+    }
+
+    #[test]
+    fn test_read_logs_from_missing_dir() {
+        let config = Config::builder()
+            .people_dir("/this/path/does/not/exist")
+            .build();
+        let log = read_logs(&config, &ParseOptions::default());
+
+        assert_eq!(log, Ok(Log { days: vec![] }));
     }
 
     #[test]
@@ -433,11 +1322,47 @@ mod tests {
             "
         );
 
-        let log = parse_log_file_content(&content);
+        let log = parse_log_file_content(content);
         let formatted = format!("{log}");
         println!("\n{content:#?}");
         println!("\n{formatted:#?}");
 
         assert_eq!(formatted, content);
     }
+
+    #[test]
+    fn test_parse_lines_outside_of_entries() {
+        let content =
+            "- #Abu : before any date\n# 2024-01-01\n\t\n  - #JaneDoe :\n\u{3000}- more\n \n";
+
+        let log = parse_log_file_content(content);
+
+        assert_eq!(log.days.len(), 1);
+        let entries: Vec<&str> = log.days[0]
+            .entries
+            .iter()
+            .map(|entry| entry.content.as_str())
+            .collect();
+        assert_eq!(entries, vec!["- #JaneDoe :\n - more"]);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_parse_any_content(content in crate::test_utils::log_content()) {
+            for mode in [LogMode::People, LogMode::Journal] {
+                let options = ParseOptions {
+                    mode,
+                    ..Default::default()
+                };
+                parse_log_file_content_with_options(&content, &options);
+            }
+            log_format::for_syntax(LogSyntax::Org).parse(&content, &ParseOptions::default());
+        }
+
+        #[test]
+        fn test_parse_any_text(content in "\\PC*(\n\\PC*){0,5}") {
+            parse_log_file_content(&content);
+            log_format::for_syntax(LogSyntax::Org).parse(&content, &ParseOptions::default());
+        }
+    }
 }
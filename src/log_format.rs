@@ -0,0 +1,214 @@
+use crate::config::LogSyntax;
+use crate::log::{parse_header_date, parse_log_file_iter, Day, ParseOptions};
+
+/// A syntax log files can be written in. Every format gives the same days and
+/// entries, whose content is markdown.
+pub trait LogFormat {
+    /// The days in the content of a log file
+    fn parse(&self, content: &str, options: &ParseOptions) -> Vec<Day>;
+}
+
+/// `# 2024-05-01` headers followed by `- #JaneDoe : lunch` entries
+pub struct Markdown;
+
+impl LogFormat for Markdown {
+    fn parse(&self, content: &str, options: &ParseOptions) -> Vec<Day> {
+        parse_log_file_iter(content, options).collect()
+    }
+}
+
+/// Emacs org-mode, with a heading per day, e.g. `* 2024-05-01` or
+/// `*** <2024-05-01 Wed>` in a datetree. Entries are either list items, as in
+/// markdown, or headings under the day with the people in their title or in a
+/// `:PEOPLE:` property.
+pub struct Org;
+
+impl LogFormat for Org {
+    fn parse(&self, content: &str, options: &ParseOptions) -> Vec<Day> {
+        Markdown.parse(&org_to_markdown(content), options)
+    }
+}
+
+pub fn for_syntax(syntax: LogSyntax) -> &'static dyn LogFormat {
+    match syntax {
+        LogSyntax::Markdown => &Markdown,
+        LogSyntax::Org => &Org,
+    }
+}
+
+/// Level and title of a heading like `** Lunch`
+fn org_heading(line: &str) -> Option<(usize, &str)> {
+    let title = line.trim_start_matches('*');
+    let level = line.len() - title.len();
+    match level > 0 && title.starts_with(' ') {
+        true => Some((level, title.trim())),
+        false => None,
+    }
+}
+
+/// Markdown header of a heading title like `2024-05-01`, `<2024-05-01 Wed>` or
+/// `[2024-05-01]`
+fn org_date_header(title: &str) -> Option<String> {
+    let word = title
+        .trim_start_matches(['<', '['])
+        .split([' ', '>', ']'])
+        .next()?;
+    let (date, precision) = parse_header_date(word)?;
+    Some(format!("# {}", precision.header(date)))
+}
+
+/// `JaneDoe, John Doe` or `JaneDoe JohnDoe` as hashtags
+fn people_tags(value: &str) -> String {
+    let names: Vec<String> = match value.contains(',') {
+        true => value
+            .split(',')
+            .map(|name| name.split_whitespace().collect())
+            .collect(),
+        false => value.split_whitespace().map(str::to_string).collect(),
+    };
+    names
+        .iter()
+        .map(|name| name.trim_start_matches('#'))
+        .filter(|name| !name.is_empty())
+        .map(|name| format!("#{name}"))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// The org content as a markdown log, line by line, so that entries keep the
+/// line numbers of the org file. What is not part of a day, property drawers
+/// and org comments become blank lines.
+fn org_to_markdown(content: &str) -> String {
+    let mut lines: Vec<String> = vec![];
+    // level of the heading of the current day, if in one
+    let mut day_level: Option<usize> = None;
+    // how deep under the day the current heading is, 0 for the day itself
+    let mut depth: usize = 0;
+    // line and title of the heading that a `:PEOPLE:` property belongs to
+    let mut last_heading: Option<(usize, String)> = None;
+    let mut in_drawer = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if in_drawer {
+            if trimmed.eq_ignore_ascii_case(":END:") {
+                in_drawer = false;
+            } else if let Some(value) = trimmed.strip_prefix(":PEOPLE:") {
+                if let Some((index, title)) = &last_heading {
+                    let indentation = "  ".repeat(depth - 1);
+                    lines[*index] = format!("{indentation}- {} : {title}", people_tags(value));
+                }
+            }
+            lines.push("".to_string());
+            continue;
+        }
+
+        if let Some((level, title)) = org_heading(line) {
+            if let Some(header) = org_date_header(title) {
+                day_level = Some(level);
+                depth = 0;
+                last_heading = None;
+                lines.push(header);
+                continue;
+            }
+
+            match day_level {
+                Some(day) if level > day => {
+                    depth = level - day;
+                    last_heading = Some((lines.len(), title.to_string()));
+                    lines.push(format!("{}- {title}", "  ".repeat(depth - 1)));
+                }
+                _ => {
+                    day_level = None;
+                    last_heading = None;
+                    lines.push("".to_string());
+                }
+            }
+            continue;
+        }
+
+        if trimmed.eq_ignore_ascii_case(":PROPERTIES:") {
+            in_drawer = true;
+            lines.push("".to_string());
+            continue;
+        }
+
+        let is_comment = trimmed.starts_with("#+") || trimmed == "#" || trimmed.starts_with("# ");
+        match (day_level, is_comment) {
+            (Some(_), false) if !trimmed.is_empty() => {
+                lines.push(format!("{}{line}", "  ".repeat(depth)));
+            }
+            _ => lines.push("".to_string()),
+        }
+    }
+
+    let mut markdown = lines.join("\n");
+    markdown.push('\n');
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::Log;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_org() {
+        let content = indoc!(
+            "
+            #+TITLE: Journal
+
+            * 2024
+            ** 2024-05 May
+            *** 2024-05-01 Wednesday
+            - #JaneDoe : coffee
+            **** Lunch :call:
+            :PROPERTIES:
+            :PEOPLE: JohnDoe, Abu
+            :END:
+            Talked about the move
+            - with #Zoe
+            * <2024-05-02 Thu>
+            ** Dinner with #JaneDoe
+            # a comment
+            * Projects
+            - #JaneDoe : not in a day
+            "
+        );
+
+        let log = Log {
+            days: Org.parse(content, &ParseOptions::default()),
+        };
+
+        let expected = indoc!(
+            "
+            # 2024-05-01
+
+            - #JaneDoe : coffee
+            - #JohnDoe, #Abu : Lunch :call:
+              Talked about the move
+              - with #Zoe
+
+            # 2024-05-02
+
+            - Dinner with #JaneDoe
+            "
+        );
+        assert_eq!(log.to_string(), expected);
+
+        let lunch = &log.days[0].entries[1];
+        assert_eq!(lunch.kind, Some("call".to_string()));
+        assert_eq!(
+            lunch.related,
+            ["JohnDoe", "Abu", "Zoe"].map(str::to_string).into()
+        );
+        assert_eq!(lunch.line_range, 7..13);
+        assert_eq!(log.days[1].date, d("2024-05-02"));
+    }
+}
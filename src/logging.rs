@@ -0,0 +1,89 @@
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+
+use clap::{ArgAction, Args};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!(
+                "unsupported log format found: {other:?}, expected 'text' or 'json'"
+            )),
+        }
+    }
+}
+
+/// Logging flags shared by every binary
+#[derive(Args, Clone, Debug, Default)]
+pub struct LoggingArgs {
+    /// Log more: -v for info, -vv for debug, -vvv for trace
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+    /// Log less: -q for errors only, -qq for nothing
+    #[arg(short, long, action = ArgAction::Count, global = true, conflicts_with = "verbose")]
+    pub quiet: u8,
+    /// `text` or `json`, one object per line
+    #[arg(long, default_value = "text", global = true)]
+    pub log_format: LogFormat,
+}
+
+/// Warnings by default
+pub fn level_filter(verbose: u8, quiet: u8) -> LevelFilter {
+    match (verbose, quiet) {
+        (0, 0) => LevelFilter::WARN,
+        (0, 1) => LevelFilter::ERROR,
+        (0, _) => LevelFilter::OFF,
+        (1, _) => LevelFilter::INFO,
+        (2, _) => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Send logs to stderr. `RUST_LOG` (e.g. `people=debug`) takes precedence over
+/// the flags.
+pub fn init(args: &LoggingArgs) {
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) => EnvFilter::default().add_directive(level_filter(args.verbose, args.quiet).into()),
+    };
+
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal());
+
+    // only fails if a subscriber was already installed, which is fine
+    let _ = match args.log_format {
+        LogFormat::Text => builder.try_init(),
+        LogFormat::Json => builder.json().try_init(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_level_filter() {
+        assert_eq!(level_filter(0, 0), LevelFilter::WARN);
+        assert_eq!(level_filter(2, 0), LevelFilter::DEBUG);
+        assert_eq!(level_filter(7, 0), LevelFilter::TRACE);
+        assert_eq!(level_filter(0, 1), LevelFilter::ERROR);
+        assert_eq!(level_filter(0, 2), LevelFilter::OFF);
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate};
+use glob::glob;
+use regex::Regex;
+
+use crate::config::Config;
+use crate::model::PersonName;
+use crate::vcard::unfold;
+
+static ADDRESS_PATTERN: &str = r#"[^\s<>,;:"'()\[\]]+@[^\s<>,;:"'()\[\]]+"#;
+
+/// Who took part in an email and when it was sent
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MailMessage {
+    pub date: NaiveDate,
+    /// Lowercased addresses in `From`, `To` and `Cc`
+    pub addresses: Vec<String>,
+}
+
+/// `Tue, 5 Mar 2024 10:00:00 +0100 (CET)`, on the day it was sent where it was sent
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    let value = match value.split_once('(') {
+        Some((date, _comment)) => date,
+        None => value,
+    };
+    DateTime::parse_from_rfc2822(value.trim())
+        .ok()
+        .map(|date| date.date_naive())
+}
+
+/// The message of the headers, if it has a date and any address
+pub fn parse_headers(content: &str) -> Option<MailMessage> {
+    let address_re = Regex::new(ADDRESS_PATTERN).unwrap();
+    let headers = content
+        .split("\n\n")
+        .next()
+        .unwrap_or("")
+        .trim_start_matches('\n');
+
+    let mut date: Option<NaiveDate> = None;
+    let mut addresses: Vec<String> = vec![];
+    for line in unfold(headers) {
+        let (name, value) = match line.split_once(':') {
+            Some(parts) => parts,
+            None => continue,
+        };
+        match name.trim().to_lowercase().as_str() {
+            "date" => date = parse_date(value),
+            "from" | "to" | "cc" => addresses.extend(
+                address_re
+                    .find_iter(value)
+                    .map(|address| address.as_str().to_lowercase()),
+            ),
+            _ => {}
+        }
+    }
+
+    match (date, addresses.is_empty()) {
+        (Some(date), false) => Some(MailMessage { date, addresses }),
+        _ => None,
+    }
+}
+
+/// Split an mbox into its messages, which start with a `From ` line
+fn split_mbox(content: &str) -> Vec<&str> {
+    let mut messages: Vec<&str> = vec![];
+    let mut start: Option<usize> = None;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if line.starts_with("From ") {
+            if let Some(start) = start {
+                messages.push(&content[start..offset]);
+            }
+            start = Some(offset + line.len());
+        }
+        offset += line.len();
+    }
+    if let Some(start) = start {
+        messages.push(&content[start..]);
+    }
+    messages
+}
+
+fn read_lossy(path: &Path) -> Result<String, String> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).replace("\r\n", "\n")),
+        Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+    }
+}
+
+/// The messages of an mbox file, or of every folder of a Maildir
+pub fn read_mailbox(path: &Path) -> Result<Vec<MailMessage>, String> {
+    if path.is_file() {
+        let content = read_lossy(path)?;
+        return Ok(split_mbox(&content)
+            .into_iter()
+            .filter_map(parse_headers)
+            .collect());
+    }
+
+    if !path.is_dir() {
+        return Err(format!("no mbox file or Maildir found at {path:?}"));
+    }
+
+    let mut files: Vec<PathBuf> = vec![];
+    for folder in ["cur", "new"] {
+        let pattern = path.join("**").join(folder).join("*");
+        match glob(&pattern.to_string_lossy()) {
+            Ok(paths) => files.extend(paths.flatten().filter(|path| path.is_file())),
+            Err(reason) => return Err(format!("failed to list {path:?}, reason: {reason}")),
+        }
+    }
+
+    let mut messages: Vec<MailMessage> = vec![];
+    for file in files {
+        if let Some(message) = parse_headers(&read_lossy(&file)?) {
+            messages.push(message);
+        }
+    }
+    Ok(messages)
+}
+
+/// Days each person with an `email` in the config took part in a message,
+/// from `since` on
+pub fn passive_interactions(
+    messages: &[MailMessage],
+    config: &Config,
+    since: Option<NaiveDate>,
+) -> BTreeMap<PersonName, BTreeSet<NaiveDate>> {
+    let people: HashMap<String, &PersonName> = config
+        .people
+        .iter()
+        .filter_map(|person| {
+            let email = person.email.as_ref()?;
+            Some((email.to_lowercase(), &person.name))
+        })
+        .collect();
+
+    let mut interactions: BTreeMap<PersonName, BTreeSet<NaiveDate>> = BTreeMap::new();
+    for message in messages {
+        if since.is_some_and(|since| message.date < since) {
+            continue;
+        }
+        for address in message.addresses.iter() {
+            if let Some(person) = people.get(address) {
+                interactions
+                    .entry((*person).clone())
+                    .or_default()
+                    .insert(message.date);
+            }
+        }
+    }
+    interactions
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Person;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_headers() {
+        let content = indoc!(
+            "
+            From: \"Jane Doe\" <Jane@Example.com>
+            To: me@example.com,
+             John <john@example.com>
+            Date: Tue, 5 Mar 2024 23:30:00 +0100 (CET)
+            Subject: Lunch
+
+            From: not@a.header
+            "
+        );
+
+        assert_eq!(
+            parse_headers(content),
+            Some(MailMessage {
+                date: d("2024-03-05"),
+                addresses: vec![
+                    "jane@example.com".to_string(),
+                    "me@example.com".to_string(),
+                    "john@example.com".to_string(),
+                ],
+            })
+        );
+        assert_eq!(parse_headers("Subject: no date\n\nhi"), None);
+    }
+
+    #[test]
+    fn test_passive_interactions() {
+        let mbox = indoc!(
+            "
+            From jane@example.com Tue Mar  5 10:00:00 2024
+            From: jane@example.com
+            To: me@example.com
+            Date: Tue, 5 Mar 2024 10:00:00 +0000
+
+            Hi!
+
+            From me@example.com Wed Mar  6 10:00:00 2024
+            From: me@example.com
+            To: jane@example.com, john@example.com
+            Date: Wed, 6 Mar 2024 10:00:00 +0000
+
+            Hi back
+            "
+        );
+        let messages: Vec<MailMessage> = split_mbox(mbox)
+            .into_iter()
+            .filter_map(parse_headers)
+            .collect();
+        let config = Config::builder()
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                email: Some("Jane@example.com".to_string()),
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(
+            passive_interactions(&messages, &config, None),
+            [(
+                "JaneDoe".to_string(),
+                [d("2024-03-05"), d("2024-03-06")].into()
+            )]
+            .into()
+        );
+        assert_eq!(
+            passive_interactions(&messages, &config, Some(d("2024-03-06"))),
+            [("JaneDoe".to_string(), [d("2024-03-06")].into())].into()
+        );
+    }
+}
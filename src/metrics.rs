@@ -0,0 +1,144 @@
+use chrono::NaiveDate;
+
+use crate::log::Log;
+use crate::use_cases::LastInteraction;
+
+/// `Content-Type` of the Prometheus text format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Label values are quoted, so quotes, backslashes and line breaks are escaped
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+fn write_header(metrics: &mut String, name: &str, help: &str) {
+    metrics.push_str(&format!("# HELP {name} {help}\n# TYPE {name} gauge\n"));
+}
+
+/// Gauges about the log and the last interaction with each person, in the
+/// Prometheus text format. `interactions` should have their reach outs
+/// assessed as of `today`.
+pub fn render_metrics(log: &Log, interactions: &[LastInteraction], today: NaiveDate) -> String {
+    let mut interactions = interactions.to_vec();
+    interactions.sort_by(|a, b| a.person.cmp(&b.person));
+
+    let mut metrics = String::new();
+
+    write_header(
+        &mut metrics,
+        "people_days_since_last_contact",
+        "Days since the last interaction with the person.",
+    );
+    for interaction in &interactions {
+        let person = escape_label(&interaction.person);
+        let days = interaction.ago(today);
+        metrics.push_str(&format!(
+            "people_days_since_last_contact{{person=\"{person}\"}} {days}\n"
+        ));
+    }
+
+    write_header(
+        &mut metrics,
+        "people_days_overdue",
+        "Days since the person's remind_after threshold passed.",
+    );
+    for interaction in &interactions {
+        if let Some(days) = interaction.days_beyond_reachout_threshold {
+            let person = escape_label(&interaction.person);
+            metrics.push_str(&format!(
+                "people_days_overdue{{person=\"{person}\"}} {days}\n"
+            ));
+        }
+    }
+
+    let overdue = interactions
+        .iter()
+        .filter(|interaction| interaction.days_beyond_reachout_threshold.is_some())
+        .count();
+    write_header(
+        &mut metrics,
+        "people_overdue_total",
+        "People beyond their remind_after threshold.",
+    );
+    metrics.push_str(&format!("people_overdue_total {overdue}\n"));
+
+    let people = interactions.len();
+    write_header(
+        &mut metrics,
+        "people_known_total",
+        "People with at least one interaction.",
+    );
+    metrics.push_str(&format!("people_known_total {people}\n"));
+
+    let entries: usize = log.days.iter().map(|day| day.entries.len()).sum();
+    write_header(&mut metrics, "people_entries_total", "Entries in the logs.");
+    metrics.push_str(&format!("people_entries_total {entries}\n"));
+
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_render_metrics() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+            - #Lucía :
+            "
+        ));
+        let interactions = vec![
+            LastInteraction {
+                person: "Lucía".to_string(),
+                last: d("2000-01-01"),
+                days_beyond_reachout_threshold: None,
+                kind: None,
+            },
+            LastInteraction {
+                person: "John\"Doe".to_string(),
+                last: d("2000-01-01"),
+                days_beyond_reachout_threshold: Some(3),
+                kind: None,
+            },
+        ];
+
+        let expected = indoc!(
+            r#"
+            # HELP people_days_since_last_contact Days since the last interaction with the person.
+            # TYPE people_days_since_last_contact gauge
+            people_days_since_last_contact{person="John\"Doe"} 10
+            people_days_since_last_contact{person="Lucía"} 10
+            # HELP people_days_overdue Days since the person's remind_after threshold passed.
+            # TYPE people_days_overdue gauge
+            people_days_overdue{person="John\"Doe"} 3
+            # HELP people_overdue_total People beyond their remind_after threshold.
+            # TYPE people_overdue_total gauge
+            people_overdue_total 1
+            # HELP people_known_total People with at least one interaction.
+            # TYPE people_known_total gauge
+            people_known_total 2
+            # HELP people_entries_total Entries in the logs.
+            # TYPE people_entries_total gauge
+            people_entries_total 2
+            "#
+        );
+
+        assert_eq!(
+            render_metrics(&log, &interactions, d("2000-01-11")),
+            expected.trim_start()
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use serde_yaml::{Mapping, Value};
+
+/// Version of the config format this build reads and writes, as `version:` at
+/// the top of the config file. Configs without it are version 1.
+pub const CONFIG_VERSION: u64 = 2;
+
+/// Version of the format of per-person logs, in their front matter
+pub const LOG_VERSION: u64 = 1;
+
+/// Top-level keys of version 1 configs
+static V1_KEYS: &[&str] = &[
+    "people_dir",
+    "ignore",
+    "not_people",
+    "people",
+    "archived",
+    "front_matter",
+    "references",
+    "carddav",
+    "caldav",
+    "tiers",
+    "templates",
+    "smtp",
+    "telegram",
+    "slack",
+    "profiles",
+    "kinds",
+    "timezone",
+    "theme_frequencies",
+    "backup",
+    "git",
+    "dedupe_entries",
+    "per_person_path",
+    "log_globs",
+    "summary",
+    "hooks",
+    "prompt",
+];
+
+/// Top-level keys added in version 2. Configs without `version` can have them
+/// too, as they mean the same in both.
+static V2_KEYS: &[&str] = &[
+    "version",
+    "include",
+    "log_mode",
+    "journal_mentions",
+    "strict_encoding",
+    "date_format",
+    "read_only",
+    "per_person",
+];
+
+/// Keys of each person in version 1 configs
+static V1_PERSON_KEYS: &[&str] = &[
+    "name",
+    "aliases",
+    "location",
+    "themes",
+    "remind_after",
+    "birthday",
+    "pinned",
+    "email",
+    "phone",
+    "telegram",
+    "frequency",
+];
+
+/// A change of the config format, from the version before `to`. Migrations
+/// only know about the format they upgrade from, so they never change once
+/// released.
+struct Migration {
+    to: u64,
+    /// What is changed in a config, each change described for the user
+    apply: fn(&mut Mapping) -> Vec<String>,
+}
+
+static MIGRATIONS: &[Migration] = &[Migration {
+    to: 2,
+    apply: remove_unknown_keys,
+}];
+
+/// Version 1 ignored keys it didn't know about, like typos, which version 2
+/// refuses: they are dropped, unless version 2 knows them
+fn remove_unknown_keys(document: &mut Mapping) -> Vec<String> {
+    let mut changes: Vec<String> = vec![];
+
+    let unknown: Vec<Value> = document
+        .keys()
+        .filter(|key| {
+            !key.as_str()
+                .is_some_and(|key| V1_KEYS.contains(&key) || V2_KEYS.contains(&key))
+        })
+        .cloned()
+        .collect();
+    for key in unknown {
+        document.remove(&key);
+        changes.push(format!("removed unknown key {}", describe(&key)));
+    }
+
+    for section in ["people", "archived"] {
+        let people = match document.get_mut(section).and_then(Value::as_sequence_mut) {
+            Some(people) => people,
+            None => continue,
+        };
+        for (index, person) in people.iter_mut().enumerate() {
+            let person = match person.as_mapping_mut() {
+                Some(person) => person,
+                None => continue,
+            };
+            let name = match person.get("name").and_then(Value::as_str) {
+                Some(name) => name.to_string(),
+                None => format!("{section}[{index}]"),
+            };
+            let unknown: Vec<Value> = person
+                .keys()
+                .filter(|key| {
+                    !key.as_str()
+                        .is_some_and(|key| V1_PERSON_KEYS.contains(&key))
+                })
+                .cloned()
+                .collect();
+            for key in unknown {
+                person.remove(&key);
+                changes.push(format!("removed unknown key {} of {name}", describe(&key)));
+            }
+        }
+    }
+
+    changes
+}
+
+fn describe(key: &Value) -> String {
+    match key.as_str() {
+        Some(key) => format!("{key:?}"),
+        None => serde_yaml::to_string(key)
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+    }
+}
+
+/// Format version of a parsed config file
+pub fn config_version(document: &Mapping) -> Result<u64, String> {
+    match document.get("version") {
+        None => Ok(1),
+        Some(version) => match version.as_u64() {
+            Some(version) if version >= 1 => Ok(version),
+            _ => Err(format!(
+                "expected version to be a positive number, but found {version:?}"
+            )),
+        },
+    }
+}
+
+/// A config upgraded to `CONFIG_VERSION`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigratedConfig {
+    pub from: u64,
+    pub content: String,
+    pub changes: Vec<String>,
+}
+
+/// Upgrade the content of a config file to `CONFIG_VERSION`, or nothing if it
+/// is already there. The upgraded content has no comments, as YAML is
+/// rewritten from scratch.
+pub fn migrate_config(content: &str) -> Result<Option<MigratedConfig>, String> {
+    let mut document: Mapping = match serde_yaml::from_str(content) {
+        Ok(document) => document,
+        Err(reason) => return Err(format!("failed to parse config, reason: {reason}")),
+    };
+
+    let from = config_version(&document)?;
+    if from > CONFIG_VERSION {
+        return Err(format!(
+            "config is at version {from}, but this version of people only supports up to {CONFIG_VERSION}, please upgrade people"
+        ));
+    }
+    if from == CONFIG_VERSION {
+        return Ok(None);
+    }
+
+    let mut changes: Vec<String> = vec![];
+    for migration in MIGRATIONS.iter().filter(|migration| migration.to > from) {
+        changes.extend((migration.apply)(&mut document));
+    }
+
+    // the version goes first, where it is easy to spot
+    document.remove("version");
+    let mut migrated = Mapping::new();
+    migrated.insert(Value::from("version"), Value::from(CONFIG_VERSION));
+    migrated.extend(document);
+
+    let content = match serde_yaml::to_string(&migrated) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to write config, reason: {reason}")),
+    };
+
+    Ok(Some(MigratedConfig {
+        from,
+        content,
+        changes,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_migrate_config_from_version_1() {
+        let content = indoc!(
+            "
+            people_dir: ~/people
+            read_only: true
+            remind: weekly
+            people:
+              - name: JaneDoe
+                location: Berlin
+                nickname: Janey
+            "
+        );
+
+        let expected = MigratedConfig {
+            from: 1,
+            content: indoc!(
+                "
+                version: 2
+                people_dir: ~/people
+                read_only: true
+                people:
+                - name: JaneDoe
+                  location: Berlin
+                "
+            )
+            .trim_start()
+            .to_string(),
+            changes: vec![
+                "removed unknown key \"remind\"".to_string(),
+                "removed unknown key \"nickname\" of JaneDoe".to_string(),
+            ],
+        };
+
+        assert_eq!(migrate_config(content), Ok(Some(expected)));
+    }
+
+    #[test]
+    fn test_migrate_config_at_current_version() {
+        assert_eq!(
+            migrate_config("version: 2\npeople_dir: ~/people\n"),
+            Ok(None)
+        );
+        assert!(migrate_config("version: 3\npeople_dir: ~/people\n").is_err());
+        assert!(migrate_config("version: latest\npeople_dir: ~/people\n").is_err());
+    }
+}
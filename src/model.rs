@@ -3,3 +3,5 @@ pub type DaysAgo = i64;
 pub type Location = String;
 pub type Theme = String;
 pub type DurationStr = String;
+/// How an interaction happened, e.g. `call`
+pub type InteractionKind = String;
@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use crate::log::{Day, Log};
+
+/// File the days without a source file are imported into
+static IMPORTED_FILE_NAME: &str = "imported.md";
+
+/// The log as newline-delimited JSON, a day with its entries per line
+pub fn to_ndjson(log: &Log) -> Result<String, String> {
+    let mut content = String::new();
+    for day in log.days.iter() {
+        match serde_json::to_string(day) {
+            Ok(line) => content.push_str(&line),
+            Err(reason) => {
+                return Err(format!(
+                    "failed to serialize {}, reason: {reason}",
+                    day.date
+                ))
+            }
+        }
+        content.push('\n');
+    }
+    Ok(content)
+}
+
+/// The log written by `to_ndjson`. Blank lines are skipped.
+pub fn from_ndjson(content: &str) -> Result<Log, String> {
+    let mut days: Vec<Day> = vec![];
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(day) => days.push(day),
+            Err(reason) => {
+                return Err(format!(
+                    "failed to parse line {}, reason: {reason}",
+                    index + 1
+                ))
+            }
+        }
+    }
+    Ok(Log { days })
+}
+
+/// A markdown log file per source file of the days, named like it with an
+/// `.md` extension, in order of first appearance. Days are kept in the order
+/// they come in.
+pub fn markdown_files(log: Log) -> Vec<(PathBuf, String)> {
+    let mut files: Vec<(PathBuf, Log)> = vec![];
+    for day in log.days {
+        let name = match day.source_file.as_deref().and_then(|path| path.file_name()) {
+            Some(name) => PathBuf::from(name).with_extension("md"),
+            None => PathBuf::from(IMPORTED_FILE_NAME),
+        };
+        match files.iter_mut().find(|(path, _)| path == &name) {
+            Some((_, file)) => file.days.push(day),
+            None => files.push((name, Log { days: vec![day] })),
+        }
+    }
+
+    files
+        .into_iter()
+        .map(|(name, log)| (name, log.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::{parse_log_file_content, DatePrecision};
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_ndjson_roundtrip() {
+        let content = indoc!(
+            "
+            # 2024-05-01
+
+            - #JaneDoe, #JohnDoe (call): lunch with #Abu
+              gift-idea: fountain pen
+
+            # 2024-W19
+
+            - #Zoe : https://example.com
+            "
+        );
+        let mut log = parse_log_file_content(content);
+        for day in log.days.iter_mut() {
+            day.source_file = Some(PathBuf::from("/home/me/people/log/2024.org"));
+        }
+
+        let ndjson = to_ndjson(&log).unwrap();
+        assert_eq!(ndjson.lines().count(), 2);
+        let first: serde_json::Value =
+            serde_json::from_str(ndjson.lines().next().unwrap()).unwrap();
+        assert_eq!(first["date"], "2024-05-01");
+        assert_eq!(
+            first["entries"][0]["main"],
+            serde_json::json!(["Abu", "JaneDoe", "JohnDoe"])
+        );
+        assert_eq!(
+            first["entries"][0]["line_range"],
+            serde_json::json!({"start": 3, "end": 5})
+        );
+
+        let imported = from_ndjson(&format!("{ndjson}\n")).unwrap();
+        assert_eq!(imported, log);
+        assert_eq!(imported.days[1].precision, DatePrecision::Week);
+        assert_eq!(imported.days[1].date, d("2024-05-06"));
+
+        assert_eq!(
+            markdown_files(imported),
+            vec![(PathBuf::from("2024.md"), content.to_string())]
+        );
+        assert_eq!(
+            from_ndjson("{}\n"),
+            Err(
+                "failed to parse line 1, reason: missing field `date` at line 1 column 2"
+                    .to_string()
+            )
+        );
+    }
+}
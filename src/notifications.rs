@@ -0,0 +1,61 @@
+use notify_rust::Notification;
+
+use crate::use_cases::LastInteraction;
+
+/// Title and body of the desktop notification for an overdue person
+pub fn reminder_message(interaction: &LastInteraction) -> (String, String) {
+    let title = format!("Reach out to {}", interaction.person);
+    let body = match interaction.days_beyond_reachout_threshold {
+        Some(1) => format!("Last interaction on {}, 1 day overdue", interaction.last),
+        Some(days) => format!(
+            "Last interaction on {}, {days} days overdue",
+            interaction.last
+        ),
+        None => format!("Last interaction on {}", interaction.last),
+    };
+
+    (title, body)
+}
+
+pub fn send_reminder(interaction: &LastInteraction) -> Result<(), String> {
+    let (title, body) = reminder_message(interaction);
+
+    match Notification::new()
+        .appname("people")
+        .summary(&title)
+        .body(&body)
+        .show()
+    {
+        Ok(_) => Ok(()),
+        Err(reason) => Err(format!(
+            "failed to notify about {}, reason: {reason}",
+            interaction.person
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_reminder_message() {
+        let interaction = LastInteraction {
+            person: "JohnDoe".to_string(),
+            last: d("2000-01-01"),
+            days_beyond_reachout_threshold: Some(3),
+            kind: None,
+        };
+
+        let expected = (
+            "Reach out to JohnDoe".to_string(),
+            "Last interaction on 2000-01-01, 3 days overdue".to_string(),
+        );
+
+        assert_eq!(reminder_message(&interaction), expected);
+    }
+}
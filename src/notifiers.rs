@@ -0,0 +1,248 @@
+use std::str::FromStr;
+
+use chrono::NaiveTime;
+use serde_json::json;
+
+use crate::config::{Config, SlackConfig, TelegramConfig};
+use crate::contact;
+use crate::notifications;
+use crate::secrets;
+use crate::use_cases::LastInteraction;
+
+/// A chat service that reminders can be posted to
+pub trait Notifier {
+    fn send(&self, message: &str) -> Result<(), String>;
+}
+
+/// Where `people notify` posts the reminders
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Channel {
+    Telegram,
+    Slack,
+}
+
+impl FromStr for Channel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "telegram" => Ok(Channel::Telegram),
+            "slack" => Ok(Channel::Slack),
+            other => Err(format!(
+                "unsupported channel found: {other:?}, expected 'telegram' or 'slack'"
+            )),
+        }
+    }
+}
+
+/// The notifier of the channel, if it is set up in the config
+pub fn notifier(channel: Channel, config: &Config) -> Result<Box<dyn Notifier>, String> {
+    match channel {
+        Channel::Telegram => match &config.telegram {
+            Some(telegram) => Ok(Box::new(TelegramNotifier::new(telegram.clone()))),
+            None => Err("no `telegram` found in the config".to_string()),
+        },
+        Channel::Slack => match &config.slack {
+            Some(slack) => Ok(Box::new(SlackNotifier::new(slack.clone()))),
+            None => Err("no `slack` found in the config".to_string()),
+        },
+    }
+}
+
+fn post_json(url: &str, body: &serde_json::Value) -> Result<(), String> {
+    match ureq::post(url)
+        .set("Content-Type", "application/json; charset=utf-8")
+        .send_string(&body.to_string())
+    {
+        Ok(_) => Ok(()),
+        Err(reason) => Err(format!("failed to post the message, reason: {reason}")),
+    }
+}
+
+pub struct TelegramNotifier {
+    config: TelegramConfig,
+}
+
+impl TelegramNotifier {
+    pub fn new(config: TelegramConfig) -> TelegramNotifier {
+        TelegramNotifier { config }
+    }
+
+    fn body(&self, message: &str) -> serde_json::Value {
+        json!({ "chat_id": self.config.chat_id, "text": message })
+    }
+}
+
+impl Notifier for TelegramNotifier {
+    fn send(&self, message: &str) -> Result<(), String> {
+        let token = match secrets::resolve_password(&self.config.token, &self.config.token_command)
+        {
+            Ok(token) => token,
+            Err(reason) => {
+                return Err(format!(
+                    "failed to get the Telegram token, reason: {reason}"
+                ))
+            }
+        };
+
+        let url = format!("https://api.telegram.org/bot{token}/sendMessage");
+        post_json(&url, &self.body(message))
+    }
+}
+
+pub struct SlackNotifier {
+    config: SlackConfig,
+}
+
+impl SlackNotifier {
+    pub fn new(config: SlackConfig) -> SlackNotifier {
+        SlackNotifier { config }
+    }
+}
+
+impl Notifier for SlackNotifier {
+    fn send(&self, message: &str) -> Result<(), String> {
+        let url = match secrets::resolve_password(
+            &self.config.webhook_url,
+            &self.config.webhook_url_command,
+        ) {
+            Ok(url) => url,
+            Err(reason) => {
+                return Err(format!(
+                    "failed to get the Slack webhook URL, reason: {reason}"
+                ))
+            }
+        };
+
+        post_json(&url, &json!({ "text": message }))
+    }
+}
+
+/// A line per overdue person, with how to reach them if known
+pub fn reminders_message(due: &[LastInteraction], config: &Config) -> String {
+    let mut lines = vec!["Overdue a reach out:".to_string()];
+    for interaction in due {
+        let (_, body) = notifications::reminder_message(interaction);
+        let contact = config
+            .get_person(&interaction.person)
+            .and_then(contact::preferred_contact);
+        match contact {
+            Some(contact) => lines.push(format!("- {}: {body} ({contact})", interaction.person)),
+            None => lines.push(format!("- {}: {body}", interaction.person)),
+        }
+    }
+    lines.join("\n")
+}
+
+/// Time of the day to not post in, e.g. `22:00-08:00`, which goes past midnight
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        match self.start <= self.end {
+            true => self.start <= time && time < self.end,
+            false => self.start <= time || time < self.end,
+        }
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_time = |text: &str| {
+            NaiveTime::parse_from_str(text.trim(), "%H:%M")
+                .map_err(|_| format!("expected a time like 22:00, but found {text:?}"))
+        };
+
+        match s.split_once('-') {
+            Some((start, end)) => Ok(QuietHours {
+                start: parse_time(start)?,
+                end: parse_time(end)?,
+            }),
+            None => Err(format!(
+                "expected a range like 22:00-08:00, but found {s:?}"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Person;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    fn t(text: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(text, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours() {
+        let night: QuietHours = "22:00-08:00".parse().unwrap();
+        assert!(night.contains(t("23:30")));
+        assert!(night.contains(t("07:59")));
+        assert!(!night.contains(t("08:00")));
+        assert!(!night.contains(t("12:00")));
+
+        let lunch: QuietHours = "13:00 - 14:00".parse().unwrap();
+        assert!(lunch.contains(t("13:30")));
+        assert!(!lunch.contains(t("23:30")));
+
+        assert!("22:00".parse::<QuietHours>().is_err());
+        assert!("22-08".parse::<QuietHours>().is_err());
+    }
+
+    #[test]
+    fn test_reminders_message() {
+        let config = Config::builder()
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                telegram: Some("janedoe".to_string()),
+                ..Default::default()
+            })
+            .build();
+        let due = vec![
+            LastInteraction {
+                person: "JaneDoe".to_string(),
+                last: d("2000-01-01"),
+                days_beyond_reachout_threshold: Some(3),
+                kind: None,
+            },
+            LastInteraction {
+                person: "JohnDoe".to_string(),
+                last: d("2000-01-02"),
+                days_beyond_reachout_threshold: Some(1),
+                kind: None,
+            },
+        ];
+
+        assert_eq!(
+            reminders_message(&due, &config),
+            "Overdue a reach out:\n\
+             - JaneDoe: Last interaction on 2000-01-01, 3 days overdue (@janedoe)\n\
+             - JohnDoe: Last interaction on 2000-01-02, 1 day overdue"
+        );
+    }
+
+    #[test]
+    fn test_telegram_body() {
+        let notifier = TelegramNotifier::new(TelegramConfig {
+            token: Some("123:abc".to_string()),
+            token_command: None,
+            chat_id: "42".to_string(),
+        });
+
+        assert_eq!(
+            notifier.body("hi"),
+            json!({ "chat_id": "42", "text": "hi" })
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::files;
+
+const OPERATION_EXTENSION: &str = ".json";
+
+/// Operations kept, the older ones can't be undone
+const KEPT_OPERATIONS: usize = 20;
+
+/// A file before and after an operation, `None` where it did not exist
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// A command that changed files, as recorded in `.people/ops/`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Operation {
+    pub command: String,
+    pub time: NaiveDateTime,
+    pub files: Vec<FileChange>,
+}
+
+/// `20000101-093000-000000.json`, which sorts oldest first
+pub fn operation_name(now: NaiveDateTime) -> String {
+    format!("{}{OPERATION_EXTENSION}", now.format("%Y%m%d-%H%M%S-%6f"))
+}
+
+/// The content of a file, or `None` if there is no such file
+fn read_current(path: &Path) -> Result<Option<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(reason) if reason.kind() == ErrorKind::NotFound => Ok(None),
+        Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+    }
+}
+
+/// Put a file back to how it was before the change
+fn restore(change: &FileChange) -> Result<(), String> {
+    let path = &change.path;
+    match &change.before {
+        Some(content) => {
+            files::create_parent_dir(path)?;
+            files::write_atomically(path, content)
+        }
+        None => match fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(reason) if reason.kind() == ErrorKind::NotFound => Ok(()),
+            Err(reason) => Err(format!("failed to delete {path:?}, reason: {reason}")),
+        },
+    }
+}
+
+/// The files a command writes and deletes, with what they had before, so that
+/// `people undo` can revert them. Writers in several threads can share it.
+pub struct Transaction {
+    config: Config,
+    command: String,
+    changes: Mutex<Vec<FileChange>>,
+}
+
+impl Transaction {
+    pub fn begin(config: &Config, command: &str) -> Transaction {
+        Transaction {
+            config: config.clone(),
+            command: command.to_string(),
+            changes: Mutex::new(vec![]),
+        }
+    }
+
+    /// A file changed more than once keeps what it had the first time
+    fn record(&self, path: &Path, before: Option<String>, after: Option<String>) {
+        let mut changes = self.changes.lock().expect("a writer panicked");
+        match changes.iter_mut().find(|change| change.path == path) {
+            Some(change) => change.after = after,
+            None => changes.push(FileChange {
+                path: path.to_path_buf(),
+                before,
+                after,
+            }),
+        }
+    }
+
+    /// Write the file atomically, remembering what it had. Its directory is
+    /// created if needed, which read-only mode refuses too.
+    pub fn write(&self, path: &Path, content: &str) -> Result<(), String> {
+        self.config.check_writable(path)?;
+        files::create_parent_dir(path)?;
+        let before = read_current(path)?;
+        files::write_atomically(path, content)?;
+        self.record(path, before, Some(content.to_string()));
+        Ok(())
+    }
+
+    pub fn remove(&self, path: &Path) -> Result<(), String> {
+        self.config.check_writable(path)?;
+        let before = read_current(path)?;
+        if let Err(reason) = fs::remove_file(path) {
+            return Err(format!("failed to delete {path:?}, reason: {reason}"));
+        }
+        self.record(path, before, None);
+        Ok(())
+    }
+
+    fn into_changes(self) -> Vec<FileChange> {
+        self.changes.into_inner().expect("a writer panicked")
+    }
+
+    /// Record the operation for `people undo`, if it changed any file. Returns
+    /// where it was recorded.
+    pub fn commit(self) -> Result<Option<PathBuf>, String> {
+        let dir = self.config.get_operations_dir();
+        let now = Local::now().naive_local();
+        let operation = Operation {
+            command: self.command.clone(),
+            time: now,
+            files: self.into_changes(),
+        };
+        if operation.files.is_empty() {
+            return Ok(None);
+        }
+
+        if let Err(reason) = fs::create_dir_all(&dir) {
+            return Err(format!("failed to create {dir:?}, reason: {reason}"));
+        }
+        let content = match serde_json::to_string_pretty(&operation) {
+            Ok(content) => content,
+            Err(reason) => {
+                return Err(format!(
+                    "failed to record {:?}, reason: {reason}",
+                    operation.command
+                ))
+            }
+        };
+        let path = dir.join(operation_name(now));
+        files::write_atomically(&path, &content)?;
+
+        for name in operations_to_prune(&operation_names(&dir)?, KEPT_OPERATIONS) {
+            let old = dir.join(name);
+            if let Err(reason) = fs::remove_file(&old) {
+                return Err(format!("failed to delete {old:?}, reason: {reason}"));
+            }
+        }
+
+        Ok(Some(path))
+    }
+
+    /// Put the files back as they were, newest change first
+    pub fn rollback(self) -> Result<(), String> {
+        for change in self.into_changes().iter().rev() {
+            restore(change)?;
+        }
+        Ok(())
+    }
+
+    /// Commit if the command went well, or else roll its changes back
+    pub fn finish<T>(self, result: Result<T, String>) -> Result<T, String> {
+        match result {
+            Ok(value) => {
+                self.commit()?;
+                Ok(value)
+            }
+            Err(reason) => match self.rollback() {
+                Ok(()) => Err(reason),
+                Err(rollback) => Err(format!("{reason}, and then {rollback}")),
+            },
+        }
+    }
+}
+
+fn is_operation_name(name: &str) -> bool {
+    name.ends_with(OPERATION_EXTENSION)
+}
+
+fn operation_names(dir: &Path) -> Result<Vec<String>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(reason) if reason.kind() == ErrorKind::NotFound => return Ok(vec![]),
+        Err(reason) => return Err(format!("failed to read {dir:?}, reason: {reason}")),
+    };
+
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| is_operation_name(name))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// The oldest operations beyond the `keep` most recent ones
+pub fn operations_to_prune(names: &[String], keep: usize) -> Vec<String> {
+    let mut operations: Vec<&String> = names
+        .iter()
+        .filter(|name| is_operation_name(name))
+        .collect();
+    operations.sort();
+
+    let excess = operations.len().saturating_sub(keep);
+    operations.into_iter().take(excess).cloned().collect()
+}
+
+/// The most recent operation and where it is recorded
+pub fn last_operation(config: &Config) -> Result<Option<(PathBuf, Operation)>, String> {
+    let dir = config.get_operations_dir();
+    let Some(name) = operation_names(&dir)?.pop() else {
+        return Ok(None);
+    };
+
+    let path = dir.join(name);
+    let content = files::read_text(&path, true)?;
+    match serde_json::from_str(&content) {
+        Ok(operation) => Ok(Some((path, operation))),
+        Err(reason) => Err(format!("failed to parse {path:?}, reason: {reason}")),
+    }
+}
+
+/// The files of the operation that were changed again since
+pub fn changed_since(operation: &Operation) -> Result<Vec<PathBuf>, String> {
+    let mut changed: Vec<PathBuf> = vec![];
+    for change in operation.files.iter() {
+        if read_current(&change.path)? != change.after {
+            changed.push(change.path.clone());
+        }
+    }
+    Ok(changed)
+}
+
+/// Revert the most recent operation and forget it, unless its
+/// files were changed again since. Returns the reverted operation.
+pub fn undo(config: &Config) -> Result<Operation, String> {
+    let Some((path, operation)) = last_operation(config)? else {
+        return Err("nothing to undo".to_string());
+    };
+
+    let changed = changed_since(&operation)?;
+    if !changed.is_empty() {
+        let paths: Vec<String> = changed.iter().map(|path| format!("{path:?}")).collect();
+        return Err(format!(
+            "{} changed after `{}`, not undoing it",
+            paths.join(", "),
+            operation.command
+        ));
+    }
+
+    for change in operation.files.iter().rev() {
+        restore(change)?;
+    }
+    if let Err(reason) = fs::remove_file(&path) {
+        return Err(format!("failed to delete {path:?}, reason: {reason}"));
+    }
+
+    Ok(operation)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_operation_names() {
+        let now = d("2000-01-02").and_hms_micro_opt(9, 30, 5, 42).unwrap();
+        assert_eq!(operation_name(now), "20000102-093005-000042.json");
+
+        let names: Vec<String> = [
+            "20000103-000000-000000.json",
+            "notes.txt",
+            "20000101-000000-000000.json",
+            "20000102-000000-000000.json",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            operations_to_prune(&names, 2),
+            vec!["20000101-000000-000000.json".to_string()]
+        );
+        assert_eq!(operations_to_prune(&names, 10), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_record_keeps_first_before() {
+        let transaction = Transaction::begin(&Config::builder().build(), "rename Abu Abe");
+        let path = Path::new("/tmp/people/log/2000.md");
+        transaction.record(path, Some("#Abu".to_string()), Some("#Abe".to_string()));
+        transaction.record(path, Some("#Abe".to_string()), None);
+        transaction.record(Path::new("/tmp/people/Abe.md"), None, Some("".to_string()));
+
+        assert_eq!(
+            transaction.into_changes(),
+            vec![
+                FileChange {
+                    path: path.to_path_buf(),
+                    before: Some("#Abu".to_string()),
+                    after: None,
+                },
+                FileChange {
+                    path: PathBuf::from("/tmp/people/Abe.md"),
+                    before: None,
+                    after: Some("".to_string()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use crate::config::{Config, IndexSort};
+use crate::log::Log;
+use crate::model::PersonName;
+use crate::operations::Transaction;
+use crate::use_cases::LogWritten;
+
+/// A person in the index of the per-person logs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexRow {
+    pub person: PersonName,
+    pub last: Option<NaiveDate>,
+    pub entries: usize,
+    /// Their per-person log, relative to the index and with `/` separators
+    pub link: String,
+}
+
+/// Everyone with a per-person log, sorted by `per_person.index_sort`. Ignored
+/// people, whose logs are deleted, are left out.
+pub fn index_rows(
+    per_person_logs: &HashMap<PersonName, Option<Log>>,
+    config: &Config,
+) -> Vec<IndexRow> {
+    let index = config.get_people_index_path();
+    let index_dir = index.parent().unwrap_or(&config.people_dir);
+
+    let mut rows: Vec<IndexRow> = per_person_logs
+        .iter()
+        .filter_map(|(person, log)| log.as_ref().map(|log| (person, log)))
+        .map(|(person, log)| IndexRow {
+            person: person.clone(),
+            last: log.days.iter().map(|day| day.date).max(),
+            entries: log.days.iter().map(|day| day.entries.len()).sum(),
+            link: link(&config.get_person_log_path(person), index_dir),
+        })
+        .collect();
+
+    rows.sort_by_key(|row| (row.person.to_lowercase(), row.person.clone()));
+    match config.per_person.index_sort {
+        IndexSort::Name => {}
+        IndexSort::Last => rows.sort_by_key(|row| Reverse(row.last)),
+        IndexSort::Entries => rows.sort_by_key(|row| Reverse(row.entries)),
+    }
+    rows
+}
+
+fn link(path: &Path, index_dir: &Path) -> String {
+    let relative = path.strip_prefix(index_dir).unwrap_or(path);
+    let parts: Vec<String> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect();
+    parts.join("/")
+}
+
+/// A markdown table with a row per person
+pub fn render_index(rows: &[IndexRow]) -> String {
+    let mut content = String::from("# People\n\n");
+    content.push_str("| Person | Last interaction | Entries |\n");
+    content.push_str("| --- | --- | --- |\n");
+    for row in rows {
+        let last = match row.last {
+            Some(date) => date.to_string(),
+            None => "".to_string(),
+        };
+        content.push_str(&format!(
+            "| [{}]({}) | {last} | {} |\n",
+            row.person, row.link, row.entries
+        ));
+    }
+    content
+}
+
+/// Write the index next to the per-person logs, unless it would not change
+pub fn write_people_index(
+    rows: &[IndexRow],
+    config: &Config,
+    transaction: &Transaction,
+) -> LogWritten {
+    let path = config.get_people_index_path();
+    if let Some(row) = rows
+        .iter()
+        .find(|row| config.get_person_log_path(&row.person) == path)
+    {
+        let reason = format!("it is the per-person log of {}", row.person);
+        return LogWritten::FailedToWrite(path, reason);
+    }
+
+    let content = render_index(rows);
+    if fs::read_to_string(&path).is_ok_and(|existing| existing == content) {
+        return LogWritten::Unchanged(path);
+    }
+
+    match transaction.write(&path, &content) {
+        Ok(()) => LogWritten::Written(path),
+        Err(reason) => LogWritten::FailedToWrite(path, reason),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::PerPersonConfig;
+    use crate::log::parse_log_file_content;
+    use crate::use_cases::split_log_per_person;
+
+    use super::*;
+
+    #[test]
+    fn test_render_index() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe : lunch
+            - #abu : call
+
+            # 2000-01-05
+
+            - #JohnDoe, #Zoe : coffee
+            "
+        ));
+        let config = |index_sort: IndexSort| {
+            Config::builder()
+                .people_dir("/tmp/people")
+                .per_person_path(
+                    "per-person-logs/{first_letter}/{person}.md"
+                        .parse()
+                        .unwrap(),
+                )
+                .per_person(PerPersonConfig {
+                    index: true,
+                    index_sort,
+                    ..Default::default()
+                })
+                .build()
+        };
+        let per_person = split_log_per_person(log, &config(IndexSort::Name));
+        let names = |index_sort: IndexSort| -> Vec<PersonName> {
+            index_rows(&per_person, &config(index_sort))
+                .into_iter()
+                .map(|row| row.person)
+                .collect()
+        };
+
+        assert_eq!(names(IndexSort::Name), vec!["abu", "JohnDoe", "Zoe"]);
+        assert_eq!(names(IndexSort::Last), vec!["JohnDoe", "Zoe", "abu"]);
+        assert_eq!(names(IndexSort::Entries), vec!["JohnDoe", "abu", "Zoe"]);
+
+        let expected = indoc!(
+            "
+            # People
+
+            | Person | Last interaction | Entries |
+            | --- | --- | --- |
+            | [abu](A/abu.md) | 2000-01-01 | 1 |
+            | [JohnDoe](J/JohnDoe.md) | 2000-01-05 | 2 |
+            | [Zoe](Z/Zoe.md) | 2000-01-05 | 1 |
+            "
+        );
+        assert_eq!(
+            render_index(&index_rows(&per_person, &config(IndexSort::Name))),
+            expected.trim_start()
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+use crate::listing;
+use crate::model::PersonName;
+use crate::use_cases::LastInteraction;
+
+/// What `people pick` does with the chosen person
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickAction {
+    /// `people show <person>`
+    Show,
+    /// `people edit <person>`
+    Edit,
+    /// `people remind done <person>`
+    Contacted,
+}
+
+impl PickAction {
+    pub fn all() -> [PickAction; 3] {
+        [PickAction::Show, PickAction::Edit, PickAction::Contacted]
+    }
+}
+
+impl FromStr for PickAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "show" => Ok(PickAction::Show),
+            "edit" => Ok(PickAction::Edit),
+            "contacted" => Ok(PickAction::Contacted),
+            other => Err(format!(
+                "expected 'show', 'edit' or 'contacted', but found {other:?}"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for PickAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let description = match self {
+            PickAction::Show => "show their log",
+            PickAction::Edit => "edit their latest entry",
+            PickAction::Contacted => "mark as contacted today",
+        };
+        write!(f, "{description}")
+    }
+}
+
+/// A person to pick from, with what tells them apart at a glance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Choice {
+    pub person: PersonName,
+    pub line: String,
+}
+
+/// A choice per person, overdue ones first and then the most recent, each as
+/// tab-separated person, days ago, last interaction and how overdue they are
+pub fn choices(mut interactions: Vec<LastInteraction>, today: NaiveDate) -> Vec<Choice> {
+    interactions.sort_by_key(|interaction| {
+        let overdue = interaction.days_beyond_reachout_threshold;
+        (
+            overdue.is_none(),
+            -overdue.unwrap_or(0),
+            today - interaction.last,
+        )
+    });
+
+    interactions
+        .into_iter()
+        .map(|interaction| {
+            let overdue = match interaction.days_beyond_reachout_threshold {
+                Some(days) => format!("overdue by {days} days"),
+                None => "".to_string(),
+            };
+            let cells = [
+                interaction.person.clone(),
+                format!("{} days ago", interaction.ago(today)),
+                interaction.last.to_string(),
+                overdue,
+            ];
+            Choice {
+                person: interaction.person,
+                line: listing::plain_row(&cells),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    fn interaction(person: &str, last: &str, overdue: Option<i64>) -> LastInteraction {
+        LastInteraction {
+            person: person.to_string(),
+            last: d(last),
+            days_beyond_reachout_threshold: overdue,
+            kind: None,
+        }
+    }
+
+    #[test]
+    fn test_choices() {
+        let interactions = vec![
+            interaction("Abu", "2000-01-01", None),
+            interaction("JaneDoe", "2000-03-01", Some(1)),
+            interaction("JohnDoe", "2000-03-30", None),
+            interaction("Ann", "2000-02-01", Some(30)),
+        ];
+
+        let lines: Vec<String> = choices(interactions, d("2000-04-01"))
+            .into_iter()
+            .map(|choice| choice.line)
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "Ann\t60 days ago\t2000-02-01\toverdue by 30 days",
+                "JaneDoe\t31 days ago\t2000-03-01\toverdue by 1 days",
+                "JohnDoe\t2 days ago\t2000-03-30\t",
+                "Abu\t91 days ago\t2000-01-01\t",
+            ]
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use regex::Regex;
+
+use crate::csv_import::build_entry;
+use crate::log::{self, DatePrecision, Day, Log};
+use crate::model::PersonName;
+use crate::use_cases::LastInteraction;
+
+static CHECKED_ITEM_PATTERN: &str = r"^\s*[-*+]\s+\[[xX]\]\s+(.*)$";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlanFormat {
+    Table,
+    Markdown,
+}
+
+impl FromStr for PlanFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(PlanFormat::Table),
+            "markdown" => Ok(PlanFormat::Markdown),
+            other => Err(format!(
+                "unsupported format found: {other:?}, expected 'table' or 'markdown'"
+            )),
+        }
+    }
+}
+
+/// One unchecked item per reach out, e.g. `- [ ] Call #Abu (overdue 12d)`
+pub fn to_markdown(plan: &[LastInteraction]) -> String {
+    plan.iter()
+        .map(
+            |interaction| match interaction.days_beyond_reachout_threshold {
+                Some(days) => format!("- [ ] Call #{} (overdue {days}d)\n", interaction.person),
+                None => format!("- [ ] Call #{}\n", interaction.person),
+            },
+        )
+        .collect()
+}
+
+/// The people in the checked items of a plan exported with `to_markdown`, in
+/// order and without repetitions
+pub fn parse_checked(content: &str) -> Vec<PersonName> {
+    let checked = Regex::new(CHECKED_ITEM_PATTERN).unwrap();
+    let hashtag = log::hashtag_regex();
+
+    let mut people: Vec<PersonName> = vec![];
+    for line in content.lines() {
+        let Some(item) = checked.captures(line) else {
+            continue;
+        };
+        let Some(person) = hashtag.captures(&item[1]) else {
+            continue;
+        };
+
+        let person = person[1].to_string();
+        if !people.contains(&person) {
+            people.push(person);
+        }
+    }
+
+    people
+}
+
+/// A log with a reach out to each person on `date`, ready to be merged into the
+/// log files
+pub fn reachouts_log(people: Vec<PersonName>, date: NaiveDate) -> Log {
+    if people.is_empty() {
+        return Log { days: vec![] };
+    }
+
+    let entries = people
+        .into_iter()
+        .map(|person| Arc::new(build_entry(person, Some("reached out"))))
+        .collect();
+
+    Log {
+        days: vec![Day {
+            date,
+            precision: DatePrecision::Day,
+            entries,
+            source_file: None,
+            line_range: 0..0,
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_to_markdown() {
+        let plan = vec![
+            LastInteraction {
+                person: "Abu".to_string(),
+                last: d("2000-01-01"),
+                days_beyond_reachout_threshold: Some(12),
+                kind: None,
+            },
+            LastInteraction {
+                person: "JaneDoe".to_string(),
+                last: d("2000-01-05"),
+                days_beyond_reachout_threshold: Some(1),
+                kind: None,
+            },
+        ];
+
+        let expected = indoc!(
+            "
+            - [ ] Call #Abu (overdue 12d)
+            - [ ] Call #JaneDoe (overdue 1d)
+            "
+        );
+
+        assert_eq!(to_markdown(&plan), expected);
+    }
+
+    #[test]
+    fn test_parse_checked() {
+        let content = indoc!(
+            "
+            ## This week
+            - [x] Call #Abu (overdue 12d)
+            - [ ] Call #JaneDoe (overdue 1d)
+              * [X] Call #Lucía
+            - [x] buy milk
+            - [x] Call #Abu again
+            - [x] Call #Jose\u{301}, like the log reads it
+            "
+        );
+
+        assert_eq!(
+            parse_checked(content),
+            vec![
+                "Abu".to_string(),
+                "Lucía".to_string(),
+                "Jose\u{301}".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reachouts_log() {
+        let log = reachouts_log(vec!["Abu".to_string()], d("2000-01-02"));
+
+        let expected = indoc!(
+            "
+            # 2000-01-02
+
+            - #Abu :
+              - reached out
+            "
+        );
+
+        assert_eq!(format!("{log}"), expected.trim_start());
+    }
+}
@@ -0,0 +1,283 @@
+use std::cmp::Reverse;
+
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::log::Log;
+use crate::model::PersonName;
+use crate::render::{EntryContext, PersonContext};
+use crate::site;
+use crate::use_cases;
+
+/// Co-mentioned people and recent entries shown in a profile
+pub const PROFILE_TOP: usize = 5;
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct ProfileStats {
+    pub total_entries: usize,
+    /// Earliest entry about the person, rather than just mentioning them
+    pub first_met: Option<NaiveDate>,
+    /// How long ago you first met, e.g. `2 years and 3 months`
+    pub relationship_age: Option<String>,
+    pub last_seen: Option<NaiveDate>,
+    pub days_since_last_seen: Option<i64>,
+    /// Median days between days with entries
+    pub typical_cadence_days: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct CoMention {
+    pub name: PersonName,
+    /// Entries in which both people are mentioned
+    pub times: usize,
+}
+
+/// Everything known about a person, from the config and from their log
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct PersonProfile {
+    pub person: PersonContext,
+    pub stats: ProfileStats,
+    pub co_mentioned: Vec<CoMention>,
+    /// Most recent first
+    pub recent_entries: Vec<EntryContext>,
+}
+
+/// Days between consecutive `dates`, which are sorted, in the middle
+pub(crate) fn median_gap(dates: &[NaiveDate]) -> Option<i64> {
+    let mut gaps: Vec<i64> = dates
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).num_days())
+        .collect();
+    gaps.sort();
+    gaps.get(gaps.len() / 2).copied()
+}
+
+/// Whole years and months from `since` to `today`, or days if less than a month
+pub fn relationship_age(since: NaiveDate, today: NaiveDate) -> String {
+    let mut months =
+        (today.year() - since.year()) * 12 + today.month() as i32 - since.month() as i32;
+    if today.day() < since.day() {
+        months -= 1;
+    }
+    let months = months.max(0) as u32;
+    let plural = |amount: u32, unit: &str| match amount {
+        1 => format!("1 {unit}"),
+        amount => format!("{amount} {unit}s"),
+    };
+
+    match (months / 12, months % 12) {
+        (0, 0) => plural((today - since).num_days().max(0) as u32, "day"),
+        (0, months) => plural(months, "month"),
+        (years, 0) => plural(years, "year"),
+        (years, months) => format!("{} and {}", plural(years, "year"), plural(months, "month")),
+    }
+}
+
+impl PersonProfile {
+    /// `history` is the person's log, as `people show` prints it
+    pub fn new(
+        person: &PersonName,
+        history: &Log,
+        config: &Config,
+        today: NaiveDate,
+    ) -> PersonProfile {
+        let mut dates: Vec<NaiveDate> = history.days.iter().map(|day| day.date).collect();
+        dates.sort();
+        dates.dedup();
+
+        let last_seen = dates.last().copied();
+        let first_met = use_cases::get_first_interactions(history).remove(person);
+        let stats = ProfileStats {
+            total_entries: history.days.iter().map(|day| day.entries.len()).sum(),
+            first_met,
+            relationship_age: first_met.map(|first_met| relationship_age(first_met, today)),
+            last_seen,
+            days_since_last_seen: last_seen.map(|last| (today - last).num_days()),
+            typical_cadence_days: median_gap(&dates),
+        };
+
+        let co_mentioned = site::co_mentioned(person, history, config)
+            .into_iter()
+            .take(PROFILE_TOP)
+            .map(|link| CoMention {
+                name: link.name,
+                times: link.times,
+            })
+            .collect();
+
+        let mut recent_entries: Vec<EntryContext> = history
+            .days
+            .iter()
+            .flat_map(|day| {
+                day.entries
+                    .iter()
+                    .map(|entry| EntryContext::new(day.date, entry))
+            })
+            .collect();
+        recent_entries.sort_by_key(|entry| Reverse(entry.date));
+        recent_entries.truncate(PROFILE_TOP);
+
+        PersonProfile {
+            person: PersonContext::new(person, config.get_person(person)),
+            stats,
+            co_mentioned,
+            recent_entries,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let person = &self.person;
+        let mut metadata: Vec<String> = vec![];
+        if !person.aliases.is_empty() {
+            metadata.push(format!("- Aliases: {}", person.aliases.join(", ")));
+        }
+        if !person.location.is_empty() {
+            metadata.push(format!("- Location: {}", person.location));
+        }
+        if !person.themes.is_empty() {
+            metadata.push(format!("- Themes: {}", person.themes.join(", ")));
+        }
+        if let Some(birthday) = person.birthday {
+            metadata.push(format!("- Birthday: {birthday}"));
+        }
+
+        let mut markdown = format!("# {}\n", person.name);
+        if !metadata.is_empty() {
+            markdown.push_str(&format!("\n{}\n", metadata.join("\n")));
+        }
+
+        let stats = &self.stats;
+        markdown.push_str("\n## Stats\n\n");
+        markdown.push_str(&format!("- Entries: {}\n", stats.total_entries));
+        if let (Some(first_met), Some(age)) = (stats.first_met, &stats.relationship_age) {
+            markdown.push_str(&format!("- First met: {first_met} ({age} ago)\n"));
+        }
+        if let (Some(last_seen), Some(days)) = (stats.last_seen, stats.days_since_last_seen) {
+            markdown.push_str(&format!("- Last seen: {last_seen} ({days} days ago)\n"));
+        }
+        if let Some(days) = stats.typical_cadence_days {
+            markdown.push_str(&format!("- Typical cadence: every {days} days\n"));
+        }
+
+        if !self.co_mentioned.is_empty() {
+            markdown.push_str("\n## Often mentioned with\n\n");
+            for other in self.co_mentioned.iter() {
+                markdown.push_str(&format!("- {} ({})\n", other.name, other.times));
+            }
+        }
+
+        if !self.recent_entries.is_empty() {
+            markdown.push_str("\n## Recent entries\n");
+            let mut last_date: Option<NaiveDate> = None;
+            for entry in self.recent_entries.iter() {
+                if last_date != Some(entry.date) {
+                    markdown.push_str(&format!("\n### {}\n\n", entry.date));
+                    last_date = Some(entry.date);
+                }
+                markdown.push_str(&format!("{}\n", entry.content));
+            }
+        }
+
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Person;
+    use crate::log::parse_log_file_content;
+    use crate::show;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_profile() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JaneDoe, #JohnDoe : lunch
+
+            # 2000-01-11
+
+            - #JaneDoe : call
+            - #Abu, #JaneDoe : drinks
+
+            # 2000-01-15
+
+            - #JaneDoe : coffee
+            "
+        ));
+        let config = Config::builder()
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                location: "Berlin".to_string(),
+                themes: vec!["work".to_string()],
+                ..Default::default()
+            })
+            .build();
+        let person = "JaneDoe".to_string();
+        let history = show::person_history(log, &person, &config, None, None).unwrap();
+
+        let profile = PersonProfile::new(&person, &history, &config, d("2000-01-20"));
+
+        let expected = indoc!(
+            "
+            # JaneDoe
+
+            - Location: Berlin
+            - Themes: work
+
+            ## Stats
+
+            - Entries: 4
+            - First met: 2000-01-01 (19 days ago)
+            - Last seen: 2000-01-15 (5 days ago)
+            - Typical cadence: every 10 days
+
+            ## Often mentioned with
+
+            - Abu (1)
+            - JohnDoe (1)
+
+            ## Recent entries
+
+            ### 2000-01-15
+
+            - #JaneDoe : coffee
+
+            ### 2000-01-11
+
+            - #JaneDoe : call
+            - #Abu, #JaneDoe : drinks
+
+            ### 2000-01-01
+
+            - #JaneDoe, #JohnDoe : lunch
+            "
+        );
+        assert_eq!(profile.to_markdown(), expected);
+    }
+
+    #[test]
+    fn test_relationship_age() {
+        assert_eq!(relationship_age(d("2000-01-31"), d("2000-02-01")), "1 day");
+        assert_eq!(
+            relationship_age(d("2000-01-31"), d("2000-03-01")),
+            "1 month"
+        );
+        assert_eq!(
+            relationship_age(d("1998-01-15"), d("2000-01-15")),
+            "2 years"
+        );
+        assert_eq!(
+            relationship_age(d("1998-01-15"), d("2000-04-14")),
+            "2 years and 2 months"
+        );
+    }
+}
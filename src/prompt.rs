@@ -0,0 +1,206 @@
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::completions;
+use crate::config::Config;
+use crate::log::Log;
+use crate::model::{PersonName, Theme};
+
+/// Fewer entries than this are sparse notes
+pub const SPARSE_ENTRIES: usize = 3;
+/// Fewer words than this per entry, on average, are sparse notes
+pub const SPARSE_WORDS: usize = 20;
+
+/// Why someone is worth writing about
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reason {
+    Sparse { entries: usize, words: usize },
+    Group(Option<Theme>),
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reason::Sparse { entries: 0, .. } => write!(f, "no entries yet"),
+            Reason::Sparse { entries: 1, words } => {
+                write!(f, "only 1 entry so far, {words} words")
+            }
+            Reason::Sparse { entries, words } => {
+                write!(f, "only {entries} entries so far, {words} words on average")
+            }
+            Reason::Group(Some(theme)) => write!(f, "picked at random from {theme}"),
+            Reason::Group(None) => write!(f, "picked at random"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    pub person: PersonName,
+    pub reasons: Vec<Reason>,
+    /// Sum of the configured weights of the reasons
+    pub weight: u32,
+}
+
+/// Everyone known with a reason to write about them, weighted as configured
+pub fn candidates(log: &Log, config: &Config) -> Vec<Candidate> {
+    let mut notes: HashMap<&PersonName, (usize, usize)> = HashMap::new();
+    for day in log.days.iter() {
+        for entry in day.entries.iter() {
+            let words = entry.content.split_whitespace().count();
+            for person in entry.related.iter() {
+                let (entries, total_words) = notes.entry(person).or_default();
+                *entries += 1;
+                *total_words += words;
+            }
+        }
+    }
+
+    let prompt = &config.prompt;
+    let mut candidates: Vec<Candidate> = vec![];
+    for person in completions::list_people(log, config) {
+        let mut candidate = Candidate {
+            person,
+            reasons: vec![],
+            weight: 0,
+        };
+
+        let (entries, total_words) = notes.get(&candidate.person).copied().unwrap_or_default();
+        let words = total_words.checked_div(entries).unwrap_or(0);
+        if prompt.weights.sparse > 0 && (entries < SPARSE_ENTRIES || words < SPARSE_WORDS) {
+            candidate.reasons.push(Reason::Sparse { entries, words });
+            candidate.weight += prompt.weights.sparse;
+        }
+
+        let in_group = match &prompt.group {
+            Some(theme) => config
+                .get_person(&candidate.person)
+                .is_some_and(|person| person.themes.contains(theme)),
+            None => true,
+        };
+        if prompt.weights.group > 0 && in_group {
+            candidate.reasons.push(Reason::Group(prompt.group.clone()));
+            candidate.weight += prompt.weights.group;
+        }
+
+        if candidate.weight > 0 {
+            candidates.push(candidate);
+        }
+    }
+
+    candidates
+}
+
+/// Up to `count` different candidates, each drawn with a chance proportional
+/// to its weight
+pub fn pick(
+    mut candidates: Vec<Candidate>,
+    count: usize,
+    rng: &mut fastrand::Rng,
+) -> Vec<Candidate> {
+    let mut picked: Vec<Candidate> = vec![];
+    while picked.len() < count && !candidates.is_empty() {
+        let total: u32 = candidates.iter().map(|candidate| candidate.weight).sum();
+        let mut target = rng.u32(0..total);
+        let index = candidates
+            .iter()
+            .position(|candidate| match target < candidate.weight {
+                true => true,
+                false => {
+                    target -= candidate.weight;
+                    false
+                }
+            })
+            .expect("the target is below the total weight");
+        picked.push(candidates.remove(index));
+    }
+    picked
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::{Person, PromptConfig, PromptWeights};
+    use crate::log::parse_log_file_content;
+
+    use super::*;
+
+    #[test]
+    fn test_candidates() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe : lunch at the usual place, he told me all about the new job and
+              the move, and that his sister is expecting a baby in spring
+            - #JaneDoe : coffee
+
+            # 2000-01-02
+
+            - #JohnDoe : a long walk along the river, we talked about the trip to Japan
+              he is planning for the autumn and which cities are worth the time
+            - #JohnDoe : dinner with the whole family at his place, great food and even
+              better company, we stayed until late talking about old times
+            "
+        ));
+        let config = Config::builder()
+            .person(Person {
+                name: "Abu".to_string(),
+                themes: vec!["family".to_string()],
+                ..Default::default()
+            })
+            .prompt(PromptConfig {
+                group: Some("family".to_string()),
+                weights: PromptWeights::default(),
+            })
+            .build();
+
+        assert_eq!(
+            candidates(&log, &config),
+            vec![
+                Candidate {
+                    person: "Abu".to_string(),
+                    reasons: vec![
+                        Reason::Sparse {
+                            entries: 0,
+                            words: 0
+                        },
+                        Reason::Group(Some("family".to_string())),
+                    ],
+                    weight: 4,
+                },
+                Candidate {
+                    person: "JaneDoe".to_string(),
+                    reasons: vec![Reason::Sparse {
+                        entries: 1,
+                        words: 4
+                    }],
+                    weight: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pick() {
+        let candidate = |person: &str, weight: u32| Candidate {
+            person: person.to_string(),
+            reasons: vec![Reason::Group(None)],
+            weight,
+        };
+        let candidates = vec![candidate("Abu", 1), candidate("JaneDoe", 1000000)];
+
+        let picked: Vec<PersonName> = pick(candidates.clone(), 1, &mut fastrand::Rng::with_seed(1))
+            .into_iter()
+            .map(|candidate| candidate.person)
+            .collect();
+        assert_eq!(picked, vec!["JaneDoe".to_string()]);
+
+        assert_eq!(
+            pick(candidates, 5, &mut fastrand::Rng::with_seed(1)).len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use similar::TextDiff;
+
+use crate::config::Config;
+use crate::front_matter::strip_front_matter;
+use crate::links;
+use crate::log::{find_log_files, ParseOptions, HASHTAG_PATTERN, WIKILINK_PATTERN};
+use crate::model::PersonName;
+use crate::operations::Transaction;
+
+/// A log file with references to the renamed person
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenamedFile {
+    pub path: PathBuf,
+    pub references: usize,
+    pub before: String,
+    pub after: String,
+}
+
+impl RenamedFile {
+    /// Unified diff of the rename
+    pub fn diff(&self) -> String {
+        let path = self.path.display().to_string();
+        TextDiff::from_lines(&self.before, &self.after)
+            .unified_diff()
+            .header(&path, &path)
+            .to_string()
+    }
+}
+
+/// Whether `name` can be written as a hashtag
+pub fn is_valid_tag(name: &str) -> bool {
+    let re = Regex::new(&format!("^{HASHTAG_PATTERN}$")).unwrap();
+    re.is_match(&format!("#{name}"))
+}
+
+/// Ranges of the references in a line, with the name they refer to: whole
+/// hashtags (not the fragment of a URL) as written, and wikilinks with the
+/// name they point to
+fn references_in_line(line: &str, options: &ParseOptions) -> Vec<(Range<usize>, PersonName)> {
+    let links = links::find_link_spans(line);
+    let in_link = |position: usize| links.iter().any(|link| link.contains(&position));
+
+    let mut found: Vec<(Range<usize>, PersonName)> = vec![];
+
+    if options.references.hashtags {
+        let hashtag = Regex::new(HASHTAG_PATTERN).unwrap();
+        for captures in hashtag.captures_iter(line) {
+            let name = captures.get(1).expect("hashtags have a name");
+            if !in_link(name.start()) {
+                found.push((name.range(), name.as_str().to_string()));
+            }
+        }
+    }
+
+    if options.references.wikilinks {
+        let wikilink = Regex::new(WIKILINK_PATTERN).unwrap();
+        for captures in wikilink.captures_iter(line) {
+            let target = captures.get(1).expect("wikilinks have a target");
+            found.push((target.range(), options.wikilink_to_name(target.as_str())));
+        }
+    }
+
+    found.sort_by_key(|(range, _)| range.start);
+    found
+}
+
+/// Rewrite every reference for which `replacement` gives a new name, leaving
+/// the front matter and any other text untouched. Returns the new content and
+/// how many references were rewritten.
+pub(crate) fn replace_references(
+    content: &str,
+    options: &ParseOptions,
+    replacement: impl Fn(&str) -> Option<String>,
+) -> (String, usize) {
+    let body = strip_front_matter(content);
+    let mut replaced = content[..content.len() - body.len()].to_string();
+    let mut references = 0;
+
+    for (index, line) in body.split('\n').enumerate() {
+        if index > 0 {
+            replaced.push('\n');
+        }
+
+        let mut rest = 0;
+        for (range, name) in references_in_line(line, options) {
+            let new = match replacement(&name) {
+                Some(new) => new,
+                None => continue,
+            };
+            replaced.push_str(&line[rest..range.start]);
+            replaced.push_str(&new);
+            rest = range.end;
+            references += 1;
+        }
+        replaced.push_str(&line[rest..]);
+    }
+
+    (replaced, references)
+}
+
+/// Rewrite the references to `old` as `new`: whole hashtags only (not
+/// `#JaneDoes` when renaming `JaneDoe`) and wikilinks that point to it
+pub fn rename_in_content(
+    content: &str,
+    old: &str,
+    new: &str,
+    options: &ParseOptions,
+) -> (String, usize) {
+    replace_references(content, options, |name| {
+        (name == old).then(|| new.to_string())
+    })
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+    }
+}
+
+/// The log files that mention `old`, with their content once renamed
+pub fn plan_rename(
+    config: &Config,
+    old: &PersonName,
+    new: &PersonName,
+    options: &ParseOptions,
+) -> Result<Vec<RenamedFile>, String> {
+    let mut renamed: Vec<RenamedFile> = vec![];
+    for path in find_log_files(config) {
+        let before = read(&path)?;
+        let (after, references) = rename_in_content(&before, old, new, options);
+        if references == 0 {
+            continue;
+        }
+
+        renamed.push(RenamedFile {
+            path,
+            references,
+            before,
+            after,
+        });
+    }
+
+    Ok(renamed)
+}
+
+pub fn apply_rename(renamed: &[RenamedFile], transaction: &Transaction) -> Result<(), String> {
+    for file in renamed {
+        transaction.write(&file.path, &file.after)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::{ReferenceSyntax, WikilinkNames};
+
+    use super::*;
+
+    #[test]
+    fn test_rename_in_content() {
+        let content = indoc!(
+            "
+            ---
+            about: #JaneDoe
+            ---
+            # 2000-01-01
+
+            - #JaneDoe, #JaneDoes :
+              - with #JaneDoe at https://example.com/#JaneDoe
+              - see [[Jane Doe]] and [[Jane Doe|her]]
+            "
+        );
+        let options = ParseOptions {
+            references: ReferenceSyntax {
+                hashtags: true,
+                wikilinks: true,
+                wikilink_names: WikilinkNames::Join,
+            },
+            ..Default::default()
+        };
+
+        let expected = indoc!(
+            "
+            ---
+            about: #JaneDoe
+            ---
+            # 2000-01-01
+
+            - #JaneSmith, #JaneDoes :
+              - with #JaneSmith at https://example.com/#JaneDoe
+              - see [[JaneSmith]] and [[JaneSmith|her]]
+            "
+        );
+
+        assert_eq!(
+            rename_in_content(content, "JaneDoe", "JaneSmith", &options),
+            (expected.to_string(), 4)
+        );
+    }
+
+    #[test]
+    fn test_is_valid_tag() {
+        assert!(is_valid_tag("Lucía"));
+        assert!(!is_valid_tag("Jane Doe"));
+        assert!(!is_valid_tag(""));
+    }
+}
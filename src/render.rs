@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use tera::{Context, Tera};
+
+use crate::config::{Config, Person};
+use crate::contact;
+use crate::front_matter::render_front_matter;
+use crate::log::{Entry, Log};
+use crate::model::{DaysAgo, InteractionKind, Location, PersonName, Theme};
+use crate::use_cases::{self, LastInteraction};
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct InteractionContext {
+    pub person: PersonName,
+    pub last: NaiveDate,
+    pub days_ago: DaysAgo,
+    /// Days beyond the reach out threshold, if any
+    pub days_overdue: Option<DaysAgo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<InteractionKind>,
+    /// How to reach them, only in the reminders
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<String>,
+}
+
+impl InteractionContext {
+    pub fn new(interaction: &LastInteraction, today: NaiveDate) -> InteractionContext {
+        InteractionContext {
+            person: interaction.person.clone(),
+            last: interaction.last,
+            days_ago: interaction.ago(today),
+            days_overdue: interaction.days_beyond_reachout_threshold,
+            kind: interaction.kind.clone(),
+            contact: None,
+        }
+    }
+}
+
+fn interaction_contexts(
+    interactions: &[LastInteraction],
+    today: NaiveDate,
+) -> Vec<InteractionContext> {
+    interactions
+        .iter()
+        .map(|interaction| InteractionContext::new(interaction, today))
+        .collect()
+}
+
+/// Available to the `summary` template
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct SummaryContext {
+    pub today: NaiveDate,
+    pub pinned: Vec<InteractionContext>,
+    /// Pinned people with nothing logged
+    pub never_seen: Vec<PersonName>,
+    pub interactions: Vec<InteractionContext>,
+}
+
+impl SummaryContext {
+    pub fn new(
+        pinned: &[LastInteraction],
+        never_seen: &[PersonName],
+        interactions: &[LastInteraction],
+        today: NaiveDate,
+    ) -> SummaryContext {
+        SummaryContext {
+            today,
+            pinned: interaction_contexts(pinned, today),
+            never_seen: never_seen.to_vec(),
+            interactions: interaction_contexts(interactions, today),
+        }
+    }
+}
+
+/// Available to the `reminder` template
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct ReminderContext {
+    pub today: NaiveDate,
+    pub due: Vec<InteractionContext>,
+}
+
+impl ReminderContext {
+    pub fn new(due: &[LastInteraction], config: &Config, today: NaiveDate) -> ReminderContext {
+        let due = interaction_contexts(due, today)
+            .into_iter()
+            .map(|interaction| InteractionContext {
+                contact: config
+                    .get_person(&interaction.person)
+                    .and_then(contact::preferred_contact),
+                ..interaction
+            })
+            .collect();
+
+        ReminderContext { today, due }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, PartialEq)]
+pub struct PersonContext {
+    pub name: PersonName,
+    pub aliases: Vec<PersonName>,
+    pub location: Location,
+    pub themes: Vec<Theme>,
+    pub birthday: Option<NaiveDate>,
+}
+
+impl PersonContext {
+    pub fn new(name: &PersonName, metadata: Option<&Person>) -> PersonContext {
+        match metadata {
+            Some(person) => PersonContext {
+                name: name.clone(),
+                aliases: person.aliases.clone(),
+                location: person.location.clone(),
+                themes: person.themes.clone(),
+                birthday: person.birthday,
+            },
+            None => PersonContext {
+                name: name.clone(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct EntryContext {
+    pub date: NaiveDate,
+    pub main: Vec<PersonName>,
+    pub related: Vec<PersonName>,
+    pub content: String,
+    pub links: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<InteractionKind>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+    /// `path:line` of the entry in the logs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl EntryContext {
+    pub fn new(date: NaiveDate, entry: &Entry) -> EntryContext {
+        EntryContext {
+            date,
+            main: sorted(entry.main.iter().cloned()),
+            related: sorted(entry.related.iter().cloned()),
+            content: entry.content.clone(),
+            links: entry.links.clone(),
+            kind: entry.kind.clone(),
+            fields: entry.fields.clone(),
+            source: entry.location(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct StatsContext {
+    pub total_entries: usize,
+    pub first_interaction: Option<NaiveDate>,
+    pub last_interaction: Option<NaiveDate>,
+    /// Earliest entry about the person, rather than just mentioning them
+    pub first_met: Option<NaiveDate>,
+    /// Entries per interaction kind, e.g. `{"call": 3, "met": 1}`
+    pub kinds: BTreeMap<InteractionKind, usize>,
+}
+
+/// Available to the `per_person` template. `front_matter` and `log` hold the
+/// built-in rendering, so templates can wrap it instead of starting over.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct PersonLogContext {
+    pub person: PersonContext,
+    pub entries: Vec<EntryContext>,
+    pub stats: StatsContext,
+    pub front_matter: String,
+    pub log: String,
+}
+
+fn sorted(people: impl Iterator<Item = PersonName>) -> Vec<PersonName> {
+    let mut people: Vec<PersonName> = people.collect();
+    people.sort();
+    people
+}
+
+impl PersonLogContext {
+    pub fn new(person: &PersonName, log: &Log, config: &Config) -> PersonLogContext {
+        let entries: Vec<EntryContext> = log
+            .days
+            .iter()
+            .flat_map(|day| {
+                day.entries
+                    .iter()
+                    .map(|entry| EntryContext::new(day.date, entry))
+            })
+            .collect();
+
+        let mut kinds: BTreeMap<InteractionKind, usize> = BTreeMap::new();
+        for kind in entries.iter().filter_map(|entry| entry.kind.as_ref()) {
+            *kinds.entry(kind.clone()).or_default() += 1;
+        }
+
+        let stats = StatsContext {
+            total_entries: entries.len(),
+            first_interaction: log.days.iter().map(|day| day.date).min(),
+            last_interaction: log.days.iter().map(|day| day.date).max(),
+            first_met: use_cases::get_first_interactions(log).remove(person),
+            kinds,
+        };
+
+        PersonLogContext {
+            person: PersonContext::new(person, config.get_person(person)),
+            entries,
+            stats,
+            front_matter: render_front_matter(person, log, config),
+            log: format!("{log}"),
+        }
+    }
+}
+
+fn render_with(
+    template: &str,
+    context: &impl Serialize,
+    autoescape: bool,
+) -> Result<String, String> {
+    let context = match Context::from_serialize(context) {
+        Ok(context) => context,
+        Err(reason) => return Err(format!("failed to build template context: {reason}")),
+    };
+
+    match Tera::one_off(template, &context, autoescape) {
+        Ok(rendered) => Ok(rendered),
+        Err(reason) => {
+            // the top-level error rarely says what is wrong with the template
+            let mut message = reason.to_string();
+            let mut source = std::error::Error::source(&reason);
+            while let Some(cause) = source {
+                message = format!("{message}: {cause}");
+                source = cause.source();
+            }
+            Err(message)
+        }
+    }
+}
+
+/// Render a Tera template, e.g. `{% for i in interactions %}{{ i.person }}{% endfor %}`
+pub fn render_str(template: &str, context: &impl Serialize) -> Result<String, String> {
+    render_with(template, context, false)
+}
+
+/// Like `render_str`, but escaping the variables for HTML
+pub fn render_html_str(template: &str, context: &impl Serialize) -> Result<String, String> {
+    render_with(template, context, true)
+}
+
+pub fn render_file(path: &Path, context: &impl Serialize) -> Result<String, String> {
+    let template = match fs::read_to_string(path) {
+        Ok(template) => template,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+
+    match render_str(&template, context) {
+        Ok(rendered) => Ok(rendered),
+        Err(reason) => Err(format!("failed to render {path:?}, reason: {reason}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_render_summary() {
+        let interactions = vec![LastInteraction {
+            person: "JohnDoe".to_string(),
+            last: d("2000-01-01"),
+            days_beyond_reachout_threshold: Some(2),
+            kind: None,
+        }];
+        let context = SummaryContext::new(&[], &[], &interactions, d("2000-01-11"));
+
+        let template = indoc!(
+            "
+            {% for i in interactions -%}
+            {{ i.person }} {{ i.days_ago }} {{ i.days_overdue }}
+            {% endfor -%}
+            "
+        );
+
+        assert_eq!(
+            render_str(template, &context),
+            Ok("JohnDoe 10 2\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_render_person_log() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - stuff: blah
+
+            # 2000-01-02
+
+            - #JohnDoe (call):
+            "
+        ));
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .front_matter(vec![])
+            .build();
+        let context = PersonLogContext::new(&"JohnDoe".to_string(), &log, &config);
+
+        let template = "{{ person.name }}: {{ stats.total_entries }} entries since {{ stats.first_interaction }}, {{ stats.kinds.call }} calls";
+
+        assert_eq!(
+            render_str(template, &context),
+            Ok("JohnDoe: 2 entries since 2000-01-01, 1 calls".to_string())
+        );
+    }
+
+    #[test]
+    fn test_render_invalid_template() {
+        let config = Config::builder().people_dir("/tmp/people").build();
+        let context = ReminderContext::new(&[], &config, d("2000-01-01"));
+
+        assert!(render_str("{% for %}", &context).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+
+use crate::completions;
+use crate::config::Config;
+use crate::log::Log;
+use crate::model::PersonName;
+
+/// How a name typed on the command line matches the known people
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    Found(PersonName),
+    /// Closest first
+    Ambiguous(Vec<PersonName>),
+    NotFound,
+}
+
+/// Typos allowed before a name stops matching, e.g. 1 for `Jhon`
+fn max_distance(query: &str) -> usize {
+    (query.chars().count() / 3).max(1)
+}
+
+/// Ignore case and separators, so that `john doe` reads as `JohnDoe`
+fn simplify(name: &str) -> String {
+    name.chars()
+        .filter(|ch| !ch.is_whitespace() && !matches!(ch, '-' | '_' | '.'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Every way to refer to a person (their name and aliases), to its canonical name
+pub(crate) fn spellings(known: &[PersonName], config: &Config) -> Vec<(String, PersonName)> {
+    let mut spellings: Vec<(String, PersonName)> = known
+        .iter()
+        .map(|name| (name.clone(), name.clone()))
+        .collect();
+
+    for person in config.all_people() {
+        if !known.contains(&person.name) {
+            continue;
+        }
+        for alias in person.aliases.iter() {
+            spellings.push((alias.clone(), person.name.clone()));
+        }
+    }
+
+    spellings
+}
+
+/// Match `query` exactly, then ignoring case, then as part of a name or with typos
+pub fn match_person(query: &str, known: &[PersonName], config: &Config) -> Resolution {
+    let query = query.trim();
+    let spellings = spellings(known, config);
+
+    if let Some((_, name)) = spellings.iter().find(|(spelling, _)| spelling == query) {
+        return Resolution::Found(name.clone());
+    }
+
+    let simplified = simplify(query);
+
+    // closest distance per person
+    let mut distances: BTreeMap<&PersonName, usize> = BTreeMap::new();
+    for (spelling, name) in spellings.iter() {
+        let spelling = simplify(spelling);
+        let distance = if spelling == simplified {
+            0
+        } else if spelling.contains(&simplified) {
+            1
+        } else {
+            strsim::levenshtein(&spelling, &simplified) + 1
+        };
+
+        if distance > max_distance(&simplified) + 1 {
+            continue;
+        }
+
+        let closest = distances.entry(name).or_insert(distance);
+        *closest = (*closest).min(distance);
+    }
+
+    // a case-insensitive match beats any fuzzy one
+    if distances.values().any(|distance| *distance == 0) {
+        distances.retain(|_, distance| *distance == 0);
+    }
+
+    let mut candidates: Vec<(&PersonName, usize)> = distances.into_iter().collect();
+    candidates.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+    let candidates: Vec<PersonName> = candidates
+        .into_iter()
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    match candidates.as_slice() {
+        [] => Resolution::NotFound,
+        [name] => Resolution::Found(name.clone()),
+        _ => Resolution::Ambiguous(candidates),
+    }
+}
+
+/// Resolve a person name given as an argument to one of the people in the logs
+/// or the config. `choose` picks one of the candidates when the name is
+/// ambiguous, e.g. by prompting.
+pub fn resolve_person(
+    query: &str,
+    log: &Log,
+    config: &Config,
+    choose: impl FnOnce(&[PersonName]) -> Option<PersonName>,
+) -> Result<PersonName, String> {
+    let known = completions::list_people(log, config);
+
+    match match_person(query, &known, config) {
+        Resolution::Found(name) => Ok(name),
+        Resolution::Ambiguous(candidates) => match choose(&candidates) {
+            Some(name) => Ok(name),
+            None => Err(format!(
+                "{query:?} matches several people: {}",
+                candidates.join(", ")
+            )),
+        },
+        Resolution::NotFound => Err(format!("no person matches {query:?}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Person;
+    use crate::log::parse_log_file_content;
+
+    use super::*;
+
+    fn known() -> Vec<PersonName> {
+        vec![
+            "JaneDoe".to_string(),
+            "JohnDoe".to_string(),
+            "JohnSmith".to_string(),
+            "Lucía".to_string(),
+        ]
+    }
+
+    fn config() -> Config {
+        Config::builder()
+            .people_dir("/tmp/people")
+            .people(vec![Person {
+                name: "JohnSmith".to_string(),
+                aliases: vec!["Smithy".to_string()],
+                ..Default::default()
+            }])
+            .build()
+    }
+
+    #[test]
+    fn test_match_person() {
+        let known = known();
+        let config = config();
+        let found = |name: &str| Resolution::Found(name.to_string());
+
+        assert_eq!(match_person("JohnDoe", &known, &config), found("JohnDoe"));
+        assert_eq!(match_person("johndoe", &known, &config), found("JohnDoe"));
+        assert_eq!(match_person("smithy", &known, &config), found("JohnSmith"));
+        assert_eq!(match_person("Jhon Doe", &known, &config), found("JohnDoe"));
+        assert_eq!(match_person("lucia", &known, &config), found("Lucía"));
+        assert_eq!(match_person("Zoe", &known, &config), Resolution::NotFound);
+        assert_eq!(
+            match_person("john", &known, &config),
+            Resolution::Ambiguous(vec!["JohnDoe".to_string(), "JohnSmith".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_resolve_ambiguous_person() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - with #JaneDoe
+            "
+        ));
+        let config = config();
+
+        let chosen = resolve_person("john", &log, &config, |candidates| {
+            assert_eq!(candidates, ["JohnDoe", "JohnSmith"]);
+            candidates.last().cloned()
+        });
+        assert_eq!(chosen, Ok("JohnSmith".to_string()));
+
+        let unchosen = resolve_person("Doe", &log, &config, |_| None);
+        assert!(unchosen.is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{Duration, NaiveDate};
+
+use crate::config::Config;
+use crate::digest::Period;
+use crate::log::Log;
+use crate::model::PersonName;
+
+/// Previous periods looked at to tell who you usually see
+pub const HISTORY_PERIODS: i64 = 12;
+
+/// Someone you usually see, but didn't in the period under review
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Missed {
+    pub person: PersonName,
+    /// Previous periods in which you interacted with them
+    pub periods_seen: i64,
+    pub last: NaiveDate,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Review {
+    pub period: Period,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// People interacted with each day, oldest day first
+    pub days: Vec<(NaiveDate, Vec<PersonName>)>,
+    pub missed: Vec<Missed>,
+}
+
+/// People you interacted with in the period ending `today`, and the people
+/// seen in at least half of the previous periods who are missing from it
+pub fn build_review(log: &Log, config: &Config, today: NaiveDate, period: Period) -> Review {
+    let length = period.days();
+    let start = today - Duration::days(length - 1);
+    let history_start = start - Duration::days(length * HISTORY_PERIODS);
+
+    let mut days: Vec<(NaiveDate, Vec<PersonName>)> = vec![];
+    let mut seen: HashSet<&PersonName> = HashSet::new();
+    // person -> (previous periods they were seen in, last seen)
+    let mut history: BTreeMap<&PersonName, (HashSet<i64>, NaiveDate)> = BTreeMap::new();
+
+    for day in log.days.iter() {
+        let people = day
+            .entries
+            .iter()
+            .flat_map(|entry| entry.interacted())
+            .filter(|person| !config.is_ignored(person));
+
+        if start <= day.date && day.date <= today {
+            let mut day_people: Vec<PersonName> = vec![];
+            for person in people {
+                seen.insert(person);
+                if !day_people.contains(person) {
+                    day_people.push(person.clone());
+                }
+            }
+            if !day_people.is_empty() {
+                days.push((day.date, day_people));
+            }
+        } else if history_start <= day.date && day.date < start {
+            // the day before `start` is the last of the previous period (0)
+            let periods_ago = ((start - day.date).num_days() - 1) / length;
+            for person in people {
+                let (periods, last) = history
+                    .entry(person)
+                    .or_insert_with(|| (HashSet::new(), day.date));
+                periods.insert(periods_ago);
+                *last = (*last).max(day.date);
+            }
+        }
+    }
+
+    days.sort_by_key(|(date, _)| *date);
+
+    let mut missed: Vec<Missed> = history
+        .into_iter()
+        .filter(|(person, (periods, _))| {
+            !seen.contains(person) && periods.len() as i64 * 2 >= HISTORY_PERIODS
+        })
+        .map(|(person, (periods, last))| Missed {
+            person: person.clone(),
+            periods_seen: periods.len() as i64,
+            last,
+        })
+        .collect();
+    missed.sort_by(|a, b| {
+        b.periods_seen
+            .cmp(&a.periods_seen)
+            .then(b.last.cmp(&a.last))
+    });
+
+    Review {
+        period,
+        start,
+        end: today,
+        days,
+        missed,
+    }
+}
+
+pub fn to_markdown(review: &Review) -> String {
+    let period = review.period;
+    let mut sections: Vec<String> = vec![format!("# Review: {} to {}", review.start, review.end)];
+
+    let mut seen = vec!["## Seen".to_string()];
+    if review.days.is_empty() {
+        seen.push("".to_string());
+        seen.push(format!("Nobody this {period}."));
+    }
+    for (date, people) in review.days.iter() {
+        seen.push("".to_string());
+        seen.push(format!("### {date}"));
+        seen.push("".to_string());
+        for person in people {
+            seen.push(format!("- {person}"));
+        }
+    }
+    sections.push(seen.join("\n"));
+
+    let mut missed = vec![
+        format!("## Not seen this {period}, but usually are"),
+        "".to_string(),
+    ];
+    if review.missed.is_empty() {
+        missed.push("Nobody.".to_string());
+    }
+    for person in review.missed.iter() {
+        missed.push(format!(
+            "- {}: seen in {} of the previous {HISTORY_PERIODS} {period}s, last on {}",
+            person.person, person.periods_seen, person.last
+        ));
+    }
+    sections.push(missed.join("\n"));
+
+    format!("{}\n", sections.join("\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_review_to_markdown() {
+        let mut content = String::new();
+        // JaneDoe used to be seen every week, Abu only once
+        for week in 1..=8 {
+            let date = d("2000-03-01") - Duration::weeks(week);
+            content.push_str(&format!("# {date}\n\n- #JaneDoe :\n  - coffee\n\n"));
+        }
+        content.push_str(indoc!(
+            "
+            # 2000-02-10
+
+            - #Abu :
+              - lunch
+
+            # 2000-02-28
+
+            - #JohnDoe, #Me :
+              - dinner
+            - #JohnDoe :
+              - call
+
+            # 2000-03-01
+
+            - #Abu :
+              - walk
+            "
+        ));
+        let log = parse_log_file_content(&content);
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .ignore(vec!["Me".to_string()])
+            .build();
+
+        let review = build_review(&log, &config, d("2000-03-01"), Period::Week);
+
+        let expected = indoc!(
+            "
+            # Review: 2000-02-24 to 2000-03-01
+
+            ## Seen
+
+            ### 2000-02-28
+
+            - JohnDoe
+
+            ### 2000-03-01
+
+            - Abu
+
+            ## Not seen this week, but usually are
+
+            - JaneDoe: seen in 8 of the previous 12 weeks, last on 2000-02-23
+            "
+        )
+        .trim_start();
+
+        assert_eq!(to_markdown(&review), expected);
+    }
+
+    #[test]
+    fn test_build_review_counts_whole_periods() {
+        let mut content = String::new();
+        // the first and last day of each of the previous 6 weeks
+        for week in 0..6 {
+            let last = d("2000-02-24") - Duration::days(1 + 7 * week);
+            let first = last - Duration::days(6);
+            for date in [first, last] {
+                content.push_str(&format!("# {date}\n\n- #JaneDoe :\n  - coffee\n\n"));
+            }
+        }
+        let log = parse_log_file_content(&content);
+        let config = Config::builder().people_dir("/tmp/people").build();
+
+        let review = build_review(&log, &config, d("2000-03-01"), Period::Week);
+
+        assert_eq!(
+            review.missed,
+            vec![Missed {
+                person: "JaneDoe".to_string(),
+                periods_seen: 6,
+                last: d("2000-02-23"),
+            }]
+        );
+    }
+}
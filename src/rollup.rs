@@ -0,0 +1,173 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::config::Config;
+use crate::log::{self, DatePrecision, Entry, Log};
+use crate::model::{InteractionKind, PersonName};
+
+/// Monday of an ISO week like `2024-W20`
+pub fn parse_week(text: &str) -> Result<NaiveDate, String> {
+    match log::parse_header_date(text) {
+        Some((date, DatePrecision::Week)) => Ok(date),
+        _ => Err(format!("expected a week like 2024-W20, but found {text:?}")),
+    }
+}
+
+/// Monday of the week before the one of `today`
+pub fn last_week(today: NaiveDate) -> NaiveDate {
+    let monday = today - Duration::days(today.weekday().num_days_from_monday().into());
+    monday - Duration::weeks(1)
+}
+
+/// The entries of the week starting on `monday`, with the date they were
+/// logged on. Entries logged for the whole week are dated on its Monday.
+fn week_entries(log: &Log, monday: NaiveDate) -> Vec<(NaiveDate, &Entry)> {
+    let sunday = monday + Duration::days(6);
+    let mut entries: Vec<(NaiveDate, &Entry)> = log
+        .days
+        .iter()
+        .filter(|day| match day.precision {
+            DatePrecision::Day => monday <= day.date && day.date <= sunday,
+            DatePrecision::Week => day.date == monday,
+            DatePrecision::Month => false,
+        })
+        .flat_map(|day| {
+            day.entries
+                .iter()
+                .map(move |entry| (day.date, entry.as_ref()))
+        })
+        .collect();
+    entries.sort_by_key(|(date, _)| *date);
+    entries
+}
+
+/// Markdown with the entries of the week starting on `monday`, grouped by
+/// person, most seen first. Rendering the same log twice gives the same file.
+pub fn render_rollup(log: &Log, config: &Config, monday: NaiveDate) -> String {
+    let sunday = monday + Duration::days(6);
+    let entries = week_entries(log, monday);
+
+    let mut per_person: BTreeMap<&PersonName, Vec<(NaiveDate, &Entry)>> = BTreeMap::new();
+    let mut kinds: BTreeMap<&InteractionKind, usize> = BTreeMap::new();
+    for (date, entry) in entries.iter() {
+        for person in entry
+            .main
+            .iter()
+            .filter(|person| !config.is_ignored(person))
+        {
+            per_person.entry(person).or_default().push((*date, *entry));
+        }
+        if let Some(kind) = &entry.kind {
+            *kinds.entry(kind).or_default() += 1;
+        }
+    }
+    let mut people: Vec<_> = per_person.into_iter().collect();
+    people.sort_by_key(|(person, entries)| (Reverse(entries.len()), *person));
+
+    let mut rollup = format!(
+        "# Week {}\n\nFrom {monday} to {sunday}.\n\n",
+        DatePrecision::Week.header(monday)
+    );
+    rollup.push_str(&format!("- Entries: {}\n", entries.len()));
+    rollup.push_str(&format!("- People: {}\n", people.len()));
+    for (kind, count) in kinds {
+        rollup.push_str(&format!("- {kind}: {count}\n"));
+    }
+
+    for (person, entries) in people {
+        rollup.push_str(&format!("\n## {person} ({})\n", entries.len()));
+        let mut last_date: Option<NaiveDate> = None;
+        for (date, entry) in entries {
+            if last_date != Some(date) {
+                rollup.push_str(&format!("\n### {date}\n\n"));
+                last_date = Some(date);
+            }
+            rollup.push_str(&format!("{}\n", entry.content));
+        }
+    }
+
+    rollup
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_week() {
+        assert_eq!(parse_week("2024-W20"), Ok(d("2024-05-13")));
+        assert!(parse_week("2024-05-13").is_err());
+        assert_eq!(last_week(d("2024-05-22")), d("2024-05-13"));
+        assert_eq!(last_week(d("2024-05-20")), d("2024-05-13"));
+    }
+
+    #[test]
+    fn test_render_rollup() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2024-05-12
+
+            - #JaneDoe : too early
+
+            # 2024-W20
+
+            - #Abu : sometime this week
+
+            # 2024-05-14
+
+            - #JaneDoe (call):
+              - about the move
+            - #JaneDoe, #JohnDoe : dinner
+
+            # 2024-05-20
+
+            - #JaneDoe : too late
+            "
+        ));
+
+        let expected = indoc!(
+            "
+            # Week 2024-W20
+
+            From 2024-05-13 to 2024-05-19.
+
+            - Entries: 3
+            - People: 3
+            - call: 1
+
+            ## JaneDoe (2)
+
+            ### 2024-05-14
+
+            - #JaneDoe (call):
+              - about the move
+            - #JaneDoe, #JohnDoe : dinner
+
+            ## Abu (1)
+
+            ### 2024-05-13
+
+            - #Abu : sometime this week
+
+            ## JohnDoe (1)
+
+            ### 2024-05-14
+
+            - #JaneDoe, #JohnDoe : dinner
+            "
+        );
+
+        assert_eq!(
+            render_rollup(&log, &Config::builder().build(), d("2024-05-13")),
+            expected
+        );
+    }
+}
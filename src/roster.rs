@@ -0,0 +1,153 @@
+use crate::config::{Config, Person, ReminderInterval};
+use crate::model::{Location, PersonName, Theme};
+
+/// Changes to a person's entry in the config. Groups are themes, like the
+/// `group` of `people prompt`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PersonChanges {
+    pub location: Option<Location>,
+    pub remind_after: Option<ReminderInterval>,
+    pub add_groups: Vec<Theme>,
+    pub remove_groups: Vec<Theme>,
+}
+
+impl PersonChanges {
+    pub fn is_empty(&self) -> bool {
+        self == &PersonChanges::default()
+    }
+
+    fn apply(&self, person: &mut Person) {
+        if let Some(location) = &self.location {
+            person.location = location.clone();
+        }
+        if let Some(remind_after) = &self.remind_after {
+            person.remind_after = Some(remind_after.clone());
+        }
+        person
+            .themes
+            .retain(|theme| !self.remove_groups.contains(theme));
+        for group in self.add_groups.iter() {
+            if !person.themes.contains(group) {
+                person.themes.push(group.clone());
+            }
+        }
+    }
+}
+
+/// The `people` section with a new person at the end
+pub fn add(
+    config: &Config,
+    name: &PersonName,
+    changes: &PersonChanges,
+) -> Result<Vec<Person>, String> {
+    if let Some(known) = config
+        .all_people()
+        .find(|person| &person.name == name || person.aliases.contains(name))
+    {
+        return Err(match &known.name == name {
+            true => format!("{name} is already in the config"),
+            false => format!("{name} is already an alias of {}", known.name),
+        });
+    }
+
+    let mut person = Person {
+        name: name.clone(),
+        ..Default::default()
+    };
+    changes.apply(&mut person);
+
+    let mut people = config.people.clone();
+    people.push(person);
+    Ok(people)
+}
+
+/// The `people` and `archived` sections once `name` is changed, wherever it is
+pub fn edit(
+    config: &Config,
+    name: &PersonName,
+    changes: &PersonChanges,
+) -> Result<(Vec<Person>, Vec<Person>), String> {
+    if config.get_person(name).is_none() {
+        return Err(format!("{name} is not in the config"));
+    }
+
+    let mut people = config.people.clone();
+    let mut archived = config.archived.clone();
+    for person in people.iter_mut().chain(archived.iter_mut()) {
+        if &person.name == name {
+            changes.apply(person);
+        }
+    }
+
+    Ok((people, archived))
+}
+
+/// The `people` and `archived` sections without `name`. Their logs are kept.
+pub fn remove(config: &Config, name: &PersonName) -> Result<(Vec<Person>, Vec<Person>), String> {
+    if config.get_person(name).is_none() {
+        return Err(format!("{name} is not in the config"));
+    }
+
+    let keep = |person: &&Person| &person.name != name;
+    Ok((
+        config.people.iter().filter(keep).cloned().collect(),
+        config.archived.iter().filter(keep).cloned().collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_add_edit_and_remove() {
+        let config = Config::builder()
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                aliases: vec!["Jane".to_string()],
+                ..Default::default()
+            })
+            .archived(Person {
+                name: "Abu".to_string(),
+                themes: vec!["work".to_string()],
+                ..Default::default()
+            })
+            .build();
+        let changes = PersonChanges {
+            location: Some("Berlin".to_string()),
+            remind_after: Some("2 weeks".parse().unwrap()),
+            add_groups: vec!["family".to_string()],
+            ..Default::default()
+        };
+
+        let people = add(&config, &"JohnDoe".to_string(), &changes).unwrap();
+        assert_eq!(
+            people[1],
+            Person {
+                name: "JohnDoe".to_string(),
+                location: "Berlin".to_string(),
+                remind_after: Some("2 weeks".parse().unwrap()),
+                themes: vec!["family".to_string()],
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            add(&config, &"Jane".to_string(), &changes),
+            Err("Jane is already an alias of JaneDoe".to_string())
+        );
+
+        let changes = PersonChanges {
+            add_groups: vec!["climbing".to_string()],
+            remove_groups: vec!["work".to_string()],
+            ..Default::default()
+        };
+        let (_, archived) = edit(&config, &"Abu".to_string(), &changes).unwrap();
+        assert_eq!(archived[0].themes, vec!["climbing".to_string()]);
+        assert!(edit(&config, &"Zoe".to_string(), &changes).is_err());
+
+        let (people, archived) = remove(&config, &"Abu".to_string()).unwrap();
+        assert_eq!((people.len(), archived.len()), (1, 0));
+    }
+}
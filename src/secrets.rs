@@ -0,0 +1,34 @@
+use std::process::Command;
+
+/// Use the password as is, or else the first line printed by the command
+pub fn resolve_password(
+    password: &Option<String>,
+    password_command: &Option<String>,
+) -> Result<String, String> {
+    if let Some(password) = password {
+        return Ok(password.clone());
+    }
+
+    let command = match password_command {
+        Some(command) => command,
+        None => return Err("either password or password_command must be set".to_string()),
+    };
+
+    let output = match Command::new("sh").arg("-c").arg(command).output() {
+        Ok(output) => output,
+        Err(reason) => return Err(format!("failed to run {command:?}, reason: {reason}")),
+    };
+
+    if !output.status.success() {
+        return Err(format!(
+            "failed to run {command:?}, it exited with {}",
+            output.status
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().next() {
+        Some(password) => Ok(password.to_string()),
+        None => Err(format!("{command:?} did not print any password")),
+    }
+}
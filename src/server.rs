@@ -0,0 +1,362 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::json;
+use tiny_http::{Header, Request, Response, Server};
+use tracing::debug;
+
+use crate::config::Config;
+use crate::log::{self, Log};
+use crate::metrics;
+use crate::model::PersonName;
+use crate::render::{EntryContext, InteractionContext, SummaryContext};
+use crate::state::State;
+use crate::use_cases;
+use crate::workspace::Workspace;
+
+const JSON: &str = "application/json; charset=utf-8";
+
+#[derive(Debug, PartialEq)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+fn ok(value: &impl Serialize) -> ApiResponse {
+    ApiResponse {
+        status: 200,
+        content_type: JSON,
+        body: serde_json::to_string(value).expect("responses should be serializable"),
+    }
+}
+
+fn error(status: u16, message: &str) -> ApiResponse {
+    ApiResponse {
+        status,
+        content_type: JSON,
+        body: json!({ "error": message }).to_string(),
+    }
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decode `%C3%AD` and `+` in URL paths and query values
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded: Vec<u8> = vec![];
+
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            // the `%` and both hex digits fit, even at the very end
+            b'%' if index + 3 <= bytes.len() => {
+                match (hex_value(bytes[index + 1]), hex_value(bytes[index + 2])) {
+                    (Some(high), Some(low)) => {
+                        decoded.push(high * 16 + low);
+                        index += 2;
+                    }
+                    _ => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn query_param(query: &str, key: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| percent_decode(value))
+}
+
+#[derive(Serialize)]
+struct PersonSummary {
+    name: PersonName,
+    last_interaction: Option<NaiveDate>,
+}
+
+fn people(workspace: &Workspace) -> ApiResponse {
+    let people: Vec<PersonSummary> = workspace
+        .people()
+        .iter()
+        .map(|name| PersonSummary {
+            name: name.clone(),
+            last_interaction: workspace
+                .last_interaction(name)
+                .map(|interaction| interaction.last),
+        })
+        .collect();
+
+    ok(&people)
+}
+
+fn person_log(workspace: &Workspace, person: &PersonName) -> ApiResponse {
+    let entries: Vec<EntryContext> = workspace
+        .entries_of(person)
+        .into_iter()
+        .map(|(date, entry)| EntryContext::new(date, entry))
+        .collect();
+
+    ok(&entries)
+}
+
+fn entries_matching(log: &Log, matches: impl Fn(&log::Entry) -> bool) -> Vec<EntryContext> {
+    log.days
+        .iter()
+        .flat_map(|day| {
+            day.entries
+                .iter()
+                .filter(|entry| matches(entry))
+                .map(|entry| EntryContext::new(day.date, entry))
+        })
+        .collect()
+}
+
+fn summary(workspace: &Workspace, today: NaiveDate) -> ApiResponse {
+    let mut interactions = workspace.interactions(today);
+    interactions.sort_by(|a, b| b.last.cmp(&a.last).then_with(|| a.person.cmp(&b.person)));
+
+    let (pinned, rest) = use_cases::split_pinned(interactions, workspace.config());
+    let never_seen = use_cases::pinned_never_seen(&pinned, workspace.config());
+    ok(&SummaryContext::new(&pinned, &never_seen, &rest, today))
+}
+
+fn reachouts(workspace: &Workspace, today: NaiveDate) -> ApiResponse {
+    let interactions = workspace.interactions(today);
+    let due = use_cases::due_reminders(interactions, workspace.config(), &State::default(), today);
+    let due: Vec<InteractionContext> = due
+        .iter()
+        .map(|interaction| InteractionContext::new(interaction, today))
+        .collect();
+
+    ok(&due)
+}
+
+fn metrics(workspace: &Workspace, today: NaiveDate) -> ApiResponse {
+    let interactions = workspace.interactions(today);
+    ApiResponse {
+        status: 200,
+        content_type: metrics::CONTENT_TYPE,
+        body: metrics::render_metrics(workspace.log(), &interactions, today),
+    }
+}
+
+/// Answer a request to `url` (path and query)
+pub fn route(method: &str, url: &str, workspace: &Workspace, today: NaiveDate) -> ApiResponse {
+    if method != "GET" {
+        return error(405, "only GET is supported");
+    }
+
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(percent_decode)
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(|segment| segment.as_str()).collect();
+
+    match segments.as_slice() {
+        ["people"] => people(workspace),
+        ["people", name, "log"] => person_log(workspace, &name.to_string()),
+        ["summary"] => summary(workspace, today),
+        ["reachouts"] => reachouts(workspace, today),
+        ["metrics"] => metrics(workspace, today),
+        ["search"] => match query_param(query, "q") {
+            Some(q) if !q.is_empty() => {
+                let q = q.to_lowercase();
+                ok(&entries_matching(workspace.log(), |entry| {
+                    entry.content.to_lowercase().contains(&q)
+                }))
+            }
+            _ => error(400, "missing query parameter: q"),
+        },
+        _ => error(404, "not found"),
+    }
+}
+
+fn respond(request: Request, response: ApiResponse) {
+    let header = Header::from_bytes("Content-Type", response.content_type)
+        .expect("the header should be valid");
+    let http_response = Response::from_string(response.body)
+        .with_status_code(response.status)
+        .with_header(header);
+
+    if let Err(reason) = request.respond(http_response) {
+        debug!("failed to respond, reason: {reason}");
+    }
+}
+
+/// Serve the read-only API until the process is stopped
+/// `today` pins the date, otherwise it is worked out on every request
+pub fn serve(
+    config: &Config,
+    address: &str,
+    watch: bool,
+    today: Option<NaiveDate>,
+) -> Result<(), String> {
+    let server = match Server::http(address) {
+        Ok(server) => server,
+        Err(reason) => return Err(format!("failed to listen on {address}, reason: {reason}")),
+    };
+
+    let mut workspace = Workspace::load(config.clone())?;
+
+    for request in server.incoming_requests() {
+        if watch {
+            workspace.refresh();
+        }
+
+        let today = today.unwrap_or_else(|| workspace.config().today());
+        let method = request.method().as_str().to_string();
+        let response = route(&method, request.url(), &workspace, today);
+        debug!(
+            "{} {} -> {}",
+            request.method(),
+            request.url(),
+            response.status
+        );
+
+        respond(request, response);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    fn workspace() -> Workspace {
+        let config = Config::builder().people_dir("/tmp/people").build();
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - went climbing with #Lucía
+
+            # 2000-01-02
+
+            - #Lucía :
+              - coffee
+            "
+        ));
+
+        Workspace::from_log(config, log)
+    }
+
+    #[test]
+    fn test_route_person_log() {
+        let response = route(
+            "GET",
+            "/people/Luc%C3%ADa/log",
+            &workspace(),
+            d("2000-01-03"),
+        );
+
+        let expected = json!([
+            {
+                "date": "2000-01-01",
+                "main": ["JohnDoe"],
+                "related": ["JohnDoe", "Lucía"],
+                "content": "- #JohnDoe :\n  - went climbing with #Lucía",
+                "links": [],
+            },
+            {
+                "date": "2000-01-02",
+                "main": ["Lucía"],
+                "related": ["Lucía"],
+                "content": "- #Lucía :\n  - coffee",
+                "links": [],
+            },
+        ]);
+
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&response.body).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_route_search() {
+        let response = route("GET", "/search?q=CLIMBING", &workspace(), d("2000-01-03"));
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        assert_eq!(body[0]["date"], "2000-01-01");
+    }
+
+    #[test]
+    fn test_route_metrics() {
+        let response = route("GET", "/metrics", &workspace(), d("2000-01-03"));
+
+        assert_eq!(response.content_type, metrics::CONTENT_TYPE);
+        assert!(response
+            .body
+            .contains("people_days_since_last_contact{person=\"Lucía\"} 1\n"));
+    }
+
+    #[test]
+    fn test_route_errors() {
+        assert_eq!(
+            route("GET", "/search", &workspace(), d("2000-01-03")).status,
+            400
+        );
+        assert_eq!(
+            route("GET", "/nope", &workspace(), d("2000-01-03")).status,
+            404
+        );
+        assert_eq!(
+            route("POST", "/people", &workspace(), d("2000-01-03")).status,
+            405
+        );
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("Luc%C3%ADa+Doe"), "Lucía Doe");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("100%A"), "100%A");
+        assert_eq!(percent_decode("Jos%C3%A9"), "José");
+    }
+
+    #[test]
+    fn test_route_person_log_ending_in_an_escape() {
+        let config = Config::builder().people_dir("/tmp/people").build();
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #José : coffee
+            "
+        ));
+        let workspace = Workspace::from_log(config, log);
+
+        let response = route("GET", "/people/Jos%C3%A9/log", &workspace, d("2000-01-03"));
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(body[0]["main"], json!(["José"]));
+    }
+}
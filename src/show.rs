@@ -0,0 +1,164 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::log::{Day, Log};
+use crate::model::PersonName;
+use crate::render::EntryContext;
+use crate::use_cases;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShowFormat {
+    Markdown,
+    Json,
+}
+
+impl FromStr for ShowFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "md" => Ok(ShowFormat::Markdown),
+            "json" => Ok(ShowFormat::Json),
+            other => Err(format!(
+                "unsupported format found: {other:?}, expected 'md' or 'json'"
+            )),
+        }
+    }
+}
+
+/// Keep the entries on or after `since`, and then only the `last` ones
+fn trim(log: Log, since: Option<NaiveDate>, last: Option<usize>) -> Log {
+    let mut days: Vec<Day> = log
+        .days
+        .into_iter()
+        .filter(|day| since.is_none_or(|since| day.date >= since))
+        .collect();
+
+    if let Some(last) = last {
+        let mut remaining = last;
+        for day in days.iter_mut().rev() {
+            let keep = day.entries.len().min(remaining);
+            day.entries.drain(..day.entries.len() - keep);
+            remaining -= keep;
+        }
+        days.retain(|day| !day.entries.is_empty());
+    }
+
+    Log { days }
+}
+
+/// The same log `people_per_person` writes for the person, optionally trimmed.
+/// `None` if the person is not in the logs or is ignored.
+pub fn person_history(
+    log: Log,
+    person: &PersonName,
+    config: &Config,
+    since: Option<NaiveDate>,
+    last: Option<usize>,
+) -> Option<Log> {
+    let mut per_person = use_cases::split_log_per_person(log, config);
+    let history = per_person.remove(person).flatten()?;
+
+    Some(trim(history, since, last))
+}
+
+#[derive(Serialize)]
+struct PersonHistory<'a> {
+    person: &'a PersonName,
+    entries: Vec<EntryContext>,
+}
+
+pub fn to_json(person: &PersonName, history: &Log) -> String {
+    let entries: Vec<EntryContext> = history
+        .days
+        .iter()
+        .flat_map(|day| {
+            day.entries
+                .iter()
+                .map(|entry| EntryContext::new(day.date, entry))
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&PersonHistory { person, entries })
+        .expect("the history should be serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    fn log() -> Log {
+        parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - climbing
+
+            # 2000-01-02
+
+            - #JaneDoe :
+              - with #JohnDoe
+            - #JohnDoe :
+              - coffee
+
+            # 2000-01-03
+
+            - #JohnDoe :
+              - dinner
+            "
+        ))
+    }
+
+    #[test]
+    fn test_person_history_last_entries() {
+        let config = Config::builder().people_dir("/tmp/people").build();
+
+        let history =
+            person_history(log(), &"JohnDoe".to_string(), &config, None, Some(2)).unwrap();
+
+        let expected = indoc!(
+            "
+            # 2000-01-02
+
+            - #JohnDoe :
+              - coffee
+
+            # 2000-01-03
+
+            - #JohnDoe :
+              - dinner
+            "
+        );
+
+        assert_eq!(format!("{history}"), expected.trim_start());
+    }
+
+    #[test]
+    fn test_person_history_since() {
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .ignore(vec!["JaneDoe".to_string()])
+            .build();
+        let john = "JohnDoe".to_string();
+
+        let history = person_history(log(), &john, &config, Some(d("2000-01-02")), None).unwrap();
+        let dates: Vec<NaiveDate> = history.days.iter().map(|day| day.date).collect();
+
+        assert_eq!(dates, vec![d("2000-01-02"), d("2000-01-03")]);
+        assert_eq!(history.days[0].entries.len(), 2);
+        assert_eq!(
+            person_history(log(), &"JaneDoe".to_string(), &config, None, None),
+            None
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::co_mentions::CoMentions;
+use crate::config::Config;
+use crate::log::Log;
+use crate::model::PersonName;
+use crate::render::{self, PersonLogContext};
+use crate::use_cases;
+
+static DEFAULT_INDEX_TEMPLATE: &str = include_str!("templates/site_index.html");
+static DEFAULT_PERSON_TEMPLATE: &str = include_str!("templates/site_person.html");
+
+/// File name of a person's page, e.g. `JaneDoe.html`
+pub fn page_name(person: &PersonName) -> String {
+    let slug: String = person
+        .chars()
+        .map(|ch| match ch {
+            '/' | '\\' | ' ' => '-',
+            ch => ch,
+        })
+        .collect();
+    format!("{slug}.html")
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct PersonLink {
+    pub name: PersonName,
+    pub href: String,
+    /// Entries in which both people are mentioned
+    pub times: usize,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct IndexPerson {
+    pub name: PersonName,
+    pub href: String,
+    pub last_interaction: Option<NaiveDate>,
+    pub total_entries: usize,
+}
+
+/// Available to the `site_index` template
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct IndexContext {
+    pub today: NaiveDate,
+    pub people: Vec<IndexPerson>,
+}
+
+/// Available to the `site_person` template: the same as `per_person`, with the
+/// entries most recent first, plus the people mentioned in them
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct PersonPageContext {
+    #[serde(flatten)]
+    pub page: PersonLogContext,
+    pub co_mentioned: Vec<PersonLink>,
+}
+
+pub(crate) fn co_mentioned(person: &PersonName, log: &Log, config: &Config) -> Vec<PersonLink> {
+    CoMentions::new(log, config)
+        .with(person)
+        .into_iter()
+        .map(|(name, times)| PersonLink {
+            href: page_name(&name),
+            name,
+            times,
+        })
+        .collect()
+}
+
+/// A rendered page, with its path relative to the output directory
+#[derive(Clone, Debug, PartialEq)]
+pub struct Page {
+    pub path: PathBuf,
+    pub html: String,
+}
+
+pub struct SiteTemplates {
+    pub index: String,
+    pub person: String,
+}
+
+impl SiteTemplates {
+    fn read(path: &Option<PathBuf>, default: &str) -> Result<String, String> {
+        match path {
+            Some(path) => match fs::read_to_string(path) {
+                Ok(template) => Ok(template),
+                Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+            },
+            None => Ok(default.to_string()),
+        }
+    }
+
+    /// The templates in the config, or else the built-in ones
+    pub fn from_config(config: &Config) -> Result<SiteTemplates, String> {
+        Ok(SiteTemplates {
+            index: SiteTemplates::read(&config.templates.site_index, DEFAULT_INDEX_TEMPLATE)?,
+            person: SiteTemplates::read(&config.templates.site_person, DEFAULT_PERSON_TEMPLATE)?,
+        })
+    }
+}
+
+/// Render the index page and one page per person
+pub fn build_site(
+    log: Log,
+    config: &Config,
+    templates: &SiteTemplates,
+    today: NaiveDate,
+) -> Result<Vec<Page>, String> {
+    let mut per_person: Vec<(PersonName, Log)> = use_cases::split_log_per_person(log, config)
+        .into_iter()
+        .filter_map(|(person, log)| log.map(|log| (person, log)))
+        .collect();
+    per_person.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut pages: Vec<Page> = vec![];
+    let mut index: Vec<IndexPerson> = vec![];
+
+    for (person, person_log) in per_person.iter() {
+        let mut page = PersonLogContext::new(person, person_log, config);
+        page.entries.reverse();
+
+        index.push(IndexPerson {
+            name: person.clone(),
+            href: page_name(person),
+            last_interaction: page.stats.last_interaction,
+            total_entries: page.stats.total_entries,
+        });
+
+        let context = PersonPageContext {
+            page,
+            co_mentioned: co_mentioned(person, person_log, config),
+        };
+        let html = match render::render_html_str(&templates.person, &context) {
+            Ok(html) => html,
+            Err(reason) => return Err(format!("failed to render {person}'s page: {reason}")),
+        };
+        pages.push(Page {
+            path: PathBuf::from(page_name(person)),
+            html,
+        });
+    }
+
+    index.sort_by(|a, b| {
+        b.last_interaction
+            .cmp(&a.last_interaction)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    let context = IndexContext {
+        today,
+        people: index,
+    };
+    let html = match render::render_html_str(&templates.index, &context) {
+        Ok(html) => html,
+        Err(reason) => return Err(format!("failed to render the index page: {reason}")),
+    };
+    pages.insert(
+        0,
+        Page {
+            path: PathBuf::from("index.html"),
+            html,
+        },
+    );
+
+    Ok(pages)
+}
+
+pub fn write_site(pages: &[Page], output_dir: &Path) -> Result<(), String> {
+    if let Err(reason) = fs::create_dir_all(output_dir) {
+        return Err(format!("failed to create {output_dir:?}, reason: {reason}"));
+    }
+
+    for page in pages {
+        let path = output_dir.join(&page.path);
+        if let Err(reason) = fs::write(&path, &page.html) {
+            return Err(format!("failed to write {path:?}, reason: {reason}"));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    fn log() -> Log {
+        parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - climbing with #JaneDoe and #Ignored
+
+            # 2000-01-02
+
+            - #JohnDoe :
+              - coffee <3
+            "
+        ))
+    }
+
+    #[test]
+    fn test_build_site_with_default_templates() {
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .ignore(vec!["Ignored".to_string()])
+            .build();
+        let templates = SiteTemplates::from_config(&config).unwrap();
+
+        let pages = build_site(log(), &config, &templates, d("2000-01-03")).unwrap();
+
+        let paths: Vec<&Path> = pages.iter().map(|page| page.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                Path::new("index.html"),
+                Path::new("JaneDoe.html"),
+                Path::new("JohnDoe.html")
+            ]
+        );
+
+        let john = &pages[2].html;
+        assert!(john.contains(r#"<a href="JaneDoe.html">JaneDoe</a> (1)"#));
+        assert!(!john.contains("Ignored.html"));
+        assert!(john.contains("coffee &lt;3"));
+        // most recent first
+        assert!(john.find("2000-01-02").unwrap() < john.find("climbing").unwrap());
+    }
+
+    #[test]
+    fn test_build_site_with_custom_templates() {
+        let config = Config::builder().people_dir("/tmp/people").build();
+        let templates = SiteTemplates {
+            index: "{% for p in people %}{{ p.name }} {% endfor %}".to_string(),
+            person: "{{ person.name }}: {{ stats.total_entries }}".to_string(),
+        };
+
+        let pages = build_site(log(), &config, &templates, d("2000-01-03")).unwrap();
+
+        assert_eq!(pages[0].html, "JohnDoe Ignored JaneDoe ");
+        assert_eq!(pages[3].html, "JohnDoe: 2");
+    }
+
+    #[test]
+    fn test_page_name() {
+        assert_eq!(page_name(&"Jane Doe".to_string()), "Jane-Doe.html");
+    }
+}
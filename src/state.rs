@@ -0,0 +1,184 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::config::Config;
+use crate::files;
+use crate::model::PersonName;
+
+/// Data that the CLI remembers between runs, stored in `people_dir`
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct State {
+    #[serde(default)]
+    pub reminders: BTreeMap<PersonName, Vec<ReminderRecord>>,
+    /// Do not notify about these people until the given date (inclusive)
+    #[serde(default)]
+    pub snoozed: BTreeMap<PersonName, NaiveDate>,
+    /// Reached out on the given date, but not logged yet
+    #[serde(default)]
+    pub acknowledged: BTreeMap<PersonName, NaiveDate>,
+    /// What `people hooks run` saw last time, to only run hooks on changes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<HooksSnapshot>,
+    /// Days each person and you emailed each other, found by `people scan-mail`.
+    /// Kept apart from the logs, which only have curated entries.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub passive: BTreeMap<PersonName, BTreeSet<NaiveDate>>,
+    /// UIDs of the journals `people export caldav` uploaded last time, to
+    /// delete the ones whose entries are gone
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub exported_journals: BTreeSet<String>,
+}
+
+impl State {
+    pub fn is_snoozed(&self, person: &PersonName, today: NaiveDate) -> bool {
+        match self.snoozed.get(person) {
+            Some(until) => today <= *until,
+            None => false,
+        }
+    }
+
+    pub fn snooze(&mut self, person: PersonName, until: NaiveDate) {
+        self.snoozed.insert(person, until);
+    }
+
+    pub fn acknowledge(&mut self, person: PersonName, date: NaiveDate) {
+        self.acknowledged.insert(person, date);
+    }
+
+    /// Forget snoozes that already expired, so the state file does not grow forever
+    pub fn clear_expired_snoozes(&mut self, today: NaiveDate) {
+        self.snoozed.retain(|_, until| today <= *until);
+    }
+}
+
+/// A person went beyond their reach out threshold, and maybe was contacted later
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ReminderRecord {
+    pub crossed: NaiveDate,
+    pub contacted: Option<NaiveDate>,
+}
+
+impl ReminderRecord {
+    pub fn is_open(&self) -> bool {
+        self.contacted.is_none()
+    }
+}
+
+/// Last interaction with each person and who was overdue, as of `checked`
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct HooksSnapshot {
+    pub checked: NaiveDate,
+    #[serde(default)]
+    pub last_contacts: BTreeMap<PersonName, NaiveDate>,
+    #[serde(default)]
+    pub overdue: BTreeSet<PersonName>,
+}
+
+fn parse_state(content: &str) -> Result<State, String> {
+    match serde_yaml::from_str::<State>(content) {
+        Ok(state) => Ok(state),
+        Err(error) => {
+            debug!("failed to parse state file, reason: {error:?}");
+            Err(error.to_string())
+        }
+    }
+}
+
+/// Load the state file, or start from scratch if it does not exist yet
+pub fn load_state(path: &Path) -> Result<State, String> {
+    if !path.exists() {
+        return Ok(State::default());
+    }
+
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to read {path:?}, reason: {reason}")),
+    };
+
+    match parse_state(&content) {
+        Ok(state) => Ok(state),
+        Err(reason) => Err(format!("failed to parse {path:?}, reason: {reason}")),
+    }
+}
+
+/// Not part of a transaction, as it changes on every check of the reminders,
+/// but written atomically like the files changed by one
+pub fn save_state(config: &Config, state: &State) -> Result<(), String> {
+    let path = config.get_state_path();
+    config.check_writable(&path)?;
+    files::create_parent_dir(&path)?;
+
+    let content = match serde_yaml::to_string(state) {
+        Ok(content) => content,
+        Err(reason) => return Err(format!("failed to serialize state, reason: {reason}")),
+    };
+
+    files::write_atomically(&path, &content)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_state() {
+        let content = indoc!(
+            "
+            reminders:
+              JohnDoe:
+                - crossed: 2000-01-01
+                  contacted: 2000-01-05
+                - crossed: 2000-02-01
+                  contacted: null
+            "
+        );
+
+        let expected = State {
+            reminders: [(
+                "JohnDoe".to_string(),
+                vec![
+                    ReminderRecord {
+                        crossed: d("2000-01-01"),
+                        contacted: Some(d("2000-01-05")),
+                    },
+                    ReminderRecord {
+                        crossed: d("2000-02-01"),
+                        contacted: None,
+                    },
+                ],
+            )]
+            .into(),
+            ..Default::default()
+        };
+
+        assert_eq!(parse_state(content), Ok(expected));
+    }
+
+    #[test]
+    fn test_snooze() {
+        let mut state = State::default();
+        state.snooze("JohnDoe".to_string(), d("2000-01-03"));
+
+        assert!(state.is_snoozed(&"JohnDoe".to_string(), d("2000-01-03")));
+        assert!(!state.is_snoozed(&"JohnDoe".to_string(), d("2000-01-04")));
+        assert!(!state.is_snoozed(&"JaneDoe".to_string(), d("2000-01-01")));
+
+        state.clear_expired_snoozes(d("2000-01-04"));
+        assert_eq!(state, State::default());
+    }
+
+    #[test]
+    fn test_parse_empty_state() {
+        assert_eq!(parse_state("{}"), Ok(State::default()));
+    }
+}
@@ -0,0 +1,302 @@
+use core::fmt;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::completions;
+use crate::config::Config;
+use crate::log::{find_log_files, parse_line_people, Log, ParseOptions};
+use crate::model::PersonName;
+use crate::operations::Transaction;
+use crate::resolve;
+
+static CAPITALIZED_WORD_PATTERN: &str = r"\p{Lu}[\p{L}\p{M}]*";
+
+/// Where in a line a person is mentioned
+pub(crate) type Mention<'a> = (Range<usize>, &'a PersonName);
+
+/// A known person mentioned by name in a line that does not tag them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TagSuggestion {
+    pub path: PathBuf,
+    /// Starting at 1
+    pub line: usize,
+    pub mention: String,
+    pub person: PersonName,
+}
+
+impl fmt::Display for TagSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {:?} could be #{}",
+            self.path.display(),
+            self.line,
+            self.mention,
+            self.person
+        )
+    }
+}
+
+/// Every name and alias of the known people, without whitespace, so that
+/// `John Doe` in the text reads as `JohnDoe`
+pub struct Spellings(HashMap<String, PersonName>);
+
+impl Spellings {
+    pub fn new(log: &Log, config: &Config) -> Self {
+        let known = completions::list_people(log, config);
+        let spellings = resolve::spellings(&known, config)
+            .into_iter()
+            .map(|(spelling, name)| (spelling.split_whitespace().collect(), name))
+            .collect();
+        Self(spellings)
+    }
+
+    fn get(&self, words: &str) -> Option<&PersonName> {
+        self.0.get(words)
+    }
+}
+
+/// Words glued to a letter, a tag, a wikilink or a path are not mentions
+fn is_word_start(line: &str, start: usize) -> bool {
+    match line[..start].chars().next_back() {
+        Some(previous) => !previous.is_alphanumeric() && !matches!(previous, '#' | '@' | '[' | '/'),
+        None => true,
+    }
+}
+
+/// The mentions in a line of people not in `tagged`, preferring the longest
+/// run of adjacent capitalized words, e.g. `John Doe` over `John`
+pub(crate) fn find_mentions<'a>(
+    line: &str,
+    spellings: &'a Spellings,
+    tagged: &HashSet<PersonName>,
+) -> Vec<Mention<'a>> {
+    let word_re = Regex::new(CAPITALIZED_WORD_PATTERN).unwrap();
+    let words: Vec<Range<usize>> = word_re
+        .find_iter(line)
+        .filter(|word| is_word_start(line, word.start()))
+        .map(|word| word.range())
+        .collect();
+
+    let mut mentions: Vec<Mention> = vec![];
+    let mut start = 0;
+    while start < words.len() {
+        let mut end = start + 1;
+        while end < words.len() && line[words[end - 1].end..words[end].start].trim().is_empty() {
+            end += 1;
+        }
+
+        let found = (start + 1..=end).rev().find_map(|end| {
+            let joined: String = words[start..end]
+                .iter()
+                .map(|word| &line[word.clone()])
+                .collect();
+            spellings.get(&joined).map(|person| (end, person))
+        });
+
+        match found {
+            Some((end, person)) => {
+                if !tagged.contains(person) {
+                    mentions.push((words[start].start..words[end - 1].end, person));
+                }
+                start = end;
+            }
+            None => start += 1,
+        }
+    }
+
+    mentions
+}
+
+/// The untagged mentions per line index, skipping the front matter and the
+/// day headers. People tagged in the first line of an entry count as tagged
+/// in the rest of it.
+fn find_mentions_in_content<'a>(
+    content: &str,
+    spellings: &'a Spellings,
+    options: &ParseOptions,
+) -> Vec<(usize, Vec<Mention<'a>>)> {
+    let mut in_entries = false;
+    let mut entry_people: HashSet<PersonName> = HashSet::new();
+    let mut mentions = vec![];
+
+    for (index, line) in content.split('\n').enumerate() {
+        if line.starts_with("# ") {
+            in_entries = true;
+            continue;
+        }
+        if !in_entries || line.trim().is_empty() {
+            continue;
+        }
+
+        let line_people = parse_line_people(line, options);
+        let is_first_line = !line.starts_with([' ', '\t']);
+        if is_first_line {
+            entry_people.clone_from(&line_people);
+        }
+        let tagged: HashSet<PersonName> = entry_people.union(&line_people).cloned().collect();
+
+        let found = find_mentions(line, spellings, &tagged);
+        if !found.is_empty() {
+            mentions.push((index, found));
+        }
+    }
+
+    mentions
+}
+
+/// Find the untagged mentions of known people in a log file
+pub fn find_suggestions(
+    path: &Path,
+    content: &str,
+    spellings: &Spellings,
+    options: &ParseOptions,
+) -> Vec<TagSuggestion> {
+    let lines: Vec<&str> = content.split('\n').collect();
+
+    let mut suggestions: Vec<TagSuggestion> = vec![];
+    for (index, mentions) in find_mentions_in_content(content, spellings, options) {
+        for (range, person) in mentions {
+            suggestions.push(TagSuggestion {
+                path: path.to_path_buf(),
+                line: index + 1,
+                mention: lines[index][range].to_string(),
+                person: person.clone(),
+            });
+        }
+    }
+
+    suggestions
+}
+
+/// Replace every untagged mention of a known person with their tag, leaving
+/// the rest untouched
+pub fn fix_content(content: &str, spellings: &Spellings, options: &ParseOptions) -> String {
+    let mut lines: Vec<String> = content.split('\n').map(String::from).collect();
+    for (index, mentions) in find_mentions_in_content(content, spellings, options) {
+        // from the end, so that the earlier ranges still hold
+        for (range, person) in mentions.into_iter().rev() {
+            lines[index].replace_range(range, &format!("#{person}"));
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+    }
+}
+
+pub fn audit_logs(
+    config: &Config,
+    spellings: &Spellings,
+    options: &ParseOptions,
+) -> Result<Vec<TagSuggestion>, String> {
+    let mut suggestions: Vec<TagSuggestion> = vec![];
+    for path in find_log_files(config) {
+        let content = read(&path)?;
+        suggestions.extend(find_suggestions(&path, &content, spellings, options));
+    }
+
+    Ok(suggestions)
+}
+
+/// Tag the mentions in the log files, returns the files changed
+pub fn fix_logs(
+    config: &Config,
+    spellings: &Spellings,
+    options: &ParseOptions,
+    transaction: &Transaction,
+) -> Result<Vec<PathBuf>, String> {
+    let mut fixed: Vec<PathBuf> = vec![];
+    for path in find_log_files(config) {
+        let content = read(&path)?;
+        let updated = fix_content(&content, spellings, options);
+        if updated == content {
+            continue;
+        }
+
+        transaction.write(&path, &updated)?;
+        fixed.push(path);
+    }
+
+    Ok(fixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn spellings() -> Spellings {
+        let spellings = [
+            ("JohnDoe", "JohnDoe"),
+            ("JaneDoe", "JaneDoe"),
+            ("Jane", "JaneDoe"),
+        ]
+        .map(|(spelling, name)| (spelling.to_string(), name.to_string()));
+        Spellings(HashMap::from(spellings))
+    }
+
+    static CONTENT: &str = indoc!(
+        "
+        # 2000-01-01
+
+        - #JaneDoe :
+          - Met John Doe and Jane at [[JohnDoe]]'s, John was late
+        - #JohnDoe, Jane :
+          - Tea with JaneDoe
+        "
+    );
+
+    #[test]
+    fn test_find_suggestions() {
+        let suggestions = find_suggestions(
+            Path::new("log.md"),
+            CONTENT,
+            &spellings(),
+            &ParseOptions::default(),
+        );
+
+        let found: Vec<(usize, &str, &str)> = suggestions
+            .iter()
+            .map(|s| (s.line, s.mention.as_str(), s.person.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (4, "John Doe", "JohnDoe"),
+                (5, "Jane", "JaneDoe"),
+                (6, "JaneDoe", "JaneDoe"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fix_content() {
+        let fixed = fix_content(CONTENT, &spellings(), &ParseOptions::default());
+
+        assert_eq!(
+            fixed,
+            indoc!(
+                "
+                # 2000-01-01
+
+                - #JaneDoe :
+                  - Met #JohnDoe and Jane at [[JohnDoe]]'s, John was late
+                - #JohnDoe, #JaneDoe :
+                  - Tea with #JaneDoe
+                "
+            )
+        );
+    }
+}
@@ -0,0 +1,817 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+use tabular::{Row, Table};
+
+use crate::config::{Config, SummaryConfig, SummaryGrouping};
+use crate::dates::DateDisplay;
+use crate::listing::{self, ListingArgs};
+use crate::log::Log;
+use crate::model::{DaysAgo, PersonName};
+use crate::use_cases::LastInteraction;
+
+/// Weeks in the activity sparkline, counting back from today
+pub const SPARKLINE_WEEKS: usize = 12;
+
+/// From the quietest weeks with interactions to the busiest ones
+static SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const NO_INTERACTIONS: char = '·';
+
+/// How often each person was interacted with, for the `--stats` columns
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InteractionStats {
+    /// Entries with each person in the whole log
+    pub counts: HashMap<PersonName, usize>,
+    /// Entries with each person per week, oldest first
+    pub weeks: HashMap<PersonName, [usize; SPARKLINE_WEEKS]>,
+    /// Last day each person and you emailed each other, see `people scan-mail`
+    pub last_mail: HashMap<PersonName, NaiveDate>,
+}
+
+impl InteractionStats {
+    pub fn new(log: &Log, today: NaiveDate) -> InteractionStats {
+        let mut stats = InteractionStats::default();
+
+        for day in log.days.iter() {
+            // a week or a month counts as its last day
+            let ago = (today - day.last_date()).num_days();
+            let week = match ago {
+                0.. => SPARKLINE_WEEKS.checked_sub(1 + ago as usize / 7),
+                _ => None,
+            };
+
+            for entry in day.entries.iter() {
+                for person in entry.interacted() {
+                    *stats.counts.entry(person.clone()).or_default() += 1;
+                    if let Some(week) = week {
+                        stats.weeks.entry(person.clone()).or_default()[week] += 1;
+                    }
+                }
+            }
+        }
+
+        stats
+    }
+
+    /// Add the passive interactions found by `people scan-mail`
+    pub fn with_passive(
+        mut self,
+        passive: &BTreeMap<PersonName, BTreeSet<NaiveDate>>,
+    ) -> InteractionStats {
+        for (person, dates) in passive {
+            if let Some(last) = dates.last() {
+                self.last_mail.insert(person.clone(), *last);
+            }
+        }
+        self
+    }
+
+    fn count(&self, person: &PersonName) -> usize {
+        self.counts.get(person).copied().unwrap_or(0)
+    }
+
+    /// A bar per week, as tall as the busiest week of anyone allows
+    fn sparkline(&self, person: &PersonName) -> String {
+        let max = self.weeks.values().flatten().max().copied().unwrap_or(0);
+        let weeks = self.weeks.get(person).copied().unwrap_or_default();
+
+        weeks
+            .iter()
+            .map(|count| match count {
+                0 => NO_INTERACTIONS,
+                count => SPARKS[(count * SPARKS.len()).div_ceil(max).clamp(1, SPARKS.len()) - 1],
+            })
+            .collect()
+    }
+}
+
+/// Order of the people in the summary
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SummarySort {
+    /// Alphabetically
+    Name,
+    /// Most recent interaction first
+    #[default]
+    Last,
+    /// Furthest beyond their reach out threshold first
+    Overdue,
+}
+
+impl FromStr for SummarySort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(SummarySort::Name),
+            "last" => Ok(SummarySort::Last),
+            "overdue" => Ok(SummarySort::Overdue),
+            other => Err(format!(
+                "expected 'name', 'last' or 'overdue', but found {other:?}"
+            )),
+        }
+    }
+}
+
+pub fn sort_interactions(
+    mut interactions: Vec<LastInteraction>,
+    sort: SummarySort,
+    reverse: bool,
+) -> Vec<LastInteraction> {
+    match sort {
+        SummarySort::Name => interactions.sort_by_key(|i| i.person.to_lowercase()),
+        SummarySort::Last => interactions.sort_by_key(|i| Reverse(i.last)),
+        SummarySort::Overdue => interactions.sort_by_key(|i| {
+            let overdue = i.days_beyond_reachout_threshold;
+            (overdue.is_none(), Reverse(overdue), Reverse(i.last))
+        }),
+    }
+
+    if reverse {
+        interactions.reverse();
+    }
+    interactions
+}
+
+/// A column of the summary table
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    DaysAgo,
+    Person,
+    /// Date of the last interaction, and its kind if annotated
+    Last,
+    /// How long ago the `remind_after` threshold passed
+    Overdue,
+    Location,
+    Themes,
+    /// Entries with the person in the whole log
+    Count,
+    /// Sparkline of the entries per week, see `SPARKLINE_WEEKS`
+    Activity,
+    /// Last email with the person, which is not a logged interaction
+    Mail,
+}
+
+impl Column {
+    pub fn defaults() -> Vec<Column> {
+        vec![
+            Column::DaysAgo,
+            Column::Person,
+            Column::Last,
+            Column::Overdue,
+        ]
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Column::DaysAgo => "Days ago",
+            Column::Person => "PERSON",
+            Column::Last => "LAST",
+            Column::Overdue => "OVERDUE",
+            Column::Location => "LOCATION",
+            Column::Themes => "THEMES",
+            Column::Count => "COUNT",
+            Column::Activity => "LAST 12 WEEKS",
+            Column::Mail => "LAST EMAIL",
+        }
+    }
+
+    fn cell(
+        &self,
+        interaction: &LastInteraction,
+        config: &Config,
+        stats: &InteractionStats,
+        today: NaiveDate,
+        dates: &DateDisplay,
+    ) -> String {
+        let person = config.get_person(&interaction.person);
+        match self {
+            Column::DaysAgo => interaction.ago(today).to_string(),
+            Column::Person => interaction.person.clone(),
+            Column::Last => match &interaction.kind {
+                Some(kind) => format!("{} ({kind})", dates.show(interaction.last, today)),
+                None => dates.show(interaction.last, today),
+            },
+            Column::Overdue => match interaction.days_beyond_reachout_threshold {
+                Some(days) => format!("by {days} days"),
+                None => "".to_string(),
+            },
+            Column::Location => person.map(|p| p.location.clone()).unwrap_or_default(),
+            Column::Themes => person.map(|p| p.themes.join(", ")).unwrap_or_default(),
+            Column::Count => stats.count(&interaction.person).to_string(),
+            Column::Activity => stats.sparkline(&interaction.person),
+            Column::Mail => match stats.last_mail.get(&interaction.person) {
+                Some(date) => dates.show(*date, today),
+                None => "".to_string(),
+            },
+        }
+    }
+
+    /// Like `cell`, for a pinned person with nothing logged
+    fn never_seen_cell(
+        &self,
+        name: &PersonName,
+        config: &Config,
+        stats: &InteractionStats,
+        today: NaiveDate,
+        dates: &DateDisplay,
+    ) -> String {
+        let person = config.get_person(name);
+        match self {
+            Column::Person => name.clone(),
+            Column::Last => "never".to_string(),
+            Column::Location => person.map(|p| p.location.clone()).unwrap_or_default(),
+            Column::Themes => person.map(|p| p.themes.join(", ")).unwrap_or_default(),
+            Column::Count => "0".to_string(),
+            Column::Mail => match stats.last_mail.get(name) {
+                Some(date) => dates.show(*date, today),
+                None => "".to_string(),
+            },
+            Column::DaysAgo | Column::Overdue | Column::Activity => "".to_string(),
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "days-ago" => Ok(Column::DaysAgo),
+            "person" => Ok(Column::Person),
+            "last" => Ok(Column::Last),
+            "overdue" | "reach-out" => Ok(Column::Overdue),
+            "location" => Ok(Column::Location),
+            "themes" => Ok(Column::Themes),
+            "count" => Ok(Column::Count),
+            "activity" => Ok(Column::Activity),
+            "mail" => Ok(Column::Mail),
+            other => Err(format!(
+                "expected one of days-ago, person, last, overdue, location, themes, count, activity, mail, but found {other:?}"
+            )),
+        }
+    }
+}
+
+/// How `people_summary` lays out its table
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableOptions {
+    pub columns: Vec<Column>,
+    pub sort: SummarySort,
+    pub reverse: bool,
+    /// Show overdue people right after the pinned ones, most overdue first
+    pub overdue_first: bool,
+    pub listing: ListingArgs,
+    /// How the dates are shown, unless `--plain`
+    pub dates: DateDisplay,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        TableOptions {
+            columns: Column::defaults(),
+            sort: SummarySort::default(),
+            reverse: false,
+            overdue_first: false,
+            listing: ListingArgs::default(),
+            dates: DateDisplay::iso(),
+        }
+    }
+}
+
+/// People shown together in the summary, under an optional header
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    pub label: Option<String>,
+    pub interactions: Vec<LastInteraction>,
+}
+
+/// How many of the boundaries were reached `ago` days ago
+fn boundaries_reached(boundaries: &[DaysAgo], ago: DaysAgo) -> usize {
+    boundaries
+        .iter()
+        .filter(|boundary| ago >= **boundary)
+        .count()
+}
+
+/// Which group the interaction belongs to, and its header
+fn group_of(
+    interaction: &LastInteraction,
+    config: &SummaryConfig,
+    today: NaiveDate,
+) -> (i64, Option<String>) {
+    match config.group_by {
+        SummaryGrouping::DaysAgo => {
+            let index = boundaries_reached(&config.boundaries, interaction.ago(today));
+            (index as i64, config.labels.get(index).cloned())
+        }
+        SummaryGrouping::Month => {
+            let last = interaction.last;
+            let month = i64::from(last.year()) * 12 + i64::from(last.month0());
+            (month, Some(last.format("%B %Y").to_string()))
+        }
+    }
+}
+
+/// Split the interactions, sorted most recent first, into consecutive groups
+pub fn group_interactions(
+    interactions: Vec<LastInteraction>,
+    config: &SummaryConfig,
+    today: NaiveDate,
+) -> Vec<Group> {
+    let mut groups: Vec<Group> = vec![];
+    let mut current: Option<i64> = None;
+
+    for interaction in interactions {
+        let (key, label) = group_of(&interaction, config, today);
+        match groups.last_mut() {
+            Some(group) if current == Some(key) => group.interactions.push(interaction),
+            _ => groups.push(Group {
+                label,
+                interactions: vec![interaction],
+            }),
+        }
+        current = Some(key);
+    }
+
+    groups
+}
+
+fn table_spec(columns: &[Column]) -> String {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            let separator = match (index, column) {
+                (0, _) => "",
+                (_, Column::Overdue) => "    ",
+                _ => "  ",
+            };
+            let alignment = match column {
+                Column::DaysAgo | Column::Count => "{:>}",
+                _ => "{:<}",
+            };
+            format!("{separator}{alignment}")
+        })
+        .collect()
+}
+
+/// The row with the text in the person column, or else in the first one
+fn text_row(columns: &[Column], text: &str) -> Row {
+    let text_at = columns
+        .iter()
+        .position(|column| *column == Column::Person)
+        .unwrap_or(0);
+
+    let mut row = Row::new();
+    for index in 0..columns.len() {
+        row.add_cell(if index == text_at { text } else { "" });
+    }
+    row
+}
+
+/// The cells of the rows of a group
+struct Section {
+    label: Option<String>,
+    rows: Vec<Vec<String>>,
+}
+
+/// The rows of the sections after `offset`, at most `limit` of them, without
+/// the sections left empty
+fn page_sections(sections: Vec<Section>, listing: &ListingArgs) -> Vec<Section> {
+    let mut to_skip = listing.offset;
+    let mut left = listing.limit.unwrap_or(usize::MAX);
+
+    let mut paged: Vec<Section> = vec![];
+    for mut section in sections {
+        let skipped = to_skip.min(section.rows.len());
+        to_skip -= skipped;
+        section.rows.drain(..skipped);
+        section.rows.truncate(left);
+        left -= section.rows.len();
+
+        if !section.rows.is_empty() {
+            paged.push(section);
+        }
+    }
+
+    paged
+}
+
+/// The summary table: pinned people first, followed by the pinned people never
+/// seen, then the overdue ones if asked, then the rest. People are only grouped
+/// when sorted by their last interaction.
+pub fn format_table(
+    pinned: Vec<LastInteraction>,
+    never_seen: Vec<PersonName>,
+    interactions: Vec<LastInteraction>,
+    options: &TableOptions,
+    config: &Config,
+    stats: &InteractionStats,
+    today: NaiveDate,
+) -> String {
+    if pinned.is_empty() && never_seen.is_empty() && interactions.is_empty() {
+        return "No interactions logged yet.".to_string();
+    }
+
+    let unlabeled = |interactions| Group {
+        label: None,
+        interactions,
+    };
+
+    // pinned people first, then the overdue ones if asked, then the rest
+    let mut sections = vec![unlabeled(sort_interactions(
+        pinned,
+        options.sort,
+        options.reverse,
+    ))];
+
+    let interactions = match options.overdue_first {
+        true => {
+            let (overdue, rest): (Vec<_>, Vec<_>) = interactions
+                .into_iter()
+                .partition(|i| i.days_beyond_reachout_threshold.is_some());
+            sections.push(unlabeled(sort_interactions(
+                overdue,
+                SummarySort::Overdue,
+                false,
+            )));
+            rest
+        }
+        false => interactions,
+    };
+
+    let interactions = sort_interactions(interactions, options.sort, options.reverse);
+    match options.sort {
+        SummarySort::Last => {
+            sections.extend(group_interactions(interactions, &config.summary, today))
+        }
+        _ => sections.push(unlabeled(interactions)),
+    };
+
+    let columns = &options.columns;
+    let dates = match options.listing.plain {
+        true => DateDisplay::iso(),
+        false => options.dates.clone(),
+    };
+    let cells = |interaction: &LastInteraction| -> Vec<String> {
+        columns
+            .iter()
+            .map(|column| column.cell(interaction, config, stats, today, &dates))
+            .collect()
+    };
+
+    let mut sections: Vec<Section> = sections
+        .into_iter()
+        .map(|group| Section {
+            label: group.label,
+            rows: group.interactions.iter().map(cells).collect(),
+        })
+        .collect();
+    // the first section is the pinned people
+    sections[0].rows.extend(never_seen.iter().map(|name| {
+        columns
+            .iter()
+            .map(|column| column.never_seen_cell(name, config, stats, today, &dates))
+            .collect::<Vec<String>>()
+    }));
+    let sections = page_sections(sections, &options.listing);
+
+    if options.listing.plain {
+        return sections
+            .iter()
+            .flat_map(|section| section.rows.iter())
+            .map(|row| format!("{}\n", listing::plain_row(row)))
+            .collect();
+    }
+
+    let mut table = Table::new(&table_spec(columns));
+    let mut header = Row::new();
+    for column in columns {
+        header.add_cell(column.title());
+    }
+    table.add_row(header);
+
+    for (index, section) in sections.into_iter().enumerate() {
+        if index > 0 {
+            table.add_row(text_row(columns, ""));
+        }
+        if let Some(label) = section.label {
+            table.add_row(text_row(columns, &format!("-- {label} --")));
+        }
+        for cells in section.rows {
+            let mut row = Row::new();
+            for cell in cells {
+                row.add_cell(cell);
+            }
+            table.add_row(row);
+        }
+    }
+
+    format!("{table}")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use indoc::indoc;
+
+    use crate::config::Person;
+    use crate::dates::DateStyle;
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    fn interaction(person: &str, last: &str) -> LastInteraction {
+        LastInteraction {
+            person: person.to_string(),
+            last: d(last),
+            days_beyond_reachout_threshold: None,
+            kind: None,
+        }
+    }
+
+    fn summarize(groups: Vec<Group>) -> Vec<(Option<String>, Vec<String>)> {
+        groups
+            .into_iter()
+            .map(|group| {
+                let people = group.interactions.into_iter().map(|i| i.person).collect();
+                (group.label, people)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_group_by_days_ago() {
+        let interactions = vec![
+            interaction("Abu", "2000-03-30"),
+            interaction("JaneDoe", "2000-03-10"),
+            interaction("JohnDoe", "2000-03-09"),
+            interaction("Ann", "2000-01-01"),
+        ];
+        let config = SummaryConfig {
+            boundaries: vec![7, 14, 28],
+            labels: vec![
+                "this week".to_string(),
+                "last week".to_string(),
+                "this month".to_string(),
+                "older".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        // crossing several boundaries at once starts a single group
+        assert_eq!(
+            summarize(group_interactions(interactions, &config, d("2000-04-01"))),
+            vec![
+                (Some("this week".to_string()), vec!["Abu".to_string()]),
+                (
+                    Some("this month".to_string()),
+                    vec!["JaneDoe".to_string(), "JohnDoe".to_string()]
+                ),
+                (Some("older".to_string()), vec!["Ann".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_month() {
+        let interactions = vec![
+            interaction("Abu", "2000-03-30"),
+            interaction("JaneDoe", "2000-03-01"),
+            interaction("Ann", "1999-03-31"),
+        ];
+        let config = SummaryConfig {
+            group_by: SummaryGrouping::Month,
+            ..SummaryConfig::default()
+        };
+
+        assert_eq!(
+            summarize(group_interactions(interactions, &config, d("2000-04-01"))),
+            vec![
+                (
+                    Some("March 2000".to_string()),
+                    vec!["Abu".to_string(), "JaneDoe".to_string()]
+                ),
+                (Some("March 1999".to_string()), vec!["Ann".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_table_with_columns_and_sort() {
+        let mut overdue = interaction("JaneDoe", "2000-01-01");
+        overdue.days_beyond_reachout_threshold = Some(61);
+        let interactions = vec![
+            interaction("Abu", "2000-03-30"),
+            overdue,
+            interaction("JohnDoe", "2000-03-09"),
+        ];
+        let config = Config::builder()
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                location: "Bilbao".to_string(),
+                ..Default::default()
+            })
+            .build();
+        let options = TableOptions {
+            columns: vec![Column::Person, Column::Location, Column::Overdue],
+            sort: SummarySort::Name,
+            reverse: true,
+            overdue_first: false,
+            listing: ListingArgs::default(),
+            dates: DateDisplay::iso(),
+        };
+
+        let stats = InteractionStats::default();
+        let table = format_table(
+            vec![],
+            vec![],
+            interactions,
+            &options,
+            &config,
+            &stats,
+            d("2000-04-01"),
+        );
+
+        let lines: Vec<&str> = table.lines().map(str::trim_end).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "PERSON   LOCATION    OVERDUE",
+                "JohnDoe",
+                "JaneDoe  Bilbao      by 61 days",
+                "Abu",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_table_with_overdue_first() {
+        let mut overdue = interaction("JaneDoe", "2000-01-01");
+        overdue.days_beyond_reachout_threshold = Some(61);
+        let interactions = vec![
+            interaction("Abu", "2000-03-30"),
+            overdue,
+            interaction("JohnDoe", "2000-03-09"),
+        ];
+        let options = TableOptions {
+            columns: vec![Column::Person, Column::Overdue],
+            overdue_first: true,
+            ..TableOptions::default()
+        };
+        let config = Config::builder().build();
+
+        let stats = InteractionStats::default();
+        let table = format_table(
+            vec![],
+            vec![],
+            interactions,
+            &options,
+            &config,
+            &stats,
+            d("2000-04-01"),
+        );
+
+        let lines: Vec<&str> = table.lines().map(str::trim_end).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "PERSON     OVERDUE",
+                "JaneDoe    by 61 days",
+                "",
+                "Abu",
+                "",
+                "JohnDoe",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interaction_stats() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 1999-01-01
+
+            - #JaneDoe :
+
+            # 2000-03-20
+
+            - #JaneDoe :
+            - #JaneDoe, #Abu :
+
+            # 2000-03-31
+
+            - #JaneDoe :
+
+            # 2000-04-02
+
+            - #Abu :
+            "
+        ));
+
+        let stats = InteractionStats::new(&log, d("2000-04-01"));
+
+        assert_eq!(stats.count(&"JaneDoe".to_string()), 4);
+        assert_eq!(stats.count(&"Abu".to_string()), 2);
+        assert_eq!(stats.sparkline(&"JaneDoe".to_string()), "··········█▄");
+        assert_eq!(stats.sparkline(&"Abu".to_string()), "··········▄·");
+        assert_eq!(stats.sparkline(&"Ann".to_string()), "············");
+
+        let passive = [(
+            "JaneDoe".to_string(),
+            [d("2000-03-01"), d("2000-03-25")].into(),
+        )]
+        .into();
+        let stats = stats.with_passive(&passive);
+        assert_eq!(
+            Column::Mail.cell(
+                &interaction("JaneDoe", "2000-03-31"),
+                &Config::builder().build(),
+                &stats,
+                d("2000-04-01"),
+                &DateDisplay::iso()
+            ),
+            "2000-03-25"
+        );
+        let relative = DateDisplay {
+            style: DateStyle::Relative,
+            format: None,
+        };
+        assert_eq!(
+            Column::Mail.cell(
+                &interaction("JaneDoe", "2000-03-31"),
+                &Config::builder().build(),
+                &stats,
+                d("2000-04-01"),
+                &relative
+            ),
+            "a week ago"
+        );
+    }
+
+    #[test]
+    fn test_format_table_page_as_plain_rows() {
+        let interactions = vec![
+            interaction("Abu", "2000-03-30"),
+            interaction("JaneDoe", "2000-03-10"),
+            interaction("JohnDoe", "2000-03-09"),
+            interaction("Ann", "2000-01-01"),
+        ];
+        let options = TableOptions {
+            columns: vec![Column::Person, Column::Last],
+            listing: ListingArgs {
+                limit: Some(2),
+                offset: 1,
+                plain: true,
+            },
+            ..TableOptions::default()
+        };
+        let config = Config::builder().build();
+        let stats = InteractionStats::default();
+
+        let table = format_table(
+            vec![],
+            vec![],
+            interactions,
+            &options,
+            &config,
+            &stats,
+            d("2000-04-01"),
+        );
+
+        assert_eq!(table, "JaneDoe\t2000-03-10\nJohnDoe\t2000-03-09\n");
+    }
+
+    #[test]
+    fn test_format_table_shows_pinned_people_never_seen() {
+        let options = TableOptions {
+            columns: vec![Column::Person, Column::Last, Column::Count],
+            listing: ListingArgs {
+                plain: true,
+                ..ListingArgs::default()
+            },
+            ..TableOptions::default()
+        };
+        let config = Config::builder().build();
+        let stats = InteractionStats::default();
+
+        let table = format_table(
+            vec![interaction("JaneDoe", "2000-03-10")],
+            vec!["Zoe".to_string()],
+            vec![interaction("Abu", "2000-03-30")],
+            &options,
+            &config,
+            &stats,
+            d("2000-04-01"),
+        );
+
+        assert_eq!(
+            table,
+            "JaneDoe\t2000-03-10\t0\nZoe\tnever\t0\nAbu\t2000-03-30\t0\n"
+        );
+    }
+}
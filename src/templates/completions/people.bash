@@ -0,0 +1,17 @@
+
+# Complete person names from `people completions --people`
+_people_with_names() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+
+    if [[ "$prev" == "--person" || "$prev" == "--snooze" ]] \
+        || [[ $COMP_CWORD -eq 2 && ( "${COMP_WORDS[1]}" == "show" || "${COMP_WORDS[1]}" == "where" || "${COMP_WORDS[1]}" == "links" || "${COMP_WORDS[1]}" == "contact" || "${COMP_WORDS[1]}" == "heatmap" || "${COMP_WORDS[1]}" == "history" || "${COMP_WORDS[1]}" == "facts" || "${COMP_WORDS[1]}" == "gift-ideas" || "${COMP_WORDS[1]}" == "followups" || "${COMP_WORDS[1]}" == "edit" ) ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$(people completions --people 2>/dev/null)" -- "$cur"))
+        return 0
+    fi
+
+    _people "$@"
+}
+
+complete -F _people_with_names -o nosort -o bashdefault -o default people
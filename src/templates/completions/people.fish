@@ -0,0 +1,5 @@
+
+# Complete person names from `people completions --people`
+complete -c people -n "__fish_seen_subcommand_from show where links contact heatmap history facts gift-ideas followups edit" -f -a "(people completions --people 2>/dev/null)"
+complete -c people -l person -x -a "(people completions --people 2>/dev/null)"
+complete -c people -l snooze -x -a "(people completions --people 2>/dev/null)"
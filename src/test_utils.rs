@@ -1,7 +1,46 @@
 use std::str::FromStr;
+use std::sync::Arc;
 
 use chrono::NaiveDate;
 
+use crate::log::Log;
+
 pub fn d(s: &str) -> NaiveDate {
-    NaiveDate::from_str(s).expect(&format!("Invalid date: {s}"))
+    NaiveDate::from_str(s).unwrap_or_else(|_| panic!("Invalid date: {s}"))
+}
+
+/// The log without where its days and entries were read from, to compare logs
+/// parsed from different text
+pub fn without_provenance(mut log: Log) -> Log {
+    for day in log.days.iter_mut() {
+        day.source_file = None;
+        day.line_range = 0..0;
+        for entry in day.entries.iter_mut() {
+            let entry = Arc::make_mut(entry);
+            entry.source_file = None;
+            entry.line_range = 0..0;
+        }
+    }
+    log
+}
+
+/// Text that looks like a log, with the odd line that doesn't, for property
+/// tests of the parser
+#[cfg(test)]
+pub fn log_content() -> impl proptest::strategy::Strategy<Value = String> {
+    use proptest::prelude::*;
+
+    let line = prop_oneof![
+        "# (19|20)[0-9]{2}-(0[1-9]|1[0-2])-(0[1-9]|1[0-9]|2[0-8])",
+        "# 20[0-9]{2}-W[0-5][0-9]",
+        "# 20[0-9]{2}-[01][0-9] ?",
+        "#{1,3} [a-zA-Z ]{0,8}",
+        "[ \t\u{3000}\u{a0}]{0,6}[-*+] (#[a-zA-Zñé]{1,6}[,:]? ?){0,3}[a-z :()]{0,12}",
+        "[ \t\u{3000}]{0,6}[a-z-]{1,6}: [a-z ]{1,6}",
+        "[ \t\u{3000}]{0,6}\\[\\[[a-zA-Z |]{1,10}\\]\\] https://[a-z.]{1,8}",
+        "---",
+        "[ \t]{0,3}",
+        "\\PC{0,16}",
+    ];
+    proptest::collection::vec(line, 0..30).prop_map(|lines| lines.join("\n"))
 }
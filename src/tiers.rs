@@ -0,0 +1,209 @@
+use core::fmt;
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate};
+
+use crate::config::{Config, TierThresholds};
+use crate::log::Log;
+use crate::model::PersonName;
+use crate::use_cases::{parse_duration, DAYS_IN_A_MONTH};
+
+/// How recently a person was interacted with
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tier {
+    Fresh,
+    Warm,
+    Cooling,
+    Cold,
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Tier::Fresh => "fresh",
+            Tier::Warm => "warm",
+            Tier::Cooling => "cooling",
+            Tier::Cold => "cold",
+        };
+        write!(f, "{name}")
+    }
+}
+
+struct Thresholds {
+    warm: Duration,
+    cooling: Duration,
+    cold: Duration,
+}
+
+impl Thresholds {
+    fn parse(tiers: &TierThresholds) -> Result<Thresholds, String> {
+        let thresholds = Thresholds {
+            warm: parse_duration(&tiers.warm)?,
+            cooling: parse_duration(&tiers.cooling)?,
+            cold: parse_duration(&tiers.cold)?,
+        };
+
+        if thresholds.warm > thresholds.cooling || thresholds.cooling > thresholds.cold {
+            return Err("tiers must be sorted: warm <= cooling <= cold".to_string());
+        }
+
+        Ok(thresholds)
+    }
+
+    fn classify(&self, since_last: Duration) -> Tier {
+        if since_last >= self.cold {
+            Tier::Cold
+        } else if since_last >= self.cooling {
+            Tier::Cooling
+        } else if since_last >= self.warm {
+            Tier::Warm
+        } else {
+            Tier::Fresh
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TierChange {
+    pub person: PersonName,
+    pub last: NaiveDate,
+    /// Tier a month ago, if the person had been interacted with by then
+    pub before: Option<Tier>,
+    pub now: Tier,
+}
+
+impl TierChange {
+    /// Early warning: the person is not overdue yet, but starting to fade
+    pub fn just_started_cooling(&self) -> bool {
+        self.before == Some(Tier::Warm) && self.now == Tier::Cooling
+    }
+}
+
+/// Last interaction with each person on or before `reference`
+fn last_interactions_at(log: &Log, reference: NaiveDate) -> HashMap<PersonName, NaiveDate> {
+    let mut last: HashMap<PersonName, NaiveDate> = HashMap::new();
+
+    for day in log.days.iter().filter(|day| day.date <= reference) {
+        for entry in day.entries.iter() {
+            for person in entry.interacted() {
+                let date = last.entry(person.clone()).or_insert(day.date);
+                if day.date > *date {
+                    *date = day.date;
+                }
+            }
+        }
+    }
+
+    last
+}
+
+/// Classify each person by recency now and a month ago, coldest first
+pub fn compute_tiers(
+    log: &Log,
+    config: &Config,
+    today: NaiveDate,
+) -> Result<Vec<TierChange>, String> {
+    let thresholds = Thresholds::parse(&config.tiers)?;
+    let a_month_ago = today - Duration::days(DAYS_IN_A_MONTH);
+
+    let before = last_interactions_at(log, a_month_ago);
+
+    let mut changes: Vec<TierChange> = last_interactions_at(log, today)
+        .into_iter()
+        .filter(|(person, _)| !config.is_ignored(person))
+        .map(|(person, last)| TierChange {
+            before: before
+                .get(&person)
+                .map(|date| thresholds.classify(a_month_ago - *date)),
+            now: thresholds.classify(today - last),
+            person,
+            last,
+        })
+        .collect();
+
+    changes.sort_by(|a, b| {
+        b.now
+            .cmp(&a.now)
+            .then_with(|| a.last.cmp(&b.last))
+            .then_with(|| a.person.cmp(&b.person))
+    });
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_compute_tiers() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #Old :
+
+            # 2000-02-20
+
+            - #Slipping :
+
+            # 2000-03-28
+
+            - #Recent :
+            "
+        ));
+        let config = Config::builder().people_dir("/tmp/people").build();
+
+        let expected = vec![
+            TierChange {
+                person: "Old".to_string(),
+                last: d("2000-01-01"),
+                before: Some(Tier::Cooling),
+                now: Tier::Cold,
+            },
+            TierChange {
+                person: "Slipping".to_string(),
+                last: d("2000-02-20"),
+                before: Some(Tier::Fresh),
+                now: Tier::Cooling,
+            },
+            TierChange {
+                person: "Recent".to_string(),
+                last: d("2000-03-28"),
+                before: None,
+                now: Tier::Fresh,
+            },
+        ];
+
+        assert_eq!(compute_tiers(&log, &config, d("2000-04-01")), Ok(expected));
+    }
+
+    #[test]
+    fn test_just_started_cooling() {
+        let change = TierChange {
+            person: "JohnDoe".to_string(),
+            last: d("2000-01-01"),
+            before: Some(Tier::Warm),
+            now: Tier::Cooling,
+        };
+
+        assert!(change.just_started_cooling());
+    }
+
+    #[test]
+    fn test_unsorted_thresholds() {
+        let tiers = TierThresholds {
+            warm: "2 months".to_string(),
+            cooling: "1 month".to_string(),
+            cold: "3 months".to_string(),
+        };
+
+        assert!(Thresholds::parse(&tiers).is_err());
+    }
+}
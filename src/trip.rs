@@ -0,0 +1,149 @@
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::digest;
+use crate::locations::{self, CurrentLocation};
+use crate::log::Log;
+use crate::model::{DaysAgo, Location, PersonName};
+use crate::use_cases;
+
+/// Someone who lives where you are going
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TripContact {
+    pub person: PersonName,
+    pub location: Location,
+    pub last: Option<NaiveDate>,
+    /// Days beyond the reach out threshold when the trip starts
+    pub days_overdue: Option<DaysAgo>,
+    /// Their birthday, if it falls during the trip
+    pub birthday: Option<NaiveDate>,
+}
+
+/// People in `location` during a trip from `start` to `end`, most overdue a
+/// reach out first, then the ones you haven't seen for longest
+pub fn plan_trip(
+    log: &Log,
+    config: &Config,
+    location: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<TripContact> {
+    let timelines = locations::location_timelines(log, config);
+    let interactions = use_cases::get_last_interactions(log);
+    let interactions = use_cases::identify_reachouts(interactions, config, start);
+    let birthdays = digest::upcoming_birthdays(config, start, end);
+
+    let mut contacts: Vec<TripContact> = locations::people_near(location, config, &timelines)
+        .into_iter()
+        .map(|(person, current)| {
+            let interaction = interactions
+                .iter()
+                .find(|interaction| interaction.person == person);
+            let birthday = birthdays
+                .iter()
+                .find(|birthday| birthday.person == person)
+                .map(|birthday| birthday.date);
+            let location = match current {
+                CurrentLocation::Logged(change) => change.location,
+                CurrentLocation::Configured(location) => location,
+            };
+
+            TripContact {
+                location,
+                last: interaction.map(|interaction| interaction.last),
+                days_overdue: interaction
+                    .and_then(|interaction| interaction.days_beyond_reachout_threshold),
+                birthday,
+                person,
+            }
+        })
+        .collect();
+
+    contacts.sort_by(|a, b| {
+        b.days_overdue
+            .cmp(&a.days_overdue)
+            .then_with(|| a.last.cmp(&b.last))
+            .then_with(|| a.person.cmp(&b.person))
+    });
+
+    contacts
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::{Person, ReminderInterval};
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_plan_trip() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JaneDoe :
+              - coffee
+            - #JohnDoe moved-to: Berlin
+
+            # 2000-02-01
+
+            - #Abu :
+              - lunch
+            "
+        ));
+        let remind_after: ReminderInterval = "2 weeks".parse().unwrap();
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .person(Person {
+                name: "JaneDoe".to_string(),
+                location: "Berlin, Germany".to_string(),
+                remind_after: Some(remind_after),
+                ..Default::default()
+            })
+            .person(Person {
+                name: "Abu".to_string(),
+                location: "Berlin".to_string(),
+                birthday: Some(d("1990-03-02")),
+                ..Default::default()
+            })
+            .person(Person {
+                name: "Far".to_string(),
+                location: "London".to_string(),
+                ..Default::default()
+            })
+            .build();
+
+        let trip = plan_trip(&log, &config, "berlin", d("2000-03-01"), d("2000-03-03"));
+
+        let expected = vec![
+            TripContact {
+                person: "JaneDoe".to_string(),
+                location: "Berlin, Germany".to_string(),
+                last: Some(d("2000-01-01")),
+                days_overdue: Some(46),
+                birthday: None,
+            },
+            TripContact {
+                person: "JohnDoe".to_string(),
+                location: "Berlin".to_string(),
+                last: Some(d("2000-01-01")),
+                days_overdue: None,
+                birthday: None,
+            },
+            TripContact {
+                person: "Abu".to_string(),
+                location: "Berlin".to_string(),
+                last: Some(d("2000-02-01")),
+                days_overdue: None,
+                birthday: Some(d("2000-03-02")),
+            },
+        ];
+
+        assert_eq!(trip, expected);
+    }
+}
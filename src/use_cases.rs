@@ -1,20 +1,31 @@
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
-use std::{cmp, fs};
+use core::fmt;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::{cmp, fs, thread};
 
-use chrono::{Duration, Local, NaiveDate};
+use chrono::{Duration, Months, NaiveDate};
+use similar::TextDiff;
 
-use crate::config::{self, Config};
-use crate::log::{Day, Log};
-use crate::model::{DaysAgo, PersonName};
+use crate::config::{self, Config, Frequency, LogOrder, MentionLevel, PerPersonConfig};
+use crate::front_matter::render_front_matter;
+use crate::log::{DatePrecision, Day, Entry, Log};
+use crate::model::{DaysAgo, InteractionKind, PersonName};
+use crate::operations::Transaction;
+use crate::profile;
+use crate::render::{self, PersonLogContext};
+use crate::state::{ReminderRecord, State};
 
-const DAYS_IN_A_MONTH: i64 = 30;
+pub(crate) const DAYS_IN_A_MONTH: i64 = 30;
+pub(crate) const DAYS_IN_A_YEAR: i64 = 365;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LastInteraction {
     pub person: PersonName,
     pub last: NaiveDate,
     pub days_beyond_reachout_threshold: Option<DaysAgo>,
+    /// How the last interaction happened, if annotated
+    pub kind: Option<InteractionKind>,
 }
 
 impl LastInteraction {
@@ -22,9 +33,11 @@ impl LastInteraction {
         (reference - self.last).num_days()
     }
 
-    pub fn assess_reminder(self: &LastInteraction, reminder_after: Duration) -> LastInteraction {
-        let today = Local::now().naive_local().date();
-
+    pub fn assess_reminder(
+        self: &LastInteraction,
+        reminder_after: Duration,
+        today: NaiveDate,
+    ) -> LastInteraction {
         let threshold = self.last + reminder_after;
         let time_to_threshold = threshold - today;
         let days_to_threshold = time_to_threshold.num_days();
@@ -37,36 +50,54 @@ impl LastInteraction {
             } else {
                 Some(-days_to_threshold)
             },
+            kind: self.kind.clone(),
         }
     }
 }
 
 /// Get each person's last interaction
 pub fn get_last_interactions(log: &Log) -> Vec<LastInteraction> {
-    let mut last_interactions: HashMap<PersonName, NaiveDate> = HashMap::new();
+    get_last_interactions_at(log, MentionLevel::Main)
+}
+
+/// Get each person's last interaction, counting the entries that mention them
+/// at `level`
+pub fn get_last_interactions_at(log: &Log, level: MentionLevel) -> Vec<LastInteraction> {
+    let mut last_interactions: HashMap<PersonName, (NaiveDate, Option<InteractionKind>)> =
+        HashMap::new();
 
     for day in log.days.iter() {
+        // a week or a month counts as its last day
+        let date = day.last_date();
         for entry in day.entries.iter() {
-            for person in entry.main.iter() {
-                let desired_date: NaiveDate;
+            for person in entry.interacted_at(level) {
+                let desired: (NaiveDate, Option<InteractionKind>);
 
-                if let Some(existing_date) = last_interactions.get(person) {
-                    desired_date = cmp::max(day.date, *existing_date);
+                if let Some((existing_date, existing_kind)) = last_interactions.get(person) {
+                    desired = match date.cmp(existing_date) {
+                        cmp::Ordering::Less => (*existing_date, existing_kind.clone()),
+                        // the last annotated entry of the day wins
+                        cmp::Ordering::Equal => {
+                            (date, entry.kind.clone().or(existing_kind.clone()))
+                        }
+                        cmp::Ordering::Greater => (date, entry.kind.clone()),
+                    };
                 } else {
-                    desired_date = day.date;
+                    desired = (date, entry.kind.clone());
                 }
 
-                last_interactions.insert(person.clone(), desired_date);
+                last_interactions.insert(person.clone(), desired);
             }
         }
     }
 
     let mut interactions: Vec<LastInteraction> = last_interactions
         .into_iter()
-        .map(|(person, date)| LastInteraction {
+        .map(|(person, (date, kind))| LastInteraction {
             person,
             last: date,
             days_beyond_reachout_threshold: None,
+            kind,
         })
         .collect();
 
@@ -75,177 +106,805 @@ pub fn get_last_interactions(log: &Log) -> Vec<LastInteraction> {
     interactions
 }
 
-/// Identify who should have been reached out and how long ago
-pub fn identify_reachouts(
-    without_reminders: Vec<LastInteraction>,
-    config: &Config,
-) -> Result<Vec<LastInteraction>, String> {
+/// The earliest interaction with each person, i.e. when you first met. A week
+/// or a month counts as its first day.
+pub fn get_first_interactions(log: &Log) -> HashMap<PersonName, NaiveDate> {
+    let mut first_interactions: HashMap<PersonName, NaiveDate> = HashMap::new();
+    for day in log.days.iter() {
+        for entry in day.entries.iter() {
+            for person in entry.interacted() {
+                first_interactions
+                    .entry(person.clone())
+                    .and_modify(|first| *first = (*first).min(day.date))
+                    .or_insert(day.date);
+            }
+        }
+    }
+    first_interactions
+}
+
+fn get_reminder_thresholds(config: &Config) -> HashMap<PersonName, Duration> {
     let mut to_be_reminded: HashMap<PersonName, Duration> = HashMap::new();
     for person in &config.people {
-        if let Some(duration_str) = person.remind_after.clone() {
-            let duration = match parse_duration_text(duration_str) {
-                Ok(d) => d,
-                Err(reason) => return Err(reason),
-            };
-            to_be_reminded.insert(person.name.clone(), duration);
+        if let Some(remind_after) = &person.remind_after {
+            to_be_reminded.insert(person.name.clone(), remind_after.duration());
         }
     }
 
+    to_be_reminded
+}
+
+/// Separate the interactions of pinned people from the rest
+pub fn split_pinned(
+    interactions: Vec<LastInteraction>,
+    config: &Config,
+) -> (Vec<LastInteraction>, Vec<LastInteraction>) {
+    let pinned: HashSet<&PersonName> = config
+        .people
+        .iter()
+        .filter(|person| person.pinned)
+        .map(|person| &person.name)
+        .collect();
+
+    interactions
+        .into_iter()
+        .partition(|interaction| pinned.contains(&interaction.person))
+}
+
+/// Pinned people without any of the interactions, who are shown anyway
+pub fn pinned_never_seen(interactions: &[LastInteraction], config: &Config) -> Vec<PersonName> {
+    config
+        .people
+        .iter()
+        .filter(|person| person.pinned && !config.is_ignored(&person.name))
+        .filter(|person| {
+            !interactions
+                .iter()
+                .any(|interaction| interaction.person == person.name)
+        })
+        .map(|person| person.name.clone())
+        .collect()
+}
+
+/// Identify who should have been reached out and how long ago
+pub fn identify_reachouts(
+    without_reminders: Vec<LastInteraction>,
+    config: &Config,
+    today: NaiveDate,
+) -> Vec<LastInteraction> {
+    let to_be_reminded = get_reminder_thresholds(config);
+
     let mut with_reminder: Vec<LastInteraction> = vec![];
 
     for interaction in without_reminders {
         if let Some(reminder) = to_be_reminded.get(&interaction.person) {
-            with_reminder.push(interaction.assess_reminder(*reminder));
+            with_reminder.push(interaction.assess_reminder(*reminder, today));
         } else {
             with_reminder.push(interaction);
         }
     }
 
-    Ok(with_reminder)
+    with_reminder
 }
 
-fn parse_duration_text(str: String) -> Result<Duration, String> {
-    let parts: Vec<&str> = str.split_whitespace().collect();
-    let amount_str = parts[0];
-    let amount: i64 = match amount_str.parse() {
-        Ok(amount) => amount,
-        Err(_) => {
-            return Err(format!(
-                "failed to parse '{str}', reason: unsupported amount found: {amount_str:?}"
-            ));
+/// Get the sorted dates in which each person was interacted with
+fn get_interaction_dates(log: &Log) -> HashMap<PersonName, Vec<NaiveDate>> {
+    let mut dates: HashMap<PersonName, Vec<NaiveDate>> = HashMap::new();
+
+    for day in log.days.iter() {
+        for entry in day.entries.iter() {
+            for person in entry.interacted() {
+                dates.entry(person.clone()).or_default().push(day.date);
+            }
         }
-    };
+    }
 
-    let unit = parts[1];
+    for person_dates in dates.values_mut() {
+        person_dates.sort();
+        person_dates.dedup();
+    }
 
-    match unit {
-        "month" | "months" => Ok(Duration::days(amount * DAYS_IN_A_MONTH)),
-        "week" | "weeks" => Ok(Duration::weeks(amount)),
-        "day" | "days" => Ok(Duration::days(amount)),
-        _ => {
-            return Err(format!(
-                "failed to parse '{str}', reason: unsupported unit found: {unit:?}"
-            ));
+    dates
+}
+
+/// Record who went beyond their reach out threshold, and when they were
+/// eventually contacted
+pub fn update_reminder_history(state: &mut State, log: &Log, config: &Config, today: NaiveDate) {
+    let thresholds = get_reminder_thresholds(config);
+
+    let interaction_dates = get_interaction_dates(log);
+
+    for (person, threshold) in thresholds {
+        let dates = match interaction_dates.get(&person) {
+            Some(dates) => dates,
+            None => continue,
+        };
+
+        let records = state.reminders.entry(person.clone()).or_default();
+
+        for record in records.iter_mut().filter(|record| record.is_open()) {
+            record.contacted = dates.iter().find(|date| **date >= record.crossed).copied();
+        }
+
+        let last = *dates
+            .last()
+            .expect("people in the log have at least one date");
+        let crossed = last + threshold;
+        let already_recorded = records
+            .iter()
+            .any(|record| record.is_open() || record.crossed == crossed);
+
+        if crossed < today && !already_recorded {
+            records.push(ReminderRecord {
+                crossed,
+                contacted: None,
+            });
+        }
+
+        if records.is_empty() {
+            state.reminders.remove(&person);
         }
     }
 }
 
-fn merge_days(previous: Day, new: Day) -> Day {
-    Day {
-        date: previous.date,
-        entries: vec![previous.entries, new.entries].concat(),
+#[derive(Clone, Debug, PartialEq)]
+pub struct Adherence {
+    pub person: PersonName,
+    pub times_overdue: usize,
+    /// Average days between crossing the threshold and reaching out
+    pub average_lateness: Option<f64>,
+    pub overdue_now: Option<DaysAgo>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdherenceReport {
+    pub people: Vec<Adherence>,
+    pub average_lateness: Option<f64>,
+}
+
+fn average(values: &[DaysAgo]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
     }
+
+    let total: DaysAgo = values.iter().sum();
+    Some(total as f64 / values.len() as f64)
+}
+
+/// Measure how late reach outs happen once a person is overdue
+pub fn compute_adherence(state: &State, today: NaiveDate) -> AdherenceReport {
+    let mut people: Vec<Adherence> = vec![];
+    let mut all_lateness: Vec<DaysAgo> = vec![];
+
+    for (person, records) in state.reminders.iter() {
+        if records.is_empty() {
+            continue;
+        }
+
+        let lateness: Vec<DaysAgo> = records
+            .iter()
+            .filter_map(|record| {
+                record
+                    .contacted
+                    .map(|date| (date - record.crossed).num_days())
+            })
+            .collect();
+
+        let overdue_now = records
+            .iter()
+            .find(|record| record.is_open())
+            .map(|record| (today - record.crossed).num_days());
+
+        people.push(Adherence {
+            person: person.clone(),
+            times_overdue: records.len(),
+            average_lateness: average(&lateness),
+            overdue_now,
+        });
+
+        all_lateness.extend(lateness);
+    }
+
+    people.sort_by(|a, b| {
+        let a_lateness = a.average_lateness.unwrap_or(0.0);
+        let b_lateness = b.average_lateness.unwrap_or(0.0);
+        b_lateness
+            .total_cmp(&a_lateness)
+            .then_with(|| a.person.cmp(&b.person))
+    });
+
+    AdherenceReport {
+        people,
+        average_lateness: average(&all_lateness),
+    }
+}
+
+/// Days looked back on by `compute_goals`
+pub(crate) const GOAL_WINDOW_DAYS: i64 = 365;
+
+/// How well a contact goal was met over the last year
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GoalProgress {
+    pub person: PersonName,
+    pub frequency: Frequency,
+    /// Whole intervals since a year ago, or since you first met them
+    pub intervals: usize,
+    /// Intervals with at least one interaction
+    pub met: usize,
+    /// Consecutive intervals met, counting back from today
+    pub streak: usize,
 }
 
-fn merge_logs(previous: Log, new: Log) -> Log {
-    let mut previous_days: HashMap<NaiveDate, Day> = previous
-        .days
+impl GoalProgress {
+    pub fn percentage(&self) -> usize {
+        match self.intervals {
+            0 => 100,
+            intervals => self.met * 100 / intervals,
+        }
+    }
+}
+
+/// Split the last year into intervals of each person's `frequency`, ending
+/// today, and check which ones had an interaction. Worst adherence first.
+pub fn compute_goals(log: &Log, config: &Config, today: NaiveDate) -> Vec<GoalProgress> {
+    let dates = get_interaction_dates(log);
+    let window_start = today - Duration::days(GOAL_WINDOW_DAYS);
+
+    let mut goals: Vec<GoalProgress> = vec![];
+    for person in config.people.iter() {
+        if config.is_ignored(&person.name) {
+            continue;
+        }
+
+        let frequency = match config.get_frequency(person) {
+            Some(frequency) => frequency,
+            None => continue,
+        };
+
+        let person_dates: &[NaiveDate] = match dates.get(&person.name) {
+            Some(person_dates) => person_dates,
+            None => &[],
+        };
+        // met within the last year: only count from then on
+        let first = person_dates
+            .first()
+            .copied()
+            .filter(|first| *first > window_start);
+
+        // most recent first: (today - period, today], then the one before...
+        let period = frequency.duration();
+        let mut met_per_interval: Vec<bool> = vec![];
+        let mut end = today;
+        // periods before the earliest date are never counted anyway
+        while let Some(begin) = end.checked_sub_signed(period) {
+            let counts = match first {
+                Some(first) => first <= end,
+                None => window_start <= begin,
+            };
+            if !counts {
+                break;
+            }
+
+            met_per_interval.push(
+                person_dates
+                    .iter()
+                    .any(|date| begin < *date && *date <= end),
+            );
+            end = begin;
+        }
+
+        goals.push(GoalProgress {
+            person: person.name.clone(),
+            frequency: frequency.clone(),
+            intervals: met_per_interval.len(),
+            met: met_per_interval.iter().filter(|met| **met).count(),
+            streak: met_per_interval.iter().take_while(|met| **met).count(),
+        });
+    }
+
+    goals.sort_by(|a, b| {
+        a.percentage()
+            .cmp(&b.percentage())
+            .then_with(|| a.person.cmp(&b.person))
+    });
+
+    goals
+}
+
+/// Count the reach outs acknowledged with `people remind done` as interactions,
+/// and forget the ones that a logged interaction already covers
+pub fn apply_acknowledgments(
+    interactions: Vec<LastInteraction>,
+    state: &mut State,
+) -> Vec<LastInteraction> {
+    interactions
         .into_iter()
-        .map(|day| (day.date, day))
+        .map(
+            |interaction| match state.acknowledged.get(&interaction.person) {
+                Some(&date) if date > interaction.last => LastInteraction {
+                    last: date,
+                    kind: None,
+                    ..interaction
+                },
+                Some(_) => {
+                    state.acknowledged.remove(&interaction.person);
+                    interaction
+                }
+                None => interaction,
+            },
+        )
+        .collect()
+}
+
+/// People beyond their reach out threshold who are not ignored nor snoozed,
+/// most overdue first
+pub fn due_reminders(
+    interactions: Vec<LastInteraction>,
+    config: &Config,
+    state: &State,
+    today: NaiveDate,
+) -> Vec<LastInteraction> {
+    let mut due: Vec<LastInteraction> = interactions
+        .into_iter()
+        .filter(|interaction| interaction.days_beyond_reachout_threshold.is_some())
+        .filter(|interaction| !config.is_ignored(&interaction.person))
+        .filter(|interaction| !state.is_snoozed(&interaction.person, today))
         .collect();
 
-    let mut days: Vec<Day> = vec![];
-    for day in new.days {
-        let date = day.date;
+    due.sort_by(|a, b| {
+        b.days_beyond_reachout_threshold
+            .cmp(&a.days_beyond_reachout_threshold)
+            .then_with(|| a.person.cmp(&b.person))
+    });
 
-        let merged_day: Day;
-        if let Some(previous_day) = previous_days.remove(&date) {
-            merged_day = merge_days(previous_day, day);
-        } else {
-            merged_day = day;
+    due
+}
+
+/// A duration argument, e.g. `--last 90d`
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    match parse_duration_text(text) {
+        Ok(duration) => Ok(duration),
+        Err(reason) => Err(format!("failed to parse '{text}', reason: {reason}")),
+    }
+}
+
+/// Why a duration could not be read, see `parse_duration_text`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DurationError {
+    Empty,
+    /// A unit with no amount before it, e.g. `weeks`
+    MissingAmount(String),
+    /// An amount with no unit after it, e.g. `3`
+    MissingUnit(i64),
+    UnsupportedUnit(String),
+    UnexpectedCharacter(char),
+    TooLong,
+}
+
+impl fmt::Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DurationError::Empty => write!(f, "expected '<amount> <unit>', e.g. '3 months'"),
+            DurationError::MissingAmount(unit) => write!(f, "no amount found before {unit:?}"),
+            DurationError::MissingUnit(amount) => write!(f, "no unit found after {amount}"),
+            DurationError::UnsupportedUnit(unit) => write!(f, "unsupported unit found: {unit:?}"),
+            DurationError::UnexpectedCharacter(c) => write!(f, "unexpected character found: {c:?}"),
+            DurationError::TooLong => write!(f, "too long"),
         }
-        days.push(merged_day);
     }
+}
 
-    for previous_day in previous_days.into_values() {
-        days.push(previous_day);
+fn unit_days(unit: &str) -> Option<i64> {
+    match unit {
+        "y" | "year" | "years" => Some(DAYS_IN_A_YEAR),
+        "m" | "month" | "months" => Some(DAYS_IN_A_MONTH),
+        "w" | "week" | "weeks" => Some(7),
+        "d" | "day" | "days" => Some(1),
+        _ => None,
     }
+}
 
-    days.sort_by_key(|day| day.date);
+/// Longest duration parsed, 100 000 years, far from where subtracting it from
+/// a date would overflow
+const MAX_DURATION_DAYS: i64 = 100_000 * DAYS_IN_A_YEAR;
 
-    Log { days }
+/// `90d`, `3 months`, or several of them added up, like `2 months 1 week` or
+/// `1y6m`. A month is 30 days and a year 365.
+pub fn parse_duration_text(text: &str) -> Result<Duration, DurationError> {
+    let mut chars = text.chars().peekable();
+    let mut days: i64 = 0;
+    let mut parts = 0;
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        let first = match chars.peek() {
+            Some(first) => *first,
+            None => break,
+        };
+
+        let mut amount = String::new();
+        while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+            amount.push(digit);
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut unit = String::new();
+        while let Some(letter) = chars.next_if(|c| c.is_alphabetic()) {
+            unit.push(letter);
+        }
+
+        let amount: i64 = match (amount.is_empty(), unit.is_empty()) {
+            (true, true) => return Err(DurationError::UnexpectedCharacter(first)),
+            (true, false) => return Err(DurationError::MissingAmount(unit)),
+            (false, _) => match amount.parse() {
+                Ok(amount) => amount,
+                Err(_) => return Err(DurationError::TooLong),
+            },
+        };
+        if unit.is_empty() {
+            return Err(DurationError::MissingUnit(amount));
+        }
+        let unit_days = match unit_days(&unit.to_lowercase()) {
+            Some(unit_days) => unit_days,
+            None => return Err(DurationError::UnsupportedUnit(unit)),
+        };
+
+        days = match amount
+            .checked_mul(unit_days)
+            .and_then(|d| d.checked_add(days))
+        {
+            Some(days) => days,
+            None => return Err(DurationError::TooLong),
+        };
+        parts += 1;
+    }
+
+    match parts {
+        0 => Err(DurationError::Empty),
+        _ if days > MAX_DURATION_DAYS => Err(DurationError::TooLong),
+        _ => Ok(Duration::days(days)),
+    }
+}
+
+/// After the first line of entries in a person's log that only mention them
+/// in passing
+pub static IN_PASSING_MARKER: &str = "_(mentioned)_";
+
+fn mark_in_passing(entry: &mut Entry) {
+    let end = entry.content.find('\n').unwrap_or(entry.content.len());
+    entry
+        .content
+        .insert_str(end, &format!(" {IN_PASSING_MARKER}"));
 }
 
+/// Entries per date and precision, in the order they were found
+type PersonDays = BTreeMap<(NaiveDate, DatePrecision), Vec<Arc<Entry>>>;
+
+/// Group the entries by the people mentioned in them, in a single pass. Their
+/// logs share each entry, but for the people mentioned in passing, who get a
+/// marked copy. Ignored people get `None`, so their logs can be deleted.
 pub fn split_log_per_person(log: Log, config: &config::Config) -> HashMap<PersonName, Option<Log>> {
-    let should_ignore: HashSet<PersonName> = config.ignore.clone().into_iter().collect();
-    let mut per_person: HashMap<PersonName, Option<Log>> = HashMap::new();
+    let mut per_person: HashMap<PersonName, PersonDays> = HashMap::new();
+    let mut ignored: HashSet<PersonName> = HashSet::new();
+
     for day in log.days {
         for entry in day.entries {
-            for person in entry.clone().related {
-                if should_ignore.contains(&person) {
-                    per_person.insert(person, None);
+            for person in entry.related.iter() {
+                if config.is_ignored(person) {
+                    ignored.insert(person.clone());
                     continue;
                 }
 
-                let new = Log {
-                    days: vec![Day {
-                        date: day.date,
-                        entries: vec![entry.clone()],
-                    }],
-                };
-
-                if let Some(existing_log) = per_person.remove(&person) {
-                    if let Some(previous) = existing_log {
-                        let updated = merge_logs(previous, new);
-                        per_person.insert(person, Some(updated));
-                    } else {
-                        per_person.insert(person, Some(new));
+                let entry = match entry.mentions_in_passing(person) {
+                    true => {
+                        let mut marked = Entry::clone(&entry);
+                        mark_in_passing(&mut marked);
+                        Arc::new(marked)
                     }
-                } else {
-                    per_person.insert(person, Some(new));
-                }
+                    false => Arc::clone(&entry),
+                };
+                per_person
+                    .entry(person.clone())
+                    .or_default()
+                    .entry((day.date, day.precision))
+                    .or_default()
+                    .push(entry);
             }
         }
     }
 
-    per_person
+    let logs = per_person.into_iter().map(|(person, days)| {
+        let days = days
+            .into_iter()
+            .map(|((date, precision), entries)| Day {
+                date,
+                precision,
+                entries,
+                source_file: None,
+                line_range: 0..0,
+            })
+            .collect();
+        (person, Some(Log { days }))
+    });
+
+    logs.chain(ignored.into_iter().map(|person| (person, None)))
+        .collect()
+}
+
+/// Files written at the same time by default: one per CPU, up to 8
+pub fn default_jobs() -> usize {
+    match thread::available_parallelism() {
+        Ok(cpus) => cpus.get().min(8),
+        Err(_) => 1,
+    }
 }
 
-fn infer_log_path(person: PersonName, dir: &PathBuf) -> PathBuf {
-    let file_name = format!("{person}.md");
-    let path = dir.join(file_name);
-    path
+/// Write the logs in `jobs` threads at most, in no particular order
+pub fn write_person_logs(
+    per_person_logs: HashMap<PersonName, Option<Log>>,
+    config: &Config,
+    jobs: usize,
+    transaction: &Transaction,
+) -> Vec<LogWritten> {
+    let queue = Mutex::new(per_person_logs.into_iter());
+    let results: Mutex<Vec<LogWritten>> = Mutex::new(vec![]);
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            scope.spawn(|| loop {
+                let next = queue.lock().expect("a writer panicked").next();
+                let Some((person, person_log)) = next else {
+                    break;
+                };
+
+                let written = write_person_log(person, person_log, config, transaction);
+                results.lock().expect("a writer panicked").push(written);
+            });
+        }
+    });
+
+    results.into_inner().expect("a writer panicked")
 }
 
 type ErrorReason = String;
 
 pub enum LogWritten {
     Written(PathBuf),
+    Unchanged(PathBuf),
     FailedToWrite(PathBuf, ErrorReason),
     NothingToDelete(PathBuf),
     Deleted(PathBuf),
     FailedToDelete(PathBuf, ErrorReason),
 }
 
-pub fn write_person_log(person: PersonName, log_opt: Option<Log>, dir: PathBuf) -> LogWritten {
-    let path = infer_log_path(person, &dir);
+/// The days of a person's log that their per-person log shows, in its order
+pub fn arrange_person_log(log: &Log, options: &PerPersonConfig, today: NaiveDate) -> Log {
+    let since = options.last_years.map(|years| {
+        today
+            .checked_sub_months(Months::new(years * 12))
+            .unwrap_or(NaiveDate::MIN)
+    });
 
-    if let Some(log) = log_opt {
-        let content = format!("{log}");
-        match fs::write(path.clone(), content) {
-            Ok(()) => LogWritten::Written(path),
-            Err(reason) => LogWritten::FailedToWrite(path, format!("{reason}")),
+    // newest first, to keep the last entries
+    let mut days: Vec<Day> = vec![];
+    let mut left = options.last_entries.unwrap_or(usize::MAX);
+    for day in log.days.iter().rev() {
+        if left == 0 || since.is_some_and(|since| day.date < since) {
+            break;
         }
-    } else {
-        // delete logs of ignored people
-        if path.exists() {
-            match fs::remove_file(path.clone()) {
-                Ok(()) => LogWritten::Deleted(path),
-                Err(reason) => LogWritten::FailedToDelete(path, format!("{reason}")),
+        let skipped = day.entries.len().saturating_sub(left);
+        left -= day.entries.len() - skipped;
+        days.push(Day {
+            entries: day.entries[skipped..].to_vec(),
+            ..day.clone()
+        });
+    }
+
+    if options.order == LogOrder::OldestFirst {
+        days.reverse();
+    }
+    Log { days }
+}
+
+/// `> Last seen on 2024-05-01, typically every 10 days, 42 entries since
+/// 2019-03-02`, without relative dates so that it does not change every day
+fn render_summary_header(log: &Log) -> String {
+    let mut dates: Vec<NaiveDate> = log.days.iter().map(|day| day.date).collect();
+    dates.sort();
+    dates.dedup();
+    let (Some(first), Some(last)) = (dates.first(), dates.last()) else {
+        return "".to_string();
+    };
+
+    let mut parts = vec![format!("Last seen on {last}")];
+    if let Some(days) = profile::median_gap(&dates) {
+        parts.push(format!("typically every {days} days"));
+    }
+    let entries: usize = log.days.iter().map(|day| day.entries.len()).sum();
+    parts.push(format!("{entries} entries since {first}"));
+
+    format!("> {}\n\n", parts.join(", "))
+}
+
+/// Content of a per-person log file: the `per_person` template if there is one,
+/// or else the front matter followed by the entries. The front matter and the
+/// summary header are about every entry, even if `per_person` shows only some.
+pub fn render_person_log(
+    person: &PersonName,
+    log: &Log,
+    config: &Config,
+) -> Result<String, String> {
+    let shown = arrange_person_log(log, &config.per_person, config.today());
+    match &config.templates.per_person {
+        Some(template) => {
+            let context = PersonLogContext::new(person, &shown, config);
+            render::render_file(template, &context)
+        }
+        None => {
+            let front_matter = render_front_matter(person, log, config);
+            let header = match config.per_person.summary_header {
+                true => render_summary_header(log),
+                false => "".to_string(),
+            };
+            Ok(format!("{front_matter}{header}{shown}"))
+        }
+    }
+}
+
+/// Write the person's log, or delete it if they are ignored. Files that would
+/// not change are left untouched.
+pub fn write_person_log(
+    person: PersonName,
+    log_opt: Option<Log>,
+    config: &Config,
+    transaction: &Transaction,
+) -> LogWritten {
+    let path = config.get_person_log_path(&person);
+
+    let planned = match plan_person_log(person, log_opt, config) {
+        Ok(planned) => planned,
+        Err(reason) => return LogWritten::FailedToWrite(path, reason),
+    };
+
+    match planned {
+        // templated paths can put each person in their own directory, which
+        // the transaction creates
+        PlannedWrite::Create { path, after } | PlannedWrite::Update { path, after, .. } => {
+            match transaction.write(&path, &after) {
+                Ok(()) => LogWritten::Written(path),
+                Err(reason) => LogWritten::FailedToWrite(path, reason),
             }
-        } else {
-            LogWritten::NothingToDelete(path)
         }
+        PlannedWrite::Unchanged(path) => LogWritten::Unchanged(path),
+        // delete logs of ignored people
+        PlannedWrite::Delete { path, .. } => match transaction.remove(&path) {
+            Ok(()) => LogWritten::Deleted(path),
+            Err(reason) => LogWritten::FailedToDelete(path, reason),
+        },
+        PlannedWrite::NothingToDelete(path) => LogWritten::NothingToDelete(path),
+    }
+}
+
+/// What `write_person_log` would do to a per-person log file
+#[derive(Debug, PartialEq, Eq)]
+pub enum PlannedWrite {
+    Create {
+        path: PathBuf,
+        after: String,
+    },
+    Update {
+        path: PathBuf,
+        before: String,
+        after: String,
+    },
+    Unchanged(PathBuf),
+    Delete {
+        path: PathBuf,
+        before: String,
+    },
+    NothingToDelete(PathBuf),
+}
+
+impl PlannedWrite {
+    pub fn path(&self) -> &PathBuf {
+        match self {
+            PlannedWrite::Create { path, .. } => path,
+            PlannedWrite::Update { path, .. } => path,
+            PlannedWrite::Unchanged(path) => path,
+            PlannedWrite::Delete { path, .. } => path,
+            PlannedWrite::NothingToDelete(path) => path,
+        }
+    }
+
+    /// Unified diff between the file on disk and what would be written, empty
+    /// if nothing changes
+    pub fn diff(&self) -> String {
+        let (before, after) = match self {
+            PlannedWrite::Create { after, .. } => ("", after.as_str()),
+            PlannedWrite::Update { before, after, .. } => (before.as_str(), after.as_str()),
+            PlannedWrite::Delete { before, .. } => (before.as_str(), ""),
+            PlannedWrite::Unchanged(_) | PlannedWrite::NothingToDelete(_) => return String::new(),
+        };
+
+        let path = self.path().display().to_string();
+        TextDiff::from_lines(before, after)
+            .unified_diff()
+            .header(&path, &path)
+            .to_string()
+    }
+}
+
+impl fmt::Display for PlannedWrite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlannedWrite::Create { path, .. } => write!(f, "Would create {path:?}"),
+            PlannedWrite::Update { path, .. } => write!(f, "Would update {path:?}"),
+            PlannedWrite::Unchanged(path) => write!(f, "Unchanged: {path:?}"),
+            PlannedWrite::Delete { path, .. } => write!(f, "Would delete {path:?}"),
+            PlannedWrite::NothingToDelete(path) => write!(f, "Nothing to delete: {path:?}"),
+        }
+    }
+}
+
+fn read_existing(path: &Path) -> Result<Option<String>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+    }
+}
+
+/// Like `write_person_log`, but only compare with what is on disk
+pub fn plan_person_log(
+    person: PersonName,
+    log_opt: Option<Log>,
+    config: &Config,
+) -> Result<PlannedWrite, String> {
+    let path = config.get_person_log_path(&person);
+    let before = read_existing(&path)?;
+    let after = match log_opt {
+        Some(log) => Some(render_person_log(&person, &log, config)?),
+        None => None,
+    };
+
+    Ok(compare_person_log(path, before, after))
+}
+
+fn compare_person_log(
+    path: PathBuf,
+    before: Option<String>,
+    after: Option<String>,
+) -> PlannedWrite {
+    match (before, after) {
+        (None, Some(after)) => PlannedWrite::Create { path, after },
+        (Some(before), Some(after)) if before == after => PlannedWrite::Unchanged(path),
+        (Some(before), Some(after)) => PlannedWrite::Update {
+            path,
+            before,
+            after,
+        },
+        (Some(before), None) => PlannedWrite::Delete { path, before },
+        (None, None) => PlannedWrite::NothingToDelete(path),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::Person;
     use crate::{log, test_utils::d};
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
+    fn config_with_people(people: Vec<Person>) -> Config {
+        Config::builder()
+            .people_dir("/tmp/people")
+            .people(people)
+            .build()
+    }
+
     fn sort_to_compare(summary: Vec<LastInteraction>) -> Vec<LastInteraction> {
         let mut copy = summary.clone();
         copy.sort_by_key(|interaction| (interaction.last, interaction.person.clone()));
@@ -279,19 +938,615 @@ mod tests {
                 person: "JohnDoe".to_string(),
                 last: d("2000-01-02"),
                 days_beyond_reachout_threshold: None,
+                kind: None,
             },
             LastInteraction {
                 person: "JaneDoe".to_string(),
                 last: d("2000-01-02"),
                 days_beyond_reachout_threshold: None,
+                kind: None,
             },
             LastInteraction {
                 person: "Abu".to_string(),
                 last: d("2000-01-02"),
                 days_beyond_reachout_threshold: None,
+                kind: None,
             },
         ];
 
         assert_eq!(sort_to_compare(summary), sort_to_compare(expected));
     }
+
+    #[test]
+    fn test_get_last_interactions_count_periods_as_their_last_day() {
+        let log = log::parse_log_file_content(indoc!(
+            "
+            # 2000-02
+
+            - #JohnDoe :
+            - #JaneDoe :
+
+            # 2000-W03
+
+            - #Abu :
+
+            # 2000-02-10
+
+            - #JaneDoe :
+            ",
+        ));
+
+        let last: Vec<(PersonName, NaiveDate)> = get_last_interactions(&log)
+            .into_iter()
+            .map(|interaction| (interaction.person, interaction.last))
+            .collect();
+
+        assert_eq!(
+            last,
+            vec![
+                ("Abu".to_string(), d("2000-01-23")),
+                ("JaneDoe".to_string(), d("2000-02-29")),
+                ("JohnDoe".to_string(), d("2000-02-29")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_last_interactions_skip_no_contact_entries() {
+        let log = log::parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+
+            # 2000-01-05
+
+            - #JohnDoe : saw a photo of his trip @no-contact
+              - #JaneDoe was in it too
+            "
+        ));
+
+        let last: Vec<(PersonName, NaiveDate)> = get_last_interactions(&log)
+            .into_iter()
+            .map(|interaction| (interaction.person, interaction.last))
+            .collect();
+        assert_eq!(last, vec![("JohnDoe".to_string(), d("2000-01-01"))]);
+
+        let mut per_person = split_log_per_person(log, &Config::builder().build());
+        let history = per_person.remove("JohnDoe").unwrap().unwrap();
+        assert_eq!(history.days.len(), 2);
+    }
+
+    #[test]
+    fn test_get_last_interactions_at_mention_level() {
+        let log = log::parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+
+            # 2000-01-05
+
+            - #JaneDoe :
+              - with #JohnDoe
+            "
+        ));
+
+        let last = |level: MentionLevel| -> Vec<(PersonName, NaiveDate)> {
+            get_last_interactions_at(&log, level)
+                .into_iter()
+                .map(|interaction| (interaction.person, interaction.last))
+                .collect()
+        };
+
+        assert_eq!(
+            last(MentionLevel::Main),
+            vec![
+                ("JohnDoe".to_string(), d("2000-01-01")),
+                ("JaneDoe".to_string(), d("2000-01-05")),
+            ]
+        );
+        assert_eq!(
+            last(MentionLevel::Related),
+            vec![("JohnDoe".to_string(), d("2000-01-05"))]
+        );
+        assert_eq!(
+            last(MentionLevel::Both),
+            vec![
+                ("JaneDoe".to_string(), d("2000-01-05")),
+                ("JohnDoe".to_string(), d("2000-01-05")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_last_interactions_from_empty_log() {
+        let log = log::parse_log_file_content("");
+
+        assert_eq!(get_last_interactions(&log), vec![]);
+    }
+
+    #[test]
+    fn test_assess_reminder() {
+        let interaction = LastInteraction {
+            person: "JohnDoe".to_string(),
+            last: d("2000-01-01"),
+            days_beyond_reachout_threshold: None,
+            kind: None,
+        };
+
+        let on_time = interaction.assess_reminder(Duration::days(10), d("2000-01-11"));
+        assert_eq!(on_time.days_beyond_reachout_threshold, None);
+
+        let late = interaction.assess_reminder(Duration::days(10), d("2000-01-14"));
+        assert_eq!(late.days_beyond_reachout_threshold, Some(3));
+    }
+
+    #[test]
+    fn test_split_empty_log_per_person() {
+        let config = config_with_people(vec![]);
+
+        let per_person = split_log_per_person(Log { days: vec![] }, &config);
+
+        assert!(per_person.is_empty());
+    }
+
+    #[test]
+    fn test_split_log_per_person() {
+        let log = log::parse_log_file_content(indoc!(
+            "
+            # 2000-01-02
+
+            - #JohnDoe :
+              - with #JaneDoe and #Ignored
+
+            # 2000-01-01
+
+            - #JaneDoe :
+              - coffee
+
+            # 2000-01-02
+
+            - #JaneDoe :
+              - later that day
+            "
+        ));
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .ignore(vec!["Ignored".to_string()])
+            .build();
+
+        let mut per_person = split_log_per_person(log, &config);
+
+        let jane = per_person.remove("JaneDoe").unwrap().unwrap();
+        let expected = indoc!(
+            "
+            # 2000-01-01
+
+            - #JaneDoe :
+              - coffee
+
+            # 2000-01-02
+
+            - #JohnDoe : _(mentioned)_
+              - with #JaneDoe and #Ignored
+            - #JaneDoe :
+              - later that day
+            "
+        );
+        assert_eq!(format!("{jane}"), expected.trim_start());
+        assert_eq!(per_person.remove("Ignored"), Some(None));
+        assert_eq!(per_person.remove("JohnDoe").unwrap().unwrap().days.len(), 1);
+        assert!(per_person.is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid_duration_text() {
+        assert_eq!(parse_duration_text(""), Err(DurationError::Empty));
+        assert_eq!(parse_duration_text("3"), Err(DurationError::MissingUnit(3)));
+        assert_eq!(
+            parse_duration_text("w"),
+            Err(DurationError::MissingAmount("w".to_string()))
+        );
+        assert_eq!(
+            parse_duration_text("3 fortnights"),
+            Err(DurationError::UnsupportedUnit("fortnights".to_string()))
+        );
+        assert_eq!(
+            parse_duration_text("-3d"),
+            Err(DurationError::UnexpectedCharacter('-'))
+        );
+        assert_eq!(
+            parse_duration_text("99999999999999999999d"),
+            Err(DurationError::TooLong)
+        );
+        assert_eq!(
+            parse_duration_text("100001 years"),
+            Err(DurationError::TooLong)
+        );
+        assert_eq!(
+            parse_duration_text("100000 years"),
+            Ok(Duration::days(36_500_000))
+        );
+        assert_eq!(
+            parse_duration("3").unwrap_err(),
+            "failed to parse '3', reason: no unit found after 3"
+        );
+    }
+
+    #[test]
+    fn test_parse_duration_text() {
+        assert_eq!(parse_duration_text("2w"), Ok(Duration::weeks(2)));
+        assert_eq!(parse_duration_text("10d"), Ok(Duration::days(10)));
+        assert_eq!(parse_duration_text("1y"), Ok(Duration::days(365)));
+        assert_eq!(parse_duration_text(" 3 Months "), Ok(Duration::days(90)));
+        assert_eq!(
+            parse_duration_text("2 months 1 week"),
+            Ok(Duration::days(67))
+        );
+        assert_eq!(parse_duration_text("1y6m, 2d"), Ok(Duration::days(547)));
+    }
+
+    #[test]
+    fn test_compute_goals() {
+        let log = log::parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+            - #JaneDoe :
+
+            # 2000-01-20
+
+            - #JohnDoe :
+
+            # 2000-01-25
+
+            - #JohnDoe :
+            "
+        ));
+        let config = config_with_people(vec![
+            Person {
+                name: "JohnDoe".to_string(),
+                frequency: Some("every 1 week".parse().unwrap()),
+                ..Default::default()
+            },
+            Person {
+                name: "JaneDoe".to_string(),
+                themes: vec!["uni".to_string()],
+                ..Default::default()
+            },
+            Person {
+                name: "Abu".to_string(),
+                ..Default::default()
+            },
+        ]);
+        let config = Config {
+            theme_frequencies: [("uni".to_string(), "2w".parse().unwrap())].into(),
+            ..config
+        };
+
+        let goals = compute_goals(&log, &config, d("2000-01-29"));
+
+        let summary: Vec<(&str, usize, usize, usize)> = goals
+            .iter()
+            .map(|goal| (goal.person.as_str(), goal.intervals, goal.met, goal.streak))
+            .collect();
+        // JaneDoe: (15, 29], (1, 15], (-13, 1]
+        // JohnDoe: (22, 29], (15, 22], (8, 15], (1, 8], (-6, 1]
+        assert_eq!(summary, vec![("JaneDoe", 3, 1, 0), ("JohnDoe", 5, 3, 2)]);
+        assert_eq!(goals[0].percentage(), 33);
+    }
+
+    #[test]
+    fn test_apply_acknowledgments() {
+        let interaction = |person: &str, last: &str| LastInteraction {
+            person: person.to_string(),
+            last: d(last),
+            days_beyond_reachout_threshold: None,
+            kind: None,
+        };
+        let mut state = State::default();
+        state.acknowledge("JohnDoe".to_string(), d("2000-01-10"));
+        state.acknowledge("JaneDoe".to_string(), d("2000-01-10"));
+
+        let interactions = apply_acknowledgments(
+            vec![
+                interaction("JohnDoe", "2000-01-01"),
+                interaction("JaneDoe", "2000-01-12"),
+            ],
+            &mut state,
+        );
+
+        assert_eq!(
+            interactions,
+            vec![
+                interaction("JohnDoe", "2000-01-10"),
+                interaction("JaneDoe", "2000-01-12"),
+            ]
+        );
+        // JaneDoe's interaction was logged, so it is not needed anymore
+        assert_eq!(
+            state.acknowledged,
+            [("JohnDoe".to_string(), d("2000-01-10"))].into()
+        );
+    }
+
+    #[test]
+    fn test_update_reminder_history() {
+        let log = log::parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - stuff: blah
+
+            # 2000-01-20
+
+            - #JohnDoe :
+              - stuff: bleh
+            "
+        ));
+        let config = config_with_people(vec![Person {
+            name: "JohnDoe".to_string(),
+            location: "Here".to_string(),
+            remind_after: Some("1 week".parse().unwrap()),
+            ..Default::default()
+        }]);
+        let mut state = State {
+            reminders: [(
+                "JohnDoe".to_string(),
+                vec![ReminderRecord {
+                    crossed: d("2000-01-08"),
+                    contacted: None,
+                }],
+            )]
+            .into(),
+            ..Default::default()
+        };
+
+        update_reminder_history(&mut state, &log, &config, d("2000-02-01"));
+
+        let expected = vec![
+            ReminderRecord {
+                crossed: d("2000-01-08"),
+                contacted: Some(d("2000-01-20")),
+            },
+            ReminderRecord {
+                crossed: d("2000-01-27"),
+                contacted: None,
+            },
+        ];
+
+        assert_eq!(state.reminders["JohnDoe"], expected);
+    }
+
+    #[test]
+    fn test_compute_adherence() {
+        let state = State {
+            reminders: [
+                (
+                    "JohnDoe".to_string(),
+                    vec![
+                        ReminderRecord {
+                            crossed: d("2000-01-01"),
+                            contacted: Some(d("2000-01-05")),
+                        },
+                        ReminderRecord {
+                            crossed: d("2000-02-01"),
+                            contacted: None,
+                        },
+                    ],
+                ),
+                (
+                    "JaneDoe".to_string(),
+                    vec![ReminderRecord {
+                        crossed: d("2000-01-01"),
+                        contacted: Some(d("2000-01-11")),
+                    }],
+                ),
+            ]
+            .into(),
+            ..Default::default()
+        };
+
+        let expected = AdherenceReport {
+            people: vec![
+                Adherence {
+                    person: "JaneDoe".to_string(),
+                    times_overdue: 1,
+                    average_lateness: Some(10.0),
+                    overdue_now: None,
+                },
+                Adherence {
+                    person: "JohnDoe".to_string(),
+                    times_overdue: 2,
+                    average_lateness: Some(4.0),
+                    overdue_now: Some(3),
+                },
+            ],
+            average_lateness: Some(7.0),
+        };
+
+        assert_eq!(compute_adherence(&state, d("2000-02-04")), expected);
+    }
+
+    #[test]
+    fn test_compute_adherence_without_history() {
+        let expected = AdherenceReport {
+            people: vec![],
+            average_lateness: None,
+        };
+
+        assert_eq!(
+            compute_adherence(&State::default(), d("2000-01-01")),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_split_pinned() {
+        let config = config_with_people(vec![Person {
+            name: "JaneDoe".to_string(),
+            pinned: true,
+            ..Default::default()
+        }]);
+        let interaction = |person: &str| LastInteraction {
+            person: person.to_string(),
+            last: d("2000-01-01"),
+            days_beyond_reachout_threshold: None,
+            kind: None,
+        };
+
+        let (pinned, rest) = split_pinned(
+            vec![interaction("JohnDoe"), interaction("JaneDoe")],
+            &config,
+        );
+
+        assert_eq!(pinned, vec![interaction("JaneDoe")]);
+        assert_eq!(rest, vec![interaction("JohnDoe")]);
+        assert_eq!(pinned_never_seen(&pinned, &config), Vec::<String>::new());
+        assert_eq!(pinned_never_seen(&[], &config), vec!["JaneDoe".to_string()]);
+    }
+
+    #[test]
+    fn test_due_reminders() {
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .ignore(vec!["Ignored".to_string()])
+            .build();
+        let interaction = |person: &str, overdue: Option<DaysAgo>| LastInteraction {
+            person: person.to_string(),
+            last: d("2000-01-01"),
+            days_beyond_reachout_threshold: overdue,
+            kind: None,
+        };
+        let mut state = State::default();
+        state.snooze("Snoozed".to_string(), d("2000-02-01"));
+
+        let due = due_reminders(
+            vec![
+                interaction("OnTime", None),
+                interaction("Ignored", Some(9)),
+                interaction("Snoozed", Some(9)),
+                interaction("JohnDoe", Some(2)),
+                interaction("JaneDoe", Some(5)),
+            ],
+            &config,
+            &state,
+            d("2000-02-01"),
+        );
+
+        assert_eq!(
+            due,
+            vec![
+                interaction("JaneDoe", Some(5)),
+                interaction("JohnDoe", Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_person_log() {
+        let path = PathBuf::from("/tmp/people/per-person-logs/JohnDoe.md");
+        let text = |content: &str| Some(content.to_string());
+
+        let updated = compare_person_log(
+            path.clone(),
+            text("# 2000-01-01\n"),
+            text("# 2000-01-02\n\n- #JohnDoe :\n"),
+        );
+        let expected_diff = indoc!(
+            "
+            --- /tmp/people/per-person-logs/JohnDoe.md
+            +++ /tmp/people/per-person-logs/JohnDoe.md
+            @@ -1 +1,3 @@
+            -# 2000-01-01
+            +# 2000-01-02
+            +
+            +- #JohnDoe :
+            "
+        );
+        assert_eq!(updated.diff(), expected_diff.trim_start());
+
+        assert_eq!(
+            compare_person_log(path.clone(), text("same"), text("same")),
+            PlannedWrite::Unchanged(path.clone())
+        );
+        assert_eq!(
+            compare_person_log(path.clone(), text("stale"), None),
+            PlannedWrite::Delete {
+                path: path.clone(),
+                before: "stale".to_string()
+            }
+        );
+        assert_eq!(
+            compare_person_log(path.clone(), None, None),
+            PlannedWrite::NothingToDelete(path)
+        );
+    }
+
+    #[test]
+    fn test_arrange_person_log() {
+        let log = log::parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe : a
+
+            # 2000-01-11
+
+            - #JohnDoe : b
+            - #JohnDoe : c
+
+            # 2000-01-21
+
+            - #JohnDoe : d
+            "
+        ));
+        let dates = |log: Log| -> Vec<(NaiveDate, usize)> {
+            log.days
+                .iter()
+                .map(|day| (day.date, day.entries.len()))
+                .collect()
+        };
+
+        let last_year = PerPersonConfig {
+            last_years: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            dates(arrange_person_log(&log, &last_year, d("2001-01-15"))),
+            vec![(d("2000-01-21"), 1)]
+        );
+
+        let options = PerPersonConfig {
+            order: LogOrder::NewestFirst,
+            last_entries: Some(2),
+            summary_header: true,
+            ..Default::default()
+        };
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .front_matter(vec![])
+            .per_person(options)
+            .build();
+        let expected = indoc!(
+            "
+            > Last seen on 2000-01-21, typically every 10 days, 4 entries since 2000-01-01
+
+            # 2000-01-21
+
+            - #JohnDoe : d
+
+            # 2000-01-11
+
+            - #JohnDoe : c
+            "
+        );
+        assert_eq!(
+            render_person_log(&"JohnDoe".to_string(), &log, &config),
+            Ok(expected.trim_start().to_string())
+        );
+    }
 }
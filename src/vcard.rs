@@ -0,0 +1,409 @@
+use core::fmt;
+
+use chrono::NaiveDate;
+
+use crate::config::Person;
+use crate::model::{Location, PersonName};
+
+/// The bits of a vCard that are relevant to the config
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Contact {
+    pub full_name: String,
+    pub location: Option<Location>,
+    pub birthday: Option<NaiveDate>,
+}
+
+impl Contact {
+    /// `Jane Doe` becomes `JaneDoe`, so it can be referenced as `#JaneDoe`
+    pub fn person_name(&self) -> PersonName {
+        self.full_name
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join("")
+    }
+}
+
+/// Join folded lines: a line starting with a space or a tab continues the previous one
+pub(crate) fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+
+    for line in content.lines() {
+        let line = line.trim_end_matches('\r');
+        if let Some(continuation) = line.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(continuation);
+                continue;
+            }
+        }
+        lines.push(line.to_string());
+    }
+
+    lines
+}
+
+pub(crate) fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+/// Split a structured value on `;`, ignoring escaped semicolons
+fn split_components(value: &str) -> Vec<String> {
+    let mut components: Vec<String> = vec![];
+    let mut current = String::new();
+    let mut escaped = false;
+
+    for ch in value.chars() {
+        if escaped {
+            current.push('\\');
+            current.push(ch);
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == ';' {
+            components.push(unescape(&current));
+            current = String::new();
+        } else {
+            current.push(ch);
+        }
+    }
+    components.push(unescape(&current));
+
+    components
+}
+
+fn parse_birthday(value: &str) -> Option<NaiveDate> {
+    // dates without year (`--0501`) cannot be represented
+    if value.starts_with("--") {
+        return None;
+    }
+
+    let date = value.split('T').next().unwrap_or(value);
+    for format in ["%Y-%m-%d", "%Y%m%d"] {
+        if let Ok(date) = NaiveDate::parse_from_str(date, format) {
+            return Some(date);
+        }
+    }
+
+    None
+}
+
+/// Use the most specific non-empty part of an `ADR` (locality, region, country)
+fn parse_location(value: &str) -> Option<Location> {
+    let components = split_components(value);
+
+    [3, 4, 6]
+        .iter()
+        .filter_map(|index| components.get(*index))
+        .map(|component| component.trim())
+        .find(|component| !component.is_empty())
+        .map(|component| component.to_string())
+}
+
+fn name_from_structured_name(value: &str) -> String {
+    let components = split_components(value);
+    let family = components.first().map(|s| s.as_str()).unwrap_or("");
+    let given = components.get(1).map(|s| s.as_str()).unwrap_or("");
+
+    format!("{given} {family}").trim().to_string()
+}
+
+pub fn parse_vcards(content: &str) -> Vec<Contact> {
+    let mut contacts: Vec<Contact> = vec![];
+    let mut current: Option<Contact> = None;
+    let mut structured_name: Option<String> = None;
+
+    for line in unfold(content) {
+        let (property, value) = match line.split_once(':') {
+            Some(parts) => parts,
+            None => continue,
+        };
+
+        // drop the group prefix (`item1.ADR`) and the parameters (`ADR;TYPE=home`)
+        let name = property.split(';').next().unwrap_or("");
+        let name = name.rsplit('.').next().unwrap_or("").to_uppercase();
+
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VCARD") => {
+                current = Some(Contact::default());
+                structured_name = None;
+            }
+            ("END", Some(contact)) if value.eq_ignore_ascii_case("VCARD") => {
+                if contact.full_name.is_empty() {
+                    if let Some(n) = &structured_name {
+                        contact.full_name = name_from_structured_name(n);
+                    }
+                }
+                if !contact.full_name.is_empty() {
+                    contacts.push(contact.clone());
+                }
+                current = None;
+            }
+            ("FN", Some(contact)) => contact.full_name = unescape(value).trim().to_string(),
+            ("N", Some(_)) => structured_name = Some(value.to_string()),
+            ("ADR", Some(contact)) if contact.location.is_none() => {
+                contact.location = parse_location(value);
+            }
+            ("BDAY", Some(contact)) => contact.birthday = parse_birthday(value),
+            _ => {}
+        }
+    }
+
+    contacts
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PersonChange {
+    Added(Box<Person>),
+    Updated {
+        before: Box<Person>,
+        after: Box<Person>,
+    },
+}
+
+impl fmt::Display for PersonChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PersonChange::Added(person) => {
+                writeln!(f, "+ {}", person.name)?;
+                if !person.location.is_empty() {
+                    writeln!(f, "+   location: {}", person.location)?;
+                }
+                if let Some(birthday) = person.birthday {
+                    writeln!(f, "+   birthday: {birthday}")?;
+                }
+            }
+            PersonChange::Updated { before, after } => {
+                writeln!(f, "~ {}", after.name)?;
+                if before.location != after.location {
+                    writeln!(f, "-   location: {}", before.location)?;
+                    writeln!(f, "+   location: {}", after.location)?;
+                }
+                if before.birthday != after.birthday {
+                    if let Some(birthday) = before.birthday {
+                        writeln!(f, "-   birthday: {birthday}")?;
+                    }
+                    if let Some(birthday) = after.birthday {
+                        writeln!(f, "+   birthday: {birthday}")?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn find_person<'a>(people: &'a [Person], contact: &Contact) -> Option<&'a Person> {
+    let name = contact.person_name();
+
+    people.iter().find(|person| {
+        person.name == name
+            || person.aliases.contains(&name)
+            || person.aliases.contains(&contact.full_name)
+    })
+}
+
+/// Work out which people must be added or updated in the config
+pub fn plan_import(people: &[Person], contacts: &[Contact]) -> Vec<PersonChange> {
+    let mut changes: Vec<PersonChange> = vec![];
+
+    for contact in contacts {
+        match find_person(people, contact) {
+            Some(existing) => {
+                let mut updated = existing.clone();
+                if let Some(location) = &contact.location {
+                    updated.location = location.clone();
+                }
+                if contact.birthday.is_some() {
+                    updated.birthday = contact.birthday;
+                }
+
+                if &updated != existing {
+                    changes.push(PersonChange::Updated {
+                        before: Box::new(existing.clone()),
+                        after: Box::new(updated),
+                    });
+                }
+            }
+            None => {
+                // the same contact more than once, e.g. from two address books
+                let name = contact.person_name();
+                let added = changes.iter().any(|change| match change {
+                    PersonChange::Added(person) => person.name == name,
+                    PersonChange::Updated { .. } => false,
+                });
+                if added {
+                    continue;
+                }
+                changes.push(PersonChange::Added(Box::new(Person {
+                    name,
+                    location: contact.location.clone().unwrap_or_default(),
+                    birthday: contact.birthday,
+                    ..Default::default()
+                })));
+            }
+        }
+    }
+
+    changes
+}
+
+pub fn apply_changes(people: &[Person], changes: &[PersonChange]) -> Vec<Person> {
+    let mut updated: Vec<Person> = people.to_vec();
+
+    for change in changes {
+        match change {
+            PersonChange::Added(person) => updated.push(person.as_ref().clone()),
+            PersonChange::Updated { before, after } => {
+                for person in updated.iter_mut() {
+                    if person == before.as_ref() {
+                        *person = after.as_ref().clone();
+                    }
+                }
+            }
+        }
+    }
+
+    updated
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_vcards() {
+        let content = indoc!(
+            "
+            BEGIN:VCARD
+            VERSION:3.0
+            FN:Jane Doe
+            item1.ADR;TYPE=home:;;Some street 1;Berlin;;10115;Germany
+            BDAY:1990-05-01
+            END:VCARD
+            BEGIN:VCARD
+            VERSION:3.0
+            N:Doe;John;;;
+            ADR;TYPE=work:;;;;;;United
+              Kingdom
+            BDAY:--0501
+            END:VCARD
+            "
+        );
+
+        let expected = vec![
+            Contact {
+                full_name: "Jane Doe".to_string(),
+                location: Some("Berlin".to_string()),
+                birthday: Some(d("1990-05-01")),
+            },
+            Contact {
+                full_name: "John Doe".to_string(),
+                location: Some("United Kingdom".to_string()),
+                birthday: None,
+            },
+        ];
+
+        assert_eq!(parse_vcards(content), expected);
+    }
+
+    #[test]
+    fn test_plan_import() {
+        let people = vec![Person {
+            name: "JohnDoe".to_string(),
+            location: "London".to_string(),
+            ..Default::default()
+        }];
+        let contacts = vec![
+            Contact {
+                full_name: "John Doe".to_string(),
+                location: Some("Berlin".to_string()),
+                birthday: None,
+            },
+            Contact {
+                full_name: "Jane Doe".to_string(),
+                location: None,
+                birthday: Some(d("1990-05-01")),
+            },
+        ];
+
+        let expected = vec![
+            PersonChange::Updated {
+                before: Box::new(people[0].clone()),
+                after: Box::new(Person {
+                    name: "JohnDoe".to_string(),
+                    location: "Berlin".to_string(),
+                    ..Default::default()
+                }),
+            },
+            PersonChange::Added(Box::new(Person {
+                name: "JaneDoe".to_string(),
+                birthday: Some(d("1990-05-01")),
+                ..Default::default()
+            })),
+        ];
+
+        assert_eq!(plan_import(&people, &contacts), expected);
+    }
+
+    #[test]
+    fn test_plan_import_without_changes() {
+        let people = vec![Person {
+            name: "JohnDoe".to_string(),
+            location: "London".to_string(),
+            ..Default::default()
+        }];
+        let contacts = vec![Contact {
+            full_name: "John Doe".to_string(),
+            location: Some("London".to_string()),
+            birthday: None,
+        }];
+
+        assert_eq!(plan_import(&people, &contacts), vec![]);
+    }
+
+    #[test]
+    fn test_plan_import_adds_a_repeated_contact_once() {
+        let contact = Contact {
+            full_name: "Jane Doe".to_string(),
+            location: None,
+            birthday: None,
+        };
+
+        assert_eq!(
+            plan_import(&[], &[contact.clone(), contact]),
+            vec![PersonChange::Added(Box::new(Person {
+                name: "JaneDoe".to_string(),
+                ..Default::default()
+            }))]
+        );
+    }
+
+    #[test]
+    fn test_display_change() {
+        let change = PersonChange::Updated {
+            before: Box::new(Person {
+                name: "JohnDoe".to_string(),
+                location: "London".to_string(),
+                ..Default::default()
+            }),
+            after: Box::new(Person {
+                name: "JohnDoe".to_string(),
+                location: "Berlin".to_string(),
+                ..Default::default()
+            }),
+        };
+
+        let expected = "~ JohnDoe\n-   location: London\n+   location: Berlin\n";
+
+        assert_eq!(format!("{change}"), expected);
+    }
+}
@@ -0,0 +1,178 @@
+use core::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use regex::{Captures, Regex};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::config::Config;
+use crate::log::find_log_files;
+use crate::operations::Transaction;
+
+/// Broader than the parser on purpose: also match the combining marks and
+/// invisible characters that would otherwise cut a name short
+static HASHTAG_PATTERN: &str = r"#[\p{L}\p{M}\p{Cf}]+";
+static WIKILINK_PATTERN: &str = r"\[\[[^\[\]|#]+";
+
+// compiled once, as references are looked for in every line of every log
+static HASHTAG_REGEX: OnceLock<Regex> = OnceLock::new();
+static WIKILINK_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn hashtag_regex() -> &'static Regex {
+    HASHTAG_REGEX.get_or_init(|| Regex::new(HASHTAG_PATTERN).unwrap())
+}
+
+fn wikilink_regex() -> &'static Regex {
+    WIKILINK_REGEX.get_or_init(|| Regex::new(WIKILINK_PATTERN).unwrap())
+}
+
+/// A person reference that is written differently than its normalized form
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NameIssue {
+    pub path: PathBuf,
+    /// Starting at 1
+    pub line: usize,
+    pub found: String,
+    pub normalized: String,
+}
+
+impl fmt::Display for NameIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {:?} should be {:?}",
+            self.path.display(),
+            self.line,
+            self.found,
+            self.normalized
+        )
+    }
+}
+
+fn is_invisible(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{00AD}' | '\u{200B}'..='\u{200F}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}'
+    )
+}
+
+/// Drop invisible characters (e.g. zero-width joiners) and compose to NFC
+pub fn normalize_name(name: &str) -> String {
+    name.chars().filter(|ch| !is_invisible(*ch)).nfc().collect()
+}
+
+fn normalize_references(line: &str) -> String {
+    let normalize = |captures: &Captures| normalize_name(&captures[0]);
+    let line = hashtag_regex().replace_all(line, normalize);
+    let line = wikilink_regex().replace_all(&line, normalize);
+
+    line.to_string()
+}
+
+/// Find the references in a log file that are not normalized
+pub fn find_issues(path: &Path, content: &str) -> Vec<NameIssue> {
+    let mut issues: Vec<NameIssue> = vec![];
+    for (index, line) in content.lines().enumerate() {
+        let references = hashtag_regex()
+            .find_iter(line)
+            .chain(wikilink_regex().find_iter(line))
+            .map(|found| found.as_str());
+
+        for found in references {
+            let normalized = normalize_name(found);
+            if normalized != found {
+                issues.push(NameIssue {
+                    path: path.to_path_buf(),
+                    line: index + 1,
+                    found: found.to_string(),
+                    normalized,
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+/// Normalize every reference in a log file, leaving the rest untouched
+pub fn fix_content(content: &str) -> String {
+    content
+        .split('\n')
+        .map(normalize_references)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(reason) => Err(format!("failed to read {path:?}, reason: {reason}")),
+    }
+}
+
+pub fn audit_logs(config: &Config) -> Result<Vec<NameIssue>, String> {
+    let mut issues: Vec<NameIssue> = vec![];
+    for path in find_log_files(config) {
+        let content = read(&path)?;
+        issues.extend(find_issues(&path, &content));
+    }
+
+    Ok(issues)
+}
+
+/// Rewrite the log files that have issues, returns the files changed
+pub fn fix_logs(config: &Config, transaction: &Transaction) -> Result<Vec<PathBuf>, String> {
+    let mut fixed: Vec<PathBuf> = vec![];
+    for path in find_log_files(config) {
+        let content = read(&path)?;
+        let updated = fix_content(&content);
+        if updated == content {
+            continue;
+        }
+
+        transaction.write(&path, &updated)?;
+        fixed.push(path);
+    }
+
+    Ok(fixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_find_issues() {
+        let content =
+            "# 2000-01-01\n\n- #Luci\u{301}a :\n  - with #Lucía and [[Jo\u{200D}hn Doe]]\n";
+
+        let expected = vec![
+            NameIssue {
+                path: PathBuf::from("log.md"),
+                line: 3,
+                found: "#Luci\u{301}a".to_string(),
+                normalized: "#Lucía".to_string(),
+            },
+            NameIssue {
+                path: PathBuf::from("log.md"),
+                line: 4,
+                found: "[[Jo\u{200D}hn Doe".to_string(),
+                normalized: "[[John Doe".to_string(),
+            },
+        ];
+
+        assert_eq!(find_issues(Path::new("log.md"), content), expected);
+    }
+
+    #[test]
+    fn test_fix_content() {
+        let content = "# 2000-01-01\n\n- #Luci\u{301}a :\n  - stuff: blah\u{200D}\n";
+
+        let expected = "# 2000-01-01\n\n- #Lucía :\n  - stuff: blah\u{200D}\n";
+
+        assert_eq!(fix_content(content), expected);
+    }
+}
@@ -0,0 +1,208 @@
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use chrono::NaiveDate;
+use tracing::{info, warn};
+
+use crate::completions::{self, modified_at};
+use crate::config::Config;
+use crate::log::{self, Entry, Log};
+use crate::model::PersonName;
+use crate::state;
+use crate::use_cases::{self, LastInteraction};
+
+type Fingerprint = Vec<(PathBuf, Option<SystemTime>)>;
+
+fn fingerprint(config: &Config) -> Fingerprint {
+    log::find_log_files(config)
+        .into_iter()
+        .map(|path| {
+            let modified = modified_at(&path);
+            (path, modified)
+        })
+        .collect()
+}
+
+/// Position of an entry in `Log::days`: (day, entry)
+type EntryIndex = (usize, usize);
+
+/// The parsed logs and what is derived from them, kept in memory so that
+/// long-running modes (e.g. `people serve`) answer repeated queries without
+/// parsing and aggregating everything again. Derived data is computed on first
+/// use and dropped when the log files change.
+pub struct Workspace {
+    config: Config,
+    log: Log,
+    fingerprint: Fingerprint,
+    people: OnceCell<Vec<PersonName>>,
+    interactions: OnceCell<Vec<LastInteraction>>,
+    entries_by_person: OnceCell<HashMap<PersonName, Vec<EntryIndex>>>,
+}
+
+impl Workspace {
+    pub fn load(config: Config) -> Result<Workspace, String> {
+        let log = log::read_config_logs(&config)?;
+        let fingerprint = fingerprint(&config);
+        Ok(Workspace {
+            fingerprint,
+            ..Workspace::from_log(config, log)
+        })
+    }
+
+    /// A workspace that is never reloaded, e.g. for tests
+    pub fn from_log(config: Config, log: Log) -> Workspace {
+        Workspace {
+            config,
+            log,
+            fingerprint: vec![],
+            people: OnceCell::new(),
+            interactions: OnceCell::new(),
+            entries_by_person: OnceCell::new(),
+        }
+    }
+
+    /// Read the logs again if any file was added, removed or modified. Returns
+    /// whether they were reloaded. The logs read before are kept if they can't
+    /// be read now.
+    pub fn refresh(&mut self) -> bool {
+        if fingerprint(&self.config) == self.fingerprint {
+            return false;
+        }
+
+        info!("log files changed, reloading");
+        match Workspace::load(self.config.clone()) {
+            Ok(workspace) => {
+                *self = workspace;
+                true
+            }
+            Err(reason) => {
+                warn!("failed to reload the logs, reason: {reason}");
+                false
+            }
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn log(&self) -> &Log {
+        &self.log
+    }
+
+    /// Everyone in the logs or the config, except the ignored people, sorted
+    pub fn people(&self) -> &[PersonName] {
+        self.people
+            .get_or_init(|| completions::list_people(&self.log, &self.config))
+    }
+
+    /// Last interaction with each person that is not ignored
+    fn last_interactions(&self) -> &[LastInteraction] {
+        self.interactions.get_or_init(|| {
+            use_cases::get_last_interactions(&self.log)
+                .into_iter()
+                .filter(|interaction| !self.config.is_ignored(&interaction.person))
+                .collect()
+        })
+    }
+
+    /// Last interaction with each person that is not ignored, including how
+    /// overdue a reach out is as of `today`
+    pub fn interactions(&self, today: NaiveDate) -> Vec<LastInteraction> {
+        let interactions = self.last_interactions().to_vec();
+        let interactions = match state::load_state(&self.config.get_state_path()) {
+            Ok(mut state) => use_cases::apply_acknowledgments(interactions, &mut state),
+            Err(reason) => {
+                warn!("ignoring acknowledged reach outs, reason: {reason}");
+                interactions
+            }
+        };
+        use_cases::identify_reachouts(interactions, &self.config, today)
+    }
+
+    pub fn last_interaction(&self, person: &PersonName) -> Option<&LastInteraction> {
+        self.last_interactions()
+            .iter()
+            .find(|interaction| &interaction.person == person)
+    }
+
+    /// Entries that mention the person, oldest first
+    pub fn entries_of(&self, person: &PersonName) -> Vec<(NaiveDate, &Entry)> {
+        let index = self.entries_by_person.get_or_init(|| {
+            let mut index: HashMap<PersonName, Vec<EntryIndex>> = HashMap::new();
+            for (day_index, day) in self.log.days.iter().enumerate() {
+                for (entry_index, entry) in day.entries.iter().enumerate() {
+                    for person in entry.related.iter() {
+                        index
+                            .entry(person.clone())
+                            .or_default()
+                            .push((day_index, entry_index));
+                    }
+                }
+            }
+            index
+        });
+
+        match index.get(person) {
+            Some(positions) => positions
+                .iter()
+                .map(|(day, entry)| {
+                    let day = &self.log.days[*day];
+                    (day.date, day.entries[*entry].as_ref())
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::log::parse_log_file_content;
+    use crate::test_utils::d;
+
+    use super::*;
+
+    #[test]
+    fn test_workspace_queries() {
+        let log = parse_log_file_content(indoc!(
+            "
+            # 2000-01-01
+
+            - #JohnDoe :
+              - climbing with #JaneDoe and #Ignored
+
+            # 2000-01-02
+
+            - #JaneDoe :
+              - coffee
+            "
+        ));
+        let config = Config::builder()
+            .people_dir("/tmp/people")
+            .ignore(vec!["Ignored".to_string()])
+            .build();
+        let workspace = Workspace::from_log(config, log);
+
+        assert_eq!(workspace.people(), ["JaneDoe", "JohnDoe"]);
+        assert_eq!(
+            workspace
+                .last_interaction(&"JaneDoe".to_string())
+                .map(|interaction| interaction.last),
+            Some(d("2000-01-02"))
+        );
+        assert_eq!(workspace.last_interaction(&"Ignored".to_string()), None);
+
+        let dates: Vec<NaiveDate> = workspace
+            .entries_of(&"JaneDoe".to_string())
+            .into_iter()
+            .map(|(date, _)| date)
+            .collect();
+        assert_eq!(dates, vec![d("2000-01-01"), d("2000-01-02")]);
+    }
+}